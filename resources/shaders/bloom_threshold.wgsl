@@ -0,0 +1,20 @@
+// 泛光第一步：只保留亮度较高的像素
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@group(1) @binding(0)
+var t: texture_2d<f32>;
+
+@group(1) @binding(1)
+var s: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t, s, in.uv);
+    let luma = dot(color.rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+    let weight = smoothstep(0.55, 0.9, luma);
+    return vec4<f32>(color.rgb * weight, 1.0);
+}
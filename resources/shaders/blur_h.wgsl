@@ -0,0 +1,24 @@
+// 泛光：横向高斯模糊（9 个采样点）
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@group(1) @binding(0)
+var t: texture_2d<f32>;
+
+@group(1) @binding(1)
+var s: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.5 / vec2<f32>(textureDimensions(t));
+    let offset = vec2<f32>(texel.x, 0.0);
+    var result = textureSample(t, s, in.uv).rgb * 0.227027;
+    result += (textureSample(t, s, in.uv + offset).rgb + textureSample(t, s, in.uv - offset).rgb) * 0.1945946;
+    result += (textureSample(t, s, in.uv + offset * 2.0).rgb + textureSample(t, s, in.uv - offset * 2.0).rgb) * 0.1216216;
+    result += (textureSample(t, s, in.uv + offset * 3.0).rgb + textureSample(t, s, in.uv - offset * 3.0).rgb) * 0.054054;
+    result += (textureSample(t, s, in.uv + offset * 4.0).rgb + textureSample(t, s, in.uv - offset * 4.0).rgb) * 0.016216;
+    return vec4<f32>(result, 1.0);
+}
@@ -0,0 +1,39 @@
+// CRT 复古滤镜：屏幕弯曲、色差、扫描线和暗角
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@group(1) @binding(0)
+var t: texture_2d<f32>;
+
+@group(1) @binding(1)
+var s: sampler;
+
+const CURVATURE: f32 = 0.08;    // 桶形畸变强度
+const ABERRATION: f32 = 0.0015; // 红蓝通道的横向偏移（uv）
+const SCANLINE: f32 = 0.22;     // 扫描线暗部的强度
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // 以屏幕中心为原点做桶形畸变，越靠近边角弯曲越明显
+    let centered = in.uv * 2.0 - 1.0;
+    let curved = centered * (1.0 + CURVATURE * centered.yx * centered.yx);
+    let uv = curved * 0.5 + 0.5;
+
+    let shift = vec2<f32>(ABERRATION, 0.0);
+    let r = textureSample(t, s, uv + shift).r;
+    let g = textureSample(t, s, uv).g;
+    let b = textureSample(t, s, uv - shift).b;
+
+    // 每两行像素一条扫描线
+    let height = f32(textureDimensions(t).y);
+    let scanline = 1.0 - SCANLINE * (0.5 + 0.5 * cos(uv.y * height * 3.14159265));
+    let vignette = clamp(1.0 - dot(centered, centered) * 0.18, 0.0, 1.0);
+
+    // 弯曲后落在屏幕外的部分为黑色
+    let inside = uv.x >= 0.0 && uv.x <= 1.0 && uv.y >= 0.0 && uv.y <= 1.0;
+    let mask = select(0.0, 1.0, inside);
+    return vec4<f32>(vec3<f32>(r, g, b) * scanline * vignette * mask, 1.0);
+}
@@ -0,0 +1,122 @@
+// animation.rs
+// 精灵表帧动画（爆炸等）
+use ggez::graphics::{Canvas, DrawParam, Image, Rect};
+use glam::Vec2;
+
+use crate::WindowSize;
+
+// 横向排列的等尺寸帧精灵表
+pub struct SpriteSheet {
+    image: Image,
+    frame_width: u32,
+    frame_height: u32,
+    frame_count: u32,
+    frame_duration: f32, // 每帧持续时间（秒）
+}
+
+impl SpriteSheet {
+    pub fn new(image: Image, frame_width: u32, frame_height: u32, frame_duration: f32) -> Self {
+        let frame_count = (image.width() / frame_width).max(1);
+        SpriteSheet {
+            image,
+            frame_width,
+            frame_height,
+            frame_count,
+            frame_duration,
+        }
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frame_count as f32 * self.frame_duration
+    }
+
+    // 返回指定帧在精灵表中的归一化源矩形
+    fn frame_rect(&self, frame: u32) -> Rect {
+        let w = self.frame_width as f32 / self.image.width() as f32;
+        let h = self.frame_height as f32 / self.image.height() as f32;
+        Rect::new(frame.min(self.frame_count - 1) as f32 * w, 0.0, w, h)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ExplosionKind {
+    Enemy,
+    Player,
+}
+
+struct Explosion {
+    kind: ExplosionKind,
+    pos: Vec2,
+    size: f32,
+    elapsed: f32,
+}
+
+// 管理场景中正在播放的爆炸动画
+pub struct Explosions {
+    enemy_sheet: SpriteSheet,
+    player_sheet: SpriteSheet,
+    active: Vec<Explosion>,
+}
+
+impl Explosions {
+    pub fn new(enemy_sheet: SpriteSheet, player_sheet: SpriteSheet) -> Self {
+        Explosions {
+            enemy_sheet,
+            player_sheet,
+            active: Vec::new(),
+        }
+    }
+
+    fn sheet(&self, kind: ExplosionKind) -> &SpriteSheet {
+        match kind {
+            ExplosionKind::Enemy => &self.enemy_sheet,
+            ExplosionKind::Player => &self.player_sheet,
+        }
+    }
+
+    // size 为基准坐标下的显示边长
+    pub fn spawn(&mut self, kind: ExplosionKind, pos: Vec2, size: f32) {
+        self.active.push(Explosion {
+            kind,
+            pos,
+            size,
+            elapsed: 0.0,
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let enemy_duration = self.enemy_sheet.duration();
+        let player_duration = self.player_sheet.duration();
+        self.active.retain_mut(|explosion| {
+            explosion.elapsed += dt;
+            explosion.elapsed < match explosion.kind {
+                ExplosionKind::Enemy => enemy_duration,
+                ExplosionKind::Player => player_duration,
+            }
+        });
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize) {
+        for explosion in &self.active {
+            let sheet = self.sheet(explosion.kind);
+            let frame = (explosion.elapsed / sheet.frame_duration) as u32;
+            let scaled_size = window_size.scale_vec2(Vec2::splat(explosion.size));
+
+            canvas.draw(
+                &sheet.image,
+                DrawParam::default()
+                    .src(sheet.frame_rect(frame))
+                    .dest(window_size.scale_vec2(explosion.pos))
+                    .offset(Vec2::new(0.5, 0.5))
+                    .scale(Vec2::new(
+                        scaled_size.x / sheet.frame_width as f32,
+                        scaled_size.y / sheet.frame_height as f32,
+                    )),
+            );
+        }
+    }
+}
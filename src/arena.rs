@@ -0,0 +1,135 @@
+// arena.rs
+// 带代数的对象池：对象以 Handle 引用，Handle 记录槽位和槽位的代数。
+// 对象移除后槽位代数加一，旧 Handle 再也取不到对象，即使槽位已被新对象复用，
+// 不会像 Vec 下标那样在移除后悄悄指向别的对象
+use std::ops::{Index, IndexMut};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Clone)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+#[derive(Clone)]
+pub struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>, // 空闲槽位
+    len: usize,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Arena { slots: Vec::new(), free: Vec::new(), len: 0 }
+    }
+}
+
+impl<T> Arena<T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, value: T) -> Handle {
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.value = Some(value);
+            return Handle { index, generation: slot.generation };
+        }
+        self.slots.push(Slot { generation: 0, value: Some(value) });
+        Handle { index: self.slots.len() as u32 - 1, generation: 0 }
+    }
+
+    // 移除 handle 指向的对象；handle 已失效时返回 None
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        let value = slot.value.take()?;
+        slot.generation += 1;
+        self.free.push(handle.index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.slots
+            .get_mut(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+
+    // 移除所有对象，之前发出的 handle 全部失效
+    pub fn clear(&mut self) {
+        let handles: Vec<Handle> = self.entries().map(|(handle, _)| handle).collect();
+        for handle in handles {
+            self.remove(handle);
+        }
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        let removed: Vec<Handle> = self
+            .entries()
+            .filter(|(_, value)| !keep(value))
+            .map(|(handle, _)| handle)
+            .collect();
+        for handle in removed {
+            self.remove(handle);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+
+    // 带 handle 遍历，用于记下要在之后引用的对象
+    pub fn entries(&self) -> impl Iterator<Item = (Handle, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let handle = Handle { index: index as u32, generation: slot.generation };
+            slot.value.as_ref().map(|value| (handle, value))
+        })
+    }
+}
+
+impl<T> Extend<T> for Arena<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T> Index<Handle> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, handle: Handle) -> &T {
+        self.get(handle).expect("stale arena handle")
+    }
+}
+
+impl<T> IndexMut<Handle> for Arena<T> {
+    fn index_mut(&mut self, handle: Handle) -> &mut T {
+        self.get_mut(handle).expect("stale arena handle")
+    }
+}
@@ -0,0 +1,187 @@
+// assets.rs
+// 资源预加载：启动时由载入界面分多帧读取全部贴图和音频，之后生成对象、播放音效都从缓存中取，
+// 游戏中途不再读取文件。缺少的文件在载入结束时一并列出
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "audio")]
+use ggez::audio::SoundData;
+use ggez::graphics::{FontData, Image};
+use ggez::{GameError, GameResult};
+
+use crate::{boss, pickups, sounds, text};
+
+// 游戏对象和动画使用的贴图（道具贴图由 pickups 表给出）
+const IMAGES: &[&str] = &[
+    "/img/player.png",
+    "/img/bullet.png",
+    "/img/rock.png",
+    "/img/rock1.png",
+    "/img/rock4.png",
+    "/img/shield.png",
+    "/img/explosion_sheet.png",
+    "/img/player_explosion_sheet.png",
+];
+
+const FRAME_BUDGET: Duration = Duration::from_millis(12); // 每帧用于载入的时间，至少载入一个文件
+
+#[derive(Clone, Copy)]
+enum Asset {
+    Font(&'static str), // 载入后直接注册到图形上下文
+    Image(&'static str),
+    Sound(&'static str),
+}
+
+impl Asset {
+    fn path(self) -> &'static str {
+        match self {
+            Asset::Font(path) | Asset::Image(path) | Asset::Sound(path) => path,
+        }
+    }
+}
+
+// 已载入的资源；贴图和音频数据内部共享，克隆开销很小
+#[derive(Clone, Default)]
+pub struct Assets {
+    images: HashMap<&'static str, Image>,
+    #[cfg(feature = "audio")]
+    sounds: HashMap<&'static str, SoundData>,
+}
+
+impl Assets {
+    pub fn image(&self, path: &str) -> GameResult<Image> {
+        self.images
+            .get(path)
+            .cloned()
+            .ok_or_else(|| GameError::ResourceNotFound(format!("{} (not preloaded)", path), Vec::new()))
+    }
+
+    #[cfg(feature = "audio")]
+    pub fn sound(&self, path: &str) -> GameResult<SoundData> {
+        self.sounds
+            .get(path)
+            .cloned()
+            .ok_or_else(|| GameError::ResourceNotFound(format!("{} (not preloaded)", path), Vec::new()))
+    }
+
+    #[cfg(feature = "audio")]
+    pub fn has_sounds(&self) -> bool {
+        !self.sounds.is_empty()
+    }
+
+    // 没有启用 audio 特性时不载入任何音频
+    #[cfg(not(feature = "audio"))]
+    pub fn has_sounds(&self) -> bool {
+        false
+    }
+}
+
+// 载入结果：缺少字体或贴图时无法开始游戏；只缺音频时仍可静音运行
+pub enum LoadResult {
+    Loaded(Assets),
+    Missing(Vec<String>),
+}
+
+pub struct AssetLoader {
+    pending: Vec<Asset>, // 倒序存放，从末尾取出
+    total: usize,
+    assets: Assets,
+    missing: Vec<String>,       // 缺少的字体和贴图
+    missing_sounds: Vec<String>,
+}
+
+impl AssetLoader {
+    // load_sounds 为 false（--no-audio）或没有启用 audio 特性时不载入音频
+    pub fn new(load_sounds: bool) -> Self {
+        let mut pending = vec![Asset::Font(text::FONT_PATH)];
+        pending.extend(
+            IMAGES
+                .iter()
+                .copied()
+                .chain(pickups::PICKUPS.iter().map(|info| info.image))
+                .map(Asset::Image),
+        );
+        if load_sounds && cfg!(feature = "audio") {
+            pending.extend(sounds::clip_paths().into_iter().chain(boss::music_tracks()).map(Asset::Sound));
+        }
+        let mut seen = HashSet::new();
+        pending.retain(|asset| seen.insert(asset.path()));
+        pending.reverse();
+        let total = pending.len();
+        AssetLoader { pending, total, assets: Assets::default(), missing: Vec::new(), missing_sounds: Vec::new() }
+    }
+
+    // 载入进度（0~1）
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        1.0 - self.pending.len() as f32 / self.total as f32
+    }
+
+    // 下一个要载入的文件
+    pub fn current(&self) -> Option<&'static str> {
+        self.pending.last().map(|asset| asset.path())
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    // 在本帧的时间预算内尽量多载入一些文件
+    pub fn update(&mut self, ctx: &mut ggez::Context) {
+        let start = Instant::now();
+        while let Some(asset) = self.pending.pop() {
+            self.load(ctx, asset);
+            if start.elapsed() >= FRAME_BUDGET {
+                break;
+            }
+        }
+    }
+
+    fn load(&mut self, ctx: &mut ggez::Context, asset: Asset) {
+        match asset {
+            Asset::Font(path) => match FontData::from_path(ctx, path) {
+                Ok(font) => text::install_font(ctx, font),
+                Err(err) => {
+                    log::error!("Failed to load {}: {}", path, err);
+                    self.missing.push(path.to_string());
+                }
+            },
+            Asset::Image(path) => match Image::from_path(ctx, path) {
+                Ok(image) => {
+                    self.assets.images.insert(path, image);
+                }
+                Err(err) => {
+                    log::error!("Failed to load {}: {}", path, err);
+                    self.missing.push(path.to_string());
+                }
+            },
+            #[cfg(feature = "audio")]
+            Asset::Sound(path) => match SoundData::new(ctx, path) {
+                Ok(data) => {
+                    self.assets.sounds.insert(path, data);
+                }
+                Err(err) => {
+                    log::warn!("Failed to load {}: {}", path, err);
+                    self.missing_sounds.push(path.to_string());
+                }
+            },
+            #[cfg(not(feature = "audio"))]
+            Asset::Sound(path) => self.missing_sounds.push(path.to_string()),
+        }
+    }
+
+    // 全部载入后取出结果；缺少音频时丢弃已载入的音频，改为静音运行
+    pub fn finish(&mut self) -> LoadResult {
+        if !self.missing.is_empty() {
+            return LoadResult::Missing(std::mem::take(&mut self.missing));
+        }
+        if !self.missing_sounds.is_empty() {
+            log::warn!("Missing {} sound file(s), continuing without sound", self.missing_sounds.len());
+            #[cfg(feature = "audio")]
+            self.assets.sounds.clear();
+        }
+        LoadResult::Loaded(std::mem::take(&mut self.assets))
+    }
+}
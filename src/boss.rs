@@ -0,0 +1,442 @@
+// boss.rs
+// Boss 阶段状态机：生命值降到阈值以下时切换阶段，
+// 每个阶段有各自的弹幕、移动方式、着色和背景音乐，部分阶段开始时召唤护卫，护卫全灭前 Boss 无敌；
+// 登场时先按固定的演出步骤入场、亮出名字和血条，演出期间不攻击也不受伤
+use ggez::graphics::Color;
+use glam::Vec2;
+
+use crate::arena::Handle;
+use crate::enemies::EnemyKind;
+
+const HOVER_Y: f32 = 140.0; // 悬停高度（基准坐标）
+const LASER_LENGTH: f32 = 1200.0; // 激光长度，足够覆盖到屏幕底部的两角
+const LASER_SWEEP: (f32, f32) = (20.0, 160.0); // 扫射的起止角度（度，0 指向右、90 指向正下方）
+
+// 每隔 BOSS_WAVE_INTERVAL 波出现的 Boss 依次使用的名字
+pub const NAMES: [&str; 3] = ["DREADNOUGHT", "HIVE MOTHER", "IRON TEMPEST"];
+
+// 登场演出的步骤，按顺序执行，全部结束后开始战斗
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntroStep {
+    Scroll,     // 从屏幕上方滑入到悬停高度
+    Banner,     // 名字横幅闪烁出现
+    FillHealth, // 血条从空涨满
+}
+
+const INTRO_SEQUENCE: [(IntroStep, f32); 3] = [
+    (IntroStep::Scroll, 2.0), // 秒
+    (IntroStep::Banner, 0.8),
+    (IntroStep::FillHealth, 1.2),
+];
+
+// 登场演出的进度
+#[derive(Clone)]
+struct Intro {
+    elapsed: f32,
+    from_y: Option<f32>, // 入场起点，第一次更新时记录
+}
+
+impl Intro {
+    // 当前步骤和该步骤的进度（0 到 1）；演出结束时返回 None
+    fn current(&self) -> Option<(IntroStep, f32)> {
+        let mut start = 0.0;
+        for (step, duration) in INTRO_SEQUENCE {
+            if self.elapsed < start + duration {
+                return Some((step, (self.elapsed - start) / duration));
+            }
+            start += duration;
+        }
+        None
+    }
+}
+
+// 名字横幅的显示状态
+pub struct Banner {
+    pub name: &'static str,
+    pub alpha: f32,
+}
+
+// 弹幕类型，速度为 60fps 每帧像素
+#[derive(Clone, Copy, Debug)]
+pub enum BulletPattern {
+    Aimed { count: u32, spread: f32, speed: f32 }, // 朝玩家的扇形，spread 为总角度（度）
+    Ring { count: u32, speed: f32 },               // 全方向一圈
+    Spiral { arms: u32, speed: f32, turn: f32 },   // 旋转的螺旋，turn 为每次射击旋转的角度（度）
+}
+
+// 移动方式
+#[derive(Clone, Copy, Debug)]
+pub enum Movement {
+    Sway { amplitude: f32, frequency: f32 }, // 在屏幕中央左右摆动
+    Chase { speed: f32 },                    // 横向追踪玩家
+}
+
+// 激光扫射：预警 warning 秒后，粗光束在 duration 秒内扫过屏幕下半部分
+// 扫射期间 Boss 停止移动和射击
+#[derive(Clone, Copy, Debug)]
+pub struct LaserDef {
+    pub interval: f32, // 秒
+    pub warning: f32,
+    pub duration: f32,
+    pub width: f32,
+}
+
+// 当前激光的位置（基准坐标），预警期间停在起始角度
+#[derive(Clone, Copy, Debug)]
+pub struct LaserBeam {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub width: f32,
+    pub firing: bool,
+}
+
+// 护卫：进入阶段时召唤 count 架 kind 敌机，在 Boss 周围环绕
+#[derive(Clone, Copy, Debug)]
+pub struct EscortDef {
+    pub kind: EnemyKind,
+    pub count: u32,
+    pub radius: f32,      // 环绕半径（基准坐标）
+    pub orbit_speed: f32, // 环绕角速度（弧度/秒）
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BossPhase {
+    Opening,
+    Enraged,
+    Desperate,
+}
+
+pub struct PhaseDef {
+    pub phase: BossPhase,
+    pub threshold: f32, // 生命值比例降到该值及以下时进入此阶段
+    pub pattern: BulletPattern,
+    pub fire_interval: f32, // 秒
+    pub movement: Movement,
+    pub laser: Option<LaserDef>,
+    pub escorts: Option<EscortDef>,
+    pub tint: Color,
+    pub music: &'static str,
+    pub music_pitch: f32,
+}
+
+pub const PHASES: [PhaseDef; 3] = [
+    PhaseDef {
+        phase: BossPhase::Opening,
+        threshold: 1.0,
+        pattern: BulletPattern::Aimed { count: 5, spread: 40.0, speed: 3.0 },
+        fire_interval: 1.2,
+        movement: Movement::Sway { amplitude: 260.0, frequency: 0.25 },
+        laser: None,
+        escorts: None,
+        tint: Color::new(0.8, 0.8, 1.0, 1.0),
+        music: "/sound/background.ogg",
+        music_pitch: 1.0,
+    },
+    PhaseDef {
+        phase: BossPhase::Enraged,
+        threshold: 0.6,
+        pattern: BulletPattern::Ring { count: 16, speed: 2.5 },
+        fire_interval: 0.9,
+        movement: Movement::Sway { amplitude: 360.0, frequency: 0.45 },
+        laser: Some(LaserDef { interval: 7.0, warning: 1.0, duration: 2.0, width: 26.0 }),
+        escorts: Some(EscortDef { kind: EnemyKind::Fighter, count: 4, radius: 110.0, orbit_speed: 1.2 }),
+        tint: Color::new(1.0, 0.6, 0.4, 1.0),
+        music: "/sound/rumble.ogg",
+        music_pitch: 1.0,
+    },
+    PhaseDef {
+        phase: BossPhase::Desperate,
+        threshold: 0.25,
+        pattern: BulletPattern::Spiral { arms: 4, speed: 3.5, turn: 11.0 },
+        fire_interval: 0.12,
+        movement: Movement::Chase { speed: 2.0 },
+        laser: Some(LaserDef { interval: 5.0, warning: 0.8, duration: 2.0, width: 32.0 }),
+        escorts: None,
+        tint: Color::new(1.0, 0.25, 0.25, 1.0),
+        music: "/sound/rumble.ogg",
+        music_pitch: 1.25,
+    },
+];
+
+// 各阶段的背景音乐（可能重复）
+pub fn music_tracks() -> impl Iterator<Item = &'static str> {
+    PHASES.iter().map(|def| def.music)
+}
+
+pub fn phase_def(phase: BossPhase) -> &'static PhaseDef {
+    PHASES
+        .iter()
+        .find(|def| def.phase == phase)
+        .expect("missing boss phase definition")
+}
+
+// 一次更新的结果
+#[derive(Default)]
+pub struct BossUpdate {
+    pub shots: Vec<(Vec2, Vec2)>, // 子弹的位置和速度
+    pub phase_changed: Option<BossPhase>,
+    pub laser_warning: Option<f32>, // 开始激光预警，值为预警秒数
+    pub intro_step: Option<IntroStep>, // 登场演出进入了新的步骤
+    pub intro_finished: bool,          // 登场演出结束，开始战斗
+    pub summon: Option<EscortDef>,     // 新阶段需要召唤的护卫，由调用方生成后交给 summon
+}
+
+// 进行中的激光扫射
+#[derive(Clone)]
+struct LaserState {
+    def: LaserDef,
+    elapsed: f32,
+    reverse: bool, // 从右往左扫
+}
+
+#[derive(Clone)]
+pub struct Boss {
+    pub name: &'static str,
+    pub health: u32,
+    pub max_health: u32,
+    pub phase: BossPhase,
+    fire_timer: f32,
+    time: f32,
+    spiral_angle: f32,
+    laser_timer: f32,
+    laser: Option<LaserState>,
+    laser_reverse: bool, // 下一次扫射的方向，每次交替
+    intro: Option<Intro>,
+    escorts: Vec<Handle>, // 存活的护卫，不为空时 Boss 无敌
+    orbit: f32,           // 护卫环绕的角度（弧度）
+}
+
+impl Boss {
+    pub fn new(name: &'static str, max_health: u32) -> Self {
+        Boss {
+            name,
+            health: max_health,
+            max_health,
+            phase: BossPhase::Opening,
+            fire_timer: phase_def(BossPhase::Opening).fire_interval,
+            time: 0.0,
+            spiral_angle: 0.0,
+            laser_timer: 0.0,
+            laser: None,
+            laser_reverse: false,
+            intro: Some(Intro { elapsed: 0.0, from_y: None }),
+            escorts: Vec::new(),
+            orbit: 0.0,
+        }
+    }
+
+    pub fn in_intro(&self) -> bool {
+        self.intro.is_some()
+    }
+
+    // 横幅在演出的后两步显示，出现时闪烁
+    pub fn banner(&self) -> Option<Banner> {
+        let (step, progress) = self.intro.as_ref()?.current()?;
+        let alpha = match step {
+            IntroStep::Scroll => return None,
+            IntroStep::Banner if (progress * 6.0).fract() < 0.5 => 0.3,
+            _ => 1.0,
+        };
+        Some(Banner { name: self.name, alpha })
+    }
+
+    // 血条显示的比例：演出期间随动画涨满，之后为实际生命值
+    pub fn displayed_health(&self) -> f32 {
+        match self.intro.as_ref().and_then(Intro::current) {
+            Some((IntroStep::FillHealth, progress)) => progress * self.health_fraction(),
+            Some(_) => 0.0,
+            None => self.health_fraction(),
+        }
+    }
+
+    pub fn def(&self) -> &'static PhaseDef {
+        phase_def(self.phase)
+    }
+
+    pub fn health_fraction(&self) -> f32 {
+        self.health as f32 / self.max_health as f32
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health == 0
+    }
+
+    // 护卫存活期间 Boss 被护盾保护
+    pub fn is_shielded(&self) -> bool {
+        !self.escorts.is_empty()
+    }
+
+    pub fn escorts(&self) -> &[Handle] {
+        &self.escorts
+    }
+
+    // 记录召唤出的护卫
+    pub fn summon(&mut self, escorts: Vec<Handle>) {
+        self.escorts = escorts;
+    }
+
+    // 去掉已被击毁的护卫，返回护盾是否因此解除
+    pub fn retain_escorts(&mut self, alive: impl Fn(Handle) -> bool) -> bool {
+        let shielded = self.is_shielded();
+        self.escorts.retain(|handle| alive(*handle));
+        shielded && !self.is_shielded()
+    }
+
+    // 第 index 个护卫当前的环绕位置，center 为 Boss 的位置
+    pub fn escort_slot(&self, index: usize, center: Vec2) -> Vec2 {
+        let radius = self.def().escorts.map_or(0.0, |escorts| escorts.radius);
+        let angle = self.orbit + index as f32 / self.escorts.len().max(1) as f32 * std::f32::consts::TAU;
+        center + Vec2::from_angle(angle) * radius
+    }
+
+    // 练习模式从指定阶段开始：生命值直接降到该阶段的阈值，登场演出结束后随即切换
+    pub fn skip_to(&mut self, phase: BossPhase) {
+        let threshold = phase_def(phase).threshold;
+        self.health = ((self.max_health as f32 * threshold) as u32).clamp(1, self.max_health);
+    }
+
+    // 登场演出期间和护卫存活期间不受伤
+    pub fn damage(&mut self, amount: u32) {
+        if self.in_intro() || self.is_shielded() {
+            return;
+        }
+        self.health = self.health.saturating_sub(amount);
+    }
+
+    // 推进 dt 秒：检查阶段切换、移动 pos 并返回这一步发射的子弹
+    // frame_scale 为相对 60fps 的速度换算系数，screen_width 为基准屏幕宽度
+    pub fn update(&mut self, dt: f32, frame_scale: f32, pos: &mut Vec2, player: Vec2, screen_width: f32) -> BossUpdate {
+        let mut result = BossUpdate::default();
+
+        // 登场演出：只按脚本入场，不移动也不射击
+        if let Some(intro) = &mut self.intro {
+            let before = intro.current().map(|(step, _)| step);
+            intro.elapsed += dt;
+            let current = intro.current();
+            let from_y = *intro.from_y.get_or_insert(pos.y);
+            match current {
+                Some((IntroStep::Scroll, progress)) => {
+                    // 缓出：越接近悬停高度越慢
+                    let eased = 1.0 - (1.0 - progress).powi(3);
+                    pos.y = from_y + (HOVER_Y - from_y) * eased;
+                }
+                _ => pos.y = HOVER_Y,
+            }
+            let step = current.map(|(step, _)| step);
+            if step != before {
+                result.intro_step = step;
+            }
+            if current.is_none() {
+                self.intro = None;
+                result.intro_finished = true;
+            }
+            return result;
+        }
+
+        self.time += dt;
+
+        // 取生命值比例满足的最深阶段
+        let fraction = self.health_fraction();
+        let next = PHASES
+            .iter()
+            .rfind(|def| fraction <= def.threshold)
+            .map_or(self.phase, |def| def.phase);
+        if next != self.phase {
+            self.phase = next;
+            self.fire_timer = 0.0;
+            self.laser = None;
+            self.laser_timer = self.def().laser.map_or(0.0, |laser| laser.interval);
+            result.phase_changed = Some(next);
+            result.summon = self.def().escorts;
+        }
+        if let Some(escorts) = self.def().escorts {
+            self.orbit += escorts.orbit_speed * dt;
+        }
+
+        // 先下降到悬停高度
+        pos.y += (HOVER_Y - pos.y) * (0.02 * frame_scale).min(1.0);
+
+        // 激光扫射期间停下，结束后恢复移动和射击
+        if let Some(laser) = &mut self.laser {
+            laser.elapsed += dt;
+            if laser.elapsed < laser.def.warning + laser.def.duration {
+                return result;
+            }
+            self.laser = None;
+        }
+
+        let def = self.def();
+        if let Some(laser) = def.laser {
+            self.laser_timer -= dt;
+            if self.laser_timer <= 0.0 {
+                self.laser_timer = laser.interval;
+                self.laser = Some(LaserState { def: laser, elapsed: 0.0, reverse: self.laser_reverse });
+                self.laser_reverse = !self.laser_reverse;
+                result.laser_warning = Some(laser.warning);
+                return result;
+            }
+        }
+
+        match def.movement {
+            Movement::Sway { amplitude, frequency } => {
+                let target = screen_width / 2.0 + amplitude * (self.time * frequency * std::f32::consts::TAU).sin();
+                pos.x += (target - pos.x) * (0.05 * frame_scale).min(1.0);
+            }
+            Movement::Chase { speed } => {
+                let step = speed * frame_scale;
+                pos.x += (player.x - pos.x).clamp(-step, step);
+            }
+        }
+
+        self.fire_timer -= dt;
+        if self.fire_timer <= 0.0 {
+            self.fire_timer += def.fire_interval;
+            result.shots = self.fire(def.pattern, *pos, player);
+        }
+
+        result
+    }
+
+    // pos 为 Boss 当前位置；没有激光时返回 None
+    pub fn laser(&self, pos: Vec2) -> Option<LaserBeam> {
+        let laser = self.laser.as_ref()?;
+        let sweep = ((laser.elapsed - laser.def.warning) / laser.def.duration).clamp(0.0, 1.0);
+        let (from, to) = if laser.reverse { (LASER_SWEEP.1, LASER_SWEEP.0) } else { LASER_SWEEP };
+        let angle = (from + (to - from) * sweep).to_radians();
+        Some(LaserBeam {
+            start: pos,
+            end: pos + Vec2::new(angle.cos(), angle.sin()) * LASER_LENGTH,
+            width: laser.def.width,
+            firing: laser.elapsed >= laser.def.warning,
+        })
+    }
+
+    fn fire(&mut self, pattern: BulletPattern, pos: Vec2, player: Vec2) -> Vec<(Vec2, Vec2)> {
+        let direction = |angle: f32| Vec2::new(angle.cos(), angle.sin());
+        match pattern {
+            BulletPattern::Aimed { count, spread, speed } => {
+                let aim = (player - pos).try_normalize().unwrap_or(Vec2::Y);
+                let base = aim.y.atan2(aim.x);
+                let spread = spread.to_radians();
+                (0..count)
+                    .map(|i| {
+                        let t = if count > 1 { i as f32 / (count - 1) as f32 - 0.5 } else { 0.0 };
+                        (pos, direction(base + t * spread) * speed)
+                    })
+                    .collect()
+            }
+            BulletPattern::Ring { count, speed } => (0..count)
+                .map(|i| (pos, direction(i as f32 / count as f32 * std::f32::consts::TAU) * speed))
+                .collect(),
+            BulletPattern::Spiral { arms, speed, turn } => {
+                self.spiral_angle += turn.to_radians();
+                (0..arms)
+                    .map(|i| {
+                        let angle = self.spiral_angle + i as f32 / arms as f32 * std::f32::consts::TAU;
+                        (pos, direction(angle) * speed)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
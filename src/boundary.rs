@@ -0,0 +1,33 @@
+// boundary.rs
+// 玩家移动边界策略：默认限制在屏幕内，可选左右穿越屏幕边缘
+use glam::Vec2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundaryPolicy {
+    Clamp, // 停在屏幕边缘
+    Wrap,  // 从左边缘出去后从右边缘进来（反之亦然），上下仍然限制
+}
+
+impl BoundaryPolicy {
+    pub const ALL: [BoundaryPolicy; 2] = [BoundaryPolicy::Clamp, BoundaryPolicy::Wrap];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BoundaryPolicy::Clamp => "Solid",
+            BoundaryPolicy::Wrap => "Wrap",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BoundaryPolicy> {
+        BoundaryPolicy::ALL.iter().copied().find(|policy| policy.name() == name)
+    }
+
+    // 把移动后的位置约束到屏幕内；size 为对象尺寸，screen 为基准屏幕尺寸
+    pub fn apply(self, pos: Vec2, size: Vec2, screen: Vec2) -> Vec2 {
+        let y = pos.y.clamp(0.0, screen.y - size.y);
+        match self {
+            BoundaryPolicy::Clamp => Vec2::new(pos.x.clamp(0.0, screen.x - size.x), y),
+            BoundaryPolicy::Wrap => Vec2::new(pos.x.rem_euclid(screen.x), y),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+// bounds.rs
+// 离屏回收策略：每类对象越过屏幕边缘多远、朝哪个方向移动时被移除，统一在这里判断，
+// 不再在各处的 retain 里各写一套只检查部分边缘的条件
+use glam::Vec2;
+
+use crate::{BASE_WINDOW_HEIGHT, BASE_WINDOW_WIDTH};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Despawn {
+    // 玩家、僚机等始终保留
+    Never,
+    // 越过任意一条边 margin 以外即移除
+    Outside { margin: f32 },
+    // 越过某条边 margin 以外且仍在朝外移动时移除；适合从屏幕外进入或斜穿屏幕的对象
+    Leaving { margin: f32 },
+    // 只检查下边缘（向下飘落的道具）
+    Below { margin: f32 },
+}
+
+impl Despawn {
+    // pos 为对象中心（基准坐标），velocity 为移动方向，不要求归一化
+    pub fn should_remove(self, pos: Vec2, velocity: Vec2) -> bool {
+        let min = |margin: f32| Vec2::splat(-margin);
+        let max = |margin: f32| Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT) + margin;
+        match self {
+            Despawn::Never => false,
+            Despawn::Outside { margin } => {
+                pos.cmplt(min(margin)).any() || pos.cmpgt(max(margin)).any()
+            }
+            Despawn::Leaving { margin } => {
+                let (min, max) = (min(margin), max(margin));
+                (pos.x < min.x && velocity.x <= 0.0)
+                    || (pos.x > max.x && velocity.x >= 0.0)
+                    || (pos.y < min.y && velocity.y <= 0.0)
+                    || (pos.y > max.y && velocity.y >= 0.0)
+            }
+            Despawn::Below { margin } => pos.y > BASE_WINDOW_HEIGHT + margin,
+        }
+    }
+
+    // 按 velocity 匀速移动时还要移动几步才会被移除（velocity 为每帧位移时即为帧数）；
+    // 已经满足移除条件时为 0，不会离开时为 None
+    pub fn time_to_exit(self, pos: Vec2, velocity: Vec2) -> Option<f32> {
+        if self.should_remove(pos, velocity) {
+            return Some(0.0);
+        }
+        // 沿一个轴越过 [min, max] 以外所需的步数
+        let cross = |pos: f32, velocity: f32, min: f32, max: f32| {
+            if velocity > 0.0 {
+                Some(((max - pos) / velocity).max(0.0))
+            } else if velocity < 0.0 {
+                Some(((min - pos) / velocity).max(0.0))
+            } else {
+                None
+            }
+        };
+        let size = Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT);
+        match self {
+            Despawn::Never => None,
+            Despawn::Outside { margin } | Despawn::Leaving { margin } => {
+                let x = cross(pos.x, velocity.x, -margin, size.x + margin);
+                let y = cross(pos.y, velocity.y, -margin, size.y + margin);
+                match (x, y) {
+                    (Some(x), Some(y)) => Some(x.min(y)),
+                    (x, y) => x.or(y),
+                }
+            }
+            Despawn::Below { margin } if velocity.y > 0.0 => Some((size.y + margin - pos.y) / velocity.y),
+            Despawn::Below { .. } => None,
+        }
+    }
+}
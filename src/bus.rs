@@ -0,0 +1,155 @@
+// bus.rs
+// 游戏事件总线：模拟中发生的事件（击毁敌人、玩家中弹、拾取道具、清波、Boss 换阶段和被击毁）先发布到总线，
+// tick 末尾依次分发给订阅的系统。音效、粒子、统计和本局记录各自决定怎样响应，不再在碰撞循环里逐处手写
+use ggez::graphics::Color;
+use glam::Vec2;
+
+use crate::boss::BossPhase;
+use crate::enemies::EnemyKind;
+use crate::sim::{Cue, Cues};
+use crate::sounds::{SoundEvent, Stinger};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    EnemyKilled { kind: EnemyKind, pos: Vec2, points: i32 },
+    PlayerHit { pos: Vec2 },
+    PickupCollected { pos: Vec2, color: Color }, // color 为道具的代表色
+    WaveCleared { wave: u32 },                   // wave 为刚结束的波次
+    BossPhaseChanged { phase: BossPhase, pos: Vec2, color: Color },
+    BossKilled { pos: Vec2, points: i32 },
+}
+
+// 订阅总线的系统；time 为事件发生时的本局时间（秒），效果通过 cues 交给表现层
+pub trait Subscriber {
+    fn on_event(&mut self, event: &GameEvent, time: f32, cues: &mut Cues);
+}
+
+// 本 tick 发布、尚未分发的事件
+#[derive(Default)]
+pub struct EventBus {
+    pending: Vec<GameEvent>,
+}
+
+impl EventBus {
+    pub fn publish(&mut self, event: GameEvent) {
+        self.pending.push(event);
+    }
+
+    // 按发布顺序把每个事件交给所有订阅者；同一 tick 的事件共用 time
+    pub fn dispatch(&mut self, time: f32, subscribers: &mut [&mut dyn Subscriber], cues: &mut Cues) {
+        for event in self.pending.drain(..) {
+            for subscriber in subscribers.iter_mut() {
+                subscriber.on_event(&event, time, cues);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+// 音效：击毁、清波和 Boss 换阶段的声音
+pub struct SoundCues;
+
+impl Subscriber for SoundCues {
+    fn on_event(&mut self, event: &GameEvent, _time: f32, cues: &mut Cues) {
+        match *event {
+            GameEvent::EnemyKilled { kind, .. } => cues.sound(SoundEvent::EnemyDeath(kind)),
+            GameEvent::WaveCleared { .. } => cues.sound(SoundEvent::Stinger(Stinger::WaveClear)),
+            GameEvent::BossPhaseChanged { .. } => cues.sound(SoundEvent::BossPhase),
+            GameEvent::BossKilled { .. } => {
+                cues.sound(SoundEvent::BossDeath);
+                cues.sound(SoundEvent::Stinger(Stinger::BossKill));
+            }
+            GameEvent::PlayerHit { .. } | GameEvent::PickupCollected { .. } => {}
+        }
+    }
+}
+
+// 画面效果：击毁的得分文字，拾取和换阶段时迸出的粒子
+pub struct EffectCues;
+
+impl Subscriber for EffectCues {
+    fn on_event(&mut self, event: &GameEvent, _time: f32, cues: &mut Cues) {
+        match *event {
+            GameEvent::EnemyKilled { pos, points, .. } | GameEvent::BossKilled { pos, points } => {
+                cues.push(Cue::Popup { pos, points, color: Color::WHITE })
+            }
+            GameEvent::PickupCollected { pos, color } | GameEvent::BossPhaseChanged { pos, color, .. } => cues.burst(pos, color),
+            GameEvent::PlayerHit { .. } | GameEvent::WaveCleared { .. } => {}
+        }
+    }
+}
+
+// 本局统计，结算界面显示
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RunStats {
+    pub kills: u32,
+    pub hits: u32, // 被击中的次数（包括靠炸弹挽回的）
+    pub pickups: u32,
+    pub waves_cleared: u32,
+    pub boss_phases: u32,
+}
+
+impl Subscriber for RunStats {
+    fn on_event(&mut self, event: &GameEvent, _time: f32, _cues: &mut Cues) {
+        match event {
+            GameEvent::EnemyKilled { .. } | GameEvent::BossKilled { .. } => self.kills += 1,
+            GameEvent::PlayerHit { .. } => self.hits += 1,
+            GameEvent::PickupCollected { .. } => self.pickups += 1,
+            GameEvent::WaveCleared { .. } => self.waves_cleared += 1,
+            GameEvent::BossPhaseChanged { .. } => self.boss_phases += 1,
+        }
+    }
+}
+
+// 本局记录中的一条
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogKind {
+    Hit,
+    PowerUp(Color), // 道具的代表色
+    BossKilled,
+    WaveCleared(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LogEntry {
+    pub time: f32, // 本局时间（秒）
+    pub kind: LogKind,
+}
+
+// 本局的重要事件（中弹、拾取道具、击毁 Boss、清波）及其发生时间，结算界面画成时间轴
+#[derive(Clone, Debug, Default)]
+pub struct RunLog {
+    entries: Vec<LogEntry>,
+}
+
+impl RunLog {
+    const MAX_ENTRIES: usize = 500;
+
+    pub fn entries(&self) -> &[LogEntry] {
+        &self.entries
+    }
+
+    // 某一类记录的条数
+    pub fn count(&self, matches: impl Fn(&LogKind) -> bool) -> usize {
+        self.entries.iter().filter(|entry| matches(&entry.kind)).count()
+    }
+}
+
+impl Subscriber for RunLog {
+    fn on_event(&mut self, event: &GameEvent, time: f32, _cues: &mut Cues) {
+        let kind = match *event {
+            GameEvent::PlayerHit { .. } => LogKind::Hit,
+            GameEvent::PickupCollected { color, .. } => LogKind::PowerUp(color),
+            GameEvent::BossKilled { .. } => LogKind::BossKilled,
+            GameEvent::WaveCleared { wave } => LogKind::WaveCleared(wave),
+            GameEvent::EnemyKilled { .. } | GameEvent::BossPhaseChanged { .. } => return,
+        };
+        // 挂机刷分的超长一局只保留开头，时间轴不至于无限增长
+        if self.entries.len() < Self::MAX_ENTRIES {
+            self.entries.push(LogEntry { time, kind });
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// camera.rs
+// 调试用自由镜头：在逻辑画面上平移和缩放，缩小后能看到屏幕外的生成位置和离屏回收行为。
+// 只改变游戏画面层的投影，HUD 与各类界面仍按窗口绘制
+use ggez::graphics::Rect;
+use glam::Vec2;
+
+use crate::{WindowSize, BASE_WINDOW_HEIGHT, BASE_WINDOW_WIDTH};
+
+const PAN_SPEED: f32 = 600.0; // 缩放为 1 时每秒平移的基准坐标距离
+const ZOOM_STEP: f32 = 1.25;
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 4.0;
+
+pub struct FreeCamera {
+    center: Vec2, // 镜头中心（基准坐标）
+    zoom: f32,    // 大于 1 放大，小于 1 缩小
+}
+
+impl Default for FreeCamera {
+    fn default() -> Self {
+        FreeCamera {
+            center: Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT) / 2.0,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl FreeCamera {
+    pub fn center(&self) -> Vec2 {
+        self.center
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    // direction 为按住的方向键合成的方向，缩小时平移得更快
+    pub fn pan(&mut self, direction: Vec2, dt: f32) {
+        self.center += direction * PAN_SPEED * dt / self.zoom;
+    }
+
+    // steps 为正时放大，为负时缩小
+    pub fn zoom_by(&mut self, steps: i32) {
+        self.zoom = (self.zoom * ZOOM_STEP.powi(steps)).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    // 画布的投影范围（窗口像素坐标），供 Canvas::set_screen_coordinates 使用
+    pub fn screen_coordinates(&self, window_size: &WindowSize) -> Rect {
+        let size = Vec2::new(window_size.width, window_size.height) / self.zoom;
+        let corner = window_size.scale_vec2(self.center) - size / 2.0;
+        Rect::new(corner.x, corner.y, size.x, size.y)
+    }
+}
@@ -0,0 +1,158 @@
+// cli.rs
+// 命令行启动参数解析
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use log::LevelFilter;
+
+use crate::mutators::Mutators;
+use crate::run_code::RunCode;
+
+pub const USAGE: &str = "\
+Usage: shooter [OPTIONS]
+
+Options:
+  --windowed        以窗口模式启动（默认）
+  --fullscreen      以全屏模式启动
+  --seed N|CODE     使用固定随机种子；也可以是分享码，同时指定种子和挑战规则
+  --daily           挑战今日关卡（种子和规则由日期决定）
+  --level PATH      加载指定关卡文件（RON 格式，可在主菜单的关卡编辑器中编辑和导出）
+  --resources PATH  从指定的资源目录或 zip 资源包加载资源（默认 resources/，其次 resources.zip）
+  --editor NAME     打开敌人路线编辑器，编辑 paths/NAME.txt（需以 editor 特性编译）
+  --mute            静音启动
+  --no-audio        不加载音频（完全没有音频设备时需以 --no-default-features 编译）
+  --debug           显示调试信息（碰撞范围等），F5-F8 切换模拟倍速，暂停时 F9 单步、F11 后退、F3 重新模拟校验，F10 自由镜头
+  --tick-rate HZ    模拟更新频率（默认 120）
+  --log-level LEVEL 日志级别：off/error/warn/info/debug/trace（默认 warn，也可用 RUST_LOG）
+  -h, --help        显示帮助";
+
+pub const DEFAULT_TICK_RATE: u32 = 120;
+
+// 启动选项
+#[derive(Clone, Debug)]
+pub struct LaunchOptions {
+    pub fullscreen: bool,
+    pub seed: Option<u64>,
+    pub mutators: Option<Mutators>, // 来自分享码的挑战规则，覆盖设置中的规则
+    pub daily: bool,
+    pub level: Option<PathBuf>,
+    pub resources: Option<PathBuf>, // 资源目录或 zip 资源包
+    #[cfg_attr(not(feature = "editor"), allow(dead_code))]
+    pub editor: Option<String>,     // 要编辑的路线名称
+    pub mute: bool,
+    pub no_audio: bool,
+    pub debug: bool,
+    pub tick_rate: u32,
+    pub log_level: Option<LevelFilter>,
+    pub help: bool,
+}
+
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        LaunchOptions {
+            fullscreen: false,
+            seed: None,
+            mutators: None,
+            daily: false,
+            level: None,
+            resources: None,
+            editor: None,
+            mute: false,
+            no_audio: false,
+            debug: false,
+            tick_rate: DEFAULT_TICK_RATE,
+            log_level: None,
+            help: false,
+        }
+    }
+}
+
+impl LaunchOptions {
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut options = LaunchOptions::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            // 同时支持 --key value 和 --key=value 两种写法
+            let (key, inline_value) = match arg.split_once('=') {
+                Some((key, value)) => (key.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            let mut value = |name: &str| -> Result<String, String> {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} 需要一个参数", name))
+            };
+
+            match key.as_str() {
+                "--windowed" => options.fullscreen = false,
+                "--fullscreen" => options.fullscreen = true,
+                "--seed" => {
+                    let raw = value("--seed")?;
+                    if let Ok(seed) = raw.parse::<u64>() {
+                        options.seed = Some(seed);
+                    } else {
+                        let code = RunCode::decode(&raw).ok_or_else(|| format!("无效的种子或分享码: {}", raw))?;
+                        options.seed = Some(code.seed);
+                        options.mutators = Some(code.mutators);
+                    }
+                }
+                "--daily" => options.daily = true,
+                "--level" => {
+                    let path = PathBuf::from(value("--level")?);
+                    if !path.is_file() {
+                        return Err(format!("找不到关卡文件: {}", path.display()));
+                    }
+                    options.level = Some(path);
+                }
+                "--resources" => {
+                    let path = PathBuf::from(value("--resources")?);
+                    if !path.exists() {
+                        return Err(format!("找不到资源目录或资源包: {}", path.display()));
+                    }
+                    options.resources = Some(path);
+                }
+                "--editor" => {
+                    if !cfg!(feature = "editor") {
+                        return Err("--editor 需要以 editor 特性编译（cargo run --features editor）".to_string());
+                    }
+                    let name = value("--editor")?;
+                    let valid = !name.is_empty()
+                        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+                    if !valid {
+                        return Err(format!("路线名称只能包含字母、数字、- 和 _: {}", name));
+                    }
+                    options.editor = Some(name);
+                }
+                "--mute" => options.mute = true,
+                "--no-audio" => options.no_audio = true,
+                "--debug" => options.debug = true,
+                "--tick-rate" => {
+                    let raw = value("--tick-rate")?;
+                    options.tick_rate = raw
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|rate| (30..=1000).contains(rate))
+                        .ok_or_else(|| format!("无效的更新频率（30-1000）: {}", raw))?;
+                }
+                "--log-level" => {
+                    let raw = value("--log-level")?;
+                    let level = LevelFilter::from_str(&raw).map_err(|_| format!("无效的日志级别: {}", raw))?;
+                    options.log_level = Some(level);
+                }
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("未知参数: {}", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
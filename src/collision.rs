@@ -0,0 +1,237 @@
+// collision.rs
+// 碰撞形状：圆、胶囊体（线段加半径）和旋转矩形，所有坐标都是基准坐标
+use glam::Vec2;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+impl Circle {
+    pub fn new(center: Vec2, radius: f32) -> Self {
+        Circle { center, radius }
+    }
+
+    // 以 origin 为原点、旋转 rotation 弧度后的局部偏移圆
+    pub fn offset(origin: Vec2, rotation: f32, local: Vec2, radius: f32) -> Self {
+        Circle::new(origin + Vec2::from_angle(rotation).rotate(local), radius)
+    }
+
+    pub fn overlaps(&self, other: &Circle) -> bool {
+        self.center.distance_squared(other.center) < (self.radius + other.radius).powi(2)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Collider {
+    Circle(Circle),
+    // 线段 a-b 向外扩展 radius
+    Capsule { a: Vec2, b: Vec2, radius: f32 },
+    // 以 center 为中心、旋转 rotation 弧度的矩形
+    OrientedRect { center: Vec2, half_extents: Vec2, rotation: f32 },
+}
+
+impl Collider {
+    // 以 center 为中心、沿 rotation 方向（0 为竖直）长 length 的胶囊体
+    pub fn capsule(center: Vec2, length: f32, radius: f32, rotation: f32) -> Self {
+        let half = Vec2::from_angle(rotation).rotate(Vec2::new(0.0, (length / 2.0 - radius).max(0.0)));
+        Collider::Capsule { a: center - half, b: center + half, radius }
+    }
+
+    pub fn intersects(&self, other: &Collider) -> bool {
+        match (*self, *other) {
+            (Collider::Circle(a), Collider::Circle(b)) => a.overlaps(&b),
+            (Collider::Circle(circle), Collider::Capsule { a, b, radius })
+            | (Collider::Capsule { a, b, radius }, Collider::Circle(circle)) => {
+                circle_capsule(circle, a, b, radius)
+            }
+            (Collider::Capsule { a, b, radius }, Collider::Capsule { a: c, b: d, radius: other_radius }) => {
+                segment_segment_distance(a, b, c, d) < radius + other_radius
+            }
+            (Collider::Circle(circle), Collider::OrientedRect { center, half_extents, rotation })
+            | (Collider::OrientedRect { center, half_extents, rotation }, Collider::Circle(circle)) => {
+                let local = to_local(circle.center, center, rotation);
+                point_box_distance(local, half_extents) < circle.radius
+            }
+            (Collider::Capsule { a, b, radius }, Collider::OrientedRect { center, half_extents, rotation })
+            | (Collider::OrientedRect { center, half_extents, rotation }, Collider::Capsule { a, b, radius }) => {
+                let a = to_local(a, center, rotation);
+                let b = to_local(b, center, rotation);
+                segment_box_distance(a, b, half_extents) < radius
+            }
+            (
+                Collider::OrientedRect { center, half_extents, rotation },
+                Collider::OrientedRect { center: other_center, half_extents: other_half, rotation: other_rotation },
+            ) => rects_overlap(center, half_extents, rotation, other_center, other_half, other_rotation),
+        }
+    }
+}
+
+fn circle_capsule(circle: Circle, a: Vec2, b: Vec2, radius: f32) -> bool {
+    closest_on_segment(circle.center, a, b).distance(circle.center) < circle.radius + radius
+}
+
+// 线段 a-b 上距离 point 最近的点
+fn closest_on_segment(point: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let ab = b - a;
+    let len_sq = ab.length_squared();
+    if len_sq <= f32::EPSILON {
+        return a;
+    }
+    let t = ((point - a).dot(ab) / len_sq).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+fn segments_cross(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let ab = b - a;
+    let cd = d - c;
+    let denom = ab.perp_dot(cd);
+    if denom.abs() <= f32::EPSILON {
+        return false;
+    }
+    let t = (c - a).perp_dot(cd) / denom;
+    let u = (c - a).perp_dot(ab) / denom;
+    (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)
+}
+
+fn segment_segment_distance(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> f32 {
+    if segments_cross(a, b, c, d) {
+        return 0.0;
+    }
+    [
+        closest_on_segment(a, c, d).distance(a),
+        closest_on_segment(b, c, d).distance(b),
+        closest_on_segment(c, a, b).distance(c),
+        closest_on_segment(d, a, b).distance(d),
+    ]
+    .into_iter()
+    .fold(f32::INFINITY, f32::min)
+}
+
+// 把世界坐标转换到以矩形中心为原点、不旋转的局部坐标
+fn to_local(point: Vec2, center: Vec2, rotation: f32) -> Vec2 {
+    Vec2::from_angle(-rotation).rotate(point - center)
+}
+
+// 局部坐标下点到轴对齐矩形的距离（在内部时为 0）
+fn point_box_distance(point: Vec2, half_extents: Vec2) -> f32 {
+    (point.abs() - half_extents).max(Vec2::ZERO).length()
+}
+
+fn segment_box_distance(a: Vec2, b: Vec2, half_extents: Vec2) -> f32 {
+    let corners = [
+        Vec2::new(-half_extents.x, -half_extents.y),
+        Vec2::new(half_extents.x, -half_extents.y),
+        Vec2::new(half_extents.x, half_extents.y),
+        Vec2::new(-half_extents.x, half_extents.y),
+    ];
+    let endpoints = point_box_distance(a, half_extents).min(point_box_distance(b, half_extents));
+    (0..4)
+        .map(|i| segment_segment_distance(a, b, corners[i], corners[(i + 1) % 4]))
+        .fold(endpoints, f32::min)
+}
+
+// 分离轴定理：两矩形各自的两条边方向上投影都重叠才相交
+fn rects_overlap(
+    center: Vec2,
+    half_extents: Vec2,
+    rotation: f32,
+    other_center: Vec2,
+    other_half: Vec2,
+    other_rotation: f32,
+) -> bool {
+    let axes = [Vec2::from_angle(rotation), Vec2::from_angle(other_rotation)];
+    let offset = other_center - center;
+    axes.iter()
+        .flat_map(|axis| [*axis, axis.perp()])
+        .all(|axis| {
+            let project = |half: Vec2, rotation: f32| {
+                let x = Vec2::from_angle(rotation);
+                let y = x.perp();
+                half.x * x.dot(axis).abs() + half.y * y.dot(axis).abs()
+            };
+            offset.dot(axis).abs() <= project(half_extents, rotation) + project(other_half, other_rotation)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circle(x: f32, y: f32, radius: f32) -> Collider {
+        Collider::Circle(Circle::new(Vec2::new(x, y), radius))
+    }
+
+    fn rect(x: f32, y: f32, w: f32, h: f32, rotation: f32) -> Collider {
+        Collider::OrientedRect {
+            center: Vec2::new(x, y),
+            half_extents: Vec2::new(w / 2.0, h / 2.0),
+            rotation,
+        }
+    }
+
+    #[test]
+    fn circles() {
+        assert!(circle(0.0, 0.0, 5.0).intersects(&circle(9.0, 0.0, 5.0)));
+        assert!(!circle(0.0, 0.0, 5.0).intersects(&circle(11.0, 0.0, 5.0)));
+    }
+
+    #[test]
+    fn capsule_reaches_along_its_length() {
+        let capsule = Collider::capsule(Vec2::ZERO, 40.0, 2.0, 0.0);
+        assert!(capsule.intersects(&circle(0.0, 19.0, 2.0)));
+        assert!(!capsule.intersects(&circle(6.0, 0.0, 2.0)));
+        // 旋转 90 度后沿水平方向延伸
+        let rotated = Collider::capsule(Vec2::ZERO, 40.0, 2.0, std::f32::consts::FRAC_PI_2);
+        assert!(rotated.intersects(&circle(19.0, 0.0, 2.0)));
+        assert!(!rotated.intersects(&circle(0.0, 19.0, 2.0)));
+        assert!(circle(0.0, 19.0, 2.0).intersects(&capsule));
+    }
+
+    #[test]
+    fn capsules() {
+        let vertical = Collider::capsule(Vec2::ZERO, 40.0, 2.0, 0.0);
+        let crossing = Collider::capsule(Vec2::ZERO, 40.0, 2.0, std::f32::consts::FRAC_PI_2);
+        let parallel = Collider::capsule(Vec2::new(10.0, 0.0), 40.0, 2.0, 0.0);
+        let touching = Collider::capsule(Vec2::new(3.0, 0.0), 40.0, 2.0, 0.0);
+        assert!(vertical.intersects(&crossing));
+        assert!(!vertical.intersects(&parallel));
+        assert!(vertical.intersects(&touching));
+    }
+
+    #[test]
+    fn rect_and_circle() {
+        let square = rect(0.0, 0.0, 20.0, 20.0, 0.0);
+        assert!(square.intersects(&circle(13.0, 0.0, 4.0)));
+        assert!(!square.intersects(&circle(13.0, 13.0, 4.0)));
+        // 旋转 45 度后角指向右侧
+        let diamond = rect(0.0, 0.0, 20.0, 20.0, std::f32::consts::FRAC_PI_4);
+        assert!(diamond.intersects(&circle(16.0, 0.0, 2.0)));
+        assert!(!diamond.intersects(&circle(10.0, 10.0, 2.0)));
+        assert!(circle(13.0, 0.0, 4.0).intersects(&square));
+    }
+
+    #[test]
+    fn rect_and_capsule() {
+        let square = rect(0.0, 0.0, 20.0, 20.0, 0.0);
+        // 端点都在矩形外，但线段穿过矩形
+        let through = Collider::Capsule { a: Vec2::new(-30.0, 0.0), b: Vec2::new(30.0, 0.0), radius: 1.0 };
+        let beside = Collider::Capsule { a: Vec2::new(-30.0, 15.0), b: Vec2::new(30.0, 15.0), radius: 1.0 };
+        let near = Collider::Capsule { a: Vec2::new(-30.0, 12.0), b: Vec2::new(30.0, 12.0), radius: 3.0 };
+        assert!(square.intersects(&through));
+        assert!(!square.intersects(&beside));
+        assert!(near.intersects(&square));
+    }
+
+    #[test]
+    fn rects() {
+        let square = rect(0.0, 0.0, 20.0, 20.0, 0.0);
+        assert!(square.intersects(&rect(18.0, 0.0, 20.0, 20.0, 0.0)));
+        assert!(!square.intersects(&rect(22.0, 0.0, 20.0, 20.0, 0.0)));
+        // 轴对齐包围盒重叠，但旋转后的矩形实际分离
+        let diamond = rect(23.0, 23.0, 20.0, 20.0, std::f32::consts::FRAC_PI_4);
+        assert!(!square.intersects(&diamond));
+        assert!(square.intersects(&rect(20.0, 0.0, 20.0, 20.0, std::f32::consts::FRAC_PI_4)));
+    }
+}
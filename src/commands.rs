@@ -0,0 +1,71 @@
+// commands.rs
+// 延迟指令：更新过程中产生的移除、生成和击毁先记入队列，在本 tick 末尾统一执行。
+// 遍历对象池时不会修改对象池本身
+use std::collections::HashSet;
+
+use glam::Vec2;
+
+use crate::arena::{Arena, Handle};
+use crate::enemies::EnemyKind;
+
+// 指令作用的对象池
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pool {
+    Bullets,
+    Enemies,
+    Hazards,
+    Pickups,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum GameCommand {
+    Remove(Pool, Handle),
+    SpawnEnemy { kind: EnemyKind, pos: Vec2, heading: Vec2, drift: Vec2 }, // drift 为额外的横向漂移
+    EnemyKilled { handle: Handle, kind: EnemyKind, pos: Vec2, points: i32 }, // 计分、连锁、消弹与死亡行为
+    Explosion(Vec2),
+}
+
+#[derive(Default)]
+pub struct CommandQueue {
+    commands: Vec<GameCommand>,
+    removed: HashSet<(Pool, Handle)>,
+}
+
+impl CommandQueue {
+    pub fn push(&mut self, command: GameCommand) {
+        if let GameCommand::Remove(pool, handle) = command {
+            self.remove(pool, handle);
+        } else {
+            self.commands.push(command);
+        }
+    }
+
+    // 标记移除，同一对象只记一次；返回是否为首次标记
+    pub fn remove(&mut self, pool: Pool, handle: Handle) -> bool {
+        let first = self.removed.insert((pool, handle));
+        if first {
+            self.commands.push(GameCommand::Remove(pool, handle));
+        }
+        first
+    }
+
+    pub fn is_removed(&self, pool: Pool, handle: Handle) -> bool {
+        self.removed.contains(&(pool, handle))
+    }
+
+    pub fn take(&mut self) -> Vec<GameCommand> {
+        self.removed.clear();
+        std::mem::take(&mut self.commands)
+    }
+}
+
+// 按指令移除 pool 对应对象池中的对象；已经不在池中的对象直接忽略
+pub fn remove_marked<T>(arena: &mut Arena<T>, pool: Pool, commands: &[GameCommand]) {
+    for command in commands {
+        if let GameCommand::Remove(target, handle) = command {
+            if *target == pool {
+                arena.remove(*handle);
+            }
+        }
+    }
+}
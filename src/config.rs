@@ -0,0 +1,274 @@
+// config.rs
+// 用户配置文件（音量、难度、屏幕边界、按键绑定等），以简单的 key = value 文本格式保存
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::boundary::BoundaryPolicy;
+use crate::input::{self, Action, InputMap};
+use crate::mutators::Mutators;
+use crate::palette::Palette;
+use crate::postfx::ScreenFilter;
+
+const CONFIG_FILE: &str = "config.txt";
+
+// 难度
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        Difficulty::ALL.iter().copied().find(|difficulty| difficulty.name() == name)
+    }
+
+    // 敌人生成间隔倍数
+    pub fn spawn_interval_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.4,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.7,
+        }
+    }
+
+    // 敌人移动速度倍数
+    pub fn enemy_speed_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+        }
+    }
+
+    // 玩家判定大小倍数：困难难度弹幕更密，判定也更小
+    pub fn hurtbox_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy | Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.6,
+        }
+    }
+
+    // 同屏敌人上限
+    pub fn max_enemies(self) -> usize {
+        match self {
+            Difficulty::Easy => 18,
+            Difficulty::Normal => 24,
+            Difficulty::Hard => 32,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub bindings: InputMap,
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub muted: bool,
+    pub difficulty: Difficulty,
+    pub boundary: BoundaryPolicy,
+    pub mutators: Mutators, // 下一局启用的挑战规则
+    pub bloom: bool,        // 泛光后期处理，低配机器可关闭
+    pub screen_filter: ScreenFilter,
+    pub palette: Palette, // 子弹、道具和 HUD 的配色
+    pub low_spec: bool,   // 低配模式：减少同屏敌人数量
+    pub auto_fire: bool,  // 自动射击：不用按住开火键，射速仍受武器冷却限制
+    pub record_highlights: bool, // 录制最近几秒的画面以便保存精彩片段，每秒从显卡同步读回画面开销较大，默认关闭
+    pub show_hurtbox: bool, // 一直显示玩家的判定点，而不只在专注时显示
+    pub classic_movement: bool, // 经典移动：没有加速和惯性，松开方向键立即停下
+    pub pickup_warnings: bool, // 稀有道具即将掉出屏幕时闪烁提示并播放提示音
+    pub auto_center: bool, // 自动居中：没有按左右键时飞机自己回到水平中央
+    pub dodge_hints: bool, // 敌弹快要打中时在飞机旁用箭头提示躲避方向
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            bindings: InputMap::default(),
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            muted: false,
+            difficulty: Difficulty::Normal,
+            boundary: BoundaryPolicy::Clamp,
+            mutators: Mutators::default(),
+            bloom: true,
+            screen_filter: ScreenFilter::Off,
+            palette: Palette::Standard,
+            low_spec: false,
+            auto_fire: false,
+            record_highlights: false,
+            show_hurtbox: false,
+            classic_movement: false,
+            pickup_warnings: true,
+            auto_center: false,
+            dodge_hints: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn path(dir: &Path) -> PathBuf {
+        dir.join(CONFIG_FILE)
+    }
+
+    // 文件不存在或无法解析的行都回退到默认值
+    pub fn load(dir: &Path) -> Self {
+        let mut config = Config::default();
+        let Ok(text) = fs::read_to_string(Self::path(dir)) else {
+            return config;
+        };
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            match key {
+                "master_volume" => {
+                    if let Ok(volume) = value.parse::<f32>() {
+                        config.master_volume = volume.clamp(0.0, 1.0);
+                    }
+                }
+                "sfx_volume" => {
+                    if let Ok(volume) = value.parse::<f32>() {
+                        config.sfx_volume = volume.clamp(0.0, 1.0);
+                    }
+                }
+                "muted" => {
+                    if let Ok(muted) = value.parse::<bool>() {
+                        config.muted = muted;
+                    }
+                }
+                "difficulty" => {
+                    if let Some(difficulty) = Difficulty::from_name(value) {
+                        config.difficulty = difficulty;
+                    }
+                }
+                "boundary" => {
+                    if let Some(boundary) = BoundaryPolicy::from_name(value) {
+                        config.boundary = boundary;
+                    }
+                }
+                "mutators" => config.mutators = Mutators::parse(value),
+                "bloom" => {
+                    if let Ok(bloom) = value.parse::<bool>() {
+                        config.bloom = bloom;
+                    }
+                }
+                "screen_filter" => {
+                    if let Some(filter) = ScreenFilter::from_name(value) {
+                        config.screen_filter = filter;
+                    }
+                }
+                "palette" => {
+                    if let Some(palette) = Palette::from_name(value) {
+                        config.palette = palette;
+                    }
+                }
+                "low_spec" => {
+                    if let Ok(low_spec) = value.parse::<bool>() {
+                        config.low_spec = low_spec;
+                    }
+                }
+                "auto_fire" => {
+                    if let Ok(auto_fire) = value.parse::<bool>() {
+                        config.auto_fire = auto_fire;
+                    }
+                }
+                "record_highlights" => {
+                    if let Ok(record) = value.parse::<bool>() {
+                        config.record_highlights = record;
+                    }
+                }
+                "show_hurtbox" => {
+                    if let Ok(show) = value.parse::<bool>() {
+                        config.show_hurtbox = show;
+                    }
+                }
+                "classic_movement" => {
+                    if let Ok(classic) = value.parse::<bool>() {
+                        config.classic_movement = classic;
+                    }
+                }
+                "pickup_warnings" => {
+                    if let Ok(warn) = value.parse::<bool>() {
+                        config.pickup_warnings = warn;
+                    }
+                }
+                "auto_center" => {
+                    if let Ok(center) = value.parse::<bool>() {
+                        config.auto_center = center;
+                    }
+                }
+                "dodge_hints" => {
+                    if let Ok(hints) = value.parse::<bool>() {
+                        config.dodge_hints = hints;
+                    }
+                }
+                _ => {}
+            }
+
+            if let Some(action) = key.strip_prefix("bind.").and_then(Action::from_id) {
+                let keys: Vec<_> = value
+                    .split(',')
+                    .filter_map(|name| input::parse_key(name.trim()))
+                    .collect();
+                if !keys.is_empty() {
+                    config.bindings.set_keys(action, keys);
+                }
+            }
+        }
+
+        config
+    }
+
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        let mut text = String::from("# shooter config\n");
+        text.push_str(&format!("master_volume = {:.2}\n", self.master_volume));
+        text.push_str(&format!("sfx_volume = {:.2}\n", self.sfx_volume));
+        text.push_str(&format!("muted = {}\n", self.muted));
+        text.push_str(&format!("difficulty = {}\n", self.difficulty.name()));
+        text.push_str(&format!("boundary = {}\n", self.boundary.name()));
+        text.push_str(&format!("mutators = {}\n", self.mutators.to_ids()));
+        text.push_str(&format!("bloom = {}\n", self.bloom));
+        text.push_str(&format!("screen_filter = {}\n", self.screen_filter.name()));
+        text.push_str(&format!("palette = {}\n", self.palette.name()));
+        text.push_str(&format!("low_spec = {}\n", self.low_spec));
+        text.push_str(&format!("auto_fire = {}\n", self.auto_fire));
+        text.push_str(&format!("record_highlights = {}\n", self.record_highlights));
+        text.push_str(&format!("show_hurtbox = {}\n", self.show_hurtbox));
+        text.push_str(&format!("classic_movement = {}\n", self.classic_movement));
+        text.push_str(&format!("pickup_warnings = {}\n", self.pickup_warnings));
+        text.push_str(&format!("auto_center = {}\n", self.auto_center));
+        text.push_str(&format!("dodge_hints = {}\n", self.dodge_hints));
+        for action in Action::ALL {
+            let keys: Vec<String> = self
+                .bindings
+                .keys(action)
+                .iter()
+                .map(|key| input::key_name(*key))
+                .collect();
+            text.push_str(&format!("bind.{} = {}\n", action.id(), keys.join(", ")));
+        }
+
+        fs::create_dir_all(dir)?;
+        fs::write(Self::path(dir), text)
+    }
+}
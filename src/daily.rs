@@ -0,0 +1,137 @@
+// daily.rs
+// 每日挑战：由当天日期（UTC）决定随机种子、边界和挑战规则，每天只记录第一次挑战的成绩
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::boundary::BoundaryPolicy;
+use crate::highscores::Record;
+use crate::mutators::Mutators;
+
+const DAILY_FILE: &str = "daily.txt";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// 当天的挑战参数
+#[derive(Clone, Copy, Debug)]
+pub struct DailyChallenge {
+    pub day: i64, // 自 1970-01-01 起的天数
+    pub seed: u64,
+    pub boundary: BoundaryPolicy,
+    pub mutators: Mutators,
+}
+
+impl DailyChallenge {
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        Self::for_day((secs / SECONDS_PER_DAY) as i64)
+    }
+
+    pub fn for_day(day: i64) -> Self {
+        let seed = mix(day as u64);
+        DailyChallenge {
+            day,
+            seed,
+            // 规则也由种子决定，同一天所有人相同
+            boundary: if seed & 1 == 0 { BoundaryPolicy::Clamp } else { BoundaryPolicy::Wrap },
+            mutators: Mutators::from_bits((seed >> 8) as u8),
+        }
+    }
+
+    pub fn date(&self) -> String {
+        format_date(self.day)
+    }
+}
+
+// splitmix64：让相邻日期得到差异很大的种子
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// 天数转换为 YYYY-MM-DD（公历）
+fn format_date(day: i64) -> String {
+    let z = day + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+// 每日挑战历史，按日期排序
+#[derive(Clone, Debug, Default)]
+pub struct DailyHistory {
+    entries: BTreeMap<String, Record>,
+}
+
+impl DailyHistory {
+    fn path(dir: &Path) -> PathBuf {
+        dir.join(DAILY_FILE)
+    }
+
+    // 每行格式：YYYY-MM-DD = score, seconds, mutators
+    pub fn load(dir: &Path) -> Self {
+        let mut history = DailyHistory::default();
+        let Ok(text) = fs::read_to_string(Self::path(dir)) else {
+            return history;
+        };
+
+        for line in text.lines() {
+            let Some((date, value)) = line.split_once('=') else {
+                continue;
+            };
+            let mut fields = value.split(',').map(str::trim);
+            let best_score = fields.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+            let best_time = fields.next().and_then(|v| v.parse().ok()).unwrap_or(0.0);
+            let mutators = fields.next().map(Mutators::parse).unwrap_or_default();
+            history.entries.insert(date.trim().to_string(), Record { best_score, best_time, mutators });
+        }
+
+        history
+    }
+
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        let mut text = String::new();
+        for (date, record) in &self.entries {
+            text.push_str(&format!(
+                "{} = {}, {:.2}, {}\n",
+                date,
+                record.best_score,
+                record.best_time,
+                record.mutators.to_ids()
+            ));
+        }
+        fs::create_dir_all(dir)?;
+        fs::write(Self::path(dir), text)
+    }
+
+    pub fn attempted(&self, challenge: &DailyChallenge) -> bool {
+        self.entries.contains_key(&challenge.date())
+    }
+
+    // 只记录当天的第一次挑战，返回是否记录
+    pub fn record(&mut self, challenge: &DailyChallenge, score: i32, time: f32) -> bool {
+        if self.attempted(challenge) {
+            return false;
+        }
+        let record = Record { best_score: score, best_time: time, mutators: challenge.mutators };
+        self.entries.insert(challenge.date(), record);
+        true
+    }
+
+    // 最近 count 天的成绩，新的在前
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = (&str, &Record)> {
+        self.entries.iter().rev().take(count).map(|(date, record)| (date.as_str(), record))
+    }
+}
@@ -0,0 +1,204 @@
+// dialogue.rs
+// 剧情消息栏：关卡脚本推送的台词在屏幕底部排队显示，每句带说话人头像，
+// 文字逐字出现，显示到时后自动换下一句，也可以按跳过键提前结束
+use std::collections::VecDeque;
+
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use glam::Vec2;
+
+use crate::{WindowSize, BASE_WINDOW_HEIGHT, BASE_WINDOW_WIDTH};
+
+#[cfg_attr(not(feature = "rhai"), allow(dead_code))]
+pub const DEFAULT_DURATION: f32 = 3.0; // 未指定时长的台词显示秒数（从文字全部出现后算起）
+const REVEAL_SPEED: f32 = 40.0;        // 每秒出现的字符数
+const MAX_QUEUED: usize = 16;          // 排队台词的上限，超出的直接丢弃
+const BAR_HEIGHT: f32 = 96.0;
+const PORTRAIT_SIZE: f32 = 72.0;
+
+// 只有脚本能指定说话人，未启用 rhai 特性时不会构造
+#[cfg_attr(not(feature = "rhai"), allow(dead_code))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Speaker {
+    Commander, // 指挥官
+    Wingman,   // 僚机驾驶员
+    Hostile,   // 敌方通讯
+}
+
+#[cfg_attr(not(feature = "rhai"), allow(dead_code))]
+impl Speaker {
+    pub const ALL: [Speaker; 3] = [Speaker::Commander, Speaker::Wingman, Speaker::Hostile];
+
+    // 脚本中使用的名称
+    pub fn id(self) -> &'static str {
+        match self {
+            Speaker::Commander => "commander",
+            Speaker::Wingman => "wingman",
+            Speaker::Hostile => "hostile",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Speaker> {
+        Speaker::ALL.iter().copied().find(|speaker| speaker.id() == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Speaker::Commander => "Commander",
+            Speaker::Wingman => "Wingman",
+            Speaker::Hostile => "Unknown",
+        }
+    }
+
+    // 头像底色
+    fn color(self) -> Color {
+        match self {
+            Speaker::Commander => Color::new(0.2, 0.4, 0.9, 1.0),
+            Speaker::Wingman => Color::new(0.2, 0.75, 0.35, 1.0),
+            Speaker::Hostile => Color::new(0.85, 0.15, 0.15, 1.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DialogueLine {
+    pub speaker: Option<Speaker>, // None 为旁白，不显示头像
+    pub text: String,
+    pub duration: f32, // 秒
+}
+
+// 正在显示的台词
+struct Current {
+    line: DialogueLine,
+    elapsed: f32,
+}
+
+impl Current {
+    fn reveal_time(&self) -> f32 {
+        self.line.text.chars().count() as f32 / REVEAL_SPEED
+    }
+
+    fn is_revealed(&self) -> bool {
+        self.elapsed >= self.reveal_time()
+    }
+
+    fn visible_text(&self) -> String {
+        let count = (self.elapsed * REVEAL_SPEED) as usize;
+        self.line.text.chars().take(count).collect()
+    }
+}
+
+#[derive(Default)]
+pub struct MessageBar {
+    queue: VecDeque<DialogueLine>,
+    current: Option<Current>,
+}
+
+impl MessageBar {
+    pub fn push(&mut self, line: DialogueLine) {
+        if self.current.is_none() {
+            self.current = Some(Current { line, elapsed: 0.0 });
+        } else if self.queue.len() < MAX_QUEUED {
+            self.queue.push_back(line);
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.current = None;
+    }
+
+    fn advance(&mut self) {
+        self.current = self.queue.pop_front().map(|line| Current { line, elapsed: 0.0 });
+    }
+
+    // 文字还在逐字出现时先补全整句，否则换下一句
+    pub fn skip(&mut self) {
+        match &mut self.current {
+            Some(current) if !current.is_revealed() => current.elapsed = current.reveal_time(),
+            Some(_) => self.advance(),
+            None => {}
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        if let Some(current) = &mut self.current {
+            current.elapsed += dt;
+            if current.elapsed >= current.reveal_time() + current.line.duration {
+                self.advance();
+            }
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize, skip_key: &str) {
+        let Some(current) = &self.current else {
+            return;
+        };
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let top = BASE_WINDOW_HEIGHT - BAR_HEIGHT;
+
+        // 半透明底栏
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(0.0, top)))
+                .scale(window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH, BAR_HEIGHT)))
+                .color(Color::new(0.0, 0.0, 0.0, 0.75)),
+        );
+
+        // 头像：说话人颜色的方块加首字母，旁白不画头像
+        let mut text_left = 24.0;
+        if let Some(speaker) = current.line.speaker {
+            let portrait_pos = Vec2::new(12.0, top + (BAR_HEIGHT - PORTRAIT_SIZE) / 2.0);
+            canvas.draw(
+                &graphics::Quad,
+                DrawParam::default()
+                    .dest(window_size.scale_vec2(portrait_pos))
+                    .scale(window_size.scale_vec2(Vec2::splat(PORTRAIT_SIZE)))
+                    .color(speaker.color()),
+            );
+            let initial: String = speaker.name().chars().take(1).collect();
+            canvas.draw(
+                &graphics::Text::new(initial),
+                DrawParam::default()
+                    .dest(window_size.scale_vec2(portrait_pos + Vec2::new(22.0, 14.0)))
+                    .color(Color::WHITE)
+                    .scale(scale * 3.0),
+            );
+            canvas.draw(
+                &graphics::Text::new(speaker.name()),
+                DrawParam::default()
+                    .dest(window_size.scale_vec2(Vec2::new(PORTRAIT_SIZE + 28.0, top + 12.0)))
+                    .color(speaker.color())
+                    .scale(scale),
+            );
+            text_left = PORTRAIT_SIZE + 28.0;
+        }
+
+        let mut text = graphics::Text::new(current.visible_text());
+        text.set_bounds(Vec2::new((BASE_WINDOW_WIDTH - text_left - 24.0) / 1.5, f32::INFINITY));
+        canvas.draw(
+            &text,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(text_left, top + 34.0)))
+                .color(Color::CYAN)
+                .scale(scale * 1.5),
+        );
+
+        let hint = if self.queue.is_empty() {
+            format!("{}: skip", skip_key)
+        } else {
+            format!("{}: next ({} more)", skip_key, self.queue.len())
+        };
+        canvas.draw(
+            &graphics::Text::new(hint),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH - 180.0, top + 12.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+}
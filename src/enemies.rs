@@ -0,0 +1,383 @@
+// enemies.rs
+// 敌人定义：尺寸、速度、分值以及死亡时的行为
+// 内置定义可被 mod 的 enemies.txt 覆盖（每行 `<kind>.<field> = <value>`）
+use ggez::graphics::Color;
+use glam::Vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// 关卡文件中以 id 的形式出现（例如 mine_layer）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnemyKind {
+    Fighter,   // 普通敌机
+    Bomber,    // 死亡时向四周发射子弹
+    Splitter,  // 死亡时分裂成两个小敌机
+    Shard,     // 分裂出的小敌机
+    MineLayer, // 死亡时留下追踪地雷
+    Tank,      // 缓慢的装甲敌机，只有两侧弱点能造成伤害
+    Kamikaze,  // 飞到一定高度后锁定玩家位置并直线俯冲
+    Carrier,     // 大型母舰，存活期间定时从机库放出拦截机
+    Interceptor, // 母舰放出的小型高速拦截机
+    Sentinel,    // 外层护盾泡吸收一定伤害后破裂，之后才能击中机体
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 10] = [
+        EnemyKind::Fighter,
+        EnemyKind::Bomber,
+        EnemyKind::Splitter,
+        EnemyKind::Shard,
+        EnemyKind::MineLayer,
+        EnemyKind::Tank,
+        EnemyKind::Kamikaze,
+        EnemyKind::Carrier,
+        EnemyKind::Interceptor,
+        EnemyKind::Sentinel,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            EnemyKind::Fighter => "fighter",
+            EnemyKind::Bomber => "bomber",
+            EnemyKind::Splitter => "splitter",
+            EnemyKind::Shard => "shard",
+            EnemyKind::MineLayer => "mine_layer",
+            EnemyKind::Tank => "tank",
+            EnemyKind::Kamikaze => "kamikaze",
+            EnemyKind::Carrier => "carrier",
+            EnemyKind::Interceptor => "interceptor",
+            EnemyKind::Sentinel => "sentinel",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<EnemyKind> {
+        EnemyKind::ALL.iter().copied().find(|kind| kind.id() == id)
+    }
+}
+
+// 敌人进入屏幕的边
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryEdge {
+    Top,
+    Left,
+    Right,
+    Bottom,
+}
+
+impl EntryEdge {
+    // 从该边进入时的行进方向（单位向量），侧面进入的敌人斜向下穿过屏幕
+    pub fn heading(self) -> Vec2 {
+        match self {
+            EntryEdge::Top => Vec2::Y,
+            EntryEdge::Left => Vec2::new(1.0, 0.35).normalize(),
+            EntryEdge::Right => Vec2::new(-1.0, 0.35).normalize(),
+            EntryEdge::Bottom => Vec2::NEG_Y,
+        }
+    }
+
+    // 沿该边 offset 处、屏幕外 margin 距离的生成位置
+    // offset 对上下边是 x 坐标，对左右边是 y 坐标
+    pub fn spawn_pos(self, offset: f32, screen: Vec2, margin: f32) -> Vec2 {
+        match self {
+            EntryEdge::Top => Vec2::new(offset, -margin),
+            EntryEdge::Left => Vec2::new(-margin, offset),
+            EntryEdge::Right => Vec2::new(screen.x + margin, offset),
+            EntryEdge::Bottom => Vec2::new(offset, screen.y + margin),
+        }
+    }
+
+    // 前两波只从上方进入，之后偶尔从两侧或下方出现
+    pub fn roll(rng: &mut impl Rng, wave: u32) -> EntryEdge {
+        if wave < 3 {
+            return EntryEdge::Top;
+        }
+        match rng.gen_range(0..10) {
+            0 => EntryEdge::Left,
+            1 => EntryEdge::Right,
+            2 => EntryEdge::Bottom,
+            _ => EntryEdge::Top,
+        }
+    }
+}
+
+// 死亡时的行为
+#[derive(Clone, Copy, Debug)]
+pub enum OnDeath {
+    Nothing,
+    RadialBullets { count: u32, speed: f32 },
+    Split { child: EnemyKind, count: u32 },
+    HomingMines { count: u32 },
+}
+
+// 装甲：正面命中无效，弱点被击中 hits 次后摧毁；
+// 一段时间未被击中时护盾每 regen 秒恢复一次
+#[derive(Clone, Copy, Debug)]
+pub struct Armor {
+    pub hits: u32,
+    pub regen: f32,
+    pub weak_point_radius: f32, // 相对敌人尺寸的比例
+}
+
+// 俯冲：越过 trigger_y 后锁定玩家当前位置，预警 warning 秒后沿直线加速冲过去
+#[derive(Clone, Copy, Debug)]
+pub struct Dive {
+    pub trigger_y: f32,
+    pub warning: f32,
+    pub accel: f32,     // 60fps 每帧增加的速度
+    pub max_speed: f32, // 60fps 每帧像素
+}
+
+// 护盾泡：包住整个敌人的圆形护盾，先于机体结算子弹命中，吸收 hits 点伤害后破裂
+#[derive(Clone, Copy, Debug)]
+pub struct Shield {
+    pub hits: u32,
+    pub radius: f32, // 相对敌人尺寸的比例
+}
+
+// 舰载机：每隔 interval 秒放出 count 架 child，只在母舰进入屏幕后才会放出
+#[derive(Clone, Copy, Debug)]
+pub struct Launcher {
+    pub child: EnemyKind,
+    pub interval: f32,
+    pub count: u32,
+}
+
+#[derive(Clone)]
+pub struct EnemyDef {
+    pub kind: EnemyKind,
+    pub size: f32,         // 基准坐标下的边长
+    pub speed: f32,        // 相对基础敌人速度的倍数
+    pub score: i32,
+    pub tint: Color,
+    pub spawn_weight: u32, // 0 表示不会自然生成
+    pub on_death: OnDeath,
+    pub armor: Option<Armor>,
+    pub dive: Option<Dive>,
+    pub launcher: Option<Launcher>,
+    pub shield: Option<Shield>,
+}
+
+pub const ENEMY_DEFS: [EnemyDef; 10] = [
+    EnemyDef {
+        kind: EnemyKind::Fighter,
+        size: 40.0,
+        speed: 1.0,
+        score: 10,
+        tint: Color::new(1.0, 1.0, 1.0, 1.0),
+        spawn_weight: 10,
+        on_death: OnDeath::Nothing,
+        armor: None,
+        dive: None,
+        launcher: None,
+        shield: None,
+    },
+    EnemyDef {
+        kind: EnemyKind::Bomber,
+        size: 46.0,
+        speed: 0.8,
+        score: 20,
+        tint: Color::new(1.0, 0.5, 0.5, 1.0),
+        spawn_weight: 3,
+        on_death: OnDeath::RadialBullets { count: 8, speed: 3.0 },
+        armor: None,
+        dive: None,
+        launcher: None,
+        shield: None,
+    },
+    EnemyDef {
+        kind: EnemyKind::Splitter,
+        size: 50.0,
+        speed: 0.7,
+        score: 15,
+        tint: Color::new(0.6, 1.0, 0.6, 1.0),
+        spawn_weight: 3,
+        on_death: OnDeath::Split { child: EnemyKind::Shard, count: 2 },
+        armor: None,
+        dive: None,
+        launcher: None,
+        shield: None,
+    },
+    EnemyDef {
+        kind: EnemyKind::Shard,
+        size: 24.0,
+        speed: 1.4,
+        score: 5,
+        tint: Color::new(0.6, 1.0, 0.6, 1.0),
+        spawn_weight: 0,
+        on_death: OnDeath::Nothing,
+        armor: None,
+        dive: None,
+        launcher: None,
+        shield: None,
+    },
+    EnemyDef {
+        kind: EnemyKind::MineLayer,
+        size: 44.0,
+        speed: 0.9,
+        score: 20,
+        tint: Color::new(1.0, 0.8, 0.3, 1.0),
+        spawn_weight: 2,
+        on_death: OnDeath::HomingMines { count: 2 },
+        armor: None,
+        dive: None,
+        launcher: None,
+        shield: None,
+    },
+    EnemyDef {
+        kind: EnemyKind::Tank,
+        size: 72.0,
+        speed: 0.35,
+        score: 60,
+        tint: Color::new(0.6, 0.7, 0.9, 1.0),
+        spawn_weight: 1,
+        on_death: OnDeath::RadialBullets { count: 12, speed: 2.5 },
+        armor: Some(Armor { hits: 3, regen: 2.0, weak_point_radius: 0.16 }),
+        dive: None,
+        launcher: None,
+        shield: None,
+    },
+    EnemyDef {
+        kind: EnemyKind::Kamikaze,
+        size: 36.0,
+        speed: 1.1,
+        score: 25,
+        tint: Color::new(1.0, 0.45, 0.2, 1.0),
+        spawn_weight: 2,
+        on_death: OnDeath::Nothing,
+        armor: None,
+        dive: Some(Dive { trigger_y: 220.0, warning: 0.8, accel: 0.35, max_speed: 14.0 }),
+        launcher: None,
+        shield: None,
+    },
+    EnemyDef {
+        kind: EnemyKind::Carrier,
+        size: 96.0,
+        speed: 0.25,
+        score: 80,
+        tint: Color::new(0.75, 0.55, 0.95, 1.0),
+        spawn_weight: 1,
+        on_death: OnDeath::RadialBullets { count: 10, speed: 2.5 },
+        armor: None,
+        dive: None,
+        launcher: Some(Launcher { child: EnemyKind::Interceptor, interval: 2.5, count: 2 }),
+        shield: None,
+    },
+    EnemyDef {
+        kind: EnemyKind::Interceptor,
+        size: 22.0,
+        speed: 1.9,
+        score: 5,
+        tint: Color::new(0.85, 0.7, 1.0, 1.0),
+        spawn_weight: 0,
+        on_death: OnDeath::Nothing,
+        armor: None,
+        dive: None,
+        launcher: None,
+        shield: None,
+    },
+    EnemyDef {
+        kind: EnemyKind::Sentinel,
+        size: 44.0,
+        speed: 0.6,
+        score: 40,
+        tint: Color::new(0.4, 0.9, 0.85, 1.0),
+        spawn_weight: 2,
+        on_death: OnDeath::Nothing,
+        armor: None,
+        dive: None,
+        launcher: None,
+        shield: Some(Shield { hits: 4, radius: 0.85 }),
+    },
+];
+
+// 装甲敌人两侧弱点相对敌人中心的偏移（以敌人尺寸为单位）
+pub const WEAK_POINT_OFFSETS: [(f32, f32); 2] = [(-0.5, 0.0), (0.5, 0.0)];
+
+// 运行时使用的敌人定义表（内置定义 + mod 覆盖）
+pub struct EnemyTable {
+    defs: Vec<EnemyDef>,
+}
+
+impl Default for EnemyTable {
+    fn default() -> Self {
+        EnemyTable {
+            defs: ENEMY_DEFS.to_vec(),
+        }
+    }
+}
+
+impl EnemyTable {
+    pub fn def(&self, kind: EnemyKind) -> &EnemyDef {
+        self.defs
+            .iter()
+            .find(|def| def.kind == kind)
+            .expect("missing enemy definition")
+    }
+
+    // 按生成权重随机选择敌人类型
+    pub fn roll(&self, rng: &mut impl Rng) -> EnemyKind {
+        let total: u32 = self.defs.iter().map(|def| def.spawn_weight).sum();
+        if total == 0 {
+            return EnemyKind::Fighter;
+        }
+        let mut roll = rng.gen_range(0..total);
+        for def in &self.defs {
+            if roll < def.spawn_weight {
+                return def.kind;
+            }
+            roll -= def.spawn_weight;
+        }
+        EnemyKind::Fighter
+    }
+
+    // 应用覆盖文件；无法解析的行会被跳过并返回对应的错误描述
+    pub fn apply_overrides(&mut self, source: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Err(err) = self.apply_line(line) {
+                errors.push(format!("{}: {}", line, err));
+            }
+        }
+        errors
+    }
+
+    fn apply_line(&mut self, line: &str) -> Result<(), String> {
+        let (key, value) = line.split_once('=').ok_or("expected `<kind>.<field> = <value>`")?;
+        let (kind, field) = key.trim().split_once('.').ok_or("expected `<kind>.<field>`")?;
+        let kind = EnemyKind::from_id(kind.trim()).ok_or("unknown enemy kind")?;
+        let value = value.trim();
+        let def = self
+            .defs
+            .iter_mut()
+            .find(|def| def.kind == kind)
+            .expect("missing enemy definition");
+
+        let number = || value.parse::<f32>().map_err(|_| format!("invalid number '{}'", value));
+        match field.trim() {
+            "size" => def.size = number()?.max(1.0),
+            "speed" => def.speed = number()?.max(0.0),
+            "score" => def.score = value.parse().map_err(|_| format!("invalid score '{}'", value))?,
+            "spawn_weight" => {
+                def.spawn_weight = value.parse().map_err(|_| format!("invalid weight '{}'", value))?
+            }
+            "tint" => {
+                let channels = value
+                    .split(',')
+                    .map(|channel| channel.trim().parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| format!("invalid tint '{}'", value))?;
+                let [r, g, b] = channels[..] else {
+                    return Err("tint needs three channels".to_string());
+                };
+                def.tint = Color::new(r, g, b, 1.0);
+            }
+            other => return Err(format!("unknown field '{}'", other)),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+// error_screen.rs
+// 错误界面：启动或运行中出错（如缺少资源、音频设备不可用）时显示错误信息，可以重试或退出，
+// 而不是让错误直接从 main 返回、窗口一闪而过
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::ui::{UiEvent, Widget, WidgetList};
+use crate::{WindowSize, BASE_WINDOW_WIDTH};
+
+// 错误界面按键处理的结果
+pub enum ErrorEvent {
+    None,
+    Retry,
+    Quit,
+}
+
+pub struct ErrorScreen {
+    message: String,
+    widgets: WidgetList,
+}
+
+impl ErrorScreen {
+    pub fn new(message: String) -> Self {
+        ErrorScreen {
+            message,
+            widgets: WidgetList::new(vec![
+                Widget::button("retry", "Retry"),
+                Widget::button("quit", "Quit"),
+            ]),
+        }
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> ErrorEvent {
+        if key == KeyCode::Escape {
+            return ErrorEvent::Quit;
+        }
+        match self.widgets.key_down(key) {
+            Some(UiEvent::Activated("retry")) => ErrorEvent::Retry,
+            Some(UiEvent::Activated("quit")) => ErrorEvent::Quit,
+            _ => ErrorEvent::None,
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let left = BASE_WINDOW_WIDTH / 2.0 - 300.0;
+
+        canvas.draw(
+            &graphics::Text::new("SOMETHING WENT WRONG"),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 160.0)))
+                .color(Color::RED)
+                .scale(scale * 2.0),
+        );
+        let mut message = graphics::Text::new(self.message.as_str());
+        message.set_bounds(Vec2::new(600.0, f32::INFINITY));
+        canvas.draw(
+            &message,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 220.0)))
+                .color(Color::WHITE)
+                .scale(scale),
+        );
+
+        self.widgets.draw(canvas, window_size, Vec2::new(left, 400.0));
+        canvas.draw(
+            &graphics::Text::new("Up/Down: select   Enter: choose   Esc: quit"),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 480.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+}
@@ -0,0 +1,75 @@
+// events.rs
+// 关卡事件时间表：按波次和波内时间触发的环境事件，例如斜穿屏幕的流星雨
+#[derive(Clone, Copy, Debug)]
+pub enum LevelEvent {
+    MeteorShower { duration: f32 }, // 秒，不含预警时间
+}
+
+// first_wave 起每隔 every 波（0 表示只触发一次），在波内第 at 秒触发
+pub struct ScheduledEvent {
+    pub first_wave: u32,
+    pub every: u32,
+    pub at: f32,
+    pub event: LevelEvent,
+}
+
+pub const SCHEDULE: [ScheduledEvent; 2] = [
+    ScheduledEvent { first_wave: 3, every: 4, at: 8.0, event: LevelEvent::MeteorShower { duration: 6.0 } },
+    ScheduledEvent { first_wave: 6, every: 4, at: 18.0, event: LevelEvent::MeteorShower { duration: 8.0 } },
+];
+
+// 波内时间从 from 推进到 to（秒）时到期的事件
+pub fn due(wave: u32, from: f32, to: f32) -> impl Iterator<Item = LevelEvent> {
+    SCHEDULE
+        .iter()
+        .filter(move |entry| {
+            let in_wave = match entry.every {
+                0 => wave == entry.first_wave,
+                every => wave >= entry.first_wave && (wave - entry.first_wave).is_multiple_of(every),
+            };
+            in_wave && from < entry.at && entry.at <= to
+        })
+        .map(|entry| entry.event)
+}
+
+pub const SHOWER_WARNING: f32 = 2.0; // 流星落下前显示警告横幅的时间（秒）
+const METEOR_INTERVAL: f32 = 0.3;    // 流星生成间隔（秒）
+
+// 进行中的流星雨
+#[derive(Clone)]
+pub struct MeteorShower {
+    warning: f32,
+    remaining: f32,
+    spawn_timer: f32,
+    pub direction: f32, // 1 向右下方，-1 向左下方
+}
+
+impl MeteorShower {
+    pub fn new(duration: f32, direction: f32) -> Self {
+        MeteorShower { warning: SHOWER_WARNING, remaining: duration, spawn_timer: 0.0, direction }
+    }
+
+    pub fn is_announcing(&self) -> bool {
+        self.warning > 0.0
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.remaining <= 0.0
+    }
+
+    // 推进 dt 秒，返回这一步应生成的流星数量
+    pub fn update(&mut self, dt: f32) -> u32 {
+        if self.warning > 0.0 {
+            self.warning -= dt;
+            return 0;
+        }
+        self.remaining -= dt;
+        self.spawn_timer -= dt;
+        let mut count = 0;
+        while self.spawn_timer <= 0.0 {
+            self.spawn_timer += METEOR_INTERVAL;
+            count += 1;
+        }
+        count
+    }
+}
@@ -0,0 +1,237 @@
+// game_over.rs
+// 结算界面：一局彻底结束后显示本局总结，刷新最高分时先输入留名，再选择重来或回到主菜单
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::bus::{LogKind, RunLog, RunStats};
+use crate::highscores::{self, GameMode};
+use crate::profiles::{self, MAX_NAME_LEN};
+use crate::run_code::RunCode;
+use crate::ui::{UiEvent, Widget, WidgetList};
+use crate::{WindowSize, BASE_WINDOW_WIDTH};
+
+// 界面出现后短暂忽略按键，避免死亡时仍按着的射击键直接选中“重来”
+const INPUT_DELAY: f32 = 1.0;
+
+// 本局时间轴的尺寸
+const TIMELINE_WIDTH: f32 = 420.0;
+const TIMELINE_HEIGHT: f32 = 80.0;
+const TIMELINE_HIT_COLOR: Color = Color::new(1.0, 0.3, 0.3, 1.0);
+const TIMELINE_WAVE_COLOR: Color = Color::new(0.4, 0.4, 0.4, 1.0);
+
+// 本局总结
+pub struct RunSummary {
+    pub mode: GameMode,
+    pub practice: bool, // 练习局，不计纪录
+    pub score: i32,
+    pub wave: u32,
+    pub time: f32, // 秒
+    pub grazes: u32,
+    pub stats: RunStats,
+    pub log: RunLog, // 本局重要事件的时间记录，画成时间轴
+    pub credits: u32,    // 结算获得的局外点数
+    pub best_score: i32, // 结算后的最高分
+    pub new_record: bool,
+    pub code: RunCode, // 本局的种子与规则，用于分享重玩
+}
+
+// 结算界面按键处理的结果
+pub enum GameOverEvent {
+    None,
+    NameEntered(String), // 新纪录的留名
+    Retry,
+    MainMenu,
+}
+
+pub struct GameOverScreen {
+    summary: RunSummary,
+    entering: Option<String>, // 正在输入的纪录留名
+    widgets: WidgetList,
+    delay: f32,
+}
+
+impl GameOverScreen {
+    // name 为留名的默认值（当前档案名）
+    pub fn new(summary: RunSummary, name: &str) -> Self {
+        GameOverScreen {
+            entering: summary.new_record.then(|| name.to_string()),
+            summary,
+            widgets: WidgetList::new(vec![
+                Widget::button("retry", "Retry"),
+                Widget::button("menu", "Main Menu"),
+            ]),
+            delay: INPUT_DELAY,
+        }
+    }
+
+    // 正在输入留名时字母键不应触发其他操作
+    pub fn is_entering_name(&self) -> bool {
+        self.entering.is_some()
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.delay = (self.delay - dt).max(0.0);
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> GameOverEvent {
+        if self.delay > 0.0 {
+            return GameOverEvent::None;
+        }
+        if let Some(name) = &mut self.entering {
+            match key {
+                KeyCode::Back => {
+                    name.pop();
+                }
+                KeyCode::Return if profiles::is_valid_name(name) => {
+                    let name = name.trim().to_string();
+                    self.entering = None;
+                    return GameOverEvent::NameEntered(name);
+                }
+                // 跳过留名
+                KeyCode::Escape => self.entering = None,
+                _ => {}
+            }
+            return GameOverEvent::None;
+        }
+
+        match self.widgets.key_down(key) {
+            Some(UiEvent::Activated("retry")) => GameOverEvent::Retry,
+            Some(UiEvent::Activated("menu")) => GameOverEvent::MainMenu,
+            _ => GameOverEvent::None,
+        }
+    }
+
+    // 输入留名时接收字符
+    pub fn text_input(&mut self, character: char) {
+        if self.delay > 0.0 {
+            return;
+        }
+        if let Some(name) = &mut self.entering {
+            let mut candidate = name.clone();
+            candidate.push(character);
+            if candidate.chars().count() <= MAX_NAME_LEN && profiles::is_valid_name(&candidate) {
+                *name = candidate;
+            }
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize, alert: Color) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let left = BASE_WINDOW_WIDTH / 2.0 - 200.0;
+
+        // 半透明背景
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left - 20.0, 120.0)))
+                .scale(window_size.scale_vec2(Vec2::new(460.0, 490.0 + TIMELINE_HEIGHT)))
+                .color(Color::new(0.0, 0.0, 0.0, 0.8)),
+        );
+        canvas.draw(
+            &graphics::Text::new("GAME OVER"),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 140.0)))
+                .color(alert)
+                .scale(scale * 2.0),
+        );
+
+        let summary = &self.summary;
+        let mode = match summary.mode {
+            GameMode::Endless => "Endless",
+            GameMode::Daily => "Daily Challenge",
+            GameMode::Horde => "Horde",
+        };
+        let lines = [
+            format!("Mode:     {}{}", mode, if summary.practice { " (practice)" } else { "" }),
+            format!("Score:    {}", summary.score),
+            format!("Best:     {}", summary.best_score),
+            format!("Wave:     {}", summary.wave),
+            format!("Time:     {}", highscores::format_time(summary.time)),
+            format!("Grazes:   {}", summary.grazes),
+            format!("Kills:    {}   Pickups: {}   Hits: {}", summary.stats.kills, summary.stats.pickups, summary.stats.hits),
+            format!("Credits:  +{}", summary.credits),
+            format!("Seed:     {}", summary.code.seed),
+            format!("Code:     {}", summary.code.encode()),
+        ];
+        for (idx, line) in lines.iter().enumerate() {
+            canvas.draw(
+                &graphics::Text::new(line.as_str()),
+                DrawParam::default()
+                    .dest(window_size.scale_vec2(Vec2::new(left, 200.0 + idx as f32 * 26.0)))
+                    .color(Color::WHITE)
+                    .scale(scale),
+            );
+        }
+
+        let timeline = 200.0 + lines.len() as f32 * 26.0 + 4.0;
+        self.draw_timeline(canvas, window_size, Vec2::new(left, timeline));
+
+        let bottom = timeline + TIMELINE_HEIGHT + 16.0;
+        let hint = match &self.entering {
+            Some(name) => {
+                canvas.draw(
+                    &graphics::Text::new(format!("NEW HIGH SCORE!\nName: {}_", name)),
+                    DrawParam::default()
+                        .dest(window_size.scale_vec2(Vec2::new(left, bottom)))
+                        .color(Color::YELLOW)
+                        .scale(scale * 1.2),
+                );
+                "Type a name   Enter: save   Esc: skip"
+            }
+            None => {
+                self.widgets.draw(canvas, window_size, Vec2::new(left, bottom));
+                "Up/Down: select   Enter: choose"
+            }
+        };
+        canvas.draw(
+            &graphics::Text::new(hint),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, bottom + 90.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+
+    // 本局时间轴：灰色竖线为清波，上方彩色方块为拾取的道具，下方红色为中弹，黄色长条为击毁 Boss
+    fn draw_timeline(&self, canvas: &mut Canvas, window_size: &WindowSize, origin: Vec2) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let summary = &self.summary;
+        let log = &summary.log;
+        canvas.draw(
+            &graphics::Text::new(format!(
+                "Timeline  Power-ups: {}  Hits: {}  Bosses: {}",
+                log.count(|kind| matches!(kind, LogKind::PowerUp(_))),
+                log.count(|kind| *kind == LogKind::Hit),
+                log.count(|kind| *kind == LogKind::BossKilled),
+            )),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(origin))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+
+        let axis = origin.y + 48.0;
+        let mut rect = |pos: Vec2, size: Vec2, color: Color| {
+            canvas.draw(
+                &graphics::Quad,
+                DrawParam::default()
+                    .dest(window_size.scale_vec2(pos))
+                    .scale(window_size.scale_vec2(size))
+                    .color(color),
+            );
+        };
+        rect(Vec2::new(origin.x, axis), Vec2::new(TIMELINE_WIDTH, 2.0), Color::WHITE);
+
+        let duration = summary.time.max(1.0);
+        for entry in log.entries() {
+            let x = origin.x + (entry.time / duration).clamp(0.0, 1.0) * (TIMELINE_WIDTH - 4.0);
+            match entry.kind {
+                LogKind::WaveCleared(_) => rect(Vec2::new(x, axis - 8.0), Vec2::new(1.0, 18.0), TIMELINE_WAVE_COLOR),
+                LogKind::PowerUp(color) => rect(Vec2::new(x - 2.0, axis - 16.0), Vec2::new(5.0, 5.0), color),
+                LogKind::Hit => rect(Vec2::new(x - 1.0, axis + 6.0), Vec2::new(3.0, 10.0), TIMELINE_HIT_COLOR),
+                LogKind::BossKilled => rect(Vec2::new(x - 2.0, axis - 24.0), Vec2::new(4.0, 48.0), Color::YELLOW),
+            }
+        }
+    }
+}
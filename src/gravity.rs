@@ -0,0 +1,74 @@
+// gravity.rs
+// 引力井：副武器之一。放出后持续把牵引范围内的敌人和敌方子弹拉向中心，
+// 到时后引爆，炸毁爆炸范围内的普通敌人并清除其中的敌方子弹
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Mesh};
+use ggez::GameResult;
+use glam::Vec2;
+
+use crate::WindowSize;
+
+pub const DURATION: f32 = 3.0;       // 引爆前的牵引时间（秒）
+pub const PULL_RADIUS: f32 = 260.0;  // 牵引范围（基准坐标）
+pub const BLAST_RADIUS: f32 = 110.0; // 引爆时的爆炸半径
+const PULL_SPEED: f32 = 3.0;         // 牵引范围边缘每帧被拉动的距离，越靠近中心越快
+const CORE_COLOR: Color = Color::new(0.55, 0.3, 1.0, 1.0);
+
+#[derive(Clone)]
+pub struct GravityWell {
+    center: Vec2,
+    age: f32,
+}
+
+impl GravityWell {
+    pub fn new(center: Vec2) -> Self {
+        GravityWell { center, age: 0.0 }
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.center
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.age += dt;
+    }
+
+    // 牵引时间结束，应当引爆
+    pub fn is_ready(&self) -> bool {
+        self.age >= DURATION
+    }
+
+    // pos 处的对象本帧被拉动的位移；不会越过中心
+    pub fn pull(&self, pos: Vec2, frame_scale: f32) -> Vec2 {
+        let offset = self.center - pos;
+        let distance = offset.length();
+        if distance >= PULL_RADIUS || distance <= f32::EPSILON {
+            return Vec2::ZERO;
+        }
+        let step = PULL_SPEED * (2.0 - distance / PULL_RADIUS) * frame_scale;
+        offset / distance * step.min(distance)
+    }
+
+    pub fn draw(&self, ctx: &mut ggez::Context, canvas: &mut Canvas, window_size: &WindowSize) -> GameResult {
+        let scale = window_size.scale_x.min(window_size.scale_y);
+        let center = window_size.scale_vec2(self.center);
+        let t = (self.age / DURATION).min(1.0);
+
+        // 牵引范围的淡色边界
+        let edge = Mesh::new_circle(ctx, DrawMode::stroke(1.5), center, PULL_RADIUS * scale, 1.0, Color { a: 0.25, ..CORE_COLOR })?;
+        canvas.draw(&edge, DrawParam::default());
+
+        // 三道错开相位的圆环不断向中心收缩，表现被吸入的效果
+        for i in 0..3 {
+            let phase = (self.age * 1.2 + i as f32 / 3.0).fract();
+            let radius = (PULL_RADIUS * (1.0 - phase)).max(1.0) * scale;
+            let ring = Mesh::new_circle(ctx, DrawMode::stroke(2.0), center, radius, 1.0, Color { a: phase * 0.6, ..CORE_COLOR })?;
+            canvas.draw(&ring, DrawParam::default());
+        }
+
+        // 核心随着引爆临近变大变亮
+        let core_color = Color::new(0.55 + 0.45 * t, 0.3 + 0.5 * t, 1.0, 0.9);
+        let core = Mesh::new_circle(ctx, DrawMode::fill(), center, (10.0 + 16.0 * t) * scale, 0.5, core_color)?;
+        canvas.draw(&core, DrawParam::default());
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+// hangar.rs
+// 机库界面：用局外点数解锁并装备飞船、初始武器和尾迹，从下一局开始生效
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::progression::{Progress, Unlock};
+use crate::{WindowSize, BASE_WINDOW_WIDTH};
+
+const ROW_HEIGHT: f32 = 26.0;
+
+// 机库界面按键处理的结果
+pub enum HangarEvent {
+    None,
+    Changed, // 购买或装备了项目，需要保存
+    Close,
+}
+
+pub struct Hangar {
+    items: Vec<Unlock>,
+    selected: usize,
+    message: Option<String>,
+}
+
+impl Hangar {
+    pub fn new() -> Self {
+        Hangar { items: Unlock::all(), selected: 0, message: None }
+    }
+
+    pub fn key_down(&mut self, key: KeyCode, progress: &mut Progress) -> HangarEvent {
+        let len = self.items.len();
+        match key {
+            KeyCode::Up | KeyCode::W => self.selected = (self.selected + len - 1) % len,
+            KeyCode::Down | KeyCode::S => self.selected = (self.selected + 1) % len,
+            // 未解锁时购买并装备，已解锁时直接装备
+            KeyCode::Return | KeyCode::Space => {
+                let item = self.items[self.selected];
+                if !progress.is_unlocked(item) {
+                    if !progress.buy(item) {
+                        self.message = Some(format!("Need {} credits", item.cost()));
+                        return HangarEvent::None;
+                    }
+                    self.message = Some(format!("Unlocked {}", item.name()));
+                }
+                progress.equip(item);
+                return HangarEvent::Changed;
+            }
+            KeyCode::Escape | KeyCode::F3 => return HangarEvent::Close,
+            _ => {}
+        }
+        self.message = None;
+        HangarEvent::None
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize, progress: &Progress) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let left = BASE_WINDOW_WIDTH / 2.0 - 200.0;
+
+        // 半透明背景
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left - 20.0, 100.0)))
+                .scale(window_size.scale_vec2(Vec2::new(460.0, 520.0)))
+                .color(Color::new(0.0, 0.0, 0.0, 0.85)),
+        );
+        canvas.draw(
+            &graphics::Text::new(format!("HANGAR   Credits: {}", progress.credits)),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 120.0)))
+                .color(Color::YELLOW)
+                .scale(scale * 1.5),
+        );
+
+        for (idx, item) in self.items.iter().enumerate() {
+            let is_selected = idx == self.selected;
+            let status = if progress.is_equipped(*item) {
+                "Equipped".to_string()
+            } else if progress.is_unlocked(*item) {
+                "Owned".to_string()
+            } else {
+                format!("{} cr", item.cost())
+            };
+            let color = if is_selected {
+                Color::YELLOW
+            } else if progress.is_unlocked(*item) {
+                Color::WHITE
+            } else {
+                Color::new(0.6, 0.6, 0.6, 1.0)
+            };
+            let row = Vec2::new(left, 170.0 + idx as f32 * ROW_HEIGHT);
+            canvas.draw(
+                &graphics::Text::new(format!("{} {}", if is_selected { ">" } else { " " }, item.name())),
+                DrawParam::default().dest(window_size.scale_vec2(row)).color(color).scale(scale),
+            );
+            canvas.draw(
+                &graphics::Text::new(status),
+                DrawParam::default()
+                    .dest(window_size.scale_vec2(row + Vec2::new(300.0, 0.0)))
+                    .color(color)
+                    .scale(scale),
+            );
+        }
+
+        let mut hint = "Up/Down: select   Enter: buy / equip\nApplies from the next run   Esc: close".to_string();
+        if let Some(message) = &self.message {
+            hint = format!("{}\n{}", message, hint);
+        }
+        canvas.draw(
+            &graphics::Text::new(hint),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 170.0 + self.items.len() as f32 * ROW_HEIGHT + 16.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+}
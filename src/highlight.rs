@@ -0,0 +1,175 @@
+// highlight.rs
+// 精彩片段录制：游戏中持续把缩小后的画面存入环形缓冲，只保留最近几秒；
+// 按下保存键后把缓冲中的帧交给后台线程编码成 GIF，编码不会卡住游戏
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ggez::graphics::{self, Canvas, Color, DrawParam, ImageFormat};
+use ggez::GameResult;
+use glam::Vec2;
+
+use crate::{WindowSize, BASE_WINDOW_WIDTH};
+
+pub const HIGHLIGHTS_DIR: &str = "highlights";
+const CLIP_SECONDS: f32 = 5.0;     // 保存最近几秒
+const CAPTURE_FPS: f32 = 15.0;     // 录制帧率
+const CLIP_WIDTH: u32 = 320;       // 缩小后的宽度，高度按窗口比例
+const ENCODE_SPEED: i32 = 10;      // GIF 调色板量化速度（1~30，越大越快、颜色越粗糙）
+const NOTICE_DURATION: f32 = 3.0;  // 保存结果提示的显示时间（秒）
+
+// 缩小后的一帧（RGBA）
+#[derive(Clone)]
+struct ClipFrame {
+    width: u16,
+    height: u16,
+    pixels: Vec<u8>,
+}
+
+pub struct HighlightRecorder {
+    frames: VecDeque<ClipFrame>,
+    capture_timer: f32,
+    pending: usize, // 后台正在编码的片段数
+    results: Receiver<Result<PathBuf, String>>,
+    sender: Sender<Result<PathBuf, String>>,
+    notice: Option<(String, f32)>, // 提示文字和剩余时间
+}
+
+impl Default for HighlightRecorder {
+    fn default() -> Self {
+        let (sender, results) = mpsc::channel();
+        HighlightRecorder {
+            frames: VecDeque::new(),
+            capture_timer: 0.0,
+            pending: 0,
+            results,
+            sender,
+            notice: None,
+        }
+    }
+}
+
+impl HighlightRecorder {
+    // 丢弃缓冲中的帧（新开一局时）
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.capture_timer = 0.0;
+    }
+
+    // 按录制帧率读取上一帧画面；recording 为 false 时只更新提示
+    // 此时窗口画面已在上一帧末尾提交，读到的是完整的一帧
+    pub fn update(&mut self, ctx: &mut ggez::Context, dt: f32, recording: bool) -> GameResult {
+        while let Ok(result) = self.results.try_recv() {
+            self.pending -= 1;
+            let text = match result {
+                Ok(path) => format!("Highlight saved: {}", path.display()),
+                Err(err) => format!("Highlight failed: {}", err),
+            };
+            self.notice = Some((text, NOTICE_DURATION));
+        }
+        if let Some((_, remaining)) = &mut self.notice {
+            *remaining -= dt;
+            if *remaining <= 0.0 {
+                self.notice = None;
+            }
+        }
+
+        if !recording {
+            return Ok(());
+        }
+        self.capture_timer -= dt;
+        if self.capture_timer > 0.0 {
+            return Ok(());
+        }
+        self.capture_timer += 1.0 / CAPTURE_FPS;
+        self.capture_timer = self.capture_timer.max(0.0);
+
+        let frame = ctx.gfx.frame();
+        let bgra = matches!(frame.format(), ImageFormat::Bgra8Unorm | ImageFormat::Bgra8UnormSrgb);
+        let (width, height) = (frame.width(), frame.height());
+        let pixels = frame.to_pixels(&ctx.gfx)?;
+        self.frames.push_back(downscale(&pixels, width, height, bgra));
+        let capacity = (CLIP_SECONDS * CAPTURE_FPS) as usize;
+        while self.frames.len() > capacity {
+            self.frames.pop_front();
+        }
+        Ok(())
+    }
+
+    // 把缓冲中的帧保存为 dir 下的 GIF；缓冲为空时返回 false
+    pub fn export(&mut self, dir: &Path) -> bool {
+        if self.frames.is_empty() {
+            return false;
+        }
+        let frames: Vec<ClipFrame> = self.frames.iter().cloned().collect();
+        let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+        let path = dir.join(format!("highlight_{}.gif", stamp));
+        let sender = self.sender.clone();
+        self.pending += 1;
+        self.notice = None;
+        thread::spawn(move || {
+            let result = encode_gif(&path, frames).map(|()| path).map_err(|err| err.to_string());
+            let _ = sender.send(result);
+        });
+        true
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize) {
+        let text = match &self.notice {
+            Some((text, _)) => text.as_str(),
+            None if self.pending > 0 => "Saving highlight...",
+            None => return,
+        };
+        canvas.draw(
+            &graphics::Text::new(text),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH / 2.0 - 200.0, 60.0)))
+                .color(Color::new(1.0, 1.0, 1.0, 0.85))
+                .scale(Vec2::new(window_size.scale_x, window_size.scale_y)),
+        );
+    }
+}
+
+// 最近邻缩小到 CLIP_WIDTH 宽，并统一成 RGBA
+fn downscale(pixels: &[u8], width: u32, height: u32, bgra: bool) -> ClipFrame {
+    let out_width = CLIP_WIDTH.min(width).max(1);
+    let out_height = (height * out_width / width.max(1)).max(1);
+    let mut out = Vec::with_capacity((out_width * out_height * 4) as usize);
+    for y in 0..out_height {
+        let src_y = y * height / out_height;
+        for x in 0..out_width {
+            let src_x = x * width / out_width;
+            let index = ((src_y * width + src_x) * 4) as usize;
+            let pixel = &pixels[index..index + 4];
+            if bgra {
+                out.extend_from_slice(&[pixel[2], pixel[1], pixel[0], 255]);
+            } else {
+                out.extend_from_slice(&[pixel[0], pixel[1], pixel[2], 255]);
+            }
+        }
+    }
+    ClipFrame { width: out_width as u16, height: out_height as u16, pixels: out }
+}
+
+fn encode_gif(path: &Path, frames: Vec<ClipFrame>) -> Result<(), gif::EncodingError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let (width, height) = (frames[0].width, frames[0].height);
+    let mut encoder = gif::Encoder::new(File::create(path)?, width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    let delay = (100.0 / CAPTURE_FPS).round() as u16; // 单位为 1/100 秒
+    for mut frame in frames {
+        // 录制中途改变窗口大小的帧尺寸不同，直接跳过
+        if (frame.width, frame.height) != (width, height) {
+            continue;
+        }
+        let mut gif_frame = gif::Frame::from_rgba_speed(width, height, &mut frame.pixels, ENCODE_SPEED);
+        gif_frame.delay = delay;
+        encoder.write_frame(&gif_frame)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+// highscores.rs
+// 各模式的最高分与最长生存时间记录
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::mutators::Mutators;
+
+const HIGHSCORE_FILE: &str = "highscores.txt";
+
+// 游戏模式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GameMode {
+    Endless,
+    Daily,
+    Horde, // 生存模式：守住路障
+}
+
+impl GameMode {
+    pub fn id(self) -> &'static str {
+        match self {
+            GameMode::Endless => "endless",
+            GameMode::Daily => "daily",
+            GameMode::Horde => "horde",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<GameMode> {
+        match id {
+            "endless" => Some(GameMode::Endless),
+            "daily" => Some(GameMode::Daily),
+            "horde" => Some(GameMode::Horde),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Endless => "Endless",
+            GameMode::Daily => "Daily Challenge",
+            GameMode::Horde => "Horde",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Record {
+    pub best_score: i32,
+    pub best_time: f32, // 秒
+    pub mutators: Mutators, // 创下最高分时启用的挑战规则
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct HighScores {
+    records: HashMap<GameMode, Record>,
+    holders: HashMap<GameMode, String>, // 最高分的留名
+}
+
+impl HighScores {
+    fn path(dir: &Path) -> PathBuf {
+        dir.join(HIGHSCORE_FILE)
+    }
+
+    // 每行格式：mode = score, seconds, mutators[, name]
+    pub fn load(dir: &Path) -> Self {
+        let mut scores = HighScores::default();
+        let Ok(text) = fs::read_to_string(Self::path(dir)) else {
+            return scores;
+        };
+
+        for line in text.lines() {
+            let Some((mode, value)) = line.split_once('=') else {
+                continue;
+            };
+            let Some(mode) = GameMode::from_id(mode.trim()) else {
+                continue;
+            };
+            let mut fields = value.split(',').map(str::trim);
+            let best_score = fields.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+            let best_time = fields.next().and_then(|v| v.parse().ok()).unwrap_or(0.0);
+            let mutators = fields.next().map(Mutators::parse).unwrap_or_default();
+            if let Some(name) = fields.next().filter(|name| !name.is_empty()) {
+                scores.holders.insert(mode, name.to_string());
+            }
+            scores.records.insert(mode, Record { best_score, best_time, mutators });
+        }
+
+        scores
+    }
+
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        let mut text = String::new();
+        for (mode, record) in &self.records {
+            text.push_str(&format!(
+                "{} = {}, {:.2}, {}",
+                mode.id(),
+                record.best_score,
+                record.best_time,
+                record.mutators.to_ids()
+            ));
+            if let Some(name) = self.holders.get(mode) {
+                text.push_str(&format!(", {}", name));
+            }
+            text.push('\n');
+        }
+        fs::create_dir_all(dir)?;
+        fs::write(Self::path(dir), text)
+    }
+
+    pub fn get(&self, mode: GameMode) -> Record {
+        self.records.get(&mode).copied().unwrap_or_default()
+    }
+
+    // 记录一局成绩，返回是否刷新了任一纪录
+    pub fn record(&mut self, mode: GameMode, score: i32, time: f32, mutators: Mutators) -> bool {
+        let record = self.records.entry(mode).or_default();
+        let mut improved = false;
+        if score > record.best_score {
+            record.best_score = score;
+            record.mutators = mutators;
+            self.holders.remove(&mode);
+            improved = true;
+        }
+        if time > record.best_time {
+            record.best_time = time;
+            improved = true;
+        }
+        improved
+    }
+
+    pub fn holder(&self, mode: GameMode) -> Option<&str> {
+        self.holders.get(&mode).map(String::as_str)
+    }
+
+    // 名字不能包含逗号（见 profiles::is_valid_name）
+    pub fn set_holder(&mut self, mode: GameMode, name: &str) {
+        self.holders.insert(mode, name.to_string());
+    }
+}
+
+// 以 mm:ss 格式显示时间
+pub fn format_time(seconds: f32) -> String {
+    let total = seconds.max(0.0) as u32;
+    format!("{:02}:{:02}", total / 60, total % 60)
+}
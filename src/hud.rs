@@ -0,0 +1,230 @@
+// hud.rs
+// HUD 辅助绘制：屏幕边缘的来袭威胁指示箭头、角落的雷达、滚动增长的分数、弹药图标行和武器等级刻度
+use ggez::graphics::{self, Canvas, Color, DrawParam, Image, Mesh, MeshBuilder, Rect};
+use ggez::GameResult;
+use glam::Vec2;
+
+use crate::text::TextCache;
+use crate::WindowSize;
+
+const INDICATOR_RANGE: f32 = 160.0; // 距屏幕多远开始显示（基准坐标）
+const INDICATOR_MARGIN: f32 = 14.0; // 箭头离屏幕边缘的距离
+const INDICATOR_SIZE: f32 = 10.0;
+
+const RADAR_SIZE: Vec2 = Vec2::new(150.0, 130.0); // 雷达面板大小（基准坐标）
+const RADAR_RANGE: f32 = 240.0;                   // 雷达覆盖屏幕外多远
+const RADAR_PADDING: f32 = 10.0;                  // 面板离屏幕角落的距离
+
+const SCORE_ROLL_RATE: f32 = 12.0;  // 显示分数每秒追上剩余差值的比例（指数缓动）
+const SCORE_ROLL_MIN: f32 = 60.0;   // 显示分数每秒至少增加的量，小额加分也能很快到位
+const SCORE_POP_POINTS: i32 = 500;  // 一次加分达到这个数时分数弹跳
+const SCORE_POP_DURATION: f32 = 0.25;
+const SCORE_POP_SCALE: f32 = 0.35;  // 弹跳时最大放大的比例
+
+const ICON_ROW_MAX: u32 = 8;        // 图标行最多画几个，超出的数量以文字补在后面
+const ICON_SPACING: f32 = 4.0;      // 图标之间的间距（基准坐标）
+const PIP_SIZE: Vec2 = Vec2::new(14.0, 8.0);
+const PIP_SPACING: f32 = 4.0;
+
+// 边缘指示箭头：位置、朝向（弧度，0 指向右）和透明度
+pub struct Indicator {
+    pub pos: Vec2,
+    pub angle: f32,
+    pub alpha: f32,
+}
+
+// 屏幕外且在指示范围内的对象返回指示箭头，越接近屏幕越不透明
+pub fn threat_indicator(pos: Vec2, screen: Vec2) -> Option<Indicator> {
+    let inside = pos.clamp(Vec2::ZERO, screen);
+    let distance = pos.distance(inside);
+    if distance <= 0.0 || distance > INDICATOR_RANGE {
+        return None;
+    }
+    let direction = (pos - inside) / distance;
+    Some(Indicator {
+        pos: pos.clamp(Vec2::splat(INDICATOR_MARGIN), screen - INDICATOR_MARGIN),
+        angle: direction.y.atan2(direction.x),
+        alpha: 1.0 - distance / INDICATOR_RANGE,
+    })
+}
+
+impl Indicator {
+    // scale 为基准坐标到屏幕坐标的缩放
+    pub fn draw(&self, ctx: &mut ggez::Context, canvas: &mut Canvas, scale: Vec2, color: Color) -> GameResult {
+        let points = [
+            Vec2::new(INDICATOR_SIZE, 0.0),
+            Vec2::new(-INDICATOR_SIZE * 0.6, -INDICATOR_SIZE * 0.7),
+            Vec2::new(-INDICATOR_SIZE * 0.6, INDICATOR_SIZE * 0.7),
+        ];
+        let arrow = Mesh::new_polygon(
+            ctx,
+            graphics::DrawMode::fill(),
+            &points,
+            Color::new(color.r, color.g, color.b, color.a * self.alpha),
+        )?;
+        canvas.draw(
+            &arrow,
+            DrawParam::default()
+                .dest(self.pos * scale)
+                .rotation(self.angle)
+                .scale(Vec2::splat(scale.x.min(scale.y))),
+        );
+        Ok(())
+    }
+}
+
+// 雷达上的一个点
+pub struct Blip {
+    pub pos: Vec2, // 基准坐标
+    pub color: Color,
+    pub radius: f32, // 雷达上的半径
+}
+
+// 在屏幕右下角绘制雷达：覆盖可见区域及其外围 RADAR_RANGE，超出范围的点贴在边缘
+pub fn draw_radar(
+    ctx: &mut ggez::Context,
+    canvas: &mut Canvas,
+    blips: impl IntoIterator<Item = Blip>,
+    screen: Vec2,
+    scale: Vec2,
+) -> GameResult {
+    let origin = screen - RADAR_SIZE - Vec2::splat(RADAR_PADDING);
+    let world_min = Vec2::splat(-RADAR_RANGE);
+    let world_size = screen + Vec2::splat(RADAR_RANGE * 2.0);
+    let to_radar = |pos: Vec2| {
+        let t = ((pos - world_min) / world_size).clamp(Vec2::ZERO, Vec2::ONE);
+        (origin + t * RADAR_SIZE) * scale
+    };
+    let rect = |min: Vec2, max: Vec2| Rect::new(min.x, min.y, max.x - min.x, max.y - min.y);
+
+    let mut builder = MeshBuilder::new();
+    let panel = rect(origin * scale, (origin + RADAR_SIZE) * scale);
+    builder.rectangle(graphics::DrawMode::fill(), panel, Color::new(0.0, 0.1, 0.05, 0.6))?;
+    builder.rectangle(graphics::DrawMode::stroke(1.0), panel, Color::new(0.3, 1.0, 0.5, 0.6))?;
+    // 可见区域的轮廓
+    let field = rect(to_radar(Vec2::ZERO), to_radar(screen));
+    builder.rectangle(graphics::DrawMode::stroke(1.0), field, Color::new(0.3, 1.0, 0.5, 0.3))?;
+
+    let point_scale = scale.x.min(scale.y);
+    for blip in blips {
+        builder.circle(graphics::DrawMode::fill(), to_radar(blip.pos), blip.radius * point_scale, 0.2, blip.color)?;
+    }
+
+    let mesh = Mesh::from_data(ctx, builder.build());
+    canvas.draw(&mesh, DrawParam::default());
+    Ok(())
+}
+
+// 分数显示：显示值快速滚动追上实际分数，一次加分较多时文字短暂放大
+#[derive(Default)]
+pub struct ScoreCounter {
+    target: i32,
+    shown: f32,
+    pop: f32, // 弹跳剩余时间
+}
+
+impl ScoreCounter {
+    // 新开一局或续关扣分时直接显示实际分数，不做动画
+    pub fn snap(&mut self, score: i32) {
+        self.target = score;
+        self.shown = score as f32;
+        self.pop = 0.0;
+    }
+
+    pub fn update(&mut self, score: i32, dt: f32) {
+        if score < self.target {
+            self.snap(score);
+            return;
+        }
+        if score - self.target >= SCORE_POP_POINTS {
+            self.pop = SCORE_POP_DURATION;
+        }
+        self.target = score;
+        self.pop = (self.pop - dt).max(0.0);
+
+        let remaining = self.target as f32 - self.shown;
+        let step = (remaining * SCORE_ROLL_RATE * dt).max(SCORE_ROLL_MIN * dt);
+        self.shown = (self.shown + step).min(self.target as f32);
+    }
+
+    pub fn shown(&self) -> i32 {
+        self.shown as i32
+    }
+
+    // 以 pos（基准坐标）为左上角绘制；弹跳时先放大再缓缓回落
+    pub fn draw(&self, canvas: &mut Canvas, text: &mut TextCache, window_size: &WindowSize, pos: Vec2, color: Color) {
+        let t = self.pop / SCORE_POP_DURATION;
+        let scale = 1.0 + SCORE_POP_SCALE * t * t;
+        canvas.draw(
+            text.get("score", format!("Score: {}", self.shown()), window_size),
+            DrawParam::default().dest(window_size.scale_vec2(pos)).scale(Vec2::splat(scale)).color(color),
+        );
+    }
+}
+
+// 用一排图标表示的数量（导弹、炸弹），图标取自预载的贴图，size 为单个图标的基准尺寸
+pub struct IconRow {
+    pub image: Image,
+    pub size: Vec2,
+    pub color: Color,
+}
+
+impl IconRow {
+    // 从 pos（基准坐标）开始向右画 count 个图标，超过 ICON_ROW_MAX 时在后面写上多出的数量；
+    // 数量为 0 时画一个暗淡的空位，提醒这一栏已经用完
+    pub fn draw(
+        &self,
+        canvas: &mut Canvas,
+        text: &mut TextCache,
+        slot: &'static str,
+        window_size: &WindowSize,
+        pos: Vec2,
+        count: u32,
+    ) {
+        let scale = window_size.scale_vec2(self.size) / Vec2::new(self.image.width() as f32, self.image.height() as f32);
+        let icon = |index: u32| {
+            let offset = Vec2::new(index as f32 * (self.size.x + ICON_SPACING), 0.0);
+            DrawParam::default().dest(window_size.scale_vec2(pos + offset)).scale(scale)
+        };
+        if count == 0 {
+            canvas.draw(&self.image, icon(0).color(Color { a: 0.2, ..self.color }));
+            return;
+        }
+        for index in 0..count.min(ICON_ROW_MAX) {
+            canvas.draw(&self.image, icon(index).color(self.color));
+        }
+        if count > ICON_ROW_MAX {
+            let after = pos + Vec2::new(ICON_ROW_MAX as f32 * (self.size.x + ICON_SPACING), 0.0);
+            canvas.draw(
+                text.get(slot, format!("+{}", count - ICON_ROW_MAX), window_size),
+                DrawParam::default().dest(window_size.scale_vec2(after)).color(self.color),
+            );
+        }
+    }
+}
+
+// 武器等级刻度：从 pos（基准坐标）向右画 total 格，前 filled 格实心
+pub fn draw_pips(
+    ctx: &mut ggez::Context,
+    canvas: &mut Canvas,
+    window_size: &WindowSize,
+    pos: Vec2,
+    filled: u32,
+    total: u32,
+    color: Color,
+) -> GameResult {
+    let mut builder = MeshBuilder::new();
+    for index in 0..total {
+        let corner = window_size.scale_vec2(pos + Vec2::new(index as f32 * (PIP_SIZE.x + PIP_SPACING), 0.0));
+        let size = window_size.scale_vec2(PIP_SIZE);
+        let rect = Rect::new(corner.x, corner.y, size.x, size.y);
+        if index < filled {
+            builder.rectangle(graphics::DrawMode::fill(), rect, color)?;
+        } else {
+            builder.rectangle(graphics::DrawMode::stroke(1.0), rect, Color { a: 0.5, ..color })?;
+        }
+    }
+    let mesh = Mesh::from_data(ctx, builder.build());
+    canvas.draw(&mesh, DrawParam::default());
+    Ok(())
+}
@@ -0,0 +1,342 @@
+// input.rs
+// 输入映射：游戏动作 → 按键集合，替代散落各处的 KeyCode 判断
+use std::collections::HashMap;
+
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+// 游戏动作
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Fire,
+    Missile,
+    Bomb,
+    Pause,
+    Continue,
+    Mute,
+    Skip,  // 跳过剧情台词
+    Focus, // 按住时减速并显示判定点
+    Highlight, // 把最近几秒的画面保存为 GIF
+    Preview,   // 练习模式：冻结画面并预览敌弹轨迹
+}
+
+impl Action {
+    pub const ALL: [Action; 14] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Fire,
+        Action::Missile,
+        Action::Bomb,
+        Action::Pause,
+        Action::Continue,
+        Action::Mute,
+        Action::Skip,
+        Action::Focus,
+        Action::Highlight,
+        Action::Preview,
+    ];
+
+    // 配置文件中使用的名称
+    pub fn id(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::Fire => "fire",
+            Action::Missile => "missile",
+            Action::Bomb => "bomb",
+            Action::Pause => "pause",
+            Action::Continue => "continue",
+            Action::Mute => "mute",
+            Action::Skip => "skip",
+            Action::Focus => "focus",
+            Action::Highlight => "highlight",
+            Action::Preview => "preview",
+        }
+    }
+
+    // 设置界面显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::Fire => "Fire",
+            Action::Missile => "Missile",
+            Action::Bomb => "Bomb",
+            Action::Pause => "Pause",
+            Action::Continue => "Continue",
+            Action::Mute => "Mute",
+            Action::Skip => "Skip Line",
+            Action::Focus => "Focus",
+            Action::Highlight => "Save Highlight",
+            Action::Preview => "Bullet Preview",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|action| action.id() == id)
+    }
+
+    fn default_keys(self) -> Vec<KeyCode> {
+        match self {
+            Action::MoveLeft => vec![KeyCode::Left, KeyCode::A],
+            Action::MoveRight => vec![KeyCode::Right, KeyCode::D],
+            Action::MoveUp => vec![KeyCode::Up, KeyCode::W],
+            Action::MoveDown => vec![KeyCode::Down, KeyCode::S],
+            Action::Fire => vec![KeyCode::Space],
+            Action::Missile => vec![KeyCode::X],
+            Action::Bomb => vec![KeyCode::Z],
+            Action::Pause => vec![KeyCode::P],
+            Action::Continue => vec![KeyCode::C],
+            Action::Mute => vec![KeyCode::M],
+            Action::Skip => vec![KeyCode::Tab],
+            Action::Focus => vec![KeyCode::LShift, KeyCode::RShift],
+            Action::Highlight => vec![KeyCode::F12],
+            Action::Preview => vec![KeyCode::V],
+        }
+    }
+}
+
+// 按键预设方案：双手方案只决定移动键；单手方案把射击、导弹、炸弹和专注也放到移动键旁边，
+// 选用后仍可在按键设置里逐个改绑
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlScheme {
+    ArrowsAndWasd,
+    Arrows,
+    Wasd,
+    LeftHand,  // 只用左手：WASD 移动
+    RightHand, // 只用右手：方向键移动
+}
+
+impl ControlScheme {
+    pub const ALL: [ControlScheme; 5] = [
+        ControlScheme::ArrowsAndWasd,
+        ControlScheme::Arrows,
+        ControlScheme::Wasd,
+        ControlScheme::LeftHand,
+        ControlScheme::RightHand,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ControlScheme::ArrowsAndWasd => "Arrows + WASD",
+            ControlScheme::Arrows => "Arrows",
+            ControlScheme::Wasd => "WASD",
+            ControlScheme::LeftHand => "One Hand (Left)",
+            ControlScheme::RightHand => "One Hand (Right)",
+        }
+    }
+
+    fn movement_keys(self, action: Action) -> Vec<KeyCode> {
+        let (arrow, letter) = match action {
+            Action::MoveLeft => (KeyCode::Left, KeyCode::A),
+            Action::MoveRight => (KeyCode::Right, KeyCode::D),
+            Action::MoveUp => (KeyCode::Up, KeyCode::W),
+            Action::MoveDown => (KeyCode::Down, KeyCode::S),
+            _ => return Vec::new(),
+        };
+        match self {
+            ControlScheme::ArrowsAndWasd => vec![arrow, letter],
+            ControlScheme::Arrows | ControlScheme::RightHand => vec![arrow],
+            ControlScheme::Wasd | ControlScheme::LeftHand => vec![letter],
+        }
+    }
+
+    // 单手方案放在移动键旁边的按键，双手方案不改动这些动作
+    fn hand_key(self, action: Action) -> Option<KeyCode> {
+        let (left, right) = match action {
+            Action::Fire => (KeyCode::Space, KeyCode::RControl),
+            Action::Missile => (KeyCode::Q, KeyCode::Period),
+            Action::Bomb => (KeyCode::E, KeyCode::Slash),
+            Action::Focus => (KeyCode::LShift, KeyCode::RShift),
+            _ => return None,
+        };
+        match self {
+            ControlScheme::LeftHand => Some(left),
+            ControlScheme::RightHand => Some(right),
+            _ => None,
+        }
+    }
+
+    const MOVEMENT: [Action; 4] = [Action::MoveLeft, Action::MoveRight, Action::MoveUp, Action::MoveDown];
+    const HAND: [Action; 4] = [Action::Fire, Action::Missile, Action::Bomb, Action::Focus];
+
+    pub fn apply(self, bindings: &mut InputMap) {
+        for action in Self::MOVEMENT {
+            bindings.set_keys(action, self.movement_keys(action));
+        }
+        for action in Self::HAND {
+            if let Some(key) = self.hand_key(action) {
+                bindings.bind(action, key);
+            }
+        }
+    }
+
+    // 根据当前绑定识别预设，自定义绑定返回 None；单手方案的移动键和双手方案相同，先检查单手方案
+    pub fn detect(bindings: &InputMap) -> Option<ControlScheme> {
+        Self::ALL.iter().rev().copied().find(|scheme| {
+            Self::MOVEMENT
+                .iter()
+                .all(|action| bindings.keys(*action) == scheme.movement_keys(*action).as_slice())
+                && Self::HAND.iter().all(|action| {
+                    scheme.hand_key(*action).is_none_or(|key| bindings.keys(*action) == [key])
+                })
+        })
+    }
+}
+
+// 允许绑定的按键，同时用于按键名称的解析
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::Down,
+    KeyCode::Space, KeyCode::Return, KeyCode::Tab, KeyCode::Back,
+    KeyCode::LShift, KeyCode::RShift, KeyCode::LControl, KeyCode::RControl,
+    KeyCode::LAlt, KeyCode::RAlt,
+    KeyCode::Comma, KeyCode::Period, KeyCode::Slash, KeyCode::Semicolon,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3,
+    KeyCode::Numpad4, KeyCode::Numpad5, KeyCode::Numpad6, KeyCode::Numpad7,
+    KeyCode::Numpad8, KeyCode::Numpad9,
+];
+
+pub fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS.iter().copied().find(|key| key_name(*key) == name)
+}
+
+pub fn is_bindable(key: KeyCode) -> bool {
+    BINDABLE_KEYS.contains(&key)
+}
+
+#[derive(Clone, Debug)]
+pub struct InputMap {
+    bindings: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        InputMap {
+            bindings: Action::ALL
+                .iter()
+                .map(|action| (*action, action.default_keys()))
+                .collect(),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // 动作对应的任一按键处于按下状态
+    pub fn is_down(&self, ctx: &ggez::Context, action: Action) -> bool {
+        self.keys(action).iter().any(|key| ctx.keyboard.is_key_pressed(*key))
+    }
+
+    // 按键对应的动作（绑定时保证一个键只属于一个动作）
+    pub fn action_for(&self, key: KeyCode) -> Option<Action> {
+        Action::ALL
+            .iter()
+            .copied()
+            .find(|action| self.keys(*action).contains(&key))
+    }
+
+    // 将动作重新绑定到单个按键，并从其他动作上移除该键避免冲突
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        for keys in self.bindings.values_mut() {
+            keys.retain(|bound| *bound != key);
+        }
+        self.bindings.insert(action, vec![key]);
+    }
+
+    pub fn set_keys(&mut self, action: Action, keys: Vec<KeyCode>) {
+        self.bindings.insert(action, keys);
+    }
+
+    // 例如 "Left / A"
+    pub fn describe(&self, action: Action) -> String {
+        let names: Vec<String> = self.keys(action).iter().map(|key| key_name(*key)).collect();
+        if names.is_empty() {
+            "-".to_string()
+        } else {
+            names.join(" / ")
+        }
+    }
+}
+
+// 一个 tick 的操作输入。单机时每个 tick 从键盘读取；联机合作时按 tick 与对方交换，
+// 两端用同样的输入推进模拟
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickInput {
+    pub left: bool,
+    pub right: bool,
+    pub up: bool,
+    pub down: bool,
+    pub fire: bool,    // 已计入自动射击设置
+    pub focus: bool,
+    pub missile: bool, // 本 tick 发射导弹（按键事件在 tick 之间排队）
+    pub bomb: bool,    // 本 tick 引爆炸弹
+}
+
+impl TickInput {
+    // 当前按住的移动、射击和专注键；导弹和炸弹由调用方填入
+    pub fn read(ctx: &ggez::Context, bindings: &InputMap, auto_fire: bool) -> Self {
+        TickInput {
+            left: bindings.is_down(ctx, Action::MoveLeft),
+            right: bindings.is_down(ctx, Action::MoveRight),
+            up: bindings.is_down(ctx, Action::MoveUp),
+            down: bindings.is_down(ctx, Action::MoveDown),
+            fire: auto_fire || bindings.is_down(ctx, Action::Fire),
+            focus: bindings.is_down(ctx, Action::Focus),
+            missile: false,
+            bomb: false,
+        }
+    }
+
+    // 移动方向，各分量为 -1、0 或 1
+    pub fn direction(self) -> Vec2 {
+        let axis = |negative: bool, positive: bool| positive as i32 as f32 - negative as i32 as f32;
+        Vec2::new(axis(self.left, self.right), axis(self.up, self.down))
+    }
+
+    // 网络传输用：每个按键一位
+    #[cfg_attr(not(feature = "online"), allow(dead_code))]
+    pub fn bits(self) -> u8 {
+        [self.left, self.right, self.up, self.down, self.fire, self.focus, self.missile, self.bomb]
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (index, pressed)| bits | ((*pressed as u8) << index))
+    }
+
+    #[cfg_attr(not(feature = "online"), allow(dead_code))]
+    pub fn from_bits(bits: u8) -> Self {
+        let bit = |index: u32| bits & (1 << index) != 0;
+        TickInput {
+            left: bit(0),
+            right: bit(1),
+            up: bit(2),
+            down: bit(3),
+            fire: bit(4),
+            focus: bit(5),
+            missile: bit(6),
+            bomb: bit(7),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// jobs.rs
+// 后台任务：存档写入、读取回放等文件操作交给工作线程，不占用当前帧。
+// 任务按提交顺序在同一个线程上执行，同一文件的多次写入不会乱序；
+// 任务完成后返回一个收尾函数，由主循环在下一次轮询时作用于游戏状态
+use std::io;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+// 在主线程上执行的收尾
+pub type Done<T> = Box<dyn FnOnce(&mut T) + Send>;
+type Job<T> = Box<dyn FnOnce() -> Done<T> + Send>;
+
+pub struct Jobs<T> {
+    sender: Sender<Job<T>>,
+    results: Receiver<Done<T>>,
+    pending: usize, // 已提交但还没有收尾的任务数
+}
+
+impl<T: 'static> Jobs<T> {
+    pub fn new() -> Self {
+        let (sender, jobs) = mpsc::channel::<Job<T>>();
+        let (done_sender, results) = mpsc::channel();
+        thread::Builder::new()
+            .name("io-worker".to_string())
+            .spawn(move || {
+                for job in jobs {
+                    if done_sender.send(job()).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("failed to spawn io worker thread");
+        Jobs { sender, results, pending: 0 }
+    }
+
+    // 提交任务；job 在工作线程上运行，返回的收尾函数在主线程上运行
+    pub fn spawn(&mut self, job: impl FnOnce() -> Done<T> + Send + 'static) {
+        if self.sender.send(Box::new(job)).is_ok() {
+            self.pending += 1;
+        }
+    }
+
+    // 写文件之类不需要收尾的任务，失败时只记录日志
+    pub fn save(&mut self, what: &'static str, job: impl FnOnce() -> io::Result<()> + Send + 'static) {
+        self.spawn(move || {
+            if let Err(err) = job() {
+                log::error!("Failed to save {}: {}", what, err);
+            }
+            Box::new(|_: &mut T| {})
+        });
+    }
+
+    // 取出已完成任务的收尾函数，不会阻塞
+    pub fn poll(&mut self) -> Vec<Done<T>> {
+        let done: Vec<Done<T>> = self.results.try_iter().collect();
+        self.pending -= done.len();
+        done
+    }
+
+    // 等待所有已提交的任务完成（退出或切换档案前，保证存档已经写完）
+    pub fn wait(&mut self) -> Vec<Done<T>> {
+        let mut done = Vec::new();
+        while self.pending > 0 {
+            match self.results.recv() {
+                Ok(result) => {
+                    done.push(result);
+                    self.pending -= 1;
+                }
+                Err(_) => break,
+            }
+        }
+        done
+    }
+}
@@ -0,0 +1,128 @@
+// level.rs
+// 关卡文件（RON 格式）：按开局后的时间排列的出怪事件，每个事件以某种编队放出一组敌人。
+// 由关卡编辑器导出，--level PATH 加载后按时间轴出怪，时间轴走完之前不随机生成敌人
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::enemies::EnemyKind;
+
+pub const LEVELS_DIR: &str = "levels";
+pub const MAX_COUNT: u32 = 8;            // 一个编队最多的敌机数
+const FORMATION_SPACING: f32 = 60.0;     // 编队中相邻敌机的横向间距（基准坐标）
+const FORMATION_DELAY: f32 = 0.35;       // 纵队、V 字队形中后一架比前一架晚出现的时间（秒）
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Formation {
+    Single, // 只取一架，忽略数量
+    Line,   // 横排同时进入
+    Column, // 同一位置依次进入
+    Vee,    // 领队在前，两翼依次靠后
+}
+
+impl Formation {
+    pub const ALL: [Formation; 4] = [Formation::Single, Formation::Line, Formation::Column, Formation::Vee];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Formation::Single => "Single",
+            Formation::Line => "Line",
+            Formation::Column => "Column",
+            Formation::Vee => "V",
+        }
+    }
+
+    // 以 x 为中心的各架敌机：横向位置和相对事件时间的延迟
+    pub fn slots(self, count: u32, x: f32) -> Vec<(f32, f32)> {
+        let count = count.clamp(1, MAX_COUNT);
+        let center = (count - 1) as f32 / 2.0;
+        match self {
+            Formation::Single => vec![(x, 0.0)],
+            Formation::Line => (0..count).map(|i| (x + (i as f32 - center) * FORMATION_SPACING, 0.0)).collect(),
+            Formation::Column => (0..count).map(|i| (x, i as f32 * FORMATION_DELAY)).collect(),
+            Formation::Vee => (0..count)
+                .map(|i| {
+                    let side = i as f32 - center;
+                    (x + side * FORMATION_SPACING, side.abs() * FORMATION_DELAY)
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpawnEvent {
+    pub time: f32, // 开局后的秒数
+    pub kind: EnemyKind,
+    pub formation: Formation,
+    pub count: u32,
+    pub x: f32, // 编队中心的横向位置（基准坐标），从上边进入
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Level {
+    pub name: String,
+    pub events: Vec<SpawnEvent>,
+}
+
+impl Level {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let mut level: Level = ron::from_str(&text).map_err(|err| err.to_string())?;
+        level.sort();
+        Ok(level)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, text)
+    }
+
+    pub fn sort(&mut self) {
+        self.events.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+}
+
+// 一局中的关卡时间轴：把编队展开成逐架敌机，按时间放出
+#[derive(Clone)]
+pub struct LevelTimeline {
+    spawns: Vec<(f32, EnemyKind, f32)>, // 出现时间、类型、横向位置，按时间排序
+    next: usize,
+}
+
+impl LevelTimeline {
+    pub fn new(level: &Level) -> Self {
+        let mut spawns: Vec<(f32, EnemyKind, f32)> = level
+            .events
+            .iter()
+            .flat_map(|event| {
+                event
+                    .formation
+                    .slots(event.count, event.x)
+                    .into_iter()
+                    .map(move |(x, delay)| (event.time + delay, event.kind, x))
+            })
+            .collect();
+        spawns.sort_by(|a, b| a.0.total_cmp(&b.0));
+        LevelTimeline { spawns, next: 0 }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.spawns.len()
+    }
+
+    // 开局后 time 秒时到期的敌机
+    pub fn update(&mut self, time: f32) -> Vec<(EnemyKind, f32)> {
+        let due = self.spawns[self.next..].iter().take_while(|(at, _, _)| *at <= time).count();
+        let spawns = self.spawns[self.next..self.next + due].iter().map(|(_, kind, x)| (*kind, *x)).collect();
+        self.next += due;
+        spawns
+    }
+}
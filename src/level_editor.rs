@@ -0,0 +1,206 @@
+// level_editor.rs
+// 关卡编辑器：在时间轴上放置出怪事件，为每个事件选择敌人类型、编队、数量和位置，导出为 RON 关卡文件。
+// 上下键选择属性、左右键调整，选中事件时修改直接作用于该事件；PageUp/PageDown 切换选中的事件
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::enemies::EnemyKind;
+use crate::level::{Formation, Level, SpawnEvent, MAX_COUNT};
+use crate::text;
+use crate::ui::{UiEvent, Widget, WidgetList};
+use crate::{WindowSize, BASE_WINDOW_HEIGHT, BASE_WINDOW_WIDTH};
+
+const MAX_TIME: f32 = 600.0;        // 事件最晚的时间（秒）
+const MIN_TIMELINE: f32 = 60.0;     // 时间轴至少显示的长度（秒）
+const TIMELINE_LEFT: f32 = 60.0;
+const TIMELINE_TOP: f32 = 110.0;
+const TIMELINE_WIDTH: f32 = BASE_WINDOW_WIDTH - 120.0;
+const TIMELINE_HEIGHT: f32 = 24.0;
+const PREVIEW_ORIGIN: Vec2 = Vec2::new(600.0, 220.0); // 编队预览框左上角
+const PREVIEW_SCALE: f32 = 0.35;                      // 预览框相对游戏画面的比例
+
+pub enum LevelEditorEvent {
+    None,
+    Export(Level),
+    Back,
+}
+
+pub struct LevelEditor {
+    level: Level,
+    selected: Option<usize>,
+    widgets: WidgetList,
+    status: String,
+}
+
+impl LevelEditor {
+    pub fn new(mut level: Level) -> Self {
+        level.sort();
+        let kinds = EnemyKind::ALL.iter().map(|kind| kind.id()).collect();
+        let formations = Formation::ALL.iter().map(|formation| formation.name()).collect();
+        let widgets = WidgetList::new(vec![
+            Widget::number("time", "Time", 0.0, 0.0, MAX_TIME, 0.5, "s"),
+            Widget::selector("kind", "Enemy", kinds, 0),
+            Widget::selector("formation", "Formation", formations, 0),
+            Widget::number("count", "Count", 3.0, 1.0, MAX_COUNT as f32, 1.0, ""),
+            Widget::number("x", "Position", BASE_WINDOW_WIDTH / 2.0, 0.0, BASE_WINDOW_WIDTH, 20.0, ""),
+            Widget::button("add", "Add Event"),
+            Widget::button("delete", "Delete Event"),
+            Widget::button("export", "Export"),
+            Widget::button("back", "Back"),
+        ]);
+        let mut editor = LevelEditor { level, selected: None, widgets, status: String::new() };
+        editor.select(if editor.level.events.is_empty() { None } else { Some(0) });
+        editor
+    }
+
+    // 导出结果的提示
+    pub fn set_status(&mut self, status: String) {
+        self.status = status;
+    }
+
+    // 选中事件并把它的属性同步到控件上
+    fn select(&mut self, index: Option<usize>) {
+        self.selected = index;
+        let Some(event) = index.map(|index| self.level.events[index]) else {
+            return;
+        };
+        self.widgets.set_number("time", event.time);
+        let kind = EnemyKind::ALL.iter().position(|kind| *kind == event.kind).unwrap_or(0);
+        self.widgets.set_selector("kind", kind);
+        let formation = Formation::ALL.iter().position(|formation| *formation == event.formation).unwrap_or(0);
+        self.widgets.set_selector("formation", formation);
+        self.widgets.set_number("count", event.count as f32);
+        self.widgets.set_number("x", event.x);
+    }
+
+    // 控件当前表示的事件
+    fn draft(&self) -> SpawnEvent {
+        SpawnEvent {
+            time: self.widgets.number_value("time").unwrap_or(0.0),
+            kind: EnemyKind::ALL[self.widgets.selector_index("kind").unwrap_or(0)],
+            formation: Formation::ALL[self.widgets.selector_index("formation").unwrap_or(0)],
+            count: self.widgets.number_value("count").unwrap_or(1.0) as u32,
+            x: self.widgets.number_value("x").unwrap_or(0.0),
+        }
+    }
+
+    // 放入事件并保持按时间排序，返回它排序后的位置
+    fn insert(&mut self, event: SpawnEvent) -> usize {
+        let index = self.level.events.partition_point(|other| other.time <= event.time);
+        self.level.events.insert(index, event);
+        index
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> LevelEditorEvent {
+        let count = self.level.events.len();
+        match key {
+            KeyCode::Escape => return LevelEditorEvent::Back,
+            KeyCode::PageUp if count > 0 => {
+                self.select(Some(self.selected.map_or(count - 1, |index| (index + count - 1) % count)));
+                return LevelEditorEvent::None;
+            }
+            KeyCode::PageDown if count > 0 => {
+                self.select(Some(self.selected.map_or(0, |index| (index + 1) % count)));
+                return LevelEditorEvent::None;
+            }
+            _ => {}
+        }
+        match self.widgets.key_down(key) {
+            // 修改时间后事件可能换到别的位置
+            Some(UiEvent::Changed(_)) => {
+                if let Some(index) = self.selected {
+                    self.level.events.remove(index);
+                    self.selected = Some(self.insert(self.draft()));
+                }
+            }
+            Some(UiEvent::Activated("add")) => {
+                let index = self.insert(self.draft());
+                self.select(Some(index));
+            }
+            Some(UiEvent::Activated("delete")) => {
+                if let Some(index) = self.selected {
+                    self.level.events.remove(index);
+                    let next = (!self.level.events.is_empty()).then(|| index.min(self.level.events.len() - 1));
+                    self.select(next);
+                }
+            }
+            Some(UiEvent::Activated("export")) => return LevelEditorEvent::Export(self.level.clone()),
+            Some(UiEvent::Activated("back")) => return LevelEditorEvent::Back,
+            _ => {}
+        }
+        LevelEditorEvent::None
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize) {
+        let quad = |canvas: &mut Canvas, pos: Vec2, size: Vec2, color: Color| {
+            canvas.draw(
+                &graphics::Quad,
+                DrawParam::default().dest(window_size.scale_vec2(pos)).scale(window_size.scale_vec2(size)).color(color),
+            );
+        };
+        let label = |canvas: &mut Canvas, content: String, pos: Vec2, color: Color| {
+            canvas.draw(&text::label(content, window_size), DrawParam::default().dest(window_size.scale_vec2(pos)).color(color));
+        };
+
+        // 遮住背后的游戏画面
+        quad(canvas, Vec2::ZERO, Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT), Color::new(0.02, 0.02, 0.06, 0.95));
+        let title = match &self.level.name {
+            name if name.is_empty() => "LEVEL EDITOR".to_string(),
+            name => format!("LEVEL EDITOR - {}", name),
+        };
+        label(canvas, title, Vec2::new(TIMELINE_LEFT, 30.0), Color::YELLOW);
+
+        // 时间轴：事件标记、选中的事件和当前编辑的时间
+        let draft = self.draft();
+        let length = self
+            .level
+            .events
+            .iter()
+            .map(|event| event.time + 10.0)
+            .fold(MIN_TIMELINE.max(draft.time + 10.0), f32::max);
+        let to_x = |time: f32| TIMELINE_LEFT + time / length * TIMELINE_WIDTH;
+        quad(
+            canvas,
+            Vec2::new(TIMELINE_LEFT, TIMELINE_TOP),
+            Vec2::new(TIMELINE_WIDTH, TIMELINE_HEIGHT),
+            Color::new(0.15, 0.15, 0.2, 1.0),
+        );
+        for (index, event) in self.level.events.iter().enumerate() {
+            let color = if Some(index) == self.selected { Color::YELLOW } else { Color::new(0.4, 0.8, 1.0, 1.0) };
+            quad(canvas, Vec2::new(to_x(event.time) - 2.0, TIMELINE_TOP), Vec2::new(4.0, TIMELINE_HEIGHT), color);
+        }
+        quad(canvas, Vec2::new(to_x(draft.time) - 1.0, TIMELINE_TOP - 6.0), Vec2::new(2.0, TIMELINE_HEIGHT + 12.0), Color::GREEN);
+        let gray = Color::new(0.7, 0.7, 0.7, 1.0);
+        label(canvas, "0s".to_string(), Vec2::new(TIMELINE_LEFT, TIMELINE_TOP + TIMELINE_HEIGHT + 6.0), gray);
+        label(
+            canvas,
+            format!("{:.0}s", length),
+            Vec2::new(TIMELINE_LEFT + TIMELINE_WIDTH - 40.0, TIMELINE_TOP + TIMELINE_HEIGHT + 6.0),
+            gray,
+        );
+        let selection = match self.selected {
+            Some(index) => format!("Event {}/{}", index + 1, self.level.events.len()),
+            None => format!("New event ({} total)", self.level.events.len()),
+        };
+        label(canvas, selection, Vec2::new(TIMELINE_LEFT + TIMELINE_WIDTH / 2.0 - 60.0, TIMELINE_TOP + TIMELINE_HEIGHT + 6.0), Color::WHITE);
+
+        self.widgets.draw(canvas, window_size, Vec2::new(TIMELINE_LEFT, 200.0));
+
+        // 编队预览：按比例缩小的游戏画面，纵向表示出现的先后
+        let preview_size = Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT) * PREVIEW_SCALE;
+        quad(canvas, PREVIEW_ORIGIN, preview_size, Color::new(0.1, 0.1, 0.15, 1.0));
+        for (x, delay) in draft.formation.slots(draft.count, draft.x) {
+            let pos = PREVIEW_ORIGIN + Vec2::new(x, 40.0 + delay * 120.0) * PREVIEW_SCALE;
+            quad(canvas, pos - 4.0, Vec2::splat(8.0), Color::new(1.0, 0.5, 0.4, 1.0));
+        }
+
+        label(
+            canvas,
+            "Up/Down select  Left/Right change  PgUp/PgDn pick event  Esc back".to_string(),
+            Vec2::new(TIMELINE_LEFT, BASE_WINDOW_HEIGHT - 70.0),
+            gray,
+        );
+        label(canvas, self.status.clone(), Vec2::new(TIMELINE_LEFT, BASE_WINDOW_HEIGHT - 40.0), Color::WHITE);
+    }
+}
@@ -0,0 +1,69 @@
+// loading_screen.rs
+// 载入界面：启动时逐帧预加载资源并显示进度条，窗口不会在读取文件时卡住无响应
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use glam::Vec2;
+
+use crate::assets::{AssetLoader, LoadResult};
+use crate::{WindowSize, BASE_WINDOW_HEIGHT, BASE_WINDOW_WIDTH};
+
+const BAR_WIDTH: f32 = 480.0;
+const BAR_HEIGHT: f32 = 16.0;
+
+pub struct LoadingScreen {
+    loader: AssetLoader,
+}
+
+impl LoadingScreen {
+    pub fn new(load_sounds: bool) -> Self {
+        LoadingScreen { loader: AssetLoader::new(load_sounds) }
+    }
+
+    // 载入一批资源；全部载入后返回结果
+    pub fn update(&mut self, ctx: &mut ggez::Context) -> Option<LoadResult> {
+        self.loader.update(ctx);
+        self.loader.is_done().then(|| self.loader.finish())
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let left = (BASE_WINDOW_WIDTH - BAR_WIDTH) / 2.0;
+        let top = BASE_WINDOW_HEIGHT / 2.0;
+
+        canvas.draw(
+            &graphics::Text::new("LOADING"),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, top - 60.0)))
+                .color(Color::WHITE)
+                .scale(scale * 2.0),
+        );
+
+        // 进度条底框和已完成部分
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, top)))
+                .scale(window_size.scale_vec2(Vec2::new(BAR_WIDTH, BAR_HEIGHT)))
+                .color(Color::new(0.2, 0.2, 0.25, 1.0)),
+        );
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, top)))
+                .scale(window_size.scale_vec2(Vec2::new(BAR_WIDTH * self.loader.progress(), BAR_HEIGHT)))
+                .color(Color::new(0.3, 0.8, 1.0, 1.0)),
+        );
+
+        let status = format!(
+            "{:.0}%  {}",
+            self.loader.progress() * 100.0,
+            self.loader.current().unwrap_or(""),
+        );
+        canvas.draw(
+            &graphics::Text::new(status),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, top + BAR_HEIGHT + 12.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+}
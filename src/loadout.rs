@@ -0,0 +1,178 @@
+// loadout.rs
+// 出击装备：每局开始前选择主武器和副武器，选择结果保存在档案的进度文件中
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::progression::{Progress, StartingWeapon, Unlock};
+use crate::ui::{UiEvent, Widget, WidgetList};
+use crate::{WindowSize, BASE_WINDOW_WIDTH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Primary {
+    Normal,
+    Spread, // 需要先在机库解锁扇形射击
+    Laser,  // 更快的细长光束，可以贯穿普通敌人
+}
+
+impl Primary {
+    pub const ALL: [Primary; 3] = [Primary::Normal, Primary::Spread, Primary::Laser];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Primary::Normal => "normal",
+            Primary::Spread => "spread",
+            Primary::Laser => "laser",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Primary> {
+        Primary::ALL.iter().copied().find(|primary| primary.id() == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Primary::Normal => "Normal",
+            Primary::Spread => "Spread",
+            Primary::Laser => "Laser",
+        }
+    }
+
+    // 当前档案可以选择的主武器
+    pub fn available(progress: &Progress) -> Vec<Primary> {
+        Primary::ALL
+            .into_iter()
+            .filter(|primary| {
+                *primary != Primary::Spread || progress.is_unlocked(Unlock::Weapon(StartingWeapon::Spread))
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Secondary {
+    Missiles,    // 额外的起始导弹
+    Bombs,       // 额外的炸弹
+    Emp,         // 炸弹键改为释放 EMP，次数更多但不清除敌方子弹
+    GravityWell, // 炸弹键改为放出引力井，把敌人和敌方子弹吸到一起后引爆
+}
+
+impl Secondary {
+    pub const ALL: [Secondary; 4] = [Secondary::Missiles, Secondary::Bombs, Secondary::Emp, Secondary::GravityWell];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Secondary::Missiles => "missiles",
+            Secondary::Bombs => "bombs",
+            Secondary::Emp => "emp",
+            Secondary::GravityWell => "gravity_well",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Secondary> {
+        Secondary::ALL.iter().copied().find(|secondary| secondary.id() == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Secondary::Missiles => "Missiles",
+            Secondary::Bombs => "Bombs",
+            Secondary::Emp => "EMP",
+            Secondary::GravityWell => "Gravity Well",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Loadout {
+    pub primary: Primary,
+    pub secondary: Secondary,
+}
+
+impl Default for Loadout {
+    fn default() -> Self {
+        Loadout { primary: Primary::Normal, secondary: Secondary::Missiles }
+    }
+}
+
+// 装备界面按键处理的结果
+pub enum LoadoutEvent {
+    None,
+    Launch(Loadout),
+    Back,
+}
+
+pub struct LoadoutScreen {
+    primaries: Vec<Primary>,
+    widgets: WidgetList,
+}
+
+impl LoadoutScreen {
+    pub fn new(progress: &Progress) -> Self {
+        let primaries = Primary::available(progress);
+        let primary = primaries.iter().position(|p| *p == progress.loadout.primary).unwrap_or(0);
+        let secondary = Secondary::ALL.iter().position(|s| *s == progress.loadout.secondary).unwrap_or(0);
+        let widgets = WidgetList::new(vec![
+            Widget::selector("primary", "Primary", primaries.iter().map(|p| p.name()).collect(), primary),
+            Widget::selector("secondary", "Secondary", Secondary::ALL.iter().map(|s| s.name()).collect(), secondary),
+            Widget::button("launch", "Launch"),
+            Widget::button("back", "Back"),
+        ]);
+        LoadoutScreen { primaries, widgets }
+    }
+
+    fn loadout(&self) -> Loadout {
+        Loadout {
+            primary: self.primaries[self.widgets.selector_index("primary").unwrap_or(0)],
+            secondary: Secondary::ALL[self.widgets.selector_index("secondary").unwrap_or(0)],
+        }
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> LoadoutEvent {
+        if key == KeyCode::Escape {
+            return LoadoutEvent::Back;
+        }
+        match self.widgets.key_down(key) {
+            Some(UiEvent::Activated("launch")) => LoadoutEvent::Launch(self.loadout()),
+            Some(UiEvent::Activated("back")) => LoadoutEvent::Back,
+            _ => LoadoutEvent::None,
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let left = BASE_WINDOW_WIDTH / 2.0 - 200.0;
+
+        // 半透明背景
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left - 20.0, 160.0)))
+                .scale(window_size.scale_vec2(Vec2::new(460.0, 320.0)))
+                .color(Color::new(0.0, 0.0, 0.0, 0.8)),
+        );
+        canvas.draw(
+            &graphics::Text::new("LOADOUT"),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 180.0)))
+                .color(Color::YELLOW)
+                .scale(scale * 2.0),
+        );
+
+        self.widgets.draw(canvas, window_size, Vec2::new(left, 240.0));
+
+        let description = match self.loadout().secondary {
+            Secondary::Missiles => "+3 starting missiles",
+            Secondary::Bombs => "+2 starting bombs",
+            Secondary::Emp => "Bomb key fires an EMP (no bullet clear)",
+            Secondary::GravityWell => "Bomb key drops a gravity well that pulls foes in, then detonates",
+        };
+        canvas.draw(
+            &graphics::Text::new(format!("{}\nLeft/Right: change   Enter: choose   Esc: back", description)),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 400.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+}
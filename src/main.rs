@@ -1,18 +1,143 @@
 // main.rs
-use ggez::GameResult;
-use ggez::graphics::{self, Color, DrawParam, Canvas, Image, Mesh, MeshBuilder};
+mod animation;
+mod arena;
+mod assets;
+mod boss;
+mod boundary;
+mod bounds;
+mod bus;
+mod camera;
+mod cli;
+mod collision;
+mod commands;
+mod config;
+mod daily;
+mod dialogue;
+mod enemies;
+mod error_screen;
+mod events;
+mod game_over;
+mod gravity;
+mod hangar;
+mod highlight;
+mod highscores;
+mod hud;
+mod input;
+mod jobs;
+mod level;
+mod level_editor;
+mod loadout;
+mod loading_screen;
+mod main_menu;
+mod mods;
+mod mutators;
+mod net;
+mod palette;
+mod particles;
+#[cfg(feature = "editor")]
+mod path_editor;
+mod paths;
+mod pickups;
+mod popups;
+mod postfx;
+mod practice;
+mod presence;
+mod profile_menu;
+mod profiles;
+mod progression;
+mod replay;
+mod run_code;
+mod render;
+mod scripting;
+mod settings;
+mod shop;
+mod sim;
+mod spawner;
+mod sounds;
+mod spatial;
+mod status;
+mod text;
+mod transition;
+mod tuning;
+mod ui;
+mod warnings;
+
+use ggez::{GameError, GameResult};
+use ggez::graphics::{self, Color, DrawParam, Canvas, Image, Mesh};
 use ggez::event::{self, EventHandler};
-use ggez::input::keyboard::{self, KeyCode};
+use ggez::input::keyboard::{KeyCode, KeyInput};
 use glam::Vec2;
-use rand::Rng;
+use log::{debug, error, info, warn};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 use std::time::Duration;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 use std::path;
+use std::fs;
 use std::env;
-use ggez::audio;
-use ggez::audio::{SoundSource, Source};
-use rand::distributions::Uniform;
 use ggez::conf;
+use ggez::winit::window::UserAttentionType;
+use animation::{ExplosionKind, Explosions, SpriteSheet};
+use arena::{Arena, Handle};
+use assets::{Assets, LoadResult};
+use boss::{Boss, IntroStep};
+use boundary::BoundaryPolicy;
+use bounds::Despawn;
+use bus::{EffectCues, EventBus, GameEvent, RunLog, RunStats, SoundCues};
+use camera::FreeCamera;
+use cli::LaunchOptions;
+use collision::{Circle, Collider};
+use commands::{CommandQueue, GameCommand, Pool};
+use config::{Config, Difficulty};
+use daily::{DailyChallenge, DailyHistory};
+use dialogue::MessageBar;
+use hangar::{Hangar, HangarEvent};
+use enemies::{Armor, Dive, EnemyDef, EnemyKind, EnemyTable, EntryEdge, OnDeath, Shield};
+use error_screen::{ErrorEvent, ErrorScreen};
+use highlight::{HighlightRecorder, HIGHLIGHTS_DIR};
+use hud::{IconRow, ScoreCounter};
+use jobs::Jobs;
+use level::{Level, LevelTimeline, LEVELS_DIR};
+use level_editor::{LevelEditor, LevelEditorEvent};
+use events::{LevelEvent, MeteorShower};
+use game_over::{GameOverEvent, GameOverScreen, RunSummary};
+use gravity::GravityWell;
+use highscores::{GameMode, HighScores};
+use mods::ModInfo;
+use mutators::{Mutator, Mutators};
+use net::CoopRules;
+use popups::{ChainTracker, ScorePopups};
+use replay::Replay;
+use run_code::RunCode;
+use scripting::{ScriptCommand, WaveScript};
+use input::{Action, TickInput};
+use loadout::{Loadout, LoadoutEvent, LoadoutScreen, Primary, Secondary};
+use loading_screen::LoadingScreen;
+use main_menu::{MainMenu, MainMenuEvent};
+use settings::{SettingsEvent, SettingsMenu};
+use spawner::{SpawnQueue, SpawnRequest};
+use transition::{Transition, TransitionKind};
+use tuning::{Curve, Tuning};
+use sounds::{Audio, NullAudio, SoundEvent, Stinger, Weapon};
+use shop::{Shop, ShopEvent, ShopItem};
+use sim::{Cue, Cues, Inputs, Resources, Rollback};
+use spatial::SpatialGrid;
+use status::{StatusEffects, StatusKind};
+use text::TextCache;
+use palette::Swatch;
+use particles::{AmbientEffect, ColorGradient, EmitterKind, ParticleSystem};
+#[cfg(feature = "editor")]
+use path_editor::{EditorEvent, PathEditor};
+use paths::EnemyPath;
+use postfx::{PostProcess, ScreenFilter};
+use practice::{Practice, PracticeEvent, PracticeScreen};
+use presence::{PresenceReporter, PresenceState};
+use render::{DrawQueue, Layer};
+use profile_menu::{ProfileEvent, ProfileMenu};
+use profiles::ProfileStore;
+use progression::{Progress, Ship, StartingWeapon};
+use warnings::{Attack, WarningShape, Warnings};
 
 // 基准窗口尺寸
 const BASE_WINDOW_WIDTH: f32 = 1024.0;
@@ -20,16 +145,197 @@ const BASE_WINDOW_HEIGHT: f32 = 768.0;
 
 // 游戏常量现在使用相对值
 const PLAYER_SPEED_RATIO: f32 = 5.0 / 1024.0; // 相对于窗口宽度的速度
+const FOCUS_SPEED_SCALE: f32 = 0.45;           // 专注模式下的移动速度倍率
 const BULLET_SPEED_RATIO: f32 = 8.0 / 768.0;  // 相对于窗口高度的速度
+const LASER_SPEED_SCALE: f32 = 1.6;            // 激光相对普通子弹的速度
 const ENEMY_SPEED_RATIO: f32 = 2.0 / 768.0;   // 相对于窗口高度的速度
 
-// 粒子系统常量
-const PARTICLE_LIFETIME: f32 = 0.5;
-const EXPLOSION_PARTICLES: i32 = 10;
-const PARTICLE_SPEED: f32 = 50.0;
-const PARTICLE_SIZE: f32 = 2.0;
-const MAX_PARTICLES: usize = 1000;
 const RESOURCE_DIR: &str = "resources";
+const RESOURCE_PACK: &str = "resources.zip";  // 打包发布时代替资源目录的 zip 资源包
+const WINDOW_TITLE: &str = "Vertical Shooter";
+const WINDOW_ICON: &str = "/img/player.png";  // 窗口图标，mod 中的同名文件优先
+const TITLE_FLASH_INTERVAL: f32 = 0.5;         // 失去焦点时刷新纪录，标题闪烁的间隔（秒）
+const ENEMY_SPAWN_MARGIN: f32 = 50.0;  // 敌人在屏幕外多远处生成
+
+// 固定步长模拟常量
+const REFERENCE_FPS: f32 = 60.0;       // 速度常量按此帧率设计
+const MAX_TICKS_PER_FRAME: u32 = 8;    // 单帧最多追赶的 tick 数
+const REWIND_SECONDS: f32 = 2.0;       // 调试模式下保留多少秒的状态快照，用于回退和重新模拟
+
+// 爆炸精灵表配置
+const EXPLOSION_FRAME_SIZE: u32 = 192;
+const PLAYER_EXPLOSION_FRAME_SIZE: u32 = 288;
+const EXPLOSION_FRAME_DURATION: f32 = 0.05;
+
+// 波次与续关
+const WAVE_DURATION: f32 = 30.0;           // 每波持续秒数
+const DEFAULT_BACKGROUND: Color = Color::new(0.0, 0.05, 0.1, 1.0);
+const MAX_CONTINUES: u32 = 3;              // 每局可续关次数
+const CONTINUE_COUNTDOWN: f32 = 10.0;      // 续关倒计时（秒）
+const CONTINUE_SCORE_FRACTION: f32 = 0.5;  // 续关后保留的分数比例
+const SHOP_WAVE_INTERVAL: u32 = 3;         // 每隔几波开一次商店
+const SHOT_INTERVAL_MS: u64 = 250;         // 普通射击间隔（毫秒）
+const SHOT_INTERVAL_PER_TIER_MS: u64 = 40; // 每级武器升级缩短的射击间隔
+
+// 敌方弹幕
+const MINE_SPEED: f32 = 1.5;               // 追踪地雷速度（60fps 每帧像素）
+const MINE_TURN_RATE: f32 = 0.02;          // 追踪地雷转向系数
+const MINE_LIFETIME: f32 = 6.0;            // 追踪地雷存在时间（秒）
+
+// 漂浮水雷
+const HAZARD_SPAWN_INTERVAL: f32 = 8.0;    // 生成间隔（秒）
+const HAZARD_BLAST_RADIUS: f32 = 90.0;     // 爆炸半径
+
+// 射击散布：按住射击时散布逐渐扩大，松开后回正
+const NORMAL_SHOT_ACCURACY: f32 = 0.9;     // 武器精度（0~1，越高散布越小）
+const SPREAD_SHOT_ACCURACY: f32 = 0.75;
+const MAX_SPREAD_ANGLE: f32 = 60.0;        // 精度为 0 且后坐力满时的最大偏角（度）
+const RECOIL_PER_SHOT: f32 = 0.25;         // 每发增加的后坐力
+const RECOIL_RECOVERY: f32 = 2.0;          // 停火时每秒恢复的后坐力
+
+// 重武器的击退：发射导弹或使用炸弹时飞船被向后推，速度逐渐衰减
+const MISSILE_KNOCKBACK: f32 = 90.0;       // 发射导弹时的后退初速度（像素/秒）
+const BOMB_KNOCKBACK: f32 = 240.0;         // 使用炸弹时的后退初速度（像素/秒）
+const KNOCKBACK_DAMPING: f32 = 8.0;        // 经典移动时后退速度的衰减率（每秒）
+
+// 道具离屏提示：稀有道具还剩这么多秒就掉出屏幕下方时闪烁提示
+const PICKUP_EXIT_WARNING: f32 = 1.5;
+const VACUUM_DURATION: f32 = 1.0;          // 清波后场上道具被吸到玩家身边所用的时间（秒）
+
+// 移动辅助
+const AUTO_CENTER_DEADZONE: f32 = 24.0;    // 自动居中时离中央这么近就不再移动
+const DODGE_HINT_FRAMES: f32 = 45.0;       // 躲避提示预判敌弹接下来多少帧（60fps）的轨迹
+const DODGE_HINT_RADIUS: f32 = 40.0;       // 预判轨迹离飞机这么近时提示躲避
+
+// 练习模式的轨迹预览：冻结时推演敌弹接下来多少秒的轨迹
+const PREVIEW_DURATION: f32 = 1.0;
+
+// 僚机
+const MAX_DRONES: usize = 2;
+const DRONE_SPRING: f32 = 40.0;            // 弹簧刚度
+const DRONE_DAMPING: f32 = 9.0;            // 阻尼
+const DRONE_FIRE_INTERVAL: f32 = 0.6;      // 自动射击间隔（秒）
+const DRONE_SHOT_SPEED: f32 = 6.0;         // 子弹速度（60fps 每帧像素）
+const DRONE_SHOT_LIFETIME: f32 = 2.0;      // 子弹存在时间（秒）
+
+// 导弹燃料
+const MISSILE_FUEL: f32 = 4.0;             // 燃料可飞行的时间（秒），耗尽后自毁
+const MISSILE_BLAST_RADIUS: f32 = 45.0;    // 自毁爆炸半径
+
+// Boss 参数
+const BOSS_WAVE_INTERVAL: u32 = 5;         // 每隔几波出现一次 Boss
+const BOSS_HEALTH: u32 = 80;
+const BOSS_SIZE: f32 = 160.0;
+const BOSS_SCORE: i32 = 500;
+const BOSS_DEBRIS_DURATION: f32 = 5.0;     // Boss 被击毁后残骸碎片雨持续的秒数
+const BOSS_SHIELD_COLOR: Color = Color::new(0.5, 0.9, 1.0, 1.0); // 护卫存活期间 Boss 的护盾
+
+// Boss 换阶段时崩落的残骸
+const DEBRIS_COUNT: u32 = 4;               // 每次崩落的块数
+const DEBRIS_SIZE: (f32, f32) = (36.0, 52.0); // 尺寸范围
+const DEBRIS_SPLIT_SIZE: f32 = 34.0;       // 不小于这个尺寸的残骸打碎后分成两小块
+const DEBRIS_HITS: u32 = 3;                // 打碎一块需要的命中次数
+const DEBRIS_GRAVITY: f32 = 0.05;          // 下落加速度（60fps 每帧像素）
+
+// 状态效果持续时间（秒）
+const MISSILE_BURN_DURATION: f32 = 3.0;    // 导弹命中后燃烧
+const SPREAD_SLOW_DURATION: f32 = 2.0;     // 扇形子弹命中后减速
+const BLAST_STUN_DURATION: f32 = 1.5;      // 水雷冲击波边缘的敌人短暂瘫痪
+const EMP_STUN_DURATION: f32 = 3.0;        // EMP 让屏幕内的敌人瘫痪
+
+// 擦弹奖励
+const GRAZE_MARGIN: f32 = 22.0;            // 玩家碰撞半径外多远内算擦弹
+const GRAZE_SCORE: i32 = 5;
+
+// 连锁奖励星：连锁击破后出现，离开屏幕前接住才能得分
+const BONUS_STAR_SCORE: i32 = 500;
+const BONUS_STAR_SIZE: f32 = 32.0;
+
+// 消弹：击毁敌人时附近的敌弹（包括 Boss 的）变成得分宝石，击毁 Boss 时它在全屏的敌弹都会变成宝石
+const CANCEL_RADIUS: f32 = 120.0;
+const SCORE_GEM_SCORE: i32 = 10;
+const SCORE_GEM_SIZE: f32 = 12.0;
+
+// 标题画面演示
+const DEMO_REPLAY: &str = "/replays/demo.txt";
+const DEMO_SEED: u64 = 1128;
+
+// EMP 冲击波
+const SHOCKWAVE_DURATION: f32 = 0.6;       // 扩散时间（秒）
+const SHOCKWAVE_RADIUS: f32 = 700.0;       // 最终半径（基准坐标）
+
+// 受击反馈
+const HIT_FLASH_DURATION: f32 = 0.12;      // 受击闪白时间（秒）
+const BLINK_INTERVAL: f32 = 0.1;           // 无敌期间的闪烁间隔（秒）
+const BARRICADE_COUNT: u32 = 4;            // 生存模式的路障数量
+const BARRICADE_SIZE: (f32, f32) = (96.0, 32.0); // 路障的宽和高
+const BARRICADE_OFFSET: f32 = 160.0;       // 路障距屏幕底部的距离
+const BARRICADE_HP: u32 = 12;              // 路障能承受的伤害
+const BARRICADE_RAM_DAMAGE: u32 = 3;       // 敌人撞上路障造成的伤害，敌方子弹为 1
+const BARRICADE_TURN_RATE: f32 = 0.04;     // 敌人转向目标路障的速率（60fps 每帧）
+const RESPAWN_INVULNERABILITY: f32 = 2.0;  // 续关后的无敌时间（秒）
+
+// 炸弹与“最后一搏”
+const STARTING_BOMBS: u32 = 1;             // 每局（及续关后）至少拥有的炸弹数
+const LOADOUT_BONUS_BOMBS: u32 = 2;        // 副武器选择炸弹时额外的炸弹
+const LOADOUT_EMP_CHARGES: u32 = 3;        // 副武器选择 EMP 时的次数
+const LOADOUT_GRAVITY_WELLS: u32 = 2;      // 副武器选择引力井时的次数
+const GRAVITY_WELL_OFFSET: f32 = 220.0;    // 引力井放在玩家前方多远处
+const LOADOUT_BONUS_MISSILES: i32 = 3;     // 副武器选择导弹时额外的导弹
+const BOMB_INVULNERABILITY: f32 = 1.5;     // 引爆炸弹后的无敌时间（秒）
+const LAST_STAND_WINDOW: f32 = 1.0;        // 致命一击后可以引爆炸弹的真实时间（秒）
+const LAST_STAND_TIME_SCALE: f32 = 0.2;    // 最后一搏期间的时间流速
+const PARTNER_OFFSET: f32 = 120.0;         // 联机合作时二号机在一号机右侧多远处出发
+
+// 屏幕外炮击：预警区域闪烁一段时间后落下
+const ARTILLERY_MIN_WAVE: u32 = 4;         // 从第几波开始
+const ARTILLERY_INTERVAL: f32 = 9.0;       // 炮击间隔（秒）
+const ARTILLERY_WARNING: f32 = 1.5;        // 预警时间（秒）
+const ARTILLERY_ZONE: f32 = 130.0;         // 落点区域边长（基准坐标）
+
+// 流星雨
+const METEOR_SPEED: f32 = 9.0;             // 60fps 每帧像素
+const METEOR_SIZE: (f32, f32) = (28.0, 56.0); // 尺寸范围
+
+// 按挑战规则倍率换算得分
+fn scaled_score(points: i32, multiplier: f32) -> i32 {
+    (points as f32 * multiplier).round() as i32
+}
+
+// 路障随受到的伤害从沙色逐渐变为暗红
+fn barricade_tint(damage: u32) -> Color {
+    let worn = (damage as f32 / BARRICADE_HP as f32).min(1.0);
+    Color::new(0.85 - 0.3 * worn, 0.8 - 0.5 * worn, 0.6 - 0.35 * worn, 1.0)
+}
+
+// 特殊武器命中时附带的状态效果
+fn bullet_status(bullet: &GameObject) -> Option<(StatusKind, f32)> {
+    match bullet.object_type {
+        GameObjectType::GuidedMissile => Some((StatusKind::Burning, MISSILE_BURN_DURATION)),
+        GameObjectType::SpreadShot => Some((StatusKind::Slowed, SPREAD_SLOW_DURATION)),
+        _ => None,
+    }
+}
+
+// 状态效果的粒子反馈：燃烧冒出火苗，减速和瘫痪闪出光点
+fn emit_status_particles(particles: &mut ParticleSystem, object: &GameObject, dt: f32) {
+    let mut rng = rand::thread_rng();
+    for kind in object.status.kinds() {
+        if !rng.gen_bool((dt * 8.0).min(1.0) as f64) {
+            continue;
+        }
+        let jitter = Vec2::new(rng.gen_range(-0.3..0.3), rng.gen_range(-0.3..0.3)) * object.base_size;
+        let gradient = match kind {
+            StatusKind::Burning => ColorGradient::new(
+                Color::new(1.0, 0.8, 0.3, 0.9),
+                Color::new(1.0, 0.1, 0.0, 0.0),
+            ),
+            StatusKind::Slowed | StatusKind::Stunned => ColorGradient::fade(kind.tint()),
+        };
+        let emitter = if kind == StatusKind::Burning { EmitterKind::Trail } else { EmitterKind::Ambient };
+        particles.emit(emitter, object.pos + jitter, -std::f32::consts::FRAC_PI_2, gradient);
+    }
+}
 
 // 窗口尺寸管理结构体
 struct WindowSize {
@@ -52,10 +358,6 @@ impl WindowSize {
     fn scale_vec2(&self, vec: Vec2) -> Vec2 {
         Vec2::new(vec.x * self.scale_x, vec.y * self.scale_y)
     }
-
-    fn unscale_vec2(&self, vec: Vec2) -> Vec2 {
-        Vec2::new(vec.x / self.scale_x, vec.y / self.scale_y)
-    }
 }
 
 // 游戏对象类型枚举
@@ -68,356 +370,808 @@ enum GameObjectType {
     MissileAmmo,  // 新增：导弹弹药补给
     SpreadShot,     // 新增：扇形子弹
     SpreadAmmo,     // 新增：扇形弹药
+    EnemyBullet,    // 新增：敌方子弹
+    HomingMine,     // 新增：追踪地雷
+    FloatingMine,   // 新增：漂浮水雷（场景危险物）
+    Drone,          // 新增：僚机
+    DroneShot,      // 新增：僚机子弹
+    DronePickup,    // 新增：僚机道具
+    Debris,         // 新增：Boss 外壳崩落的残骸，挡住玩家子弹
+    Barricade,      // 新增：生存模式的路障，挡住敌人和敌方子弹
+    EmpPickup,      // 新增：EMP 道具
+    Meteor,         // 新增：流星雨中的流星（场景危险物）
+    BonusStar,      // 新增：连锁奖励星
+    ScoreGem,       // 新增：消弹变成的得分宝石
+}
+
+// 敌方子弹的发射者
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Shooter {
+    Enemy(Handle),
+    Boss,
 }
 
 // 游戏对象结构体
+#[derive(Clone)]
 struct GameObject {
     pos: Vec2,
+    prev_pos: Vec2,         // 新增：上一 tick 的位置，用于渲染插值
     base_size: Vec2,
     speed: Vec2,
     image: Option<Image>,
     rotation: f32,
     object_type: GameObjectType,
-    target: Option<usize>,  // 新增：用于存储目标敌人的索引
+    target: Option<Handle>, // 新增：追踪的目标敌人，目标被摧毁后 handle 随之失效
+    tint: Color,            // 新增：绘制时的着色
+    enemy_kind: Option<EnemyKind>, // 新增：敌人类型（仅敌人有效）
+    age: f32,               // 新增：存在时间（秒）
+    lifetime: Option<f32>,  // 新增：存在时间上限（秒），None 表示只在离屏时移除
+    heading: Vec2,          // 新增：敌人行进方向（单位向量）
+    damage: u32,            // 新增：装甲敌人已承受的弱点命中次数
+    regen_timer: f32,       // 新增：距上次弱点命中的时间（秒）
+    status: StatusEffects,  // 新增：燃烧、减速等状态效果
+    grazed: bool,           // 新增：是否已经擦过玩家（每个对象只计一次）
+    flash_timer: f32,       // 新增：受击闪白剩余时间（秒）
+    invulnerable_timer: f32, // 新增：无敌剩余时间（秒），期间闪烁
+    dive_target: Option<Vec2>, // 新增：俯冲敌人锁定的玩家位置
+    dive_timer: f32,        // 新增：俯冲前剩余的预警时间（秒）
+    piercing: bool,         // 新增：命中普通敌人后继续飞行（激光）
+    launch_timer: f32,      // 新增：母舰下次放出舰载机前的剩余时间（秒）
+    ricochets: u32,         // 新增：还能在屏幕左右边缘反弹的次数
+    shield: u32,            // 新增：护盾泡还能吸收的伤害，0 表示没有护盾或已破裂
+    path: Option<(Rc<EnemyPath>, f32)>, // 新增：沿路线飞行的敌人所走的路线和已飞行的距离
+    hitbox_scale: f32,      // 新增：受击判定相对碰撞圆的倍数（玩家判定随难度缩小，拾取和擦弹仍用完整的碰撞圆）
+    exit_warned: bool,      // 新增：是否已提示过即将掉出屏幕（道具）
+    shooter: Option<Shooter>, // 新增：发射这颗敌弹的敌人或 Boss
 }
 
 impl GameObject {
-    fn new(ctx: &mut ggez::Context, x: f32, y: f32, width: f32, height: f32, object_type: GameObjectType) -> GameResult<Self> {
+    fn new(assets: &Assets, x: f32, y: f32, width: f32, height: f32, object_type: GameObjectType) -> GameResult<Self> {
         let (image, rotation) = match object_type {
-            GameObjectType::Player => (Some(Image::from_path(ctx, "/img/player.png")?), 0.0),
-            GameObjectType::Bullet => (Some(Image::from_path(ctx, "/img/bullet.png")?), 0.0),
-            GameObjectType::Enemy => (Some(Image::from_path(ctx, "/img/player.png")?), std::f32::consts::PI),
-            GameObjectType::GuidedMissile => (Some(Image::from_path(ctx, "/img/bullet.png")?), 0.0),  // 使用子弹图片
-            GameObjectType::MissileAmmo => (Some(Image::from_path(ctx, "/img/bullet.png")?), 0.0),  // 暂时使用子弹图片
-            GameObjectType::SpreadShot => (Some(Image::from_path(ctx, "/img/bullet.png")?), 0.0),  // 使用子弹图片
-            GameObjectType::SpreadAmmo => (Some(Image::from_path(ctx, "/img/bullet.png")?), 0.0),  // 使用子弹图片
+            GameObjectType::Player => (Some(assets.image("/img/player.png")?), 0.0),
+            GameObjectType::Bullet => (Some(assets.image("/img/bullet.png")?), 0.0),
+            GameObjectType::Enemy => (Some(assets.image("/img/player.png")?), std::f32::consts::PI),
+            GameObjectType::GuidedMissile => (Some(assets.image("/img/bullet.png")?), 0.0),  // 使用子弹图片
+            GameObjectType::EnemyBullet => (Some(assets.image("/img/bullet.png")?), 0.0),
+            GameObjectType::HomingMine => (Some(assets.image("/img/rock1.png")?), 0.0),
+            GameObjectType::FloatingMine => (Some(assets.image("/img/rock4.png")?), 0.0),
+            GameObjectType::Meteor => (Some(assets.image("/img/rock.png")?), 0.0),
+            GameObjectType::Debris => (Some(assets.image("/img/rock4.png")?), 0.0),
+            GameObjectType::Barricade => (Some(assets.image("/img/rock.png")?), 0.0),
+            GameObjectType::BonusStar => (Some(assets.image("/img/shield.png")?), 0.0),
+            GameObjectType::ScoreGem => (Some(assets.image("/img/bullet.png")?), 0.0),
+            GameObjectType::Drone => (Some(assets.image("/img/player.png")?), 0.0),
+            GameObjectType::DroneShot => (Some(assets.image("/img/bullet.png")?), 0.0),
+            GameObjectType::MissileAmmo | GameObjectType::SpreadAmmo | GameObjectType::DronePickup | GameObjectType::EmpPickup => {
+                // 道具外观由 pickups 表决定
+                let info = pickups::info(&object_type).expect("pickup without info");
+                (Some(assets.image(info.image)?), 0.0)
+            }
+            GameObjectType::SpreadShot => (Some(assets.image("/img/bullet.png")?), 0.0),  // 使用子弹图片
 
 
         };
 
-        Ok(GameObject {
+        Ok(Self::with_image(image, rotation, x, y, width, height, object_type))
+    }
+
+    // 贴图已经取好（或不需要贴图）时直接构造对象
+    fn with_image(image: Option<Image>, rotation: f32, x: f32, y: f32, width: f32, height: f32, object_type: GameObjectType) -> Self {
+        GameObject {
             pos: Vec2::new(x, y),
+            prev_pos: Vec2::new(x, y),
             base_size: Vec2::new(width, height),
             speed: Vec2::ZERO,
             image,
             rotation,
             object_type,
             target: None,
-        })
+            tint: Color::WHITE,
+            enemy_kind: None,
+            age: 0.0,
+            lifetime: None,
+            heading: Vec2::Y,
+            damage: 0,
+            regen_timer: 0.0,
+            status: StatusEffects::default(),
+            grazed: false,
+            flash_timer: 0.0,
+            invulnerable_timer: 0.0,
+            dive_target: None,
+            dive_timer: 0.0,
+            piercing: false,
+            launch_timer: 0.0,
+            ricochets: 0,
+            shield: 0,
+            path: None,
+            hitbox_scale: 1.0,
+            exit_warned: false,
+            shooter: None,
+        }
     }
 
-    fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize) {
+    // alpha 为两次 tick 之间的插值系数
+    fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize, alpha: f32) {
         if let Some(ref image) = self.image {
-            let scaled_pos = window_size.scale_vec2(self.pos);
+            let scaled_pos = window_size.scale_vec2(self.prev_pos.lerp(self.pos, alpha));
             let scaled_size = window_size.scale_vec2(self.base_size);
 
-            canvas.draw(
-                image,
-                DrawParam::default()
-                    .dest(scaled_pos)
-                    .rotation(self.rotation)
-                    .offset(Vec2::new(0.5, 0.5))  // 这里使用了 0.5 offset，意味着旋转中心在图片中心
-                    .scale(Vec2::new(
-                        scaled_size.x / image.width() as f32,
-                        scaled_size.y / image.height() as f32
-                    ))
-            );
+            // 无敌期间隔帧变淡形成闪烁
+            let mut color = self.status.tint(self.tint);
+            if self.invulnerable_timer > 0.0 && (self.invulnerable_timer / BLINK_INTERVAL) as u32 % 2 == 1 {
+                color.a *= 0.25;
+            }
+            let param = DrawParam::default()
+                .dest(scaled_pos)
+                .rotation(self.rotation)
+                .offset(Vec2::new(0.5, 0.5))  // 这里使用了 0.5 offset，意味着旋转中心在图片中心
+                .scale(Vec2::new(
+                    scaled_size.x / image.width() as f32,
+                    scaled_size.y / image.height() as f32
+                ));
+            canvas.draw(image, param.color(color));
+
+            // 受击闪白：叠加模式再画一遍，逐渐淡出
+            if self.flash_timer > 0.0 {
+                canvas.set_blend_mode(graphics::BlendMode::ADD);
+                canvas.draw(image, param.color(Color::new(1.0, 1.0, 1.0, self.flash_timer / HIT_FLASH_DURATION)));
+                canvas.set_blend_mode(graphics::BlendMode::ALPHA);
+            }
         }
     }
 
+    // 受击时闪白
+    fn flash(&mut self) {
+        self.flash_timer = HIT_FLASH_DURATION;
+    }
+
+    fn is_invulnerable(&self) -> bool {
+        self.invulnerable_timer > 0.0
+    }
+
+    // 推进闪白与无敌计时
+    fn update_feedback(&mut self, dt: f32) {
+        self.flash_timer = (self.flash_timer - dt).max(0.0);
+        self.invulnerable_timer = (self.invulnerable_timer - dt).max(0.0);
+    }
+
     // 添加导弹追踪逻辑
-    fn update_guided_missile(&mut self, enemies: &Vec<GameObject>, window_size: &WindowSize) {
+    fn update_guided_missile(&mut self, enemies: &Arena<GameObject>, window_size: &WindowSize, frame_scale: f32) {
         const MISSILE_SPEED: f32 = 4.0;  // 导弹基础速度
         const TURN_RATE: f32 = 0.1;      // 转向速率
 
-        if let Some(target_idx) = self.target {
-            if target_idx < enemies.len() {
-                let target = &enemies[target_idx];
-                let direction = target.pos - self.pos;
-                let distance = direction.length();
-
-                if distance > 0.0 {
-                    // 计算目标角度
-                    let target_angle = direction.y.atan2(direction.x);
-
-                    // 平滑转向
-                    let angle_diff = target_angle - self.rotation;
-                    let angle_diff = if angle_diff > std::f32::consts::PI {
-                        angle_diff - 2.0 * std::f32::consts::PI
-                    } else if angle_diff < -std::f32::consts::PI {
-                        angle_diff + 2.0 * std::f32::consts::PI
-                    } else {
-                        angle_diff
-                    };
+        // 目标已被摧毁时改为追踪离导弹最近的敌人
+        if self.target.and_then(|target| enemies.get(target)).is_none() {
+            self.target = enemies
+                .entries()
+                .min_by(|(_, a), (_, b)| a.pos.distance(self.pos).total_cmp(&b.pos.distance(self.pos)))
+                .map(|(handle, _)| handle);
+        }
+        if let Some(target) = self.target.and_then(|target| enemies.get(target)) {
+            let direction = target.pos - self.pos;
+            let distance = direction.length();
+
+            if distance > 0.0 {
+                // 计算目标角度
+                let target_angle = direction.y.atan2(direction.x);
+
+                // 平滑转向
+                let angle_diff = target_angle - self.rotation;
+                let angle_diff = if angle_diff > std::f32::consts::PI {
+                    angle_diff - 2.0 * std::f32::consts::PI
+                } else if angle_diff < -std::f32::consts::PI {
+                    angle_diff + 2.0 * std::f32::consts::PI
+                } else {
+                    angle_diff
+                };
 
-                    self.rotation += angle_diff * TURN_RATE;
+                self.rotation += angle_diff * (TURN_RATE * frame_scale).min(1.0);
 
-                    // 更新速度
-                    self.speed.x = self.rotation.cos() * MISSILE_SPEED * window_size.scale_x;
-                    self.speed.y = self.rotation.sin() * MISSILE_SPEED * window_size.scale_y;
-                }
+                // 更新速度
+                self.speed.x = self.rotation.cos() * MISSILE_SPEED * window_size.scale_x;
+                self.speed.y = self.rotation.sin() * MISSILE_SPEED * window_size.scale_y;
             }
         }
     }
 
 
-    // 添加一个新方法来绘制碰撞范围
-    fn draw_collision_circle(&self, ctx: &mut ggez::Context, canvas: &mut Canvas, window_size: &WindowSize) -> GameResult {
-        let center = self.pos;
-
-        // 在 draw_collision_circle 中的半径设置
-        let radius = match self.object_type {
-            GameObjectType::Bullet => self.base_size.x * 0.8,      // 匹配碰撞检测逻辑
-            GameObjectType::Enemy => self.base_size.x * 0.45,      // 匹配碰撞检测逻辑
-            GameObjectType::Player => self.base_size.x * 0.4,      // 保持不变
-            GameObjectType::GuidedMissile => self.base_size.x * 1.0, // 导弹的碰撞范围稍大
-            GameObjectType::MissileAmmo => self.base_size.x * 0.6,   // 弹药包的碰撞范围
-            GameObjectType::SpreadShot => self.base_size.x * 0.8,    // 与普通子弹相同
-            GameObjectType::SpreadAmmo => self.base_size.x * 0.6,    // 与普通弹药包相同
-        };
-
-
-        let scaled_center = window_size.scale_vec2(center);
-        let scaled_radius = radius * window_size.scale_x.min(window_size.scale_y);
-
-        // 在 draw_collision_circle 中的颜色设置
-        let color = match self.object_type {
-            GameObjectType::Bullet => Color::new(1.0, 1.0, 0.0, 0.5),    // 黄色
-            GameObjectType::Enemy => Color::new(1.0, 0.0, 0.0, 0.5),     // 红色
-            GameObjectType::Player => Color::new(0.0, 1.0, 0.0, 0.5),    // 绿色
-            GameObjectType::GuidedMissile => Color::new(1.0, 0.0, 1.0, 0.5), // 紫色
-            GameObjectType::MissileAmmo => Color::new(0.0, 1.0, 1.0, 0.5),   // 青色
-            GameObjectType::SpreadShot => Color::new(1.0, 0.5, 0.0, 0.5),    // 橙色
-            GameObjectType::SpreadAmmo => Color::new(1.0, 0.5, 0.0, 0.5),    // 橙色
-        };
+    // 绘制装甲敌人发光的弱点，受伤越多颜色越红
+    fn draw_weak_points(
+        &self,
+        ctx: &mut ggez::Context,
+        canvas: &mut Canvas,
+        window_size: &WindowSize,
+        armor: &Armor,
+        alpha: f32,
+        pulse: f32,
+    ) -> GameResult {
+        let offset = self.prev_pos.lerp(self.pos, alpha) - self.pos;
+        let health = 1.0 - self.damage as f32 / armor.hits as f32;
+        let color = Color::new(1.0, 0.3 + 0.6 * health, 0.2, pulse);
+        for point in self.weak_points(armor) {
+            let center = window_size.scale_vec2(point.center + offset);
+            let radius = point.radius * window_size.scale_x.min(window_size.scale_y);
+            let glow = Mesh::new_circle(
+                ctx,
+                graphics::DrawMode::fill(),
+                [center.x, center.y],
+                radius,
+                0.1,
+                color,
+            )?;
+            canvas.draw(&glow, DrawParam::default());
+        }
+        Ok(())
+    }
 
-        let circle = Mesh::new_circle(
-            ctx,
-            graphics::DrawMode::stroke(2.0),
-            [scaled_center.x, scaled_center.y],
-            scaled_radius,
-            0.1,
-            color,
-        )?;
+    // 护盾泡的碰撞圆（基准坐标），包住整个敌人
+    fn shield_collider(&self, shield: &Shield) -> Collider {
+        Collider::Circle(Circle::new(self.pos, self.base_size.x * shield.radius))
+    }
 
-        canvas.draw(&circle, DrawParam::default());
+    // 半透明的护盾泡：越弱越淡，每吸收一点伤害多一道从边缘向内的裂纹；破裂后不再绘制
+    fn draw_shield(
+        &self,
+        ctx: &mut ggez::Context,
+        canvas: &mut Canvas,
+        window_size: &WindowSize,
+        shield: &Shield,
+        alpha: f32,
+    ) -> GameResult {
+        if self.shield == 0 {
+            return Ok(());
+        }
+        let center = window_size.scale_vec2(self.prev_pos.lerp(self.pos, alpha));
+        let radius = self.base_size.x * shield.radius * window_size.scale_x.min(window_size.scale_y);
+        let health = self.shield as f32 / shield.hits as f32;
+        let color = Color::new(0.5, 0.9, 1.0, 1.0);
+
+        let mut builder = graphics::MeshBuilder::new();
+        builder.circle(graphics::DrawMode::fill(), center, radius, 0.5, Color { a: 0.1 + 0.15 * health, ..color })?;
+        builder.circle(graphics::DrawMode::stroke(2.0), center, radius, 0.5, Color { a: 0.3 + 0.5 * health, ..color })?;
+        // 裂纹按黄金角错开，随机体一起旋转
+        for i in 0..shield.hits.saturating_sub(self.shield) {
+            let angle = self.rotation + 0.7 + i as f32 * 2.4;
+            let edge = Vec2::from_angle(angle);
+            let bend = Vec2::from_angle(angle + 0.5);
+            let points = [center + edge * radius, center + edge * radius * 0.6, center + bend * radius * 0.35];
+            builder.line(&points, 1.5, Color { a: 0.9, ..color })?;
+        }
+        let mesh = Mesh::from_data(ctx, builder.build());
+        canvas.draw(&mesh, DrawParam::default());
         Ok(())
     }
 
-    fn intersects(&self, other: &GameObject, window_size: &WindowSize) -> bool {
-        let self_center = self.pos;
-        let other_center = other.pos;
-
-        // 专门处理子弹和敌机的碰撞
-        let (self_radius, other_radius) = match (&self.object_type, &other.object_type) {
-            // 子弹打敌机的情况
-            (GameObjectType::Bullet, GameObjectType::Enemy) |
-            (GameObjectType::SpreadShot, GameObjectType::Enemy) => {
-                let bullet_radius = self.base_size.x * 0.8;
-                let enemy_radius = other.base_size.x * 0.45;
-                (bullet_radius, enemy_radius)
-            },
-            // 敌机被子弹打的情况
-            (GameObjectType::Enemy, GameObjectType::Bullet) |
-            (GameObjectType::Enemy, GameObjectType::SpreadShot) => {
-                let enemy_radius = self.base_size.x * 0.45;
-                let bullet_radius = other.base_size.x * 0.8;
-                (enemy_radius, bullet_radius)
-            },
-            // 玩家和弹药包的碰撞
-            (GameObjectType::Player, GameObjectType::MissileAmmo) |
-            (GameObjectType::Player, GameObjectType::SpreadAmmo) |
-            (GameObjectType::MissileAmmo, GameObjectType::Player) |
-            (GameObjectType::SpreadAmmo, GameObjectType::Player) => {
-                let radius = self.base_size.x.min(self.base_size.y) * 0.6;
-                (radius, radius)
-            },
-            // 玩家和敌机的碰撞
-            (GameObjectType::Player, GameObjectType::Enemy) |
-            (GameObjectType::Enemy, GameObjectType::Player) => {
-                let radius = self.base_size.x.min(self.base_size.y) * 0.4;
-                (radius, radius)
-            },
-            // 其他情况
-            _ => {
-                let radius = self.base_size.x.min(self.base_size.y) * 0.4;
-                (radius, radius)
+    // 俯冲敌人的行为，返回是否仍按普通方式巡航
+    // 越过触发高度后悬停预警，之后沿锁定方向加速冲刺
+    fn update_dive(&mut self, dive: &Dive, player: Vec2, dt: f32, frame_scale: f32) -> bool {
+        let on_screen = self.pos.y >= 0.0 && self.pos.y <= BASE_WINDOW_HEIGHT
+            && self.pos.x >= 0.0 && self.pos.x <= BASE_WINDOW_WIDTH;
+        if self.dive_target.is_none() {
+            if !on_screen || self.pos.y < dive.trigger_y {
+                return true;
             }
-        };
-
-        // 计算实际的碰撞距离
-        let scaled_self_center = window_size.scale_vec2(self_center);
-        let scaled_other_center = window_size.scale_vec2(other_center);
-        let scaled_self_radius = self_radius * window_size.scale_x.min(window_size.scale_y);
-        let scaled_other_radius = other_radius * window_size.scale_x.min(window_size.scale_y);
+            self.dive_target = Some(player);
+            self.dive_timer = dive.warning;
+            self.heading = (player - self.pos).try_normalize().unwrap_or(Vec2::Y);
+            self.rotation = self.heading.y.atan2(self.heading.x) + std::f32::consts::FRAC_PI_2;
+            self.speed = Vec2::ZERO;
+        }
 
-        // 计算中心点距离并判断是否碰撞
-        let distance = scaled_self_center.distance(scaled_other_center);
-        distance < (scaled_self_radius + scaled_other_radius)
+        let time_scale = self.status.speed_scale();
+        if self.dive_timer > 0.0 {
+            self.dive_timer -= dt * time_scale;
+        } else {
+            self.speed = (self.speed + self.heading * dive.accel * frame_scale * time_scale)
+                .clamp_length_max(dive.max_speed);
+        }
+        false
     }
-}
 
-// 粒子结构体
-#[derive(Clone)]
-struct Particle {
-    pos: Vec2,
-    vel: Vec2,
-    color: Color,
-    lifetime: f32,
-    size: f32,
-}
+    // 装甲敌人两侧的弱点（基准坐标）
+    fn weak_points(&self, armor: &Armor) -> [Circle; 2] {
+        enemies::WEAK_POINT_OFFSETS.map(|(x, y)| {
+            Circle::offset(
+                self.pos,
+                self.rotation,
+                Vec2::new(x, y) * self.base_size,
+                armor.weak_point_radius * self.base_size.x,
+            )
+        })
+    }
 
-impl Particle {
-    fn new(pos: Vec2, vel: Vec2, color: Color, size: f32) -> Self {
-        Particle {
-            pos,
-            vel,
-            color,
-            lifetime: PARTICLE_LIFETIME,
-            size,
+    // 离屏回收策略：从屏幕外进入或斜穿屏幕的对象只在越界且继续远离时移除
+    fn despawn(&self) -> Despawn {
+        match self.object_type {
+            GameObjectType::Player | GameObjectType::Drone | GameObjectType::Barricade => Despawn::Never,
+            // 直线飞行的子弹按速度方向判断，斜射的扇形子弹从两侧离开时同样会被回收；
+            // 还能反弹的子弹在回收前已被折回屏幕内，反弹后朝内飞行，直到再次离开才回收
+            GameObjectType::Bullet | GameObjectType::SpreadShot | GameObjectType::DroneShot => {
+                Despawn::Leaving { margin: 20.0 }
+            }
+            // 导弹和追踪地雷会转向，出界后不再等它们绕回来
+            GameObjectType::GuidedMissile | GameObjectType::EnemyBullet | GameObjectType::HomingMine => {
+                Despawn::Outside { margin: 20.0 }
+            }
+            GameObjectType::Enemy => Despawn::Leaving { margin: ENEMY_SPAWN_MARGIN },
+            GameObjectType::FloatingMine => Despawn::Leaving { margin: 30.0 },
+            GameObjectType::Meteor => Despawn::Leaving { margin: 120.0 },
+            GameObjectType::Debris => Despawn::Leaving { margin: 60.0 },
+            GameObjectType::MissileAmmo
+            | GameObjectType::SpreadAmmo
+            | GameObjectType::DronePickup
+            | GameObjectType::EmpPickup
+            | GameObjectType::BonusStar
+            | GameObjectType::ScoreGem => Despawn::Below { margin: 0.0 },
         }
     }
 
-    fn update(&mut self, dt: f32, window_size: &WindowSize) {
-        let scaled_vel = window_size.scale_vec2(self.vel);
-        self.pos += scaled_vel * dt;
-        self.lifetime -= dt;
-        self.color.a = (self.lifetime / PARTICLE_LIFETIME).min(1.0);
-        self.size = self.size * (self.lifetime / PARTICLE_LIFETIME).max(0.1);
+    fn is_off_screen(&self) -> bool {
+        // 敌人的位移主要沿 heading，speed 只是额外的漂移
+        let velocity = match self.object_type {
+            GameObjectType::Enemy => self.heading,
+            _ => self.speed,
+        };
+        self.despawn().should_remove(self.pos, velocity)
     }
-}
 
-// 粒子系统结构体
-struct ParticleSystem {
-    particles: Vec<Particle>,
-}
+    // 越过屏幕左右边缘时镜像回屏幕内并水平反弹，消耗一次反弹次数。
+    // 在离屏回收之前调用：还能反弹的子弹越界后当场被折回，不会被当作离屏移除
+    fn ricochet(&mut self) {
+        let out_left = self.pos.x < 0.0 && self.speed.x < 0.0;
+        let out_right = self.pos.x > BASE_WINDOW_WIDTH && self.speed.x > 0.0;
+        if self.ricochets == 0 || !(out_left || out_right) {
+            return;
+        }
+        self.ricochets -= 1;
+        self.pos.x = if out_left { -self.pos.x } else { 2.0 * BASE_WINDOW_WIDTH - self.pos.x };
+        self.speed.x = -self.speed.x;
+        self.rotation = -self.rotation;
+    }
 
-impl ParticleSystem {
-    fn new() -> Self {
-        ParticleSystem {
-            particles: Vec::with_capacity(MAX_PARTICLES),
+    // 被屏幕边缘挡住时去掉该方向的速度，免得贴着边缘时还要先减速才能离开；穿越边缘不算
+    fn stop_at_edges(&mut self, moved: Vec2) {
+        let blocked = self.pos - moved;
+        if blocked.x != 0.0 && blocked.x.abs() < BASE_WINDOW_WIDTH / 2.0 {
+            self.speed.x = 0.0;
+        }
+        if blocked.y != 0.0 {
+            self.speed.y = 0.0;
         }
     }
 
-    fn update(&mut self, dt: f32, window_size: &WindowSize) {
-        self.particles.retain_mut(|particle| {
-            particle.update(dt, window_size);
-            particle.lifetime > 0.0
-        });
+    // 非普通稀有度的道具按每帧下落 fall_speed 像素（60fps）还有几秒掉出屏幕；其他对象为 None
+    fn pickup_exit_time(&self, fall_speed: f32) -> Option<f32> {
+        pickups::info(&self.object_type).filter(|info| info.rarity != pickups::Rarity::Common)?;
+        let frames = self.despawn().time_to_exit(self.pos, Vec2::new(0.0, fall_speed))?;
+        Some(frames / REFERENCE_FPS)
     }
 
-    fn add_explosion(&mut self, pos: Vec2, color: Color, window_size: &WindowSize) {
-        let mut rng = rand::thread_rng();
-        let available_slots = MAX_PARTICLES.saturating_sub(self.particles.len());
-        let particles_to_add = EXPLOSION_PARTICLES.min(available_slots as i32);
+    fn is_expired(&self) -> bool {
+        self.lifetime.is_some_and(|lifetime| self.age >= lifetime)
+    }
 
-        for _ in 0..particles_to_add {
-            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
-            let base_speed  = PARTICLE_SPEED * window_size.scale_x.min(window_size.scale_y);
-            let speed = rng.gen_range(base_speed * 0.5..base_speed);
-            let vel = Vec2::new(angle.cos() * speed, angle.sin() * speed);
-            let size = PARTICLE_SIZE * window_size.scale_x.min(window_size.scale_y);
-            let scaled_size = rng.gen_range(size * 0.5..size * 1.5);
+    // 离屏或超过存在时间上限的对象在本 tick 末尾移除
+    fn should_despawn(&self) -> bool {
+        self.is_off_screen() || self.is_expired()
+    }
 
-            self.particles.push(Particle::new(pos, vel, color, scaled_size));
+    // 碰撞形状：细长的子弹和导弹使用胶囊体，道具使用矩形，其余对象使用圆
+    fn collider(&self) -> Collider {
+        let circle = |radius: f32| Collider::Circle(Circle::new(self.pos, radius));
+        match self.object_type {
+            GameObjectType::Bullet | GameObjectType::SpreadShot | GameObjectType::DroneShot => {
+                Collider::capsule(self.pos, self.base_size.y, self.base_size.x * 0.8, self.rotation)
+            }
+            // 导弹的 rotation 是飞行方向角，与子弹的朝向约定相差 90 度
+            GameObjectType::GuidedMissile => Collider::capsule(
+                self.pos,
+                self.base_size.y,
+                self.base_size.x,
+                self.rotation + std::f32::consts::FRAC_PI_2,
+            ),
+            GameObjectType::EnemyBullet => Collider::capsule(
+                self.pos,
+                self.base_size.y,
+                self.base_size.x.min(self.base_size.y) * 0.4,
+                self.rotation,
+            ),
+            GameObjectType::HomingMine => circle(self.base_size.x.min(self.base_size.y) * 0.4),
+            GameObjectType::Enemy | GameObjectType::FloatingMine | GameObjectType::Meteor | GameObjectType::Debris => {
+                circle(self.base_size.x * 0.45)
+            }
+            GameObjectType::Player | GameObjectType::Drone => circle(self.base_size.x * 0.4),
+            GameObjectType::BonusStar | GameObjectType::ScoreGem => circle(self.base_size.x * 0.5),
+            // 道具图标和路障是方形的
+            GameObjectType::MissileAmmo
+            | GameObjectType::SpreadAmmo
+            | GameObjectType::DronePickup
+            | GameObjectType::EmpPickup
+            | GameObjectType::Barricade => {
+                Collider::OrientedRect {
+                    center: self.pos,
+                    half_extents: self.base_size * 0.5,
+                    rotation: self.rotation,
+                }
+            }
         }
     }
 
-    fn draw(&self, ctx: &mut ggez::Context, canvas: &mut Canvas, window_size: &WindowSize) -> GameResult {
-        for particle in &self.particles {
-            let scaled_pos = window_size.scale_vec2(particle.pos);
-            let scaled_size = particle.size * window_size.scale_x.min(window_size.scale_y);
+    // 调试模式下绘制碰撞形状
+    fn draw_collider(&self, ctx: &mut ggez::Context, canvas: &mut Canvas, window_size: &WindowSize) -> GameResult {
+        let color = match self.object_type {
+            GameObjectType::Bullet => Color::new(1.0, 1.0, 0.0, 0.5),    // 黄色
+            GameObjectType::Enemy => Color::new(1.0, 0.0, 0.0, 0.5),     // 红色
+            GameObjectType::Player => Color::new(0.0, 1.0, 0.0, 0.5),    // 绿色
+            GameObjectType::GuidedMissile => Color::new(1.0, 0.0, 1.0, 0.5), // 紫色
+            GameObjectType::MissileAmmo => Color::new(0.0, 1.0, 1.0, 0.5),   // 青色
+            GameObjectType::SpreadShot => Color::new(1.0, 0.5, 0.0, 0.5),    // 橙色
+            GameObjectType::SpreadAmmo => Color::new(1.0, 0.5, 0.0, 0.5),    // 橙色
+            GameObjectType::EnemyBullet | GameObjectType::HomingMine => Color::new(1.0, 0.2, 0.2, 0.5), // 红色
+            GameObjectType::FloatingMine => Color::new(1.0, 0.6, 0.2, 0.5),  // 橙红色
+            GameObjectType::Meteor => Color::new(0.8, 0.5, 0.3, 0.5),        // 褐色
+            GameObjectType::Debris => Color::new(0.6, 0.6, 0.7, 0.5),        // 灰色
+            GameObjectType::Barricade => Color::new(0.5, 0.8, 0.5, 0.5),     // 灰绿色
+            GameObjectType::Drone | GameObjectType::DroneShot | GameObjectType::DronePickup => Color::new(0.4, 0.7, 1.0, 0.5), // 浅蓝色
+            GameObjectType::EmpPickup => Color::new(1.0, 1.0, 0.4, 0.5),     // 淡黄色
+            GameObjectType::BonusStar => Color::new(1.0, 0.85, 0.2, 0.5),    // 金色
+            GameObjectType::ScoreGem => Color::new(0.4, 1.0, 0.8, 0.5),      // 青绿色
+        };
+
+        let scale = window_size.scale_x.min(window_size.scale_y);
+        let mode = graphics::DrawMode::stroke(2.0);
+        let mut builder = graphics::MeshBuilder::new();
+        match self.collider() {
+            Collider::Circle(circle) => {
+                builder.circle(mode, window_size.scale_vec2(circle.center), circle.radius * scale, 0.1, color)?;
+            }
+            Collider::Capsule { a, b, radius } => {
+                // 两端的圆加上两条侧边
+                let side = (b - a).perp().normalize_or_zero() * radius;
+                builder.circle(mode, window_size.scale_vec2(a), radius * scale, 0.1, color)?;
+                builder.circle(mode, window_size.scale_vec2(b), radius * scale, 0.1, color)?;
+                for offset in [side, -side] {
+                    builder.line(
+                        &[window_size.scale_vec2(a + offset), window_size.scale_vec2(b + offset)],
+                        2.0,
+                        color,
+                    )?;
+                }
+            }
+            Collider::OrientedRect { center, half_extents, rotation } => {
+                let corners: Vec<Vec2> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                    .iter()
+                    .map(|&(x, y)| {
+                        let local = Vec2::new(x, y) * half_extents;
+                        window_size.scale_vec2(center + Vec2::from_angle(rotation).rotate(local))
+                    })
+                    .collect();
+                builder.polygon(mode, &corners, color)?;
+            }
+        }
 
-            // 创建一个简单的矩形代替圆形
-            let rect = graphics::Rect::new(
-                scaled_pos.x - scaled_size/2.0,
-                scaled_pos.y - scaled_size/2.0,
-                scaled_size,
-                scaled_size,
-            );
+        let mesh = Mesh::from_data(ctx, builder.build());
+        canvas.draw(&mesh, DrawParam::default());
+        Ok(())
+    }
 
-            let draw_param = DrawParam::default()
-                .color(particle.color);
+    fn intersects(&self, other: &GameObject) -> bool {
+        self.collider().intersects(&other.collider())
+    }
 
-            // 直接绘制矩形
-            canvas.draw(&graphics::Quad, draw_param.dest(rect.point()).scale([rect.w, rect.h]));
+    // 受击判定：圆形碰撞体按 hitbox_scale 缩放，只用于被敌方击中的判定
+    fn hurtbox(&self) -> Collider {
+        match self.collider() {
+            Collider::Circle(circle) => Collider::Circle(Circle::new(circle.center, circle.radius * self.hitbox_scale)),
+            collider => collider,
         }
-        Ok(())
+    }
+
+    // 敌方对象是否击中 target（按 target 的受击判定）
+    fn hits(&self, target: &GameObject) -> bool {
+        self.collider().intersects(&target.hurtbox())
     }
 }
 
 // 声音系统结构体
 struct SoundEffects {
-    shoot_sound: Source,
-    explosion_sound: Source,
+    backend: Box<dyn Audio>, // 新增：实际播放音频的后端（可能是静默实现）
 }
 
 impl SoundEffects {
-    fn new(ctx: &mut ggez::Context) -> GameResult<Self> {
-        let shoot_sound = Source::new(ctx, "/sound/shoot.wav")?;
-        let explosion_sound = Source::new(ctx, "/sound/expl1.wav")?;
+    // enabled 为 false（--no-audio）、音频文件缺失或音效加载失败时使用静默后端，游戏照常运行。
+    // ggez 在创建 Context 时就会打开默认音频设备，没有音频设备的机器需要不带 audio 特性构建
+    fn new(ctx: &mut ggez::Context, enabled: bool, assets: &Assets) -> Self {
+        let backend: Box<dyn Audio> = if !enabled {
+            info!("Audio disabled by --no-audio");
+            Box::new(NullAudio)
+        } else if !assets.has_sounds() {
+            warn!("No sound files loaded, continuing without sound");
+            Box::new(NullAudio)
+        } else {
+            match sounds::open(ctx, assets) {
+                Ok(audio) => audio,
+                Err(err) => {
+                    warn!("Audio unavailable, continuing without sound: {}", err);
+                    Box::new(NullAudio)
+                }
+            }
+        };
+        SoundEffects { backend }
+    }
 
-        Ok(SoundEffects {
-            shoot_sound,
-            explosion_sound,
-        })
+    fn play(&mut self, ctx: &mut ggez::Context, event: SoundEvent) -> GameResult {
+        self.backend.play(ctx, event)
+    }
+
+    // 循环播放背景音乐，替换正在播放的音乐
+    fn play_music(&mut self, ctx: &mut ggez::Context, path: &str, pitch: f32) -> GameResult {
+        self.backend.play_music(ctx, path, pitch)
+    }
+
+    fn stop_music(&mut self, ctx: &mut ggez::Context) -> GameResult {
+        self.backend.stop_music(ctx)
     }
 
-    fn play_shoot(&mut self, ctx: &mut ggez::Context) -> GameResult {
-        if self.shoot_sound.playing() {
-            self.shoot_sound.stop(ctx)?;
+    // 按主音量、音效音量与静音开关设置各音效的实际音量，mute 为额外的强制静音
+    fn apply_volume(&mut self, config: &Config, mute: bool) {
+        let volume = if mute || config.muted { 0.0 } else { config.master_volume * config.sfx_volume };
+        self.backend.set_volume(volume);
+    }
+}
+
+// 当前场景：主菜单、出击装备选择、游戏中（含续关倒计时）、一局结束后的结算界面或主菜单闲置时的演示
+enum Scene {
+    MainMenu(MainMenu),
+    Playing,
+    GameOver(GameOverScreen),
+    Loadout(LoadoutScreen), // 开始前选择出击装备
+    Practice(PracticeScreen), // 练习模式的起点与装备设置
+    Demo(Replay), // 玩家位置由内置回放驱动，自动射击且不会被击毁
+    LevelEditor(LevelEditor), // 从主菜单打开的关卡编辑器
+}
+
+impl Scene {
+    fn name(&self) -> &'static str {
+        match self {
+            Scene::MainMenu(_) => "main menu",
+            Scene::Playing => "playing",
+            Scene::GameOver(_) => "game over",
+            Scene::Loadout(_) => "loadout",
+            Scene::Practice(_) => "practice",
+            Scene::Demo(_) => "demo",
+            Scene::LevelEditor(_) => "level editor",
         }
-        self.shoot_sound.play(ctx)?;
-        Ok(())
     }
 
-    fn play_explosion(&mut self, ctx: &mut ggez::Context) -> GameResult {
-        if self.explosion_sound.playing() {
-            self.explosion_sound.stop(ctx)?;
+    // 切换到 next 时使用的过渡效果；同一种场景之间直接切换
+    fn transition_to(&self, next: &Scene) -> Option<TransitionKind> {
+        match (self, next) {
+            (Scene::MainMenu(_), Scene::Loadout(_) | Scene::Practice(_)) | (Scene::Loadout(_) | Scene::Practice(_), Scene::MainMenu(_)) => {
+                Some(TransitionKind::Slide)
+            }
+            (_, Scene::Playing) => Some(TransitionKind::Iris),
+            _ if self.name() == next.name() => None,
+            _ => Some(TransitionKind::Fade),
         }
-        self.explosion_sound.play(ctx)?;
-        Ok(())
     }
 }
 
+// 一帧中的各个绘制部分，由 draw() 按所在层排序后依次绘制
+#[derive(Clone, Copy, Debug)]
+enum DrawPass {
+    Stars,
+    Pickups,
+    Hazards,
+    Enemies,
+    Boss,
+    Ghost,
+    Player,
+    PlayerBullets,
+    EnemyBullets,
+    Hitbox,     // 专注模式下的玩家判定点
+    Viewport,   // 自由镜头下标出正常视野与敌人生成线
+    Ai,         // 调试模式下的 AI 目标线、导弹转向与僚机编队位置
+    Effects,    // 爆炸、粒子与冲击波
+    Warnings,   // 攻击预警
+    Popups,
+    Indicators, // 屏幕外威胁指示与雷达
+    Status,     // 左上角与右上角的状态文字
+    BossBar,
+    Alerts,     // 最后一搏、流星雨警告与续关倒计时
+    Highlight,  // 精彩片段的保存提示
+    MessageBar,
+    Debug,
+    Pause,
+    Scene,      // 主菜单、结算、装备与演示界面
+    Menus,      // 设置、档案、机库与商店
+    Transition,
+}
+
+// 可回滚的模拟状态：一个 tick 的模拟会读写的全部局内状态。
+// 粒子、爆炸动画、得分文字等表现层的状态不在其中，回退时保持原样
+#[derive(Clone)]
+struct SimState {
+    player: GameObject,
+    partner: Option<GameObject>,
+    partner_cooldown: Duration,
+    bullets: Arena<GameObject>,
+    enemies: Arena<GameObject>,
+    enemy_bullets: Vec<GameObject>,
+    hazards: Arena<GameObject>,
+    hazard_timer: f32,
+    meteors: Vec<GameObject>,
+    meteor_shower: Option<MeteorShower>,
+    debris: Vec<GameObject>,
+    barricades: Vec<GameObject>,
+    drones: Vec<GameObject>,
+    drone_fire_timer: f32,
+    boss: Option<(GameObject, Boss)>,
+    shockwaves: Vec<(Vec2, f32)>,
+    gravity_wells: Vec<GravityWell>,
+    warnings: Warnings,
+    artillery_timer: f32,
+    grazes: u32,
+    stats: RunStats,
+    log: RunLog,
+    chain: ChainTracker,
+    score: i32,
+    spawn_timer: Duration,
+    spawn_queue: SpawnQueue,
+    game_over: bool,
+    continues_left: u32,
+    shoot_cooldown: Duration,
+    recoil: f32,
+    missile_cooldown: Duration,
+    missile_ammo: i32,
+    ammo_spawn_timer: Duration,
+    ammo_items: Arena<GameObject>,
+    vacuum: Option<f32>,
+    bombs: u32,
+    last_stand: Option<f32>,
+    time_scale: f32,
+    has_spread_shot: bool,
+    focus_mode: bool,
+    rng: StdRng,
+    wave: u32,
+    wave_timer: Duration,
+    script: Option<WaveScript>,
+    background: Color,
+    run_time: f32,
+    level_timeline: Option<LevelTimeline>,
+}
+
 // 主游戏状态结构体
 struct MainState {
+    scene: Scene,                   // 新增：当前场景
     window_size: WindowSize,
     player: GameObject,
-    bullets: Vec<GameObject>,
-    enemies: Vec<GameObject>,
+    bullets: Arena<GameObject>,
+    enemies: Arena<GameObject>,
+    enemy_bullets: Vec<GameObject>, // 新增：敌方子弹与地雷
+    hazards: Arena<GameObject>,     // 新增：漂浮水雷等场景危险物
+    hazard_timer: f32,              // 新增：危险物生成计时（秒）
+    meteors: Vec<GameObject>,       // 新增：流星雨中的流星
+    meteor_shower: Option<MeteorShower>, // 新增：进行中的流星雨事件
+    debris: Vec<GameObject>,        // 新增：Boss 换阶段时崩落的残骸
+    barricades: Vec<GameObject>,    // 新增：生存模式中挡在玩家上方的路障
+    drones: Vec<GameObject>,        // 新增：跟随玩家的僚机
+    drone_fire_timer: f32,          // 新增：僚机射击计时（秒）
+    boss: Option<(GameObject, Boss)>, // 新增：当前的 Boss 及其阶段状态
+    shockwaves: Vec<(Vec2, f32)>,   // 新增：EMP 冲击波的中心与已扩散时间（秒）
+    gravity_wells: Vec<GravityWell>, // 新增：已放出、尚未引爆的引力井
+    warnings: Warnings,             // 新增：攻击落下前的闪烁预警
+    artillery_timer: f32,           // 新增：屏幕外炮击计时（秒）
+    grazes: u32,                    // 新增：本局擦弹次数
+    stats: RunStats,                // 新增：本局击毁、中弹、拾取等统计，由事件总线更新
+    log: RunLog,                    // 新增：本局中弹、拾取、击毁 Boss 等事件的时间记录
+    popups: ScorePopups,            // 新增：浮动得分文字
+    chain: ChainTracker,            // 新增：连锁击破计数
     score: i32,
     spawn_timer: Duration,
+    spawn_queue: SpawnQueue,        // 新增：超出同屏上限而排队的敌人
+    commands: CommandQueue,         // 新增：本 tick 积累的延迟指令（移除、生成、击毁），tick 末尾统一执行
+    cues: Cues,                     // 新增：本 tick 模拟发出的声音和视觉效果，tick 结束后播放
+    events: EventBus,               // 新增：本 tick 发布的游戏事件，tick 末尾分发给订阅者
+    rollback: Rollback<SimState>,   // 新增：调试模式下最近几秒的状态快照和输入
     game_over: bool,
     paused: bool,    // 新增：暂停状态
+    preview: bool,   // 新增：练习模式中冻结画面、预览敌弹轨迹
     shoot_cooldown: Duration,
+    recoil: f32,     // 新增：持续射击累积的后坐力（0~1）
     star_field: Vec<(Vec2, f32)>,
     particles: ParticleSystem,
+    explosions: Explosions,     // 新增：爆炸帧动画
     sounds: SoundEffects,
     missile_cooldown: Duration,  // 新增：导弹冷却时间
     missile_ammo: i32,           // 新增：当前导弹数量
     ammo_spawn_timer: Duration,  // 新增：弹药生成计时器
-    ammo_items: Vec<GameObject>, // 新增：场景中的弹药
-    p_key_pressed: bool,  // 新增：追踪 P 键状态
+    ammo_items: Arena<GameObject>, // 新增：场景中的弹药
+    vacuum: Option<f32>,           // 新增：清波后道具被吸向玩家的已进行时间（秒）
+    missile_queued: bool, // 新增：已按下导弹键、等待下一 tick 发射
+    bombs: u32,           // 新增：剩余炸弹数
+    bomb_queued: bool,    // 新增：已按下炸弹键、等待下一 tick 引爆
+    last_stand: Option<f32>, // 新增：最后一搏剩余的真实时间（秒）
+    time_scale: f32,      // 新增：全局时间流速，1 为正常速度
+    debug_speed: f32,     // 新增：调试模式下叠加在全局时间流速上的倍速
+    step_requested: bool, // 新增：暂停时请求单步推进一个 tick（调试用）
+    free_camera: Option<FreeCamera>, // 新增：调试自由镜头，None 时为正常视角
     has_spread_shot: bool,  // 新增：是否拥有扇形射击能力
+    focus_mode: bool,       // 新增：是否按住专注键（减速并显示判定点）
+    loadout: Loadout,       // 新增：本局的出击装备
+    rng: StdRng,            // 新增：可复现的随机数生成器
+    tick_accumulator: f32,  // 新增：固定步长累积的未模拟时间（秒）
+    wave: u32,              // 新增：当前波次
+    wave_timer: Duration,   // 新增：当前波次已进行的时间
+    script: Option<WaveScript>,  // 新增：当前波次正在执行的脚本
+    background: Color,           // 新增：背景色（可由脚本修改）
+    message_bar: MessageBar,       // 新增：底部剧情消息栏（脚本台词队列）
+    continues_left: u32,    // 新增：本局剩余续关次数
+    continue_timer: Option<f32>, // 新增：续关倒计时，None 表示不提供续关
+    config: Config,              // 新增：用户配置（按键绑定等）
+    config_dir: path::PathBuf,   // 新增：配置文件所在目录（当前档案的目录）
+    settings: Option<SettingsMenu>, // 新增：打开中的设置界面
+    profiles: ProfileStore,         // 新增：玩家档案存储
+    profile: String,                // 新增：当前玩家档案名
+    profile_menu: Option<ProfileMenu>, // 新增：打开中的档案选择界面
+    progress: Progress,             // 新增：局外点数与解锁（保存在档案中）
+    hangar: Option<Hangar>,         // 新增：打开中的机库界面
+    shop: Option<Shop>,             // 新增：波次间打开的商店
+    post: PostProcess,              // 新增：泛光后期处理
+    focused: bool,                  // 新增：窗口是否拥有焦点，失去焦点时静音并暂停
+    transition: Option<Transition>, // 新增：正在播放的场景过渡
+    pending_scene: Option<Scene>,   // 新增：过渡遮住画面后要切换到的场景
+    weapon_tier: u32,               // 新增：本局在商店购买的武器升级等级
+    pickup_icons: Vec<Image>,       // 新增：暂停界面道具图例使用的图标
+    mutators: Mutators,             // 新增：本局启用的挑战规则
+    mode: GameMode,                 // 新增：当前游戏模式
+    practice: Option<Practice>,     // 新增：练习模式的起点与装备，None 为正常游戏
+    daily: Option<DailyChallenge>,  // 新增：每日挑战参数（仅每日模式）
+    daily_history: DailyHistory,    // 新增：每日挑战历史
+    daily_practice: bool,           // 新增：今天已挑战过，本局不计入历史
+    run_time: f32,                  // 新增：本局生存时间（秒）
+    high_scores: HighScores,        // 新增：最高纪录
+    best_at_start: i32,             // 新增：开局时的最高分，用于判断本局是否刷新纪录
+    recording: Replay,              // 新增：本局的位置记录
+    ghost: Option<Replay>,          // 新增：最佳一局的幽灵回放
+    enemy_defs: EnemyTable,         // 新增：敌人定义（含 mod 覆盖）
+    tuning: Tuning,                 // 新增：无尽模式的难度曲线
+    mods: Vec<ModInfo>,             // 新增：已加载的 mod
+    options: LaunchOptions, // 新增：启动参数
+    run_seed: u64,                  // 新增：本局的随机种子
+    seed_override: Option<u64>,     // 新增：--seed 或分享码指定的种子，每局都从它开始
+    mutators_override: Option<Mutators>, // 新增：分享码指定的挑战规则
+    assets: Assets,                 // 新增：载入界面预加载的贴图与音频
+    highlights: HighlightRecorder,  // 新增：最近几秒画面的环形缓冲，按键保存为 GIF
+    jobs: Jobs<MainState>,          // 新增：后台执行的存档读写任务
+    hud_text: TextCache,            // 新增：HUD 文字缓存
+    enemy_paths: HashMap<String, Option<Rc<EnemyPath>>>, // 新增：已读取的敌人路线（按名称）
+    level: Option<Level>,           // 新增：--level 加载的关卡
+    level_timeline: Option<LevelTimeline>, // 新增：本局的关卡出怪时间轴
+    presence: PresenceReporter,     // 新增：向 Discord 等平台报告的在线状态
+    score_counter: ScoreCounter,    // 新增：HUD 上滚动增长的分数
+    window_title: String,           // 新增：当前窗口标题，变化时才重新设置
+    title_flash: Option<f32>,       // 新增：失去焦点时刷新纪录后标题闪烁的计时，回到窗口后停止
+    record_announced: bool,         // 新增：本局是否已提示过刷新纪录
+    coop_rules: Option<CoopRules>,  // 新增：联机合作的本局规则（由主机决定），None 为单人游戏
+    partner: Option<GameObject>,    // 新增：联机合作中的二号机
+    partner_cooldown: Duration,     // 新增：二号机的射击冷却
+    #[cfg(feature = "online")]
+    online: Option<net::Session>,   // 新增：联机合作的网络会话
 }
 
 impl MainState {
-    fn new(ctx: &mut ggez::Context) -> GameResult<MainState> {
+    fn new(ctx: &mut ggez::Context, options: LaunchOptions, mods: Vec<ModInfo>, assets: Assets) -> GameResult<MainState> {
         let window_size = WindowSize::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT);
 
-        // 修改玩家初始位置，考虑到中心点定位
-        let player = GameObject::new(
-            ctx,
-            BASE_WINDOW_WIDTH / 2.0,  // 水平居中
-            BASE_WINDOW_HEIGHT - 30.0, // 距离底部一定距离
-            50.0,
-            60.0,
-            GameObjectType::Player,
-        )?;
+        // 每日挑战使用当天的种子，指定了种子时使用固定种子，否则随机
+        let daily = options.daily.then(DailyChallenge::today);
+        let mut rng = match daily.map(|challenge| challenge.seed).or(options.seed) {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
 
         let mut star_field = Vec::new();
-        let mut rng = rand::thread_rng();
         for _ in 0..100 {
             star_field.push((
                 Vec2::new(
@@ -428,69 +1182,533 @@ impl MainState {
             ));
         }
 
-        let mut sounds = SoundEffects::new(ctx)?;
-        sounds.shoot_sound.set_volume(0.3);
-        sounds.explosion_sound.set_volume(0.5);
-
+        let explosions = Explosions::new(
+            SpriteSheet::new(
+                assets.image("/img/explosion_sheet.png")?,
+                EXPLOSION_FRAME_SIZE,
+                EXPLOSION_FRAME_SIZE,
+                EXPLOSION_FRAME_DURATION,
+            ),
+            SpriteSheet::new(
+                assets.image("/img/player_explosion_sheet.png")?,
+                PLAYER_EXPLOSION_FRAME_SIZE,
+                PLAYER_EXPLOSION_FRAME_SIZE,
+                EXPLOSION_FRAME_DURATION * 1.5,
+            ),
+        );
 
+        let pickup_icons = pickups::PICKUPS
+            .iter()
+            .map(|info| assets.image(info.image))
+            .collect::<GameResult<Vec<_>>>()?;
 
-        Ok(MainState {
+        // 存档按玩家档案分目录保存，启动时先使用上次的档案
+        let profiles = ProfileStore::new(ctx.fs.user_config_dir());
+        if let Err(err) = profiles.migrate_legacy() {
+            error!("Failed to migrate save files: {}", err);
+        }
+        let profile = profiles.last_used().unwrap_or_else(|| profiles::DEFAULT_PROFILE.to_string());
+        if let Err(err) = profiles.create(&profile) {
+            error!("Failed to create profile: {}", err);
+        }
+        let profile_menu = Some(ProfileMenu::new(&profiles, &profile));
+        let config_dir = profiles.dir(&profile);
+        let config = Config::load(&config_dir);
+        let level = match &options.level {
+            Some(path) => Some(Level::load(path).map_err(|err| {
+                GameError::ResourceLoadError(format!("Level {}: {}", path.display(), err))
+            })?),
+            None => None,
+        };
+        let high_scores = HighScores::load(&config_dir);
+        let daily_history = DailyHistory::load(&config_dir);
+        let daily_practice = daily.is_some_and(|challenge| daily_history.attempted(&challenge));
+        let mutators = daily.map_or(config.mutators, |challenge| challenge.mutators);
+        let progress = Progress::load(&config_dir);
+        let player = Self::create_player(&assets, mutators, progress.ship, config.difficulty)?;
+        let mode = if daily.is_some() { GameMode::Daily } else { GameMode::Endless };
+        let best_at_start = high_scores.get(mode).best_score;
+
+        let mut sounds = SoundEffects::new(ctx, !options.no_audio, &assets);
+        sounds.apply_volume(&config, options.mute);
+
+        let mut state = MainState {
+            scene: Scene::MainMenu(MainMenu::new()),
             window_size,
             player,
-            bullets: Vec::new(),
-            enemies: Vec::new(),
+            bullets: Arena::default(),
+            enemies: Arena::default(),
+            enemy_bullets: Vec::new(),
+            hazards: Arena::default(),
+            hazard_timer: 0.0,
+            meteors: Vec::new(),
+            meteor_shower: None,
+            debris: Vec::new(),
+            barricades: Vec::new(),
+            drones: Vec::new(),
+            drone_fire_timer: 0.0,
+            boss: None,
+            shockwaves: Vec::new(),
+            gravity_wells: Vec::new(),
+            warnings: Warnings::default(),
+            artillery_timer: 0.0,
+            grazes: 0,
+            stats: RunStats::default(),
+            log: RunLog::default(),
+            popups: ScorePopups::default(),
+            chain: ChainTracker::default(),
             score: 0,
             spawn_timer: Duration::from_secs(0),
+            spawn_queue: SpawnQueue::default(),
+            commands: CommandQueue::default(),
+            cues: Cues::default(),
+            events: EventBus::default(),
+            rollback: Rollback::new((REWIND_SECONDS * options.tick_rate as f32) as usize),
             game_over: false,
             paused: false,    // 初始化暂停状态为 false
+            preview: false,
             shoot_cooldown: Duration::from_secs(0),
+            recoil: 0.0,
             star_field,
-            particles: ParticleSystem::new(),
+            particles: ParticleSystem::new(ctx),
+            explosions,
             sounds,
             missile_cooldown: Duration::from_secs(0),
-            missile_ammo: 5,              // 初始5发导弹
+            missile_ammo: Self::starting_missiles(mutators, &progress),
             ammo_spawn_timer: Duration::from_secs(0),
-            ammo_items: Vec::new(),
-            p_key_pressed: false,  // 初始化为 false
-            has_spread_shot: false,
-        })
+            ammo_items: Arena::default(),
+            vacuum: None,
+            missile_queued: false,
+            bombs: Self::starting_bombs(progress.loadout),
+            bomb_queued: false,
+            last_stand: None,
+            time_scale: 1.0,
+            debug_speed: 1.0,
+            step_requested: false,
+            free_camera: None,
+            has_spread_shot: Self::starts_with_spread(&progress),
+            focus_mode: false,
+            loadout: progress.loadout,
+            rng,
+            tick_accumulator: 0.0,
+            wave: 1,
+            wave_timer: Duration::from_secs(0),
+            script: scripting::load(ctx, 1),
+            background: DEFAULT_BACKGROUND,
+            message_bar: MessageBar::default(),
+            continues_left: if daily.is_some() || mutators.contains(Mutator::OneHitKills) { 0 } else { MAX_CONTINUES },
+            continue_timer: None,
+            config,
+            config_dir,
+            settings: None,
+            profiles,
+            profile,
+            profile_menu,
+            progress,
+            hangar: None,
+            shop: None,
+            weapon_tier: 0,
+            post: PostProcess::new(ctx)?,
+            focused: true,
+            transition: None,
+            pending_scene: None,
+            pickup_icons,
+            mutators,
+            mode,
+            practice: None,
+            daily,
+            daily_history,
+            daily_practice,
+            run_time: 0.0,
+            high_scores,
+            best_at_start,
+            recording: Replay::default(),
+            ghost: None,
+            enemy_defs: mods::load_enemy_table(&mods),
+            tuning: Tuning::load_resource(ctx),
+            mods,
+            run_seed: 0,
+            seed_override: options.seed,
+            mutators_override: options.mutators,
+            assets,
+            highlights: HighlightRecorder::default(),
+            jobs: Jobs::new(),
+            hud_text: TextCache::default(),
+            enemy_paths: HashMap::new(),
+            level,
+            level_timeline: None,
+            presence: PresenceReporter::new(),
+            score_counter: ScoreCounter::default(),
+            window_title: WINDOW_TITLE.to_string(),
+            title_flash: None,
+            record_announced: false,
+            coop_rules: None,
+            partner: None,
+            partner_cooldown: Duration::from_secs(0),
+            #[cfg(feature = "online")]
+            online: None,
+            options,
+        };
+        state.load_ghost();
+        Ok(state)
+    }
+
+    // 玩家飞船：尺寸和颜色由机库中装备的飞船决定，Tiny Ship 规则下再缩小一半；判定大小随难度变化
+    fn create_player(assets: &Assets, mutators: Mutators, ship: Ship, difficulty: Difficulty) -> GameResult<GameObject> {
+        let scale = ship.size_scale() * if mutators.contains(Mutator::TinyShip) { 0.5 } else { 1.0 };
+        let mut player = GameObject::new(
+            assets,
+            BASE_WINDOW_WIDTH / 2.0,  // 水平居中
+            BASE_WINDOW_HEIGHT - 30.0, // 距离底部一定距离
+            50.0 * scale,
+            60.0 * scale,
+            GameObjectType::Player,
+        )?;
+        player.tint = ship.tint();
+        player.hitbox_scale = difficulty.hurtbox_scale();
+        Ok(player)
+    }
+
+    // 联机合作的二号机：标准飞船，在一号机右侧出发
+    fn create_partner(&self) -> GameResult<GameObject> {
+        let mut partner = Self::create_player(&self.assets, self.mutators, Ship::Standard, self.difficulty())?;
+        partner.pos.x += PARTNER_OFFSET;
+        partner.prev_pos = partner.pos;
+        partner.tint = self.config.palette.color(Swatch::Friendly);
+        Ok(partner)
+    }
+
+    // 初始导弹数量：基础 5 发，加上飞船、初始武器和出击装备的加成
+    fn starting_missiles(mutators: Mutators, progress: &Progress) -> i32 {
+        if mutators.contains(Mutator::NoMissiles) {
+            return 0;
+        }
+        let pack = if progress.weapon == StartingWeapon::MissilePack { 5 } else { 0 };
+        let loadout = if progress.loadout.secondary == Secondary::Missiles { LOADOUT_BONUS_MISSILES } else { 0 };
+        5 + progress.ship.bonus_missiles() + pack + loadout
+    }
+
+    // 炸弹键的初始次数由副武器决定
+    fn starting_bombs(loadout: Loadout) -> u32 {
+        match loadout.secondary {
+            Secondary::Missiles => STARTING_BOMBS,
+            Secondary::Bombs => STARTING_BOMBS + LOADOUT_BONUS_BOMBS,
+            Secondary::Emp => LOADOUT_EMP_CHARGES,
+            Secondary::GravityWell => LOADOUT_GRAVITY_WELLS,
+        }
+    }
+
+    fn starts_with_spread(progress: &Progress) -> bool {
+        progress.weapon == StartingWeapon::Spread || progress.loadout.primary == Primary::Spread
+    }
+
+    // 一局彻底结束（不再续关）时结算局外点数，并切换到结算界面
+    fn finish_run(&mut self) {
+        // 练习不获得点数；正常游戏记下到达的最远波次，供练习模式选择
+        let credits = if self.practice.is_some() { 0 } else { progression::credits_for_run(self.score, self.wave) };
+        if self.practice.is_none() {
+            self.progress.credits += credits;
+            self.progress.furthest_wave = self.progress.furthest_wave.max(self.wave);
+            self.save_progress();
+        }
+        let best_score = self.high_scores.get(self.mode).best_score;
+        let summary = RunSummary {
+            mode: self.mode,
+            practice: self.practice.is_some(),
+            score: self.score,
+            wave: self.wave,
+            time: self.run_time,
+            grazes: self.grazes,
+            stats: self.stats,
+            log: self.log.clone(),
+            credits,
+            best_score,
+            new_record: best_score > self.best_at_start,
+            code: self.run_code(),
+        };
+        self.set_scene(Scene::GameOver(GameOverScreen::new(summary, &self.profile)));
+    }
+
+    // 本局的分享码
+    fn run_code(&self) -> RunCode {
+        RunCode { seed: self.run_seed, mutators: self.mutators }
+    }
+
+    // 从菜单正常开始或联机合作时的模式；生存模式只能从菜单单独进入
+    fn default_mode(&self) -> GameMode {
+        if self.daily.is_some() { GameMode::Daily } else { GameMode::Endless }
+    }
 
+    // 每日挑战和 One-Hit Kills 规则下不能续关
+    fn max_continues(&self) -> u32 {
+        if self.daily.is_some() || self.coop_rules.is_some() || self.mutators.contains(Mutator::OneHitKills) {
+            0
+        } else {
+            MAX_CONTINUES
+        }
     }
 
     // 添加游戏重置方法
     fn reset(&mut self, ctx: &mut ggez::Context) -> GameResult {
-        self.player = GameObject::new(
-            ctx,
-            BASE_WINDOW_WIDTH / 2.0,
-            BASE_WINDOW_HEIGHT - 30.0,
-            50.0,
-            60.0,
-            GameObjectType::Player,
-        )?;
+        // 每日挑战不能续关；跨过零点后重新开始即为新一天的挑战
+        if self.daily.is_some() {
+            let challenge = DailyChallenge::today();
+            self.daily = Some(challenge);
+            self.daily_practice = self.daily_history.attempted(&challenge);
+        }
+        if self.coop_rules.is_some() {
+            self.mode = self.default_mode();
+            self.practice = None;
+        }
+        // 规则在开局时确定，局中修改设置从下一局开始生效；联机合作使用主机的规则
+        self.mutators = self
+            .coop_rules
+            .map(|rules| rules.mutators)
+            .or(self.daily.map(|challenge| challenge.mutators))
+            .or(self.mutators_override)
+            .unwrap_or(self.config.mutators);
+        self.continues_left = self.max_continues();
+        // 联机合作时双方都用初始装备出击，两端的模拟才一致
+        let progress = if self.coop_rules.is_some() { Progress::default() } else { self.progress.clone() };
+        self.player = Self::create_player(&self.assets, self.mutators, progress.ship, self.difficulty())?;
+        self.partner = match self.coop_rules {
+            Some(_) => Some(self.create_partner()?),
+            None => None,
+        };
+        self.partner_cooldown = Duration::from_secs(0);
 
         self.bullets.clear();
         self.enemies.clear();
+        self.enemy_bullets.clear();
+        self.hazards.clear();
+        self.hazard_timer = 0.0;
+        self.drones.clear();
+        self.meteors.clear();
+        self.meteor_shower = None;
+        self.debris.clear();
+        self.barricades.clear();
+        if self.mode == GameMode::Horde {
+            self.spawn_barricades()?;
+        }
+        self.drone_fire_timer = 0.0;
+        self.boss = None;
+        self.shockwaves.clear();
+        self.gravity_wells.clear();
+        self.warnings.clear();
+        self.artillery_timer = 0.0;
+        self.grazes = 0;
+        self.stats = RunStats::default();
+        self.log = RunLog::default();
+        self.popups.clear();
+        self.chain.clear();
+        self.sounds.stop_music(ctx)?;
         self.ammo_items.clear();
+        self.vacuum = None;
+        self.explosions.clear();
         self.score = 0;
+        self.score_counter.snap(0);
         self.game_over = false;
         self.paused = false;
+        self.preview = false;
         self.spawn_timer = Duration::from_secs(0);
+        self.spawn_queue.clear();
+        self.commands = CommandQueue::default();
+        self.cues.clear();
+        self.events.clear();
+        self.rollback.clear();
         self.shoot_cooldown = Duration::from_secs(0);
+        self.recoil = 0.0;
         self.missile_cooldown = Duration::from_secs(0);
-        self.missile_ammo = 5;
+        self.missile_ammo = Self::starting_missiles(self.mutators, &progress);
         self.ammo_spawn_timer = Duration::from_secs(0);
-        self.p_key_pressed = false;
-        self.has_spread_shot = false;
+        self.missile_queued = false;
+        self.loadout = progress.loadout;
+        self.bombs = Self::starting_bombs(self.loadout);
+        self.bomb_queued = false;
+        self.last_stand = None;
+        self.time_scale = 1.0;
+        self.has_spread_shot = Self::starts_with_spread(&progress);
+        self.focus_mode = false;
+        self.shop = None;
+        self.weapon_tier = 0;
+        self.tick_accumulator = 0.0;
+        self.wave = 1;
+        self.wave_timer = Duration::from_secs(0);
+        self.script = scripting::load(ctx, 1);
+        self.level_timeline = self.level.as_ref().map(LevelTimeline::new);
+        self.background = DEFAULT_BACKGROUND;
+        self.message_bar.clear();
+        self.particles.clear_ambient();
+        self.particles.start_ambient(AmbientEffect::Dust, None);
+        self.continue_timer = None;
+        self.run_time = 0.0;
+        self.best_at_start = self.high_scores.get(self.mode).best_score;
+        self.record_announced = false;
+        self.highlights.clear();
+        self.recording.clear();
+        // 每局都从一个确定的种子开始，记下来以便分享重玩；固定种子时每局都从同一个随机序列开始
+        self.run_seed = self
+            .coop_rules
+            .map(|rules| rules.seed)
+            .or(self.daily.map(|challenge| challenge.seed))
+            .or(self.seed_override)
+            .unwrap_or_else(rand::random);
+        self.rng = StdRng::seed_from_u64(self.run_seed);
+
+        // 练习模式：从选定的波次开始，Boss 波直接进入选定的阶段，续关次数和武器按设定
+        if let Some(practice) = self.practice {
+            self.wave = practice.wave;
+            self.script = scripting::load(ctx, practice.wave);
+            self.continues_left = practice.lives;
+            self.loadout.primary = practice.primary;
+            self.weapon_tier = practice.weapon_tier;
+            self.missile_ammo = practice.missiles;
+            self.bombs = practice.bombs;
+            if practice.is_boss_wave() {
+                self.spawn_boss()?;
+                if let Some((_, boss)) = &mut self.boss {
+                    boss.skip_to(practice.phase);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // 切换玩家档案：重新读取该档案的设置和纪录，并重新开始
+    fn switch_profile(&mut self, ctx: &mut ggez::Context, name: String) -> GameResult {
+        // 先写完当前档案还在排队的存档
+        self.finish_jobs();
+        self.config_dir = self.profiles.dir(&name);
+        self.config = Config::load(&self.config_dir);
+        self.high_scores = HighScores::load(&self.config_dir);
+        self.daily_history = DailyHistory::load(&self.config_dir);
+        self.load_ghost();
+        self.progress = Progress::load(&self.config_dir);
+        self.refresh_volume();
+        if let Err(err) = self.profiles.set_last_used(&name) {
+            error!("Failed to save profile selection: {}", err);
+        }
+        self.profile = name;
+        self.reset(ctx)
+    }
+
+    // 命令行 --mute 和窗口失去焦点时强制静音
+    fn refresh_volume(&mut self) {
+        self.sounds.apply_volume(&self.config, self.options.mute || !self.focused);
+    }
+
+    // 幽灵回放可能很大，在后台读取；读完时档案没有再次切换、本局也没有刷新纪录才使用
+    fn load_ghost(&mut self) {
+        self.ghost = None;
+        let dir = self.config_dir.clone();
+        self.jobs.spawn(move || {
+            let ghost = Replay::load(&dir, GameMode::Endless);
+            Box::new(move |state: &mut MainState| {
+                if state.config_dir == dir && state.ghost.is_none() {
+                    state.ghost = ghost;
+                }
+            })
+        });
+    }
+
+    // 存档在后台写入：先复制一份当前数据，写入期间游戏照常运行
+    fn save_config(&mut self) {
+        let (config, dir) = (self.config.clone(), self.config_dir.clone());
+        self.jobs.save("config", move || config.save(&dir));
+    }
+
+    fn save_progress(&mut self) {
+        let (progress, dir) = (self.progress.clone(), self.config_dir.clone());
+        self.jobs.save("progress", move || progress.save(&dir));
+    }
+
+    fn save_high_scores(&mut self) {
+        let (high_scores, dir) = (self.high_scores.clone(), self.config_dir.clone());
+        self.jobs.save("high scores", move || high_scores.save(&dir));
+    }
+
+    // 执行已完成任务的收尾
+    fn poll_jobs(&mut self) {
+        for done in self.jobs.poll() {
+            done(self);
+        }
+    }
+
+    // 等待所有后台任务完成（退出或切换档案前）
+    fn finish_jobs(&mut self) {
+        for done in self.jobs.wait() {
+            done(self);
+        }
+    }
+
+    // 静音偏好保存在配置里，下次启动仍然生效
+    fn toggle_mute(&mut self) {
+        self.config.muted = !self.config.muted;
+        self.refresh_volume();
+        self.save_config();
+    }
+
+    // 商店里某件商品的当前价格，None 表示不可购买
+    fn shop_price(&self, item: ShopItem) -> Option<i32> {
+        match item {
+            ShopItem::ExtraLife if self.max_continues() == 0 => None,
+            ShopItem::Missiles if self.mutators.contains(Mutator::NoMissiles) => None,
+            _ => item.price(self.weapon_tier),
+        }
+    }
+
+    // 用分数购买商品
+    fn buy(&mut self, item: ShopItem) -> Result<(), String> {
+        let price = self.shop_price(item).ok_or_else(|| format!("{} is not available", item.name()))?;
+        if self.score < price {
+            return Err(format!("Need {} score", price));
+        }
+        self.score -= price;
+        match item {
+            ShopItem::ExtraLife => self.continues_left += 1,
+            ShopItem::Missiles => self.missile_ammo += 5,
+            ShopItem::UpgradeTier => self.weapon_tier += 1,
+        }
+        Ok(())
+    }
+
+    // 续关：从当前波次继续，分数按比例保留
+    fn continue_run(&mut self) -> GameResult {
+        self.player = Self::create_player(&self.assets, self.mutators, self.progress.ship, self.difficulty())?;
+        self.player.invulnerable_timer = RESPAWN_INVULNERABILITY;
+        self.bombs = self.bombs.max(Self::starting_bombs(self.loadout));
+        self.bomb_queued = false;
+        self.last_stand = None;
+        self.time_scale = 1.0;
+
+        self.bullets.clear();
+        self.enemies.clear();
+        self.enemy_bullets.clear();
+        self.hazards.clear();
+        self.meteors.clear();
+        self.meteor_shower = None;
+        self.debris.clear();
+        self.ammo_items.clear();
+        self.vacuum = None;
+        self.warnings.clear();
+        self.chain.clear();
+        self.score = (self.score as f32 * CONTINUE_SCORE_FRACTION) as i32;
+        self.continues_left -= 1;
+        self.continue_timer = None;
+        self.game_over = false;
+        self.spawn_timer = Duration::from_secs(0);
+        self.spawn_queue.clear();
+        self.tick_accumulator = 0.0;
+        self.rollback.clear();
         Ok(())
     }
 
     // 添加扇形弹药生成方法
-    fn spawn_spread_ammo(&mut self, ctx: &mut ggez::Context) -> GameResult {
-        let mut rng = rand::thread_rng();
-        let x = rng.gen_range(0.0..BASE_WINDOW_WIDTH - 20.0);
+    fn spawn_spread_ammo(&mut self) -> GameResult {
+        let x = self.rng.gen_range(0.0..BASE_WINDOW_WIDTH - 20.0);
 
         let ammo = GameObject::new(
-            ctx,
+            &self.assets,
             x,
             -30.0,
             25.0,  // 稍微大一点
@@ -498,60 +1716,156 @@ impl MainState {
             GameObjectType::SpreadAmmo,
         )?;
 
-        self.ammo_items.push(ammo);
+        self.ammo_items.insert(ammo);
         Ok(())
     }
 
-    // 添加生成弹药的方法
-    fn spawn_missile_ammo(&mut self, ctx: &mut ggez::Context) -> GameResult {
-        let mut rng = rand::thread_rng();
-        let x = rng.gen_range(0.0..BASE_WINDOW_WIDTH - 20.0);
+    // 生成僚机道具
+    fn spawn_drone_pickup(&mut self) -> GameResult {
+        let x = self.rng.gen_range(0.0..BASE_WINDOW_WIDTH - 20.0);
 
-        let ammo = GameObject::new(
-            ctx,
+        let pickup = GameObject::new(
+            &self.assets,
             x,
             -30.0,
-            20.0,  // 弹药包大小
-            20.0,
-            GameObjectType::MissileAmmo,
+            24.0,
+            24.0,
+            GameObjectType::DronePickup,
         )?;
 
-        self.ammo_items.push(ammo);
+        self.ammo_items.insert(pickup);
         Ok(())
     }
 
-    // 添加发射导弹的方法
-    fn launch_missile(&mut self, ctx: &mut ggez::Context) -> GameResult {
-        if self.enemies.is_empty() || self.missile_ammo <= 0 {
-            return Ok(());  // 如果没有敌人或没有导弹，不发射
-        }
+    // 第 idx 架僚机在编队中的位置：交替排在玩家两侧
+    fn drone_anchor(player: Vec2, idx: usize) -> Vec2 {
+        let side = if idx.is_multiple_of(2) { -1.0 } else { 1.0 };
+        player + Vec2::new(side * 50.0, 15.0)
+    }
 
-        // 找到最近的敌人
-        let player_pos = self.player.pos;
-        let mut closest_enemy = 0;
-        let mut min_distance = f32::MAX;
+    // 更新僚机：弹簧阻尼跟随玩家两侧，并自动射击最近的敌人
+    fn update_drones(&mut self, dt: f32) -> GameResult {
+        for (idx, drone) in self.drones.iter_mut().enumerate() {
+            let target = Self::drone_anchor(self.player.pos, idx);
+            let accel = (target - drone.pos) * DRONE_SPRING - drone.speed * DRONE_DAMPING;
+            drone.speed += accel * dt;
+            drone.pos += drone.speed * dt;
+        }
 
-        for (idx, enemy) in self.enemies.iter().enumerate() {
-            let distance = enemy.pos.distance(player_pos);
-            if distance < min_distance {
-                min_distance = distance;
-                closest_enemy = idx;
+        self.drone_fire_timer -= dt;
+        if self.drone_fire_timer > 0.0 || self.enemies.is_empty() {
+            return Ok(());
+        }
+        self.drone_fire_timer = DRONE_FIRE_INTERVAL;
+
+        let mut shots = Vec::new();
+        for drone in &self.drones {
+            let nearest = self.enemies.iter().min_by(|a, b| {
+                a.pos.distance(drone.pos).total_cmp(&b.pos.distance(drone.pos))
+            });
+            if let Some(enemy) = nearest {
+                let direction = (enemy.pos - drone.pos).normalize_or_zero();
+                let mut shot = GameObject::new(&self.assets, drone.pos.x, drone.pos.y, 4.0, 12.0, GameObjectType::DroneShot)?;
+                shot.speed = direction * DRONE_SHOT_SPEED;
+                shot.rotation = direction.y.atan2(direction.x) + std::f32::consts::FRAC_PI_2;
+                shot.tint = self.config.palette.color(Swatch::DroneShot);
+                shot.lifetime = Some(DRONE_SHOT_LIFETIME);
+                shot.ricochets = self.ricochets();
+                shots.push(shot);
             }
         }
+        if !shots.is_empty() {
+            self.cues.sound(SoundEvent::Shot(Weapon::Drone));
+        }
+        self.bullets.extend(shots);
+        Ok(())
+    }
 
-        // 创建导弹并设置目标
-        let mut missile = GameObject::new(
-            ctx,
+    // 生成 EMP 道具
+    fn spawn_emp_pickup(&mut self) -> GameResult {
+        let x = self.rng.gen_range(0.0..BASE_WINDOW_WIDTH - 20.0);
+
+        let pickup = GameObject::new(
+            &self.assets,
+            x,
+            -30.0,
+            24.0,
+            24.0,
+            GameObjectType::EmpPickup,
+        )?;
+
+        self.ammo_items.insert(pickup);
+        Ok(())
+    }
+
+    // 释放 EMP：屏幕内的敌人和 Boss 瘫痪，无法移动和射击
+    fn trigger_emp(&mut self) {
+        let on_screen = |pos: Vec2| {
+            pos.x >= 0.0 && pos.x <= BASE_WINDOW_WIDTH && pos.y >= 0.0 && pos.y <= BASE_WINDOW_HEIGHT
+        };
+        for enemy in self.enemies.iter_mut().filter(|enemy| on_screen(enemy.pos)) {
+            enemy.status.apply(StatusKind::Stunned, EMP_STUN_DURATION);
+        }
+        if let Some((body, _)) = &mut self.boss {
+            if on_screen(body.pos) {
+                body.status.apply(StatusKind::Stunned, EMP_STUN_DURATION);
+            }
+        }
+        self.shockwaves.push((self.player.pos, 0.0));
+        self.cues.sound(SoundEvent::Emp);
+    }
+
+    // 添加生成弹药的方法
+    fn spawn_missile_ammo(&mut self) -> GameResult {
+        let x = self.rng.gen_range(0.0..BASE_WINDOW_WIDTH - 20.0);
+
+        let ammo = GameObject::new(
+            &self.assets,
+            x,
+            -30.0,
+            20.0,  // 弹药包大小
+            20.0,
+            GameObjectType::MissileAmmo,
+        )?;
+
+        self.ammo_items.insert(ammo);
+        Ok(())
+    }
+
+    // 添加发射导弹的方法
+    fn launch_missile(&mut self) -> GameResult {
+        if self.enemies.is_empty() || self.missile_ammo <= 0 {
+            return Ok(());  // 如果没有敌人或没有导弹，不发射
+        }
+
+        // 找到最近的敌人
+        let player_pos = self.player.pos;
+        let mut closest_enemy = None;
+        let mut min_distance = f32::MAX;
+
+        for (handle, enemy) in self.enemies.entries() {
+            let distance = enemy.pos.distance(player_pos);
+            if distance < min_distance {
+                min_distance = distance;
+                closest_enemy = Some(handle);
+            }
+        }
+
+        // 创建导弹并设置目标
+        let mut missile = GameObject::new(
+            &self.assets,
             self.player.pos.x,
             self.player.pos.y - self.player.base_size.y / 2.0,
             8.0,  // 稍微大一点的尺寸
             24.0,
             GameObjectType::GuidedMissile,
         )?;
-        missile.target = Some(closest_enemy);
+        missile.target = closest_enemy;
+        missile.lifetime = Some(MISSILE_FUEL);
 
-        self.bullets.push(missile);
-        self.sounds.play_shoot(ctx)?;
+        self.bullets.insert(missile);
+        self.cues.sound(SoundEvent::Shot(Weapon::Missile));
+        self.knock_back(MISSILE_KNOCKBACK);
 
         // 发射后减少弹药
         self.missile_ammo -= 1;
@@ -559,495 +1873,3854 @@ impl MainState {
     }
 
 
-    fn spawn_enemy(&mut self, ctx: &mut ggez::Context) -> GameResult {
-        let mut rng = rand::thread_rng();
-        let x = rng.gen_range(0.0..BASE_WINDOW_WIDTH - 40.0);
-        let enemy = GameObject::new(
-            ctx,
-            x,
-            -50.0,
-            40.0,
-            40.0,
+    fn spawn_enemy(&mut self) -> GameResult {
+        let kind = self.enemy_defs.roll(&mut self.rng);
+        let edge = EntryEdge::roll(&mut self.rng, self.wave);
+        // 侧面进入的敌人只出现在屏幕上半部分
+        let offset = match edge {
+            EntryEdge::Top | EntryEdge::Bottom => self.rng.gen_range(0.0..BASE_WINDOW_WIDTH - 40.0),
+            EntryEdge::Left | EntryEdge::Right => self.rng.gen_range(0.0..BASE_WINDOW_HEIGHT * 0.5),
+        };
+        self.spawn_enemy_at(kind, edge, offset)
+    }
+
+    // 同屏敌人上限
+    // 联机合作时不按本机的低配置设置减少敌人，两端的上限才一致
+    fn enemy_cap(&self) -> usize {
+        spawner::enemy_cap(self.difficulty(), self.config.low_spec && self.coop_rules.is_none())
+    }
+
+    // 本局难度：联机合作使用主机的设置
+    fn difficulty(&self) -> Difficulty {
+        self.coop_rules.map_or(self.config.difficulty, |rules| rules.difficulty)
+    }
+
+    // 无尽模式和生存模式下难度曲线在当前进度（开局后经过的波数）上的倍数；每日挑战和关卡不随波次加速
+    fn ramp(&self, curve: impl Fn(&Tuning) -> Curve) -> f32 {
+        if !matches!(self.mode, GameMode::Endless | GameMode::Horde) || self.level.is_some() {
+            return 1.0;
+        }
+        let progress = self.wave.saturating_sub(1) as f32 + self.wave_timer.as_secs_f32() / WAVE_DURATION;
+        curve(&self.tuning).at(progress)
+    }
+
+    // 模拟中按窗口尺寸换算速度时使用的尺寸；联机合作时两端窗口大小可能不同，统一按基准尺寸计算
+    fn sim_size(&self) -> WindowSize {
+        match self.coop_rules {
+            Some(_) => WindowSize::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT),
+            None => WindowSize::new(self.window_size.width, self.window_size.height),
+        }
+    }
+
+    // 本局一号机的飞船：联机合作时为标准飞船
+    fn run_ship(&self) -> Ship {
+        match self.coop_rules {
+            Some(_) => Ship::Standard,
+            None => self.progress.ship,
+        }
+    }
+
+    // 从指定边进入的敌人，offset 为沿该边的基准坐标；同屏敌人已满时排队
+    fn spawn_enemy_at(&mut self, kind: EnemyKind, edge: EntryEdge, offset: f32) -> GameResult {
+        let request = SpawnRequest { kind, edge, offset };
+        if self.enemies.len() >= self.enemy_cap() {
+            self.spawn_queue.push(request);
+            return Ok(());
+        }
+        self.place_enemy(request)
+    }
+
+    fn place_enemy(&mut self, SpawnRequest { kind, edge, offset }: SpawnRequest) -> GameResult {
+        let def = self.enemy_defs.def(kind);
+        let limit = match edge {
+            EntryEdge::Top | EntryEdge::Bottom => BASE_WINDOW_WIDTH,
+            EntryEdge::Left | EntryEdge::Right => BASE_WINDOW_HEIGHT,
+        };
+        let offset = offset.clamp(0.0, limit - def.size);
+        let screen = Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT);
+        let pos = edge.spawn_pos(offset, screen, ENEMY_SPAWN_MARGIN);
+        let enemy = Self::create_enemy(&self.assets, def, pos, edge.heading())?;
+        self.enemies.insert(enemy);
+        Ok(())
+    }
+
+    // 沿路线飞行的敌人从路线起点出发；编队由脚本编排，不进入同屏上限的排队。
+    // 路线按名称缓存，读取失败的名称也会记住，不会每次生成都报错
+    fn spawn_enemy_on_path(&mut self, resources: &dyn Resources, kind: EnemyKind, name: &str) -> GameResult {
+        let path = self
+            .enemy_paths
+            .entry(name.to_string())
+            .or_insert_with(|| resources.enemy_path(name).map(Rc::new))
+            .clone();
+        let Some((path, (pos, heading))) = path.and_then(|path| Some((path.clone(), path.sample(0.0)?))) else {
+            return Ok(());
+        };
+        let mut enemy = Self::create_enemy(&self.assets, self.enemy_defs.def(kind), pos, heading)?;
+        enemy.path = Some((path, 0.0));
+        self.enemies.insert(enemy);
+        Ok(())
+    }
+
+    fn run_script_command(&mut self, resources: &dyn Resources, command: ScriptCommand) -> GameResult {
+        match command {
+            ScriptCommand::Spawn { kind, edge, offset } => self.spawn_enemy_at(kind, edge, offset)?,
+            ScriptCommand::SpawnPath { kind, path } => self.spawn_enemy_on_path(resources, kind, &path)?,
+            ScriptCommand::Wait(_) => {}
+            ScriptCommand::SetBackground(color) => self.background = color,
+            ScriptCommand::PlaySound(name) => {
+                if let Some(event) = SoundEvent::named(name) {
+                    self.cues.sound(event);
+                }
+            }
+            ScriptCommand::Dialog(line) => self.cues.push(Cue::Dialog(line)),
+            ScriptCommand::Ambient { effect, duration } => self.cues.push(Cue::Ambient { effect, duration }),
+            ScriptCommand::StopAmbient(effect) => self.cues.push(Cue::StopAmbient(effect)),
+        }
+        Ok(())
+    }
+
+    // 在屏幕上方生成一个缓慢漂移的水雷
+    fn spawn_hazard(&mut self) -> GameResult {
+        let x = self.rng.gen_range(40.0..BASE_WINDOW_WIDTH - 40.0);
+        let mut mine = GameObject::new(&self.assets, x, -30.0, 30.0, 30.0, GameObjectType::FloatingMine)?;
+        mine.speed = Vec2::new(self.rng.gen_range(-0.4..0.4), self.rng.gen_range(0.4..0.8));
+        mine.tint = self.config.palette.color(Swatch::EnemyMine);
+        self.hazards.insert(mine);
+        Ok(())
+    }
+
+    // 连锁击破的奖励星从最后一个被击毁的敌人处落下
+    fn spawn_bonus_star(&mut self, pos: Vec2) -> GameResult {
+        let star = GameObject::new(&self.assets, pos.x, pos.y, BONUS_STAR_SIZE, BONUS_STAR_SIZE, GameObjectType::BonusStar)?;
+        self.ammo_items.insert(star);
+        Ok(())
+    }
+
+    // 消弹：center 周围 radius 内的敌方子弹变成得分宝石（地雷不受影响）；指定 shooter 时只转换它发射的子弹
+    fn cancel_bullets(&mut self, shooter: Option<Shooter>, center: Vec2, radius: f32) -> GameResult {
+        let mut gem = GameObject::new(&self.assets, center.x, center.y, SCORE_GEM_SIZE, SCORE_GEM_SIZE, GameObjectType::ScoreGem)?;
+        gem.tint = self.config.palette.color(Swatch::ScoreGem);
+        Self::convert_bullets(&mut self.enemy_bullets, &mut self.ammo_items, &gem, shooter, center, radius);
+        Ok(())
+    }
+
+    // 把符合条件的敌方子弹移出 enemy_bullets，在原处放入 gem 的复制
+    fn convert_bullets(
+        enemy_bullets: &mut Vec<GameObject>,
+        ammo_items: &mut Arena<GameObject>,
+        gem: &GameObject,
+        shooter: Option<Shooter>,
+        center: Vec2,
+        radius: f32,
+    ) {
+        enemy_bullets.retain(|bullet| {
+            let cancelled = matches!(bullet.object_type, GameObjectType::EnemyBullet)
+                && shooter.is_none_or(|shooter| bullet.shooter == Some(shooter))
+                && bullet.pos.distance(center) <= radius;
+            if cancelled {
+                let mut copy = gem.clone();
+                copy.pos = bullet.pos;
+                copy.prev_pos = bullet.pos;
+                ammo_items.insert(copy);
+            }
+            !cancelled
+        });
+    }
+
+    // 从屏幕上方斜向落下的流星，direction 决定向左还是向右
+    fn spawn_meteor(&mut self, direction: f32) -> GameResult {
+        // 起点向来向一侧偏移，保证流星能扫过整个屏幕
+        let x = self.rng.gen_range(0.0..BASE_WINDOW_WIDTH) - direction * BASE_WINDOW_WIDTH * 0.4;
+        let size = self.rng.gen_range(METEOR_SIZE.0..METEOR_SIZE.1);
+        let mut meteor = GameObject::new(&self.assets, x, -size, size, size, GameObjectType::Meteor)?;
+        meteor.speed = Vec2::new(direction * 0.6, 1.0).normalize() * METEOR_SPEED;
+        self.meteors.push(meteor);
+        Ok(())
+    }
+
+    // Boss 从屏幕上方滑入并播放登场演出，开始播放第一阶段的音乐
+    fn spawn_boss(&mut self) -> GameResult {
+        let name = boss::NAMES[(self.wave / BOSS_WAVE_INTERVAL).saturating_sub(1) as usize % boss::NAMES.len()];
+        let boss = Boss::new(name, BOSS_HEALTH + self.wave * 4);
+        info!("Boss {} spawned on wave {}", name, self.wave);
+        let mut body = GameObject::new(&self.assets, BASE_WINDOW_WIDTH / 2.0, -BOSS_SIZE, BOSS_SIZE, BOSS_SIZE, GameObjectType::Enemy)?;
+        body.tint = boss.def().tint;
+        self.cues.push(Cue::Music { path: boss.def().music, pitch: boss.def().music_pitch });
+        // 登场演出期间跃迁光条掠过屏幕
+        self.cues.push(Cue::Ambient { effect: AmbientEffect::WarpStreaks, duration: None });
+        self.boss = Some((body, boss));
+        Ok(())
+    }
+
+    // 更新 Boss：阶段切换时换色换音乐，并把发射的子弹加入敌方弹幕
+    fn update_boss(&mut self, dt: f32, frame_scale: f32) -> GameResult {
+        let Some((body, boss)) = &mut self.boss else {
+            return Ok(());
+        };
+        body.prev_pos = body.pos;
+        // 燃烧直接伤害 Boss；减速和瘫痪让 Boss 的移动与射击一起变慢
+        boss.damage(body.status.update(dt));
+        let scale = body.status.speed_scale();
+        let update = boss.update(dt * scale, frame_scale * scale, &mut body.pos, self.player.pos, BASE_WINDOW_WIDTH);
+        let pos = body.pos;
+
+        // 名字横幅出现时的提示音
+        if update.intro_step == Some(IntroStep::Banner) {
+            self.cues.sound(SoundEvent::BossPhase);
+        }
+        if update.intro_finished {
+            debug!("Boss {} intro finished", boss.name);
+            self.cues.push(Cue::StopAmbient(AmbientEffect::WarpStreaks));
+        }
+
+        if let Some(phase) = update.phase_changed {
+            let def = boss.def();
+            body.tint = def.tint;
+            self.cues.push(Cue::Music { path: def.music, pitch: def.music_pitch });
+            self.events.publish(GameEvent::BossPhaseChanged { phase, pos, color: def.tint });
+        }
+
+        // 新阶段召唤护卫：直接放进敌人列表，由 Boss 记下它们的 handle 并带着环绕
+        if let Some(escort) = update.summon {
+            let def = self.enemy_defs.def(escort.kind);
+            let mut escorts = Vec::new();
+            for _ in 0..escort.count {
+                let enemy = Self::create_enemy(&self.assets, def, pos, Vec2::Y)?;
+                escorts.push(self.enemies.insert(enemy));
+            }
+            boss.summon(escorts);
+            self.cues.burst(pos, BOSS_SHIELD_COLOR);
+            info!("Boss {} summoned {} escorts", boss.name, escort.count);
+        }
+        if boss.retain_escorts(|handle| self.enemies.get(handle).is_some()) {
+            self.cues.sound(SoundEvent::BossPhase);
+            self.cues.burst(pos, BOSS_SHIELD_COLOR);
+        }
+        for (index, handle) in boss.escorts().iter().enumerate() {
+            let slot = boss.escort_slot(index, pos);
+            let escort = &mut self.enemies[*handle];
+            // 机头朝外
+            escort.heading = (slot - pos).try_normalize().unwrap_or(Vec2::Y);
+            escort.rotation = escort.heading.y.atan2(escort.heading.x) + std::f32::consts::FRAC_PI_2;
+            escort.pos = slot;
+        }
+
+        // 激光预警线停在扫射的起始角度
+        if let (Some(duration), Some(beam)) = (update.laser_warning, boss.laser(pos)) {
+            let shape = WarningShape::Line { from: beam.start, to: beam.end, width: beam.width };
+            self.warnings.add(Attack::Laser, shape, duration);
+        }
+
+        for (origin, velocity) in update.shots {
+            let mut bullet = GameObject::new(&self.assets, origin.x, origin.y, 8.0, 16.0, GameObjectType::EnemyBullet)?;
+            bullet.speed = velocity;
+            bullet.rotation = velocity.y.atan2(velocity.x) + std::f32::consts::FRAC_PI_2;
+            bullet.tint = self.config.palette.color(Swatch::BossShot);
+            bullet.shooter = Some(Shooter::Boss);
+            self.enemy_bullets.push(bullet);
+        }
+
+        // 进入新阶段时外壳崩落，残骸从 Boss 身上向两侧抛出后落下
+        if update.phase_changed.is_some() {
+            for i in 0..DEBRIS_COUNT {
+                let side = i as f32 - (DEBRIS_COUNT - 1) as f32 / 2.0;
+                let size = self.rng.gen_range(DEBRIS_SIZE.0..DEBRIS_SIZE.1);
+                let speed = Vec2::new(side * 1.2 + self.rng.gen_range(-0.3..0.3), self.rng.gen_range(-2.0..-0.5));
+                self.spawn_debris(pos, size, speed)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn spawn_debris(&mut self, pos: Vec2, size: f32, speed: Vec2) -> GameResult {
+        let mut piece = GameObject::new(&self.assets, pos.x, pos.y, size, size, GameObjectType::Debris)?;
+        piece.speed = speed;
+        piece.tint = Color::new(0.65, 0.6, 0.7, 1.0);
+        self.debris.push(piece);
+        Ok(())
+    }
+
+    // 打碎的残骸：大块分成两块向两侧散开的小块，小块直接粉碎
+    fn break_debris(&mut self) -> GameResult {
+        let (broken, intact): (Vec<_>, Vec<_>) = std::mem::take(&mut self.debris)
+            .into_iter()
+            .partition(|piece| piece.damage >= DEBRIS_HITS);
+        self.debris = intact;
+        for piece in broken {
+            self.cues.explosion(ExplosionKind::Enemy, piece.pos, piece.base_size.x * 1.5);
+            self.cues.sound(SoundEvent::Explosion);
+            if piece.base_size.x >= DEBRIS_SPLIT_SIZE {
+                for side in [-1.0, 1.0] {
+                    let speed = Vec2::new(piece.speed.x + side * 1.5, piece.speed.y.min(0.0) - 1.0);
+                    self.spawn_debris(piece.pos, piece.base_size.x * 0.6, speed)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // 生存模式的路障沿屏幕宽度均匀排在玩家上方
+    fn spawn_barricades(&mut self) -> GameResult {
+        let (width, height) = BARRICADE_SIZE;
+        for i in 0..BARRICADE_COUNT {
+            let x = BASE_WINDOW_WIDTH * (i as f32 + 0.5) / BARRICADE_COUNT as f32;
+            let y = BASE_WINDOW_HEIGHT - BARRICADE_OFFSET;
+            let mut barricade = GameObject::new(&self.assets, x, y, width, height, GameObjectType::Barricade)?;
+            barricade.tint = barricade_tint(0);
+            self.barricades.push(barricade);
+        }
+        Ok(())
+    }
+
+    // 没有沿路线飞行或俯冲的敌人转向离自己最近、且还在自己下方的路障；撞上路障的敌人自毁并削减
+    // 路障耐久，敌方子弹也被路障挡下。耐久耗尽的路障碎裂
+    fn update_barricades(&mut self, dt: f32, frame_scale: f32) {
+        if self.barricades.is_empty() {
+            return;
+        }
+        let barricades = &mut self.barricades;
+        for barricade in barricades.iter_mut() {
+            barricade.update_feedback(dt);
+        }
+        let turn = (BARRICADE_TURN_RATE * frame_scale).min(1.0);
+        for enemy in self.enemies.iter_mut().filter(|enemy| enemy.path.is_none() && enemy.dive_target.is_none()) {
+            let target = barricades
+                .iter()
+                .filter(|barricade| barricade.pos.y > enemy.pos.y)
+                .min_by(|a, b| a.pos.distance(enemy.pos).total_cmp(&b.pos.distance(enemy.pos)));
+            if let Some(target) = target {
+                let to_target = (target.pos - enemy.pos).normalize_or_zero();
+                enemy.heading = enemy.heading.lerp(to_target, turn).try_normalize().unwrap_or(Vec2::Y);
+                enemy.rotation = enemy.heading.y.atan2(enemy.heading.x) + std::f32::consts::FRAC_PI_2;
+            }
+        }
+
+        // 撞上路障的敌人随之炸毁，但不算击毁：不计分，也不执行死亡行为，
+        // 否则分裂、放弹的敌人会在路障内侧放出小敌机和子弹，越过路障直接落到玩家身上
+        for (handle, enemy) in self.enemies.entries() {
+            if self.commands.is_removed(Pool::Enemies, handle) {
+                continue;
+            }
+            let Some(barricade) = barricades.iter_mut().find(|barricade| barricade.intersects(enemy)) else {
+                continue;
+            };
+            barricade.damage += BARRICADE_RAM_DAMAGE;
+            barricade.flash();
+            self.commands.remove(Pool::Enemies, handle);
+            self.commands.push(GameCommand::Explosion(enemy.pos));
+        }
+        let spark = self.config.palette.color(Swatch::EnemyShot);
+        let cues = &mut self.cues;
+        self.enemy_bullets.retain(|bullet| {
+            let Some(barricade) = barricades.iter_mut().find(|barricade| barricade.intersects(bullet)) else {
+                return true;
+            };
+            barricade.damage += 1;
+            barricade.flash();
+            cues.burst(bullet.pos, spark);
+            false
+        });
+
+        for barricade in barricades.iter_mut() {
+            barricade.tint = barricade_tint(barricade.damage);
+        }
+        let (broken, standing): (Vec<_>, Vec<_>) = std::mem::take(barricades)
+            .into_iter()
+            .partition(|barricade| barricade.damage >= BARRICADE_HP);
+        self.barricades = standing;
+        for barricade in broken {
+            self.cues.explosion(ExplosionKind::Enemy, barricade.pos, barricade.base_size.x * 1.5);
+            self.cues.sound(SoundEvent::Explosion);
+        }
+    }
+
+    // 按敌人定义创建敌人
+    fn create_enemy(assets: &Assets, def: &EnemyDef, pos: Vec2, heading: Vec2) -> GameResult<GameObject> {
+        let mut enemy = GameObject::new(
+            assets,
+            pos.x,
+            pos.y,
+            def.size,
+            def.size,
             GameObjectType::Enemy,
         )?;
-        self.enemies.push(enemy);
+        enemy.tint = def.tint;
+        enemy.enemy_kind = Some(def.kind);
+        // 机头朝向行进方向（贴图默认朝上）
+        enemy.heading = heading;
+        enemy.rotation = heading.y.atan2(heading.x) + std::f32::consts::FRAC_PI_2;
+        enemy.launch_timer = def.launcher.map_or(0.0, |launcher| launcher.interval);
+        enemy.shield = def.shield.map_or(0, |shield| shield.hits);
+        Ok(enemy)
+    }
+
+    // 执行敌人的死亡行为；handle 为死去的敌人，记为放出子弹的一方
+    fn apply_on_death(&mut self, handle: Handle, kind: EnemyKind, pos: Vec2) -> GameResult {
+        let on_death = self.enemy_defs.def(kind).on_death;
+        match on_death {
+            OnDeath::Nothing => {}
+            OnDeath::RadialBullets { count, speed } => {
+                for i in 0..count {
+                    let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                    let direction = Vec2::new(angle.cos(), angle.sin());
+                    let mut bullet = GameObject::new(&self.assets, pos.x, pos.y, 6.0, 14.0, GameObjectType::EnemyBullet)?;
+                    bullet.speed = direction * speed;
+                    bullet.rotation = angle + std::f32::consts::FRAC_PI_2;
+                    bullet.tint = self.config.palette.color(Swatch::EnemyShot);
+                    bullet.shooter = Some(Shooter::Enemy(handle));
+                    self.enemy_bullets.push(bullet);
+                }
+            }
+            OnDeath::Split { child, count } => {
+                for i in 0..count {
+                    // 子敌机向两侧散开
+                    let side = i as f32 - (count - 1) as f32 / 2.0;
+                    self.commands.push(GameCommand::SpawnEnemy {
+                        kind: child,
+                        pos: pos + Vec2::new(side * 20.0, 0.0),
+                        heading: Vec2::Y,
+                        drift: Vec2::new(side * 1.5, 0.0),
+                    });
+                }
+            }
+            OnDeath::HomingMines { count } => {
+                for i in 0..count {
+                    let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                    let mut mine = GameObject::new(&self.assets, pos.x, pos.y, 18.0, 18.0, GameObjectType::HomingMine)?;
+                    mine.speed = Vec2::new(angle.cos(), angle.sin()) * MINE_SPEED;
+                    mine.tint = self.config.palette.color(Swatch::EnemyMine);
+                    mine.lifetime = Some(MINE_LIFETIME);
+                    mine.shooter = Some(Shooter::Enemy(handle));
+                    self.enemy_bullets.push(mine);
+                }
+            }
+        }
         Ok(())
     }
 
-    // 修改射击方法添加扇形射击
-    fn shoot(&mut self, ctx: &mut ggez::Context) -> GameResult {
-        self.sounds.play_shoot(ctx)?;
+    // 炮弹落下：区域内的玩家被击毁
+    fn land_artillery(&mut self, shape: WarningShape) -> GameResult {
+        if let WarningShape::Zone(zone) = shape {
+            self.cues.explosion(ExplosionKind::Enemy, Vec2::from(zone.center()), ARTILLERY_ZONE * 1.2);
+        }
+        self.cues.sound(SoundEvent::Explosion);
+        if shape.contains(self.player.pos) {
+            self.hit_player()?;
+        }
+        Ok(())
+    }
 
-        let center_x = self.player.pos.x;
-        let top_y = self.player.pos.y - self.player.base_size.y / 2.0;
-        let bullet_pos = Vec2::new(center_x, top_y);
+    // 主菜单闲置时开始演示：固定种子让每次演示的敌人都与回放大致吻合
+    fn start_demo(&mut self, ctx: &mut ggez::Context) -> GameResult {
+        let Some(replay) = Replay::load_resource(ctx, DEMO_REPLAY) else {
+            // 没有演示回放时重新计时
+            warn!("Demo replay {} could not be loaded", DEMO_REPLAY);
+            self.set_scene(Scene::MainMenu(MainMenu::new()));
+            return Ok(());
+        };
+        self.practice = None;
+        self.reset(ctx)?;
+        self.rng = StdRng::seed_from_u64(DEMO_SEED);
+        self.level_timeline = None; // 演示始终随机出怪
+        self.set_scene(Scene::Demo(replay));
+        Ok(())
+    }
 
-        // 添加粒子效果
-        self.particles.add_explosion(
-            bullet_pos,
-            if self.has_spread_shot {
-                Color::new(1.0, 0.5, 0.0, 0.5)  // 橙色
-            } else {
-                Color::new(1.0, 1.0, 0.0, 0.5)  // 黄色
-            },
-            &self.window_size,
-        );
+    fn end_demo(&mut self, ctx: &mut ggez::Context) -> GameResult {
+        self.reset(ctx)?;
+        self.set_scene(Scene::MainMenu(MainMenu::new()));
+        Ok(())
+    }
 
-        if self.has_spread_shot {
-            // 扇形射击：发射5发子弹，角度范围为60度
-            let angles:[f32; 5] = [-30.0, -15.0, 0.0, 15.0, 30.0];  // 角度（度）
-            for &angle in angles.iter() {
-                let rad: f32 = angle.to_radians();
-                let direction = Vec2::new(rad.sin(), -rad.cos());
-                let mut bullet = GameObject::new(
-                    ctx,
-                    bullet_pos.x,
-                    bullet_pos.y,
-                    5.0,
-                    20.0,
-                    GameObjectType::SpreadShot,
-                )?;
-                bullet.speed = direction * BULLET_SPEED_RATIO * self.window_size.height;
-                bullet.rotation = rad;  // 设置子弹旋转角度
-                self.bullets.push(bullet);
+    // 切换场景：需要过渡时先遮住画面，等完全遮住后再在 update 中真正切换
+    fn set_scene(&mut self, scene: Scene) {
+        info!("Scene: {} -> {}", self.scene.name(), scene.name());
+        match self.scene.transition_to(&scene) {
+            Some(kind) => {
+                self.transition = Some(Transition::new(kind));
+                self.pending_scene = Some(scene);
+            }
+            None => self.scene = scene,
+        }
+    }
+
+    // 玩家被击中：还有炸弹时进入慢动作的“最后一搏”，否则直接被击毁
+    fn hit_player(&mut self) -> GameResult {
+        if let Scene::Demo(_) = self.scene {
+            return Ok(());
+        }
+        if self.game_over || self.player.is_invulnerable() || self.last_stand.is_some() {
+            return Ok(());
+        }
+        self.events.publish(GameEvent::PlayerHit { pos: self.player.pos });
+        // 最后一搏按真实时间倒计时，联机合作时两端无法一致，直接被击毁
+        if self.bombs > 0 && self.coop_rules.is_none() {
+            self.last_stand = Some(LAST_STAND_WINDOW);
+            self.time_scale = LAST_STAND_TIME_SCALE;
+            return Ok(());
+        }
+        self.kill_player()
+    }
+
+    // 引爆炸弹：EMP 效果并清除所有敌方子弹；在最后一搏中引爆可免于被击毁
+    // 副武器为 EMP 时只释放 EMP，不清除子弹；为引力井时在玩家前方放出引力井，没有无敌时间
+    fn detonate_bomb(&mut self) {
+        if self.bombs == 0 {
+            return;
+        }
+        self.bombs -= 1;
+        self.knock_back(BOMB_KNOCKBACK);
+        if self.loadout.secondary == Secondary::GravityWell {
+            let center = self.player.pos - Vec2::new(0.0, GRAVITY_WELL_OFFSET);
+            self.gravity_wells.push(GravityWell::new(center.max(Vec2::ZERO)));
+            self.cues.sound(SoundEvent::Emp);
+            // 最后一搏中放下引力井同样算挽回：和炸弹一样给出无敌时间
+            if self.last_stand.take().is_some() {
+                self.player.invulnerable_timer = BOMB_INVULNERABILITY;
+                self.time_scale = 1.0;
+            }
+            return;
+        }
+        if self.loadout.secondary != Secondary::Emp {
+            let spark = self.config.palette.color(Swatch::EnemyShot);
+            for bullet in self.enemy_bullets.drain(..) {
+                self.cues.burst(bullet.pos, spark);
+            }
+        }
+        self.player.invulnerable_timer = BOMB_INVULNERABILITY;
+        self.last_stand = None;
+        self.time_scale = 1.0;
+        self.trigger_emp();
+    }
+
+    // 飞船朝屏幕下方（射击的反方向）获得一个后退速度，由移动逻辑积分并衰减
+    fn knock_back(&mut self, impulse: f32) {
+        self.player.speed += Vec2::new(0.0, impulse);
+    }
+
+    // 引力井把牵引范围内的敌人和敌方子弹拉向中心；到时引爆，炸毁范围内的普通敌人、
+    // 点燃装甲敌人并清除敌方子弹。击毁记入指令队列，与碰撞检测的结果一起结算
+    fn update_gravity_wells(&mut self, dt: f32, frame_scale: f32) {
+        if self.gravity_wells.is_empty() {
+            return;
+        }
+        let enemy_grid = SpatialGrid::build(gravity::PULL_RADIUS, self.enemies.entries().map(|(handle, enemy)| (handle, enemy.pos)));
+        let bullet_grid = SpatialGrid::build(gravity::PULL_RADIUS, self.enemy_bullets.iter().map(|bullet| bullet.pos).enumerate());
+        for well in &mut self.gravity_wells {
+            well.update(dt);
+            for handle in enemy_grid.query_radius(well.center(), gravity::PULL_RADIUS) {
+                let enemy = &mut self.enemies[handle];
+                enemy.pos += well.pull(enemy.pos, frame_scale);
+            }
+            for idx in bullet_grid.query_radius(well.center(), gravity::PULL_RADIUS) {
+                let bullet = &mut self.enemy_bullets[idx];
+                bullet.pos += well.pull(bullet.pos, frame_scale);
+            }
+        }
+
+        let ready: Vec<Vec2> = self.gravity_wells.iter().filter(|well| well.is_ready()).map(GravityWell::center).collect();
+        self.gravity_wells.retain(|well| !well.is_ready());
+        let one_hit_kills = self.mutators.contains(Mutator::OneHitKills);
+        let score_multiplier = self.mutators.score_multiplier();
+        let spark = self.config.palette.color(Swatch::EnemyShot);
+        for center in ready {
+            // 敌人已被拉近，按当前位置重新查询
+            let blast_grid = SpatialGrid::build(gravity::BLAST_RADIUS, self.enemies.entries().map(|(handle, enemy)| (handle, enemy.pos)));
+            for handle in blast_grid.query_radius(center, gravity::BLAST_RADIUS) {
+                let enemy = &mut self.enemies[handle];
+                let kind = enemy.enemy_kind.unwrap_or(EnemyKind::Fighter);
+                if self.enemy_defs.def(kind).armor.is_some() && !one_hit_kills {
+                    enemy.status.apply(StatusKind::Burning, MISSILE_BURN_DURATION);
+                    continue;
+                }
+                if self.commands.remove(Pool::Enemies, handle) {
+                    self.commands.push(GameCommand::Explosion(enemy.pos));
+                    self.commands.push(GameCommand::EnemyKilled { handle, kind, pos: enemy.pos, points: scaled_score(self.enemy_defs.def(kind).score, score_multiplier) });
+                }
+            }
+            let cues = &mut self.cues;
+            self.enemy_bullets.retain(|bullet| {
+                let caught = bullet.pos.distance(center) <= gravity::BLAST_RADIUS;
+                if caught {
+                    cues.burst(bullet.pos, spark);
+                }
+                !caught
+            });
+            self.cues.explosion(ExplosionKind::Enemy, center, gravity::BLAST_RADIUS * 2.0);
+            self.cues.sound(SoundEvent::Explosion);
+        }
+    }
+
+    // 玩家被击毁：播放爆炸、提供续关并保存纪录
+    fn kill_player(&mut self) -> GameResult {
+        // 生存模式中还有路障和剩余命数时消耗一条命就地重生；命数用完后照常结束本局
+        if self.mode == GameMode::Horde && !self.barricades.is_empty() && self.continues_left > 0 {
+            return self.respawn_player();
+        }
+        self.game_over = true;
+        // 玩家被击中时僚机随之解散
+        for drone in self.drones.drain(..) {
+            self.cues.burst(drone.pos, Color::new(0.4, 0.7, 1.0, 1.0));
+        }
+        self.cues.explosion(ExplosionKind::Player, self.player.pos, 120.0);
+        self.cues.sound(SoundEvent::PlayerDeath);
+        // 练习不留纪录
+        if self.practice.is_some() {
+            if self.continues_left > 0 {
+                self.continue_timer = Some(CONTINUE_COUNTDOWN);
+            } else {
+                self.finish_run();
             }
+            return Ok(());
+        }
+        // 刷新最高分时把本局记录保存为新的幽灵
+        if self.score > self.high_scores.get(self.mode).best_score {
+            let (replay, dir, mode) = (self.recording.clone(), self.config_dir.clone(), self.mode);
+            self.jobs.save("ghost replay", move || replay.save(&dir, mode));
+            self.ghost = Some(self.recording.clone());
+        }
+        if self.high_scores.record(self.mode, self.score, self.run_time, self.mutators) {
+            self.save_high_scores();
+        }
+        // 每日挑战只记录当天第一局
+        if let Some(challenge) = self.daily {
+            if self.daily_history.record(&challenge, self.score, self.run_time) {
+                let (history, dir) = (self.daily_history.clone(), self.config_dir.clone());
+                self.jobs.save("daily history", move || history.save(&dir));
+            }
+        }
+        // 纪录保存之后再结算，结算界面才能判断是否刷新了最高分
+        if self.continues_left > 0 {
+            self.continue_timer = Some(CONTINUE_COUNTDOWN);
         } else {
-            // 普通射击
-            let bullet = GameObject::new(
-                ctx,
-                bullet_pos.x - 2.5,
-                bullet_pos.y,
-                5.0,
-                20.0,
-                GameObjectType::Bullet,
-            )?;
-            self.bullets.push(bullet);
+            self.finish_run();
+        }
+        Ok(())
+    }
+
+    // 清波：发布清波事件，场上剩下的道具在 VACUUM_DURATION 内被吸到玩家身边
+    fn wave_cleared(&mut self) {
+        self.events.publish(GameEvent::WaveCleared { wave: self.wave - 1 });
+        if !self.ammo_items.is_empty() && !self.game_over {
+            self.vacuum = Some(0.0);
         }
+    }
 
+    // 生存模式的就地重生：消耗一条命，飞船回到出发位置，场上的敌人和路障保持原样
+    fn respawn_player(&mut self) -> GameResult {
+        for drone in self.drones.drain(..) {
+            self.cues.burst(drone.pos, Color::new(0.4, 0.7, 1.0, 1.0));
+        }
+        self.cues.explosion(ExplosionKind::Player, self.player.pos, 120.0);
+        self.cues.sound(SoundEvent::PlayerDeath);
+        self.continues_left -= 1;
+        self.player = Self::create_player(&self.assets, self.mutators, self.run_ship(), self.difficulty())?;
+        self.player.invulnerable_timer = RESPAWN_INVULNERABILITY;
+        self.last_stand = None;
+        self.time_scale = 1.0;
         Ok(())
     }
 
-    fn update_window_size(&mut self, ctx: &mut ggez::Context) {
-        let window = ctx.gfx.window();
-        let new_size = window.inner_size();
-        self.window_size = WindowSize::new(
-            new_size.width as f32,
-            new_size.height as f32,
-        );
+    // 本地玩家这一 tick 的输入；导弹和炸弹键在 tick 之间排队，取出后清空
+    fn local_input(&mut self, ctx: &ggez::Context) -> TickInput {
+        let mut input = TickInput::read(ctx, &self.config.bindings, self.config.auto_fire);
+        input.missile = std::mem::take(&mut self.missile_queued);
+        input.bomb = std::mem::take(&mut self.bomb_queued);
+        // 自动居中：没有按左右键时替玩家按下朝向中央的方向键，和手动操作一样记入录像和联机输入
+        if self.config.auto_center && !input.left && !input.right {
+            let offset = self.local_ship().pos.x - BASE_WINDOW_WIDTH / 2.0;
+            input.left = offset > AUTO_CENTER_DEADZONE;
+            input.right = offset < -AUTO_CENTER_DEADZONE;
+        }
+        input
+    }
+
+    // 敌方子弹前进一个 tick，追踪地雷转向 target；模拟和轨迹预览共用
+    fn move_enemy_bullet(bullet: &mut GameObject, target: Vec2, dt: f32, frame_scale: f32, bullet_speed: f32) {
+        bullet.age += dt;
+        if let GameObjectType::HomingMine = bullet.object_type {
+            let to_player = (target - bullet.pos).normalize_or_zero() * MINE_SPEED;
+            bullet.speed = bullet.speed.lerp(to_player, (MINE_TURN_RATE * frame_scale).min(1.0));
+            bullet.rotation += 0.05 * frame_scale;
+        }
+        bullet.pos += bullet.speed * frame_scale * bullet_speed;
+    }
+
+    // 轨迹预览：按模拟步长把每颗敌弹往后推演 duration 秒，返回各自经过的位置。
+    // 玩家视为停在原地，重力井的牵引不计入
+    fn predict_enemy_bullets(&self, duration: f32) -> Vec<Vec<Vec2>> {
+        let dt = 1.0 / self.options.tick_rate as f32;
+        let frame_scale = dt * REFERENCE_FPS;
+        let bullet_speed = self.ramp(|tuning| tuning.bullet_speed);
+        let steps = (duration / dt).ceil() as usize;
+        self.enemy_bullets
+            .iter()
+            .map(|bullet| {
+                let mut bullet = bullet.clone();
+                let mut path = vec![bullet.pos];
+                for _ in 0..steps {
+                    Self::move_enemy_bullet(&mut bullet, self.player.pos, dt, frame_scale, bullet_speed);
+                    if bullet.should_despawn() {
+                        break;
+                    }
+                    path.push(bullet.pos);
+                }
+                path
+            })
+            .collect()
+    }
+
+    // 本机操作的飞机：联机合作时加入方操作二号机
+    fn local_ship(&self) -> &GameObject {
+        #[cfg(feature = "online")]
+        if let (Some(session), Some(partner)) = (&self.online, &self.partner) {
+            if session.role() == net::Role::Guest {
+                return partner;
+            }
+        }
+        &self.player
+    }
+
+    // 躲避提示：预判敌弹接下来的直线轨迹，把会擦近飞机的子弹的推开方向加起来，越早到的权重越大。
+    // 只是提示，不替玩家移动
+    fn dodge_hint(&self) -> Option<Vec2> {
+        let ship = self.local_ship();
+        let mut push = Vec2::ZERO;
+        for bullet in &self.enemy_bullets {
+            let offset = bullet.pos - ship.pos;
+            let velocity = bullet.speed;
+            let frames = if velocity == Vec2::ZERO {
+                0.0
+            } else {
+                (-offset.dot(velocity) / velocity.length_squared()).clamp(0.0, DODGE_HINT_FRAMES)
+            };
+            let closest = offset + velocity * frames;
+            if closest.length() >= DODGE_HINT_RADIUS {
+                continue;
+            }
+            // 正对飞机飞来时朝屏幕中央一侧横移
+            let side = if ship.pos.x < BASE_WINDOW_WIDTH / 2.0 { 1.0 } else { -1.0 };
+            let away = (-closest).try_normalize().unwrap_or(Vec2::new(side, 0.0));
+            push += away * (1.0 - frames / DODGE_HINT_FRAMES);
+        }
+        push.try_normalize()
+    }
+
+    // 联机合作的二号机：按自己的输入移动，只发射普通子弹，没有导弹和炸弹
+    fn update_partner(&mut self, input: TickInput, dt: f32) -> GameResult {
+        let (boundary, sim_size, classic) = (self.boundary(), self.sim_size(), self.classic_movement());
+        let Some(partner) = &mut self.partner else {
+            return Ok(());
+        };
+        partner.prev_pos = partner.pos;
+        partner.update_feedback(dt);
+        let top_speed = PLAYER_SPEED_RATIO * sim_size.width * REFERENCE_FPS * if input.focus { FOCUS_SPEED_SCALE } else { 1.0 };
+        let moved = partner.pos + Self::drive(partner, input.direction(), top_speed, Ship::Standard, classic, dt);
+        partner.pos = boundary.apply(moved, partner.base_size, Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT));
+        partner.stop_at_edges(moved);
+        if (partner.pos.x - moved.x).abs() > BASE_WINDOW_WIDTH / 2.0 {
+            partner.prev_pos = partner.pos;
+        }
+        let muzzle = Vec2::new(partner.pos.x, partner.pos.y - partner.base_size.y / 2.0);
+
+        self.partner_cooldown = self.partner_cooldown.saturating_sub(Duration::from_secs_f32(dt));
+        if input.fire && self.partner_cooldown.is_zero() {
+            let mut bullet = GameObject::new(&self.assets, muzzle.x - 2.5, muzzle.y, 5.0, 20.0, GameObjectType::Bullet)?;
+            bullet.speed = Vec2::new(0.0, -BULLET_SPEED_RATIO * sim_size.height);
+            bullet.ricochets = self.ricochets();
+            self.bullets.insert(bullet);
+            self.partner_cooldown = Duration::from_millis(SHOT_INTERVAL_MS);
+            self.cues.sound(SoundEvent::Shot(Weapon::Blaster));
+        }
+        Ok(())
+    }
+
+    // 二号机本 tick 是否撞上敌人、敌方子弹、Boss、流星或水雷
+    fn partner_hit(&self) -> bool {
+        let Some(partner) = &self.partner else {
+            return false;
+        };
+        if self.game_over || partner.is_invulnerable() {
+            return false;
+        }
+        self.enemies.iter().any(|enemy| enemy.hits(partner))
+            || self.enemy_bullets.iter().any(|bullet| bullet.hits(partner))
+            || self.hazards.iter().any(|mine| mine.hits(partner))
+            || self.meteors.iter().any(|meteor| meteor.hits(partner))
+            || self.debris.iter().any(|piece| piece.hits(partner))
+            || self.boss.as_ref().is_some_and(|(body, _)| body.hits(partner))
+    }
+
+    // 二号机被击毁：两人同生共死，一局随之结束
+    fn kill_partner(&mut self) -> GameResult {
+        if let Some(partner) = &self.partner {
+            self.cues.explosion(ExplosionKind::Player, partner.pos, 120.0);
+        }
+        self.kill_player()
+    }
+
+    // 经典移动：松开方向键立即停下，没有加速和惯性
+    fn classic_movement(&self) -> bool {
+        self.coop_rules.map_or(self.config.classic_movement, |rules| rules.classic_movement)
+    }
+
+    // 飞船本 tick 的位移（基准坐标），top_speed 为最高速度（像素/秒）。
+    // 经典移动时按输入方向直接以最高速度移动，speed 只记录击退速度并逐渐衰减；
+    // 否则 speed 就是飞船的速度：按住方向时按飞船的加速度逼近最高速度，松开后按摩擦减速
+    fn drive(ship: &mut GameObject, direction: Vec2, top_speed: f32, handling: Ship, classic: bool, dt: f32) -> Vec2 {
+        if classic {
+            let knockback = ship.speed * dt;
+            ship.speed *= (-KNOCKBACK_DAMPING * dt).exp();
+            return direction * top_speed * dt + knockback;
+        }
+        let (target, rate) = if direction == Vec2::ZERO {
+            (Vec2::ZERO, handling.friction())
+        } else {
+            (direction * top_speed, handling.acceleration())
+        };
+        let delta = (target - ship.speed).clamp_length_max(rate * top_speed * dt);
+        ship.speed += delta;
+        ship.speed * dt
+    }
+
+    // 当前生效的边界策略：联机合作使用主机的设置，每日挑战使用当天规则
+    fn boundary(&self) -> BoundaryPolicy {
+        self.coop_rules
+            .map(|rules| rules.boundary)
+            .or(self.daily.map(|challenge| challenge.boundary))
+            .unwrap_or(self.config.boundary)
+    }
+
+    // 修改射击方法添加扇形射击
+    // 反弹规则下玩家的直线子弹可以在左右边缘反弹一次
+    fn ricochets(&self) -> u32 {
+        if self.mutators.contains(Mutator::Ricochet) { 1 } else { 0 }
+    }
+
+    fn shoot(&mut self) -> GameResult {
+        let laser = !self.has_spread_shot && self.loadout.primary == Primary::Laser;
+        let weapon = if self.has_spread_shot {
+            Weapon::Spread
+        } else if laser {
+            Weapon::Laser
+        } else {
+            Weapon::Blaster
+        };
+        self.cues.sound(SoundEvent::Shot(weapon));
+        let ricochets = self.ricochets();
+
+        let center_x = self.player.pos.x;
+        let top_y = self.player.pos.y - self.player.base_size.y / 2.0;
+        let bullet_pos = Vec2::new(center_x, top_y);
+
+        // 添加粒子效果
+        let mut flash = self.config.palette.color(if self.has_spread_shot { Swatch::SpreadShot } else { Swatch::PlayerShot });
+        flash.a = 0.5;
+        self.cues.burst(bullet_pos, flash);
+
+        // 后坐力越大、武器精度越低，随机偏角越大
+        let accuracy = if self.has_spread_shot { SPREAD_SHOT_ACCURACY } else { NORMAL_SHOT_ACCURACY };
+        let max_jitter = MAX_SPREAD_ANGLE * (1.0 - accuracy) * self.recoil;
+        self.recoil = (self.recoil + RECOIL_PER_SHOT).min(1.0);
+
+        if self.has_spread_shot {
+            // 扇形射击：发射5发子弹，角度范围为60度
+            let angles:[f32; 5] = [-30.0, -15.0, 0.0, 15.0, 30.0];  // 角度（度）
+            for &angle in angles.iter() {
+                let jitter = self.rng.gen_range(-1.0..=1.0) * max_jitter;
+                let rad: f32 = (angle + jitter).to_radians();
+                let direction = Vec2::new(rad.sin(), -rad.cos());
+                let mut bullet = GameObject::new(
+                    &self.assets,
+                    bullet_pos.x,
+                    bullet_pos.y,
+                    5.0,
+                    20.0,
+                    GameObjectType::SpreadShot,
+                )?;
+                bullet.speed = direction * BULLET_SPEED_RATIO * self.sim_size().height;
+                bullet.rotation = rad;  // 设置子弹旋转角度
+                bullet.ricochets = ricochets;
+                self.bullets.insert(bullet);
+            }
+        } else if laser {
+            // 激光：细长、更快，贯穿普通敌人
+            let rad = (self.rng.gen_range(-1.0..=1.0) * max_jitter).to_radians();
+            let mut bullet = GameObject::new(&self.assets, bullet_pos.x - 1.5, bullet_pos.y, 3.0, 36.0, GameObjectType::Bullet)?;
+            bullet.speed = Vec2::new(rad.sin(), -rad.cos()) * BULLET_SPEED_RATIO * self.sim_size().height * LASER_SPEED_SCALE;
+            bullet.rotation = rad;
+            bullet.tint = self.config.palette.color(Swatch::PlayerShot);
+            bullet.piercing = true;
+            bullet.ricochets = ricochets;
+            self.bullets.insert(bullet);
+        } else {
+            // 普通射击
+            let rad = (self.rng.gen_range(-1.0..=1.0) * max_jitter).to_radians();
+            let mut bullet = GameObject::new(
+                &self.assets,
+                bullet_pos.x - 2.5,
+                bullet_pos.y,
+                5.0,
+                20.0,
+                GameObjectType::Bullet,
+            )?;
+            bullet.speed = Vec2::new(rad.sin(), -rad.cos()) * BULLET_SPEED_RATIO * self.sim_size().height;
+            bullet.rotation = rad;
+            bullet.ricochets = ricochets;
+            self.bullets.insert(bullet);
+        }
+
+        Ok(())
+    }
+
+    // 以固定步长 dt（秒）推进一次游戏模拟：只由当前状态和本 tick 的输入决定下一个状态，
+    // 声音和视觉效果记入 cues，由 present 播放
+    fn step(&mut self, resources: &dyn Resources, dt: f32, inputs: Inputs) -> GameResult {
+        // 所有速度常量都是按 60fps 每帧设计的，这里换算到当前步长
+        let frame_scale = dt * REFERENCE_FPS;
+        let tick = Duration::from_secs_f32(dt);
+
+        self.run_time += dt;
+
+        // 波次推进，Boss 存活期间波次不结束
+        if self.boss.is_none() {
+            self.wave_timer += tick;
+        }
+        if self.wave_timer.as_secs_f32() >= WAVE_DURATION {
+            self.wave += 1;
+            info!("Wave {} started (score {})", self.wave, self.score);
+            self.wave_cleared();
+            self.wave_timer = Duration::from_secs(0);
+            self.script = resources.wave_script(self.wave);
+            if self.wave.is_multiple_of(BOSS_WAVE_INTERVAL) {
+                self.spawn_boss()?;
+            }
+            // 商店在新一波开始前打开；联机合作时没有商店，两端的购买无法同步
+            if (self.wave - 1).is_multiple_of(SHOP_WAVE_INTERVAL) && self.coop_rules.is_none() {
+                self.shop = Some(Shop::new());
+            }
+        }
+
+        // 关卡事件时间表（Boss 战期间波内时间暂停，不触发事件）
+        if self.boss.is_none() {
+            let wave_time = self.wave_timer.as_secs_f32();
+            for event in events::due(self.wave, wave_time - dt, wave_time) {
+                match event {
+                    LevelEvent::MeteorShower { duration } => {
+                        let direction = if self.rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+                        debug!("Meteor shower for {}s on wave {}", duration, self.wave);
+                        self.meteor_shower = Some(MeteorShower::new(duration, direction));
+                    }
+                }
+            }
+        }
+
+        // 执行波次脚本中到期的指令
+        let commands = self.script.as_mut().map(|script| script.update(dt)).unwrap_or_default();
+        for command in commands {
+            self.run_script_command(resources, command)?;
+        }
+        if self.script.as_ref().is_some_and(|script| script.is_finished()) {
+            self.script = None;
+        }
+        // 关卡时间轴按开局后的时间放出编队，x 为敌机中心
+        let due = self.level_timeline.as_mut().map(|timeline| timeline.update(self.run_time)).unwrap_or_default();
+        for (kind, x) in due {
+            let offset = x - self.enemy_defs.def(kind).size / 2.0;
+            self.spawn_enemy_at(kind, EntryEdge::Top, offset)?;
+        }
+        if self.level_timeline.as_ref().is_some_and(|timeline| timeline.is_finished()) {
+            self.level_timeline = None;
+        }
+
+        // 记录上一 tick 的位置，用于渲染插值（Boss 在 update_boss 中记录）
+        self.player.prev_pos = self.player.pos;
+        for object in self.bullets.iter_mut()
+            .chain(self.enemies.iter_mut())
+            .chain(self.enemy_bullets.iter_mut())
+            .chain(self.hazards.iter_mut())
+            .chain(self.meteors.iter_mut())
+            .chain(self.debris.iter_mut())
+            .chain(self.drones.iter_mut())
+            .chain(self.ammo_items.iter_mut()) {
+            object.prev_pos = object.pos;
+        }
+
+        // 联机合作时两架飞机的输入由锁步会话给出，否则只有本地玩家的输入
+        let Inputs { player: input, partner: partner_input } = inputs;
+
+        let ship = self.run_ship();
+        let mut top_speed = PLAYER_SPEED_RATIO * self.sim_size().width * ship.speed_scale() * REFERENCE_FPS;
+        // 专注模式：按住时降低移动速度，便于在密集弹幕中微调位置
+        self.focus_mode = !matches!(self.scene, Scene::Demo(_)) && input.focus;
+        if self.focus_mode {
+            top_speed *= FOCUS_SPEED_SCALE;
+        }
+
+        let screen = Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT);
+        let classic = self.classic_movement();
+        let moved = match &self.scene {
+            Scene::Demo(replay) => replay.sample(self.run_time).unwrap_or(self.player.pos),
+            _ => self.player.pos + Self::drive(&mut self.player, input.direction(), top_speed, ship, classic, dt),
+        };
+        self.player.pos = self.boundary().apply(moved, self.player.base_size, screen);
+        self.player.stop_at_edges(moved);
+        // 穿越屏幕边缘时不做插值，避免横跨整个屏幕的一帧拖影
+        if (self.player.pos.x - moved.x).abs() > BASE_WINDOW_WIDTH / 2.0 {
+            self.player.prev_pos = self.player.pos;
+        }
+        self.recording.record(self.run_time, self.player.pos);
+
+        self.shoot_cooldown = self.shoot_cooldown.saturating_sub(tick);
+
+        // 开启自动射击时持续开火（已计入输入），射速同样由 shoot_cooldown 限制
+        let firing = matches!(self.scene, Scene::Demo(_)) || input.fire;
+        if firing && self.shoot_cooldown.is_zero() {
+            self.shoot()?;
+            self.shoot_cooldown = Duration::from_millis(SHOT_INTERVAL_MS - SHOT_INTERVAL_PER_TIER_MS * self.weapon_tier as u64);
+        }
+        if !firing {
+            self.recoil = (self.recoil - RECOIL_RECOVERY * dt).max(0.0);
+        }
+
+        // 更新导弹冷却时间
+        self.missile_cooldown = self.missile_cooldown.saturating_sub(tick);
+
+        // 处理发射追踪导弹：每次按键只发射一枚，冷却中的按键被丢弃
+        if input.missile && self.missile_cooldown.is_zero() {
+            self.launch_missile()?;
+            self.missile_cooldown = Duration::from_millis(1000);  // 1秒冷却时间
+        }
+
+        if input.bomb {
+            self.detonate_bomb();
+        }
+
+        if let Some(input) = partner_input {
+            self.update_partner(input, dt)?;
+        }
+
+        // 在子弹更新逻辑中添加扇形子弹的处理
+        let sim_size = self.sim_size();
+        for bullet in self.bullets.iter_mut() {
+            bullet.age += dt;
+            match bullet.object_type {
+                GameObjectType::Bullet | GameObjectType::SpreadShot | GameObjectType::DroneShot => {
+                    bullet.pos += bullet.speed * frame_scale;  // 使用预设的速度和方向
+                    bullet.ricochet();
+                }
+                GameObjectType::GuidedMissile => {
+                    bullet.update_guided_missile(&self.enemies, &sim_size, frame_scale);
+                    bullet.pos += bullet.speed * frame_scale;
+                }
+                _ => {}
+            }
+        }
+
+        // 在弹药生成逻辑中随机生成扇形弹药
+        self.ammo_spawn_timer += tick;
+        if self.ammo_spawn_timer.as_secs_f32() >= 1.0 {
+            // 按稀有度权重决定生成哪种道具
+            match pickups::roll(&mut self.rng).object_type {
+                GameObjectType::SpreadAmmo => self.spawn_spread_ammo()?,
+                GameObjectType::DronePickup => self.spawn_drone_pickup()?,
+                GameObjectType::EmpPickup => self.spawn_emp_pickup()?,
+                _ if self.mutators.contains(Mutator::NoMissiles) => {}
+                _ => self.spawn_missile_ammo()?,
+            }
+            self.ammo_spawn_timer = Duration::from_secs(0);
+        }
+
+
+        // 燃料耗尽的导弹自毁，爆炸在碰撞检测阶段结算
+        let missile_blasts: Vec<Vec2> = self
+            .bullets
+            .iter()
+            .filter(|bullet| matches!(bullet.object_type, GameObjectType::GuidedMissile) && bullet.is_expired())
+            .map(|bullet| bullet.pos)
+            .collect();
+        self.bullets.retain(|bullet| !bullet.should_despawn());
+
+        // 僚机跟随与射击
+        self.update_drones(dt)?;
+
+        // 处理敌人生成
+        // 有空位时先放出排队的敌人
+        let free = self.enemy_cap().saturating_sub(self.enemies.len());
+        for request in self.spawn_queue.release(free) {
+            self.place_enemy(request)?;
+        }
+        // 脚本或关卡时间轴运行期间由它们接管敌人生成，Boss 战期间不生成普通敌人；
+        // 随机生成在同屏已满时直接跳过，不占用队列
+        self.spawn_timer += tick;
+        let scripted = self.script.is_some() || self.level_timeline.is_some();
+        let spawn_interval = self.difficulty().spawn_interval_scale() / self.ramp(|tuning| tuning.spawn_rate);
+        if !scripted && self.boss.is_none() && self.spawn_timer.as_secs_f32() >= spawn_interval {
+            if self.enemies.len() < self.enemy_cap() {
+                self.spawn_enemy()?;
+            }
+            self.spawn_timer = Duration::from_secs(0);
+        }
+
+        // 更新敌人位置
+        let enemy_speed = ENEMY_SPEED_RATIO
+            * self.sim_size().height
+            * self.difficulty().enemy_speed_scale()
+            * self.ramp(|tuning| tuning.enemy_speed)
+            * if self.mutators.contains(Mutator::DoubleSpeed) { 2.0 } else { 1.0 }
+            * frame_scale;
+        // 受击闪白与无敌时间
+        self.player.update_feedback(dt);
+        if let Some((body, _)) = &mut self.boss {
+            body.update_feedback(dt);
+        }
+
+        let mut player_hit = false;
+        // 擦弹判定区：比玩家碰撞圆大一圈
+        let graze_zone = Collider::Circle(Circle::new(self.player.pos, self.player.base_size.x * 0.4 + GRAZE_MARGIN));
+        let mut grazes = Vec::new();
+        let mut launch_room = self.enemy_cap().saturating_sub(self.enemies.len());
+        for enemy in self.enemies.iter_mut() {
+            let speed_factor = enemy.enemy_kind.map_or(1.0, |kind| self.enemy_defs.def(kind).speed)
+                * enemy.status.speed_scale();
+            // 沿路线飞行的敌人飞完路线前不俯冲
+            let dive = enemy.enemy_kind.and_then(|kind| self.enemy_defs.def(kind).dive).filter(|_| enemy.path.is_none());
+            let locked = enemy.dive_target.is_some();
+            let cruising = dive.is_none_or(|dive| enemy.update_dive(&dive, self.player.pos, dt, frame_scale));
+            if let Some((path, distance)) = &mut enemy.path {
+                // 飞完路线后沿末端方向继续直线飞行
+                *distance += enemy_speed * speed_factor;
+                match path.sample(*distance) {
+                    Some((pos, heading)) => {
+                        enemy.pos = pos;
+                        enemy.heading = heading;
+                        enemy.rotation = heading.y.atan2(heading.x) + std::f32::consts::FRAC_PI_2;
+                    }
+                    None => enemy.path = None,
+                }
+            } else if cruising {
+                enemy.pos += enemy.heading * enemy_speed * speed_factor;
+            }
+            // 刚锁定目标时沿冲刺方向显示预警线
+            if let Some(dive) = dive.filter(|_| !locked && enemy.dive_target.is_some()) {
+                let to = enemy.pos + enemy.heading * BASE_WINDOW_HEIGHT * 1.5;
+                self.warnings.add(Attack::Dive, WarningShape::Line { from: enemy.pos, to, width: 3.0 }, dive.warning);
+            }
+            enemy.update_feedback(dt);
+
+            // 燃烧伤害计入装甲损伤，并打断护盾恢复
+            let burn = enemy.status.update(dt);
+            if burn > 0 {
+                enemy.damage += burn;
+                enemy.regen_timer = 0.0;
+            }
+
+            // 装甲护盾在一段时间未被命中后逐格恢复
+            let armor = enemy.enemy_kind.and_then(|kind| self.enemy_defs.def(kind).armor);
+            if let Some(armor) = armor {
+                enemy.regen_timer += dt;
+                if enemy.damage > 0 && enemy.regen_timer >= armor.regen {
+                    enemy.damage -= 1;
+                    enemy.regen_timer = 0.0;
+                }
+            }
+            enemy.pos += enemy.speed * frame_scale * enemy.status.speed_scale();  // 额外的横向漂移（如分裂出的小敌机）
+
+            // 母舰进入屏幕后定时从机库朝玩家放出舰载机，同屏敌人已满时不放出
+            let launcher = enemy.enemy_kind.and_then(|kind| self.enemy_defs.def(kind).launcher);
+            let on_screen = enemy.pos.cmpge(Vec2::ZERO).all() && enemy.pos.cmple(Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT)).all();
+            if let Some(launcher) = launcher.filter(|_| on_screen) {
+                enemy.launch_timer -= dt * enemy.status.speed_scale();
+                if enemy.launch_timer <= 0.0 {
+                    enemy.launch_timer += launcher.interval;
+                    let bay = enemy.pos + enemy.heading * enemy.base_size.y * 0.4;
+                    let aim = (self.player.pos - bay).try_normalize().unwrap_or(enemy.heading);
+                    for i in 0..launcher.count.min(launch_room as u32) {
+                        let side = i as f32 - (launcher.count - 1) as f32 / 2.0;
+                        let heading = Vec2::from_angle(side * 0.35).rotate(aim);
+                        self.commands.push(GameCommand::SpawnEnemy { kind: launcher.child, pos: bay, heading, drift: Vec2::ZERO });
+                        launch_room -= 1;
+                    }
+                    self.cues.burst(bay, enemy.tint);
+                }
+            }
+            if enemy.hits(&self.player) {
+                player_hit = true;
+            } else if !enemy.grazed && graze_zone.intersects(&enemy.collider()) {
+                enemy.grazed = true;
+                grazes.push(enemy.pos);
+            }
+        }
+        // 离开屏幕且仍在远离的敌人被移除
+        self.enemies.retain(|enemy| !enemy.is_off_screen());
+
+        // Boss 移动与射击，撞上玩家同样致命
+        self.update_boss(dt, frame_scale)?;
+        if self.boss.as_ref().is_some_and(|(body, _)| body.hits(&self.player)) {
+            player_hit = true;
+        }
+        // 激光按胶囊体（线段加半宽）与玩家判定
+        let beam = self.boss.as_ref().and_then(|(body, boss)| boss.laser(body.pos)).filter(|beam| beam.firing);
+        if let Some(beam) = beam {
+            let collider = Collider::Capsule { a: beam.start, b: beam.end, radius: beam.width / 2.0 };
+            if collider.intersects(&self.player.hurtbox()) {
+                player_hit = true;
+            }
+        }
+
+        // 更新敌方子弹和追踪地雷
+        let bullet_speed = self.ramp(|tuning| tuning.bullet_speed);
+        for bullet in &mut self.enemy_bullets {
+            Self::move_enemy_bullet(bullet, self.player.pos, dt, frame_scale, bullet_speed);
+            if bullet.hits(&self.player) {
+                player_hit = true;
+            } else if !bullet.grazed && graze_zone.intersects(&bullet.collider()) {
+                bullet.grazed = true;
+                grazes.push(bullet.pos);
+            }
+        }
+        self.enemy_bullets.retain(|bullet| !bullet.should_despawn());
+
+        // 生成并移动漂浮水雷
+        self.hazard_timer += dt;
+        if self.hazard_timer >= HAZARD_SPAWN_INTERVAL {
+            self.spawn_hazard()?;
+            self.hazard_timer = 0.0;
+        }
+        for mine in self.hazards.iter_mut() {
+            mine.pos += mine.speed * frame_scale;
+            mine.rotation += 0.01 * frame_scale;
+        }
+        self.hazards.retain(|mine| !mine.is_off_screen());
+
+        // 流星雨：流星斜穿屏幕，撞毁途经的敌人和玩家
+        if let Some(shower) = &mut self.meteor_shower {
+            let direction = shower.direction;
+            for _ in 0..shower.update(dt) {
+                self.spawn_meteor(direction)?;
+            }
+            if self.meteor_shower.as_ref().is_some_and(MeteorShower::is_finished) {
+                self.meteor_shower = None;
+            }
+        }
+        for meteor in &mut self.meteors {
+            meteor.pos += meteor.speed * frame_scale;
+            meteor.rotation += 0.08 * frame_scale;
+            if meteor.hits(&self.player) {
+                player_hit = true;
+            }
+        }
+        let meteors = &self.meteors;
+        let mut crushed = Vec::new();
+        self.enemies.retain(|enemy| {
+            let hit = meteors.iter().any(|meteor| meteor.intersects(enemy));
+            if hit {
+                crushed.push(enemy.pos);
+            }
+            !hit
+        });
+        for pos in crushed {
+            self.cues.explosion(ExplosionKind::Enemy, pos, 60.0);
+        }
+        self.meteors.retain(|meteor| !meteor.is_off_screen());
+
+        // 残骸在重力下加速下落并翻滚，砸到玩家同样致命
+        for piece in &mut self.debris {
+            piece.speed.y += DEBRIS_GRAVITY * frame_scale;
+            piece.pos += piece.speed * frame_scale;
+            piece.rotation += piece.speed.x.signum() * 0.04 * frame_scale;
+            piece.update_feedback(dt);
+            if piece.hits(&self.player) {
+                player_hit = true;
+            }
+        }
+        self.debris.retain(|piece| !piece.is_off_screen());
+
+        // 生存模式：敌人冲向路障
+        self.update_barricades(dt, frame_scale);
+
+        // 擦弹：少量加分，并在擦过的位置迸出火花
+        let graze_gradient = ColorGradient::new(
+            Color::new(1.0, 1.0, 0.9, 1.0),
+            Color::new(1.0, 0.8, 0.2, 0.0),
+        );
+        for pos in grazes {
+            self.grazes += 1;
+            let points = scaled_score(GRAZE_SCORE, self.mutators.score_multiplier());
+            self.score += points;
+            self.cues.push(Cue::Popup { pos: self.player.pos, points, color: self.config.palette.color(Swatch::HudGraze) });
+            self.cues.emit(EmitterKind::Explosion, self.player.pos.lerp(pos, 0.5), 0.0, graze_gradient);
+        }
+
+        if player_hit {
+            self.hit_player()?;
+        }
+        if self.partner_hit() {
+            self.kill_partner()?;
+        }
+
+        // 引力井牵引与引爆
+        self.update_gravity_wells(dt, frame_scale);
+
+        // 碰撞检测：被摧毁的对象、击毁和爆炸记入指令队列，在本 tick 末尾统一执行
+        let mut armor_hits: HashMap<Handle, u32> = HashMap::new();
+        let mut shield_hits: HashMap<Handle, u32> = HashMap::new();
+        let mut status_hits = Vec::new();
+        let one_hit_kills = self.mutators.contains(Mutator::OneHitKills);
+        let score_multiplier = self.mutators.score_multiplier();
+        // 装甲被命中时迸出的火花
+        let spark_gradient = ColorGradient::new(
+            Color::new(0.8, 0.9, 1.0, 1.0),
+            Color::new(0.3, 0.5, 1.0, 0.0),
+        );
+        // 护盾泡吸收子弹时的波纹
+        let shield_gradient = ColorGradient::new(
+            Color::new(0.7, 1.0, 1.0, 0.9),
+            Color::new(0.2, 0.7, 1.0, 0.0),
+        );
+
+        // 残骸挡住玩家子弹（包括激光），每块要打几下才碎
+        for (bullet_id, bullet) in self.bullets.entries() {
+            if let Some(piece) = self.debris.iter_mut().find(|piece| piece.damage < DEBRIS_HITS && bullet.intersects(piece)) {
+                self.commands.remove(Pool::Bullets, bullet_id);
+                piece.damage += 1;
+                piece.flash();
+                self.cues.emit(EmitterKind::Explosion, bullet.pos, 0.0, spark_gradient);
+            }
+        }
+        self.break_debris()?;
+
+        for (bullet_id, bullet) in self.bullets.entries() {
+            for (enemy_id, enemy) in self.enemies.entries() {
+                if self.commands.is_removed(Pool::Bullets, bullet_id) || self.commands.is_removed(Pool::Enemies, enemy_id) {
+                    continue;
+                }
+                let kind = enemy.enemy_kind.unwrap_or(EnemyKind::Fighter);
+
+                // 护盾泡是机体外的第二层碰撞体，先于机体结算：命中护盾的子弹（包括激光）被吸收
+                let shield = self.enemy_defs.def(kind).shield.filter(|_| !one_hit_kills);
+                if let Some(shield) = shield {
+                    let absorbed = shield_hits.get(&enemy_id).copied().unwrap_or(0);
+                    if enemy.shield > absorbed && bullet.collider().intersects(&enemy.shield_collider(&shield)) {
+                        self.commands.remove(Pool::Bullets, bullet_id);
+                        *shield_hits.entry(enemy_id).or_default() += 1;
+                        self.cues.emit(EmitterKind::Explosion, bullet.pos, 0.0, shield_gradient);
+                        continue;
+                    }
+                }
+
+                // 装甲敌人只有弱点受伤，正面命中的子弹被弹开
+                let armor = self.enemy_defs.def(kind).armor.filter(|_| !one_hit_kills);
+                if let Some(armor) = armor {
+                    let shot = bullet.collider();
+                    let weak_hit = enemy
+                        .weak_points(&armor)
+                        .iter()
+                        .any(|point| Collider::Circle(*point).intersects(&shot));
+                    if !weak_hit && !bullet.intersects(enemy) {
+                        continue;
+                    }
+                    self.commands.remove(Pool::Bullets, bullet_id);
+                    self.cues.emit(EmitterKind::Explosion, bullet.pos, 0.0, spark_gradient);
+                    if let Some(effect) = bullet_status(bullet) {
+                        status_hits.push((enemy_id, effect));
+                    }
+                    if !weak_hit {
+                        continue;
+                    }
+                    let hits = armor_hits.entry(enemy_id).or_default();
+                    *hits += 1;
+                    if enemy.damage + *hits < armor.hits {
+                        continue;
+                    }
+                } else if bullet.intersects(enemy) {
+                    if !bullet.piercing {
+                        self.commands.remove(Pool::Bullets, bullet_id);
+                    }
+                } else {
+                    continue;
+                }
+
+                self.commands.remove(Pool::Enemies, enemy_id);
+                // 导弹击中给双倍分数
+                let points = self.enemy_defs.def(kind).score * match bullet.object_type {
+                    GameObjectType::GuidedMissile => 2,
+                    _ => 1,
+                };
+
+                self.commands.push(GameCommand::Explosion(enemy.pos));
+                self.commands.push(GameCommand::EnemyKilled { handle: enemy_id, kind, pos: enemy.pos, points: scaled_score(points, score_multiplier) });
+            }
+        }
+
+        // 子弹命中 Boss，导弹造成双倍伤害
+        if let Some((body, boss)) = &mut self.boss {
+            for (bullet_id, bullet) in self.bullets.entries() {
+                if self.commands.is_removed(Pool::Bullets, bullet_id) || !bullet.intersects(body) {
+                    continue;
+                }
+                self.commands.remove(Pool::Bullets, bullet_id);
+                // 护卫存活期间子弹被护盾挡下
+                if boss.is_shielded() {
+                    self.cues.burst(bullet.pos, BOSS_SHIELD_COLOR);
+                    continue;
+                }
+                body.flash();
+                boss.damage(match bullet.object_type {
+                    GameObjectType::GuidedMissile => 2,
+                    _ => 1,
+                });
+                if let Some((kind, duration)) = bullet_status(bullet) {
+                    body.status.apply(kind, duration);
+                }
+                self.cues.emit(EmitterKind::Explosion, bullet.pos, 0.0, spark_gradient);
+            }
+            if boss.is_dead() {
+                let pos = body.pos;
+                self.boss = None;
+                let points = scaled_score(BOSS_SCORE, score_multiplier);
+                self.score += points;
+                self.events.publish(GameEvent::BossKilled { pos, points });
+                self.cancel_bullets(Some(Shooter::Boss), pos, f32::INFINITY)?;
+                self.cues.explosion(ExplosionKind::Player, pos, BOSS_SIZE * 1.5);
+                self.cues.push(Cue::StopMusic);
+                self.cues.push(Cue::Ambient { effect: AmbientEffect::DebrisRain, duration: Some(BOSS_DEBRIS_DURATION) });
+                self.commands.push(GameCommand::Explosion(pos));
+            }
+        }
+
+        // 导弹自毁的小范围爆炸：炸毁范围内的普通敌人，装甲敌人只会被点燃
+        for center in missile_blasts {
+            let blast = Collider::Circle(Circle::new(center, MISSILE_BLAST_RADIUS));
+            for (enemy_id, enemy) in self.enemies.entries() {
+                if self.commands.is_removed(Pool::Enemies, enemy_id) || !blast.intersects(&enemy.collider()) {
+                    continue;
+                }
+                let kind = enemy.enemy_kind.unwrap_or(EnemyKind::Fighter);
+                // 护盾泡挡住爆炸，但会被整个炸破
+                let absorbed = shield_hits.get(&enemy_id).copied().unwrap_or(0);
+                if enemy.shield > absorbed && !one_hit_kills {
+                    shield_hits.insert(enemy_id, enemy.shield);
+                    continue;
+                }
+                if self.enemy_defs.def(kind).armor.is_some() && !one_hit_kills {
+                    status_hits.push((enemy_id, (StatusKind::Burning, MISSILE_BURN_DURATION)));
+                    continue;
+                }
+                self.commands.remove(Pool::Enemies, enemy_id);
+                self.commands.push(GameCommand::Explosion(enemy.pos));
+                self.commands.push(GameCommand::EnemyKilled { handle: enemy_id, kind, pos: enemy.pos, points: scaled_score(self.enemy_defs.def(kind).score, score_multiplier) });
+            }
+            self.cues.explosion(ExplosionKind::Enemy, center, MISSILE_BLAST_RADIUS * 2.0);
+            self.cues.sound(SoundEvent::Explosion);
+        }
+
+        // 护盾泡吸收的伤害，耗尽时破裂
+        for (enemy_id, hits) in shield_hits {
+            let enemy = &mut self.enemies[enemy_id];
+            enemy.shield = enemy.shield.saturating_sub(hits);
+            if enemy.shield == 0 {
+                self.cues.burst(enemy.pos, Color::new(0.5, 0.9, 1.0, 1.0));
+            }
+        }
+
+        // 记录未被摧毁的装甲敌人所受的弱点伤害和特殊武器的状态效果
+        for (enemy_id, hits) in armor_hits {
+            let enemy = &mut self.enemies[enemy_id];
+            enemy.damage += hits;
+            enemy.regen_timer = 0.0;
+            enemy.flash();
+        }
+        for (enemy_id, (kind, duration)) in status_hits {
+            self.enemies[enemy_id].status.apply(kind, duration);
+        }
+
+        // 装甲被燃烧烧穿的敌人
+        for (enemy_id, enemy) in self.enemies.entries() {
+            let kind = enemy.enemy_kind.unwrap_or(EnemyKind::Fighter);
+            let Some(armor) = self.enemy_defs.def(kind).armor else {
+                continue;
+            };
+            if enemy.damage >= armor.hits && self.commands.remove(Pool::Enemies, enemy_id) {
+                self.commands.push(GameCommand::Explosion(enemy.pos));
+                self.commands.push(GameCommand::EnemyKilled { handle: enemy_id, kind, pos: enemy.pos, points: scaled_score(self.enemy_defs.def(kind).score, score_multiplier) });
+            }
+        }
+
+        // 水雷被击中或碰触时引爆，爆炸会连锁引爆范围内的其他水雷
+        let mut detonations = Vec::new();
+        for (mine_id, mine) in self.hazards.entries() {
+            let shot = self.bullets.entries().find(|(bullet_id, bullet)| {
+                !self.commands.is_removed(Pool::Bullets, *bullet_id) && bullet.intersects(mine)
+            });
+            if let Some((bullet_id, _)) = shot {
+                self.commands.remove(Pool::Bullets, bullet_id);
+                detonations.push(mine_id);
+            } else if mine.hits(&self.player)
+                || self.enemies.iter().any(|enemy| enemy.intersects(mine)) {
+                detonations.push(mine_id);
+            }
+        }
+
+        if !detonations.is_empty() {
+            let enemy_grid = SpatialGrid::build(HAZARD_BLAST_RADIUS, self.enemies.entries().map(|(handle, enemy)| (handle, enemy.pos)));
+            let mine_grid = SpatialGrid::build(HAZARD_BLAST_RADIUS, self.hazards.entries().map(|(handle, mine)| (handle, mine.pos)));
+            let mut detonated: HashSet<Handle> = detonations.iter().copied().collect();
+
+            while let Some(mine_id) = detonations.pop() {
+                let center = self.hazards[mine_id].pos;
+
+                for enemy_id in enemy_grid.query_radius(center, HAZARD_BLAST_RADIUS) {
+                    if self.commands.remove(Pool::Enemies, enemy_id) {
+                        let enemy = &self.enemies[enemy_id];
+                        let kind = enemy.enemy_kind.unwrap_or(EnemyKind::Fighter);
+                        self.commands.push(GameCommand::Explosion(enemy.pos));
+                        self.commands.push(GameCommand::EnemyKilled { handle: enemy_id, kind, pos: enemy.pos, points: scaled_score(self.enemy_defs.def(kind).score, score_multiplier) });
+                    }
+                }
+                // 冲击波让爆炸范围外一圈的敌人短暂瘫痪
+                for enemy_id in enemy_grid.query_radius(center, HAZARD_BLAST_RADIUS * 2.0) {
+                    if !self.commands.is_removed(Pool::Enemies, enemy_id) {
+                        self.enemies[enemy_id].status.apply(StatusKind::Stunned, BLAST_STUN_DURATION);
+                    }
+                }
+                for other in mine_grid.query_radius(center, HAZARD_BLAST_RADIUS) {
+                    if detonated.insert(other) {
+                        detonations.push(other);
+                    }
+                }
+                if self.player.pos.distance(center) <= HAZARD_BLAST_RADIUS {
+                    self.hit_player()?;
+                }
+
+                self.cues.explosion(ExplosionKind::Enemy, center, HAZARD_BLAST_RADIUS * 2.0);
+                self.cues.sound(SoundEvent::Explosion);
+            }
+
+            for mine_id in detonated {
+                self.commands.remove(Pool::Hazards, mine_id);
+            }
+        }
+
+        // 更新连锁计时
+        self.chain.update(dt);
+        for (_, age) in &mut self.shockwaves {
+            *age += dt;
+        }
+        self.shockwaves.retain(|(_, age)| *age < SHOCKWAVE_DURATION);
+
+        // 屏幕外炮击：在玩家当前位置标出落点，预警结束后落下
+        if self.wave >= ARTILLERY_MIN_WAVE && self.boss.is_none() {
+            self.artillery_timer += dt;
+            if self.artillery_timer >= ARTILLERY_INTERVAL {
+                self.artillery_timer = 0.0;
+                let corner = self.player.pos - Vec2::splat(ARTILLERY_ZONE / 2.0);
+                let zone = graphics::Rect::new(corner.x, corner.y, ARTILLERY_ZONE, ARTILLERY_ZONE);
+                self.warnings.add(Attack::Artillery, WarningShape::Zone(zone), ARTILLERY_WARNING);
+            }
+        }
+        for warning in self.warnings.update(dt) {
+            if warning.attack == Attack::Artillery {
+                self.land_artillery(warning.shape)?;
+            }
+        }
+
+
+        // 更新弹药生成计时器
+        // self.ammo_spawn_timer += tick;
+        // if self.ammo_spawn_timer.as_secs_f32() >= 15.0 { // 每15秒生成一个弹药包
+        //     self.spawn_missile_ammo()?;
+        //     self.ammo_spawn_timer = Duration::from_secs(0);
+        // }
+
+        // 清波后道具被吸向玩家：每个 tick 走完剩余距离的一部分，比例随剩余时间缩短而增大，
+        // 到时恰好落到玩家身上，由下面的拾取判定收下
+        if let Some(elapsed) = &mut self.vacuum {
+            *elapsed += dt;
+            let remaining = (VACUUM_DURATION - *elapsed).max(dt);
+            let pull = (dt / remaining).min(1.0);
+            for ammo in self.ammo_items.iter_mut() {
+                ammo.pos = ammo.pos.lerp(self.player.pos, pull);
+            }
+            if *elapsed >= VACUUM_DURATION || self.ammo_items.is_empty() {
+                self.vacuum = None;
+            }
+        }
+
+        // 更新弹药位置；稀有道具快要掉出屏幕时提示一次
+        let fall_speed = ENEMY_SPEED_RATIO * self.sim_size().height;
+        let vacuuming = self.vacuum.is_some();
+        for ammo in self.ammo_items.iter_mut() {
+            if let GameObjectType::BonusStar | GameObjectType::ScoreGem = ammo.object_type {
+                ammo.rotation += 0.08 * frame_scale;
+            }
+            if vacuuming {
+                continue;
+            }
+            ammo.pos.y += fall_speed * frame_scale;
+            let leaving = ammo.pickup_exit_time(fall_speed).is_some_and(|time| time <= PICKUP_EXIT_WARNING);
+            if leaving && !ammo.exit_warned {
+                ammo.exit_warned = true;
+                if self.config.pickup_warnings {
+                    self.cues.sound(SoundEvent::PickupWarning);
+                }
+            }
+        }
+        self.ammo_items.retain(|ammo| !ammo.is_off_screen());
+
+        // // 检测玩家与弹药的碰撞
+        // let mut collected_ammo = Vec::new();
+        // for (idx, ammo) in self.ammo_items.iter().enumerate() {
+        //     if ammo.intersects(&self.player) {
+        //         collected_ammo.push(idx);
+        //         self.missile_ammo += 3; // 每个弹药包补充3发导弹
+        //
+        //         // 添加收集效果
+        //         self.particles.add_explosion(
+        //             ammo.pos,
+        //             Color::new(0.0, 1.0, 1.0, 1.0), // 青色粒子效果
+        //             &self.window_size,
+        //         );
+        //     }
+        // }
+        //
+        // // 移除被收集的弹药
+        // for idx in collected_ammo.iter().rev() {
+        //     self.ammo_items.remove(*idx);
+        // }
+
+        // 保留并修改这段代码
+        let mut emp_triggered = false;
+        for (handle, ammo) in self.ammo_items.entries() {
+            if ammo.intersects(&self.player) {
+                self.commands.remove(Pool::Pickups, handle);
+                match ammo.object_type {
+                    GameObjectType::SpreadAmmo => {
+                        self.has_spread_shot = true;
+                        // 橙色粒子效果
+                        self.events.publish(GameEvent::PickupCollected { pos: ammo.pos, color: Color::new(1.0, 0.5, 0.0, 1.0) });
+                    }
+                    GameObjectType::DronePickup => {
+                        // 最多同时拥有两架僚机
+                        if self.drones.len() < MAX_DRONES {
+                            let mut drone = GameObject::new(&self.assets, ammo.pos.x, ammo.pos.y, 22.0, 26.0, GameObjectType::Drone)?;
+                            drone.tint = Color::new(0.6, 0.85, 1.0, 1.0);
+                            self.drones.push(drone);
+                        }
+                        // 浅蓝色粒子效果
+                        self.events.publish(GameEvent::PickupCollected { pos: ammo.pos, color: Color::new(0.4, 0.7, 1.0, 1.0) });
+                    }
+                    GameObjectType::MissileAmmo => {
+                        self.missile_ammo += 3; // 每个弹药包补充3发导弹
+                        // 青色粒子效果
+                        self.events.publish(GameEvent::PickupCollected { pos: ammo.pos, color: Color::new(0.0, 1.0, 1.0, 1.0) });
+                    }
+                    GameObjectType::EmpPickup => {
+                        emp_triggered = true;
+                        let color = self.config.palette.color(Swatch::PickupEmp);
+                        self.events.publish(GameEvent::PickupCollected { pos: ammo.pos, color });
+                    }
+                    GameObjectType::BonusStar => {
+                        let points = scaled_score(BONUS_STAR_SCORE, self.mutators.score_multiplier());
+                        let color = self.config.palette.color(Swatch::BonusStar);
+                        self.score += points;
+                        self.cues.push(Cue::Announce { pos: ammo.pos, label: "BONUS STAR".to_string(), points, color });
+                        self.events.publish(GameEvent::PickupCollected { pos: ammo.pos, color });
+                    }
+                    // 宝石数量多，只加分，不算作拾取道具
+                    GameObjectType::ScoreGem => {
+                        self.score += scaled_score(SCORE_GEM_SCORE, self.mutators.score_multiplier());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if emp_triggered {
+            self.trigger_emp();
+        }
+
+        // 移除被收集的道具，执行本 tick 的其余延迟指令，再把本 tick 的事件分发给订阅者
+        self.apply_commands()?;
+        self.dispatch_events();
+        Ok(())
+    }
+
+    // 订阅事件总线的系统：统计、音效和画面效果
+    fn dispatch_events(&mut self) {
+        self.events.dispatch(
+            self.run_time,
+            &mut [&mut self.stats, &mut self.log, &mut SoundCues, &mut EffectCues],
+            &mut self.cues,
+        );
+    }
+
+    // 执行延迟指令：先统一移除被标记的对象，再按加入顺序处理生成、击毁和爆炸。
+    // 执行中新加入的指令（如分裂出的子敌机）在同一 tick 内继续执行
+    fn apply_commands(&mut self) -> GameResult {
+        // 爆炸粒子由亮黄渐变到暗红
+        let explosion_gradient = ColorGradient::new(
+            Color::new(1.0, 0.9, 0.5, 1.0),
+            Color::new(1.0, 0.2, 0.0, 0.0),
+        );
+        loop {
+            let commands = self.commands.take();
+            if commands.is_empty() {
+                return Ok(());
+            }
+            commands::remove_marked(&mut self.bullets, Pool::Bullets, &commands);
+            commands::remove_marked(&mut self.enemies, Pool::Enemies, &commands);
+            commands::remove_marked(&mut self.hazards, Pool::Hazards, &commands);
+            commands::remove_marked(&mut self.ammo_items, Pool::Pickups, &commands);
+
+            for command in commands {
+                match command {
+                    GameCommand::Remove(..) => {}
+                    GameCommand::SpawnEnemy { kind, pos, heading, drift } => {
+                        let mut enemy = Self::create_enemy(&self.assets, self.enemy_defs.def(kind), pos, heading)?;
+                        enemy.speed = drift;
+                        self.enemies.insert(enemy);
+                    }
+                    // 计分、连锁和死亡行为
+                    GameCommand::EnemyKilled { handle, kind, pos, points } => {
+                        self.score += points;
+                        self.events.publish(GameEvent::EnemyKilled { kind, pos, points });
+                        if self.chain.record_kill() {
+                            let label = format!("CHAIN x{}!", popups::CHAIN_KILLS);
+                            self.cues.push(Cue::Announce { pos, label, points: 0, color: self.config.palette.color(Swatch::BonusStar) });
+                            self.cues.sound(SoundEvent::Stinger(Stinger::Combo));
+                            self.spawn_bonus_star(pos)?;
+                        }
+                        // 附近的敌弹不论是谁发射的都会消掉；先消弹，死亡时放出的子弹不会被立刻消掉
+                        self.cancel_bullets(None, pos, CANCEL_RADIUS)?;
+                        self.apply_on_death(handle, kind, pos)?;
+                    }
+                    GameCommand::Explosion(pos) => {
+                        self.cues.emit(EmitterKind::Explosion, pos, 0.0, explosion_gradient);
+                        self.cues.explosion(ExplosionKind::Enemy, pos, 60.0);
+                    }
+                }
+            }
+        }
+    }
+
+    // 当前模拟状态的快照
+    fn snapshot(&self) -> SimState {
+        SimState {
+            player: self.player.clone(),
+            partner: self.partner.clone(),
+            partner_cooldown: self.partner_cooldown,
+            bullets: self.bullets.clone(),
+            enemies: self.enemies.clone(),
+            enemy_bullets: self.enemy_bullets.clone(),
+            hazards: self.hazards.clone(),
+            hazard_timer: self.hazard_timer,
+            meteors: self.meteors.clone(),
+            meteor_shower: self.meteor_shower.clone(),
+            debris: self.debris.clone(),
+            barricades: self.barricades.clone(),
+            drones: self.drones.clone(),
+            drone_fire_timer: self.drone_fire_timer,
+            boss: self.boss.clone(),
+            shockwaves: self.shockwaves.clone(),
+            gravity_wells: self.gravity_wells.clone(),
+            warnings: self.warnings.clone(),
+            artillery_timer: self.artillery_timer,
+            grazes: self.grazes,
+            stats: self.stats,
+            log: self.log.clone(),
+            chain: self.chain.clone(),
+            score: self.score,
+            spawn_timer: self.spawn_timer,
+            spawn_queue: self.spawn_queue.clone(),
+            game_over: self.game_over,
+            continues_left: self.continues_left,
+            shoot_cooldown: self.shoot_cooldown,
+            recoil: self.recoil,
+            missile_cooldown: self.missile_cooldown,
+            missile_ammo: self.missile_ammo,
+            ammo_spawn_timer: self.ammo_spawn_timer,
+            ammo_items: self.ammo_items.clone(),
+            vacuum: self.vacuum,
+            bombs: self.bombs,
+            last_stand: self.last_stand,
+            time_scale: self.time_scale,
+            has_spread_shot: self.has_spread_shot,
+            focus_mode: self.focus_mode,
+            rng: self.rng.clone(),
+            wave: self.wave,
+            wave_timer: self.wave_timer,
+            script: self.script.clone(),
+            background: self.background,
+            run_time: self.run_time,
+            level_timeline: self.level_timeline.clone(),
+        }
+    }
+
+    // 回到快照时的模拟状态
+    fn restore(&mut self, state: SimState) {
+        let SimState {
+            player,
+            partner,
+            partner_cooldown,
+            bullets,
+            enemies,
+            enemy_bullets,
+            hazards,
+            hazard_timer,
+            meteors,
+            meteor_shower,
+            debris,
+            barricades,
+            drones,
+            drone_fire_timer,
+            boss,
+            shockwaves,
+            gravity_wells,
+            warnings,
+            artillery_timer,
+            grazes,
+            stats,
+            log,
+            chain,
+            score,
+            spawn_timer,
+            spawn_queue,
+            game_over,
+            continues_left,
+            shoot_cooldown,
+            recoil,
+            missile_cooldown,
+            missile_ammo,
+            ammo_spawn_timer,
+            ammo_items,
+            vacuum,
+            bombs,
+            last_stand,
+            time_scale,
+            has_spread_shot,
+            focus_mode,
+            rng,
+            wave,
+            wave_timer,
+            script,
+            background,
+            run_time,
+            level_timeline,
+        } = state;
+        self.player = player;
+        self.partner = partner;
+        self.partner_cooldown = partner_cooldown;
+        self.bullets = bullets;
+        self.enemies = enemies;
+        self.enemy_bullets = enemy_bullets;
+        self.hazards = hazards;
+        self.hazard_timer = hazard_timer;
+        self.meteors = meteors;
+        self.meteor_shower = meteor_shower;
+        self.debris = debris;
+        self.barricades = barricades;
+        self.drones = drones;
+        self.drone_fire_timer = drone_fire_timer;
+        self.boss = boss;
+        self.shockwaves = shockwaves;
+        self.gravity_wells = gravity_wells;
+        self.warnings = warnings;
+        self.artillery_timer = artillery_timer;
+        self.grazes = grazes;
+        self.stats = stats;
+        self.log = log;
+        self.chain = chain;
+        self.score = score;
+        self.spawn_timer = spawn_timer;
+        self.spawn_queue = spawn_queue;
+        self.game_over = game_over;
+        self.continues_left = continues_left;
+        self.shoot_cooldown = shoot_cooldown;
+        self.recoil = recoil;
+        self.missile_cooldown = missile_cooldown;
+        self.missile_ammo = missile_ammo;
+        self.ammo_spawn_timer = ammo_spawn_timer;
+        self.ammo_items = ammo_items;
+        self.vacuum = vacuum;
+        self.bombs = bombs;
+        self.last_stand = last_stand;
+        self.time_scale = time_scale;
+        self.has_spread_shot = has_spread_shot;
+        self.focus_mode = focus_mode;
+        self.rng = rng;
+        self.wave = wave;
+        self.wave_timer = wave_timer;
+        self.script = script;
+        self.background = background;
+        self.run_time = run_time;
+        self.level_timeline = level_timeline;
+    }
+
+    // 推进一个 tick：先模拟，再播放这个 tick 的效果
+    fn tick(&mut self, ctx: &mut ggez::Context, dt: f32, inputs: Inputs) -> GameResult {
+        self.simulate(ctx, dt, inputs)?;
+        self.present(ctx, dt)
+    }
+
+    // 调试模式下模拟前先记录状态快照和输入，供回退和重新模拟
+    fn simulate(&mut self, resources: &dyn Resources, dt: f32, inputs: Inputs) -> GameResult {
+        if self.options.debug {
+            let state = self.snapshot();
+            self.rollback.push(state, inputs);
+        }
+        self.step(resources, dt, inputs)
+    }
+
+    // 表现层：播放模拟发出的效果，再推进粒子、爆炸动画、得分文字、消息栏和星空。
+    // 尾迹、光点和状态效果这类持续的粒子直接由当前状态决定，不经过模拟
+    fn present(&mut self, ctx: &mut ggez::Context, dt: f32) -> GameResult {
+        self.play_cues(ctx)?;
+        let mut rng = rand::thread_rng();
+
+        // 装饰尾迹从飞船尾部向下喷出
+        if let Some(color) = self.progress.trail.color(self.run_time) {
+            let tail = self.player.pos + Vec2::new(0.0, self.player.base_size.y * 0.45);
+            if rng.gen_bool((dt * 30.0).min(1.0) as f64) {
+                self.particles.emit(EmitterKind::Trail, tail, std::f32::consts::FRAC_PI_2, ColorGradient::fade(color));
+            }
+        }
+        // 导弹尾焰
+        let flame = ColorGradient::new(
+            Color::new(1.0, 0.8, 1.0, 0.9),
+            Color::new(0.6, 0.0, 1.0, 0.0),
+        );
+        for missile in self.bullets.iter().filter(|bullet| matches!(bullet.object_type, GameObjectType::GuidedMissile)) {
+            self.particles.emit(EmitterKind::Trail, missile.pos, missile.rotation + std::f32::consts::PI, flame);
+        }
+        // 弹药包周围偶尔飘出光点
+        for ammo in self.ammo_items.iter() {
+            if rng.gen_bool((dt * 6.0).min(1.0) as f64) {
+                self.particles.emit(EmitterKind::Ambient, ammo.pos, 0.0, ColorGradient::fade(Color::new(0.8, 1.0, 1.0, 0.8)));
+            }
+        }
+        for object in self.enemies.iter().chain(self.boss.as_ref().map(|(body, _)| body)) {
+            emit_status_particles(&mut self.particles, object, dt);
+        }
+
+        self.particles.update(dt);
+        self.explosions.update(dt);
+        self.popups.update(dt);
+        self.score_counter.update(self.score, dt);
+        self.message_bar.update(dt);
+
+        // 更新星空
+        let frame_scale = dt * REFERENCE_FPS;
+        for (pos, _) in &mut self.star_field {
+            pos.y += 0.5 * self.window_size.scale_y * frame_scale;
+            if pos.y > BASE_WINDOW_HEIGHT {
+                pos.y = 0.0;
+            }
+        }
+        Ok(())
+    }
+
+    // 播放模拟积累的声音和视觉效果
+    fn play_cues(&mut self, ctx: &mut ggez::Context) -> GameResult {
+        for cue in self.cues.take() {
+            match cue {
+                Cue::Sound(event) => self.sounds.play(ctx, event)?,
+                Cue::Music { path, pitch } => self.sounds.play_music(ctx, path, pitch)?,
+                Cue::StopMusic => self.sounds.stop_music(ctx)?,
+                Cue::Emit { emitter, pos, direction, gradient } => self.particles.emit(emitter, pos, direction, gradient),
+                Cue::Burst { pos, color } => self.particles.add_explosion(pos, color),
+                Cue::Explosion { kind, pos, size } => self.explosions.spawn(kind, pos, size),
+                Cue::Ambient { effect, duration } => self.particles.start_ambient(effect, duration),
+                Cue::StopAmbient(effect) => self.particles.stop_ambient(effect),
+                Cue::Popup { pos, points, color } => self.popups.add(pos, points, color),
+                Cue::Announce { pos, label, points, color } => self.popups.announce(pos, &label, points, color),
+                Cue::Dialog(line) => self.message_bar.push(line),
+            }
+        }
+        Ok(())
+    }
+
+    // 调试：回到记录中最早的 tick，按记录的输入重新模拟到现在，比较前后的状态校验值。
+    // 不一致说明模拟读写了快照以外的状态，回放和联机都会因此失去同步
+    fn verify_determinism(&mut self, ctx: &ggez::Context) -> GameResult {
+        let expected = self.state_checksum();
+        let Some((state, inputs)) = self.rollback.rewind(usize::MAX) else {
+            return Ok(());
+        };
+        let ticks = inputs.len();
+        let dt = 1.0 / self.options.tick_rate as f32;
+        self.restore(state);
+        self.cues.set_muted(true);
+        let result = inputs.into_iter().try_for_each(|inputs| self.simulate(ctx, dt, inputs));
+        self.cues.set_muted(false);
+        result?;
+        let actual = self.state_checksum();
+        if actual == expected {
+            info!("Re-simulated {} ticks, state matches", ticks);
+        } else {
+            error!("Re-simulated {} ticks, state diverged ({:016x}, expected {:016x})", ticks, actual, expected);
+        }
+        Ok(())
+    }
+
+    // 调试：暂停时退回上一个 tick
+    fn step_back(&mut self) {
+        if let Some((state, _)) = self.rollback.rewind(1) {
+            self.restore(state);
+        }
+    }
+
+    // 主菜单上开始联机会话，成功后等待对方，失败时在菜单上提示原因
+    #[cfg(feature = "online")]
+    fn start_session(&mut self, session: std::io::Result<net::Session>, waiting: String) {
+        let Scene::MainMenu(menu) = &mut self.scene else {
+            return;
+        };
+        match session {
+            Ok(session) => {
+                menu.set_waiting(waiting);
+                self.online = Some(session);
+            }
+            Err(err) => {
+                error!("Failed to start co-op session: {}", err);
+                menu.set_notice(format!("Co-op failed: {}", err));
+            }
+        }
+    }
+
+    // 收取联机会话的网络包：连上后双方按主机的规则开局，大厅中断开时回到菜单提示原因；
+    // 一局结束后断开，两端会在同一个 tick 结束
+    #[cfg(feature = "online")]
+    fn poll_online(&mut self, ctx: &mut ggez::Context) -> GameResult {
+        let Some(session) = &mut self.online else {
+            return Ok(());
+        };
+        session.poll(ctx.time.delta().as_secs_f32());
+        if self.coop_rules.is_some() {
+            if self.game_over {
+                self.online = None;
+                self.coop_rules = None;
+            }
+            return Ok(());
+        }
+        if let net::SessionStatus::Closed(reason) = session.status() {
+            let reason = reason.clone();
+            self.online = None;
+            if let Scene::MainMenu(menu) = &mut self.scene {
+                menu.set_notice(reason);
+            }
+            return Ok(());
+        }
+        if let Some(rules) = session.take_start() {
+            info!("Co-op run started with seed {}", rules.seed);
+            self.coop_rules = Some(rules);
+            self.reset(ctx)?;
+            self.set_scene(Scene::Playing);
+        }
+        Ok(())
+    }
+
+    // 联机合作的锁步推进：双方当前 tick 的输入都到齐才模拟，缺对方的输入时原地等待、不积累时间
+    #[cfg(feature = "online")]
+    fn update_online(&mut self, ctx: &mut ggez::Context, tick: f32, real_dt: f32) -> GameResult {
+        self.tick_accumulator += real_dt;
+        let mut steps = 0;
+        while self.tick_accumulator >= tick && !self.game_over {
+            if self.online.as_ref().is_some_and(net::Session::needs_local) {
+                let input = self.local_input(ctx);
+                if let Some(session) = &mut self.online {
+                    session.push_local(input);
+                }
+            }
+            let Some(session) = &self.online else {
+                break;
+            };
+            let Some((host, guest)) = session.inputs() else {
+                // 对方已断开且不会再有输入：这一局到此结束
+                if let net::SessionStatus::Closed(reason) = session.status() {
+                    warn!("Co-op run ended: {}", reason);
+                    self.online = None;
+                    self.coop_rules = None;
+                    return self.kill_player();
+                }
+                self.tick_accumulator = self.tick_accumulator.min(tick);
+                break;
+            };
+            self.tick(ctx, tick, Inputs { player: host, partner: Some(guest) })?;
+            let state = self.state_checksum();
+            if let Some(session) = &mut self.online {
+                session.advance(state);
+            }
+            self.tick_accumulator -= tick;
+            steps += 1;
+            if steps >= MAX_TICKS_PER_FRAME {
+                self.tick_accumulator = 0.0;
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    // 状态校验值：分数、波次、两架飞机的位置、对象数量和随机数状态。
+    // 联机两端据此判断是否同步，调试时据此判断重新模拟的结果是否一致
+    fn state_checksum(&self) -> u64 {
+        let partner = self.partner.as_ref().map_or(Vec2::ZERO, |partner| partner.pos);
+        sim::checksum(&[
+            self.score as u64,
+            self.wave as u64,
+            self.player.pos.x.to_bits() as u64,
+            self.player.pos.y.to_bits() as u64,
+            partner.x.to_bits() as u64,
+            partner.y.to_bits() as u64,
+            self.enemies.len() as u64,
+            self.enemy_bullets.len() as u64,
+            self.bullets.len() as u64,
+            self.rng.clone().gen(),
+        ])
+    }
+
+    // 游戏中标题显示分数和波次；失去焦点时刷新纪录会让标题闪烁并请求任务栏提醒
+    fn update_window_title(&mut self, ctx: &mut ggez::Context, dt: f32) {
+        let playing = matches!(self.scene, Scene::Playing);
+        if playing && self.practice.is_none() && !self.record_announced && self.best_at_start > 0 && self.score > self.best_at_start {
+            self.record_announced = true;
+            if !self.focused {
+                self.title_flash = Some(0.0);
+                ctx.gfx.window().request_user_attention(Some(UserAttentionType::Informational));
+            }
+        }
+
+        let mut title = if playing {
+            format!("{} - Score {} - Wave {}", WINDOW_TITLE, self.score, self.wave)
+        } else {
+            WINDOW_TITLE.to_string()
+        };
+        if let Some(timer) = &mut self.title_flash {
+            *timer += dt;
+            if ((*timer / TITLE_FLASH_INTERVAL) as u32).is_multiple_of(2) {
+                title = format!("*** NEW HIGH SCORE: {} ***", self.score);
+            }
+        }
+        if title != self.window_title {
+            ctx.gfx.set_window_title(&title);
+            self.window_title = title;
+        }
+    }
+
+    fn update_window_size(&mut self, ctx: &mut ggez::Context) {
+        let window = ctx.gfx.window();
+        let new_size = window.inner_size();
+        self.window_size = WindowSize::new(
+            new_size.width as f32,
+            new_size.height as f32,
+        );
+    }
+
+    // 绘制队列中的一项
+    fn draw_pass(&mut self, ctx: &mut ggez::Context, canvas: &mut Canvas, pass: DrawPass, alpha: f32) -> GameResult {
+        let palette = self.config.palette;
+        match pass {
+            DrawPass::Stars => {
+                // 绘制星空
+                for (pos, size) in &self.star_field {
+                    let scaled_pos = self.window_size.scale_vec2(*pos);
+                    let scaled_size = size * self.window_size.scale_x.min(self.window_size.scale_y);
+
+                    let star = Mesh::new_circle(
+                        ctx,
+                        graphics::DrawMode::fill(),
+                        scaled_pos,
+                        scaled_size,
+                        0.1,
+                        Color::WHITE,
+                    )?;
+                    canvas.draw(&star, DrawParam::default());
+                }
+            }
+            DrawPass::Pickups => {
+                // 绘制道具：按稀有度着色的脉动光晕 + 图标
+                let pulse = (ctx.time.time_since_start().as_secs_f32() * 4.0).sin() * 0.5 + 0.5;
+                for ammo in self.ammo_items.iter_mut() {
+                    match ammo.object_type {
+                        GameObjectType::BonusStar => ammo.tint = palette.color(Swatch::BonusStar),
+                        GameObjectType::ScoreGem => ammo.tint = palette.color(Swatch::ScoreGem),
+                        _ => {}
+                    }
+                    if let Some(info) = pickups::info(&ammo.object_type) {
+                        // 调色板可能在暂停时切换，绘制前按当前调色板着色
+                        ammo.tint = palette.color(info.swatch);
+                        let mut glow_color = palette.color(info.rarity.glow_swatch());
+                        glow_color.a *= 0.6 + 0.4 * pulse;
+                        let radius = ammo.base_size.x * info.rarity.glow_scale() * (0.9 + 0.2 * pulse)
+                            * self.window_size.scale_x.min(self.window_size.scale_y);
+                        let glow = Mesh::new_circle(
+                            ctx,
+                            graphics::DrawMode::fill(),
+                            self.window_size.scale_vec2(ammo.prev_pos.lerp(ammo.pos, alpha)),
+                            radius,
+                            0.5,
+                            glow_color,
+                        )?;
+                        canvas.set_blend_mode(graphics::BlendMode::ADD);
+                        canvas.draw(&glow, DrawParam::default());
+                        canvas.set_blend_mode(graphics::BlendMode::ALPHA);
+                    }
+                    ammo.draw(canvas, &self.window_size, alpha);
+                    if self.options.debug {
+                        ammo.draw_collider(ctx, canvas, &self.window_size)?;
+                    }
+                }
+                // 即将掉出屏幕的稀有道具：在下边缘对应位置闪烁箭头，越接近离开闪得越快
+                if self.config.pickup_warnings && !self.game_over {
+                    let fall_speed = ENEMY_SPEED_RATIO * self.sim_size().height;
+                    let time = ctx.time.time_since_start().as_secs_f32();
+                    for ammo in self.ammo_items.iter() {
+                        let Some(remaining) = ammo.pickup_exit_time(fall_speed).filter(|time| *time <= PICKUP_EXIT_WARNING) else {
+                            continue;
+                        };
+                        let rate = 4.0 + 8.0 * (1.0 - remaining / PICKUP_EXIT_WARNING);
+                        if (time * rate).fract() >= 0.5 {
+                            continue;
+                        }
+                        let color = pickups::info(&ammo.object_type).map_or(Color::WHITE, |info| palette.color(info.rarity.glow_swatch()));
+                        let tip = Vec2::new(ammo.pos.x, BASE_WINDOW_HEIGHT - 6.0);
+                        let arrow = Mesh::new_polygon(
+                            ctx,
+                            graphics::DrawMode::fill(),
+                            &[
+                                self.window_size.scale_vec2(tip),
+                                self.window_size.scale_vec2(tip + Vec2::new(-12.0, -14.0)),
+                                self.window_size.scale_vec2(tip + Vec2::new(12.0, -14.0)),
+                            ],
+                            Color { a: 1.0, ..color },
+                        )?;
+                        canvas.draw(&arrow, DrawParam::default());
+                    }
+                }
+            }
+            DrawPass::Hazards => {
+                for mine in self.barricades.iter().chain(self.hazards.iter()).chain(&self.meteors).chain(&self.debris) {
+                    mine.draw(canvas, &self.window_size, alpha);
+                    if self.options.debug {
+                        mine.draw_collider(ctx, canvas, &self.window_size)?;
+                    }
+                }
+            }
+            DrawPass::Enemies => {
+                let pulse = 0.6 + 0.4 * (ctx.time.time_since_start().as_secs_f32() * 6.0).sin();
+                for enemy in self.enemies.iter() {
+                    enemy.draw(canvas, &self.window_size, alpha);
+                    if let Some(armor) = enemy.enemy_kind.and_then(|kind| self.enemy_defs.def(kind).armor) {
+                        enemy.draw_weak_points(ctx, canvas, &self.window_size, &armor, alpha, pulse)?;
+                    }
+                    if let Some(shield) = enemy.enemy_kind.and_then(|kind| self.enemy_defs.def(kind).shield) {
+                        enemy.draw_shield(ctx, canvas, &self.window_size, &shield, alpha)?;
+                    }
+                    if self.options.debug {
+                        enemy.draw_collider(ctx, canvas, &self.window_size)?;
+                    }
+                }
+            }
+            DrawPass::Boss => {
+                if let Some((body, boss)) = &self.boss {
+                    // 激光：外层彩色光晕加白色核心
+                    if let Some(beam) = boss.laser(body.pos).filter(|beam| beam.firing) {
+                        let points = [self.window_size.scale_vec2(beam.start), self.window_size.scale_vec2(beam.end)];
+                        let width = beam.width * self.window_size.scale_x.min(self.window_size.scale_y);
+                        let glow = Mesh::new_line(ctx, &points, width, Color { a: 0.6, ..body.tint })?;
+                        let core = Mesh::new_line(ctx, &points, width * 0.4, Color::WHITE)?;
+                        canvas.set_blend_mode(graphics::BlendMode::ADD);
+                        canvas.draw(&glow, DrawParam::default());
+                        canvas.draw(&core, DrawParam::default());
+                        canvas.set_blend_mode(graphics::BlendMode::ALPHA);
+                    }
+                    body.draw(canvas, &self.window_size, alpha);
+                    // 护盾：包住 Boss 的光罩，加上连向每个护卫的能量线
+                    if boss.is_shielded() {
+                        let center = self.window_size.scale_vec2(body.prev_pos.lerp(body.pos, alpha));
+                        let radius = BOSS_SIZE * 0.6 * self.window_size.scale_x.min(self.window_size.scale_y);
+                        let flicker = 0.7 + 0.3 * (ctx.time.time_since_start().as_secs_f32() * 8.0).sin();
+                        let mut builder = graphics::MeshBuilder::new();
+                        builder.circle(graphics::DrawMode::fill(), center, radius, 0.5, Color { a: 0.15 * flicker, ..BOSS_SHIELD_COLOR })?;
+                        builder.circle(graphics::DrawMode::stroke(3.0), center, radius, 0.5, Color { a: 0.7 * flicker, ..BOSS_SHIELD_COLOR })?;
+                        for escort in boss.escorts().iter().filter_map(|handle| self.enemies.get(*handle)) {
+                            let end = self.window_size.scale_vec2(escort.prev_pos.lerp(escort.pos, alpha));
+                            builder.line(&[center, end], 1.5, Color { a: 0.5 * flicker, ..BOSS_SHIELD_COLOR })?;
+                        }
+                        let mesh = Mesh::from_data(ctx, builder.build());
+                        canvas.draw(&mesh, DrawParam::default());
+                    }
+                    if self.options.debug {
+                        body.draw_collider(ctx, canvas, &self.window_size)?;
+                    }
+                }
+            }
+            DrawPass::Ghost => {
+                // 无尽模式下绘制最佳一局的半透明幽灵
+                if let (GameMode::Endless, Some(ghost), Some(image)) = (self.mode, &self.ghost, &self.player.image) {
+                    if let Some(pos) = ghost.sample(self.run_time) {
+                        let size = self.window_size.scale_vec2(self.player.base_size);
+                        canvas.draw(
+                            image,
+                            DrawParam::default()
+                                .dest(self.window_size.scale_vec2(pos))
+                                .offset(Vec2::new(0.5, 0.5))
+                                .color(Color::new(0.6, 0.8, 1.0, 0.3))
+                                .scale(Vec2::new(
+                                    size.x / image.width() as f32,
+                                    size.y / image.height() as f32,
+                                )),
+                        );
+                    }
+                }
+            }
+            DrawPass::Player => {
+                if !self.game_over {
+                    self.player.draw(canvas, &self.window_size, alpha);
+                    if let Some(partner) = &self.partner {
+                        partner.draw(canvas, &self.window_size, alpha);
+                    }
+                }
+                for drone in &self.drones {
+                    drone.draw(canvas, &self.window_size, alpha);
+                }
+                if self.options.debug {
+                    self.player.draw_collider(ctx, canvas, &self.window_size)?;
+                }
+            }
+            DrawPass::PlayerBullets => {
+                for bullet in self.bullets.iter() {
+                    bullet.draw(canvas, &self.window_size, alpha);
+                    if self.options.debug {
+                        bullet.draw_collider(ctx, canvas, &self.window_size)?;
+                    }
+                }
+            }
+            DrawPass::EnemyBullets => {
+                for bullet in &self.enemy_bullets {
+                    bullet.draw(canvas, &self.window_size, alpha);
+                    if self.options.debug {
+                        bullet.draw_collider(ctx, canvas, &self.window_size)?;
+                    }
+                }
+            }
+            DrawPass::Viewport => {
+                // 正常视野的边框，以及敌人生成位置所在的外圈
+                let scale = self.window_size.scale_x.min(self.window_size.scale_y);
+                let screen = Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT);
+                let spawn_corner = self.window_size.scale_vec2(Vec2::splat(-ENEMY_SPAWN_MARGIN));
+                let spawn_size = self.window_size.scale_vec2(screen + 2.0 * ENEMY_SPAWN_MARGIN);
+                let mut builder = graphics::MeshBuilder::new();
+                builder.rectangle(
+                    graphics::DrawMode::stroke(2.0 * scale),
+                    graphics::Rect::new(0.0, 0.0, self.window_size.width, self.window_size.height),
+                    Color::new(1.0, 1.0, 1.0, 0.8),
+                )?;
+                builder.rectangle(
+                    graphics::DrawMode::stroke(1.0 * scale),
+                    graphics::Rect::new(spawn_corner.x, spawn_corner.y, spawn_size.x, spawn_size.y),
+                    Color::new(1.0, 0.5, 0.2, 0.6),
+                )?;
+                let mesh = Mesh::from_data(ctx, builder.build());
+                canvas.draw(&mesh, DrawParam::default());
+            }
+            DrawPass::Ai => {
+                // 红：俯冲锁定点与追踪地雷的目标；橙：敌人行进方向；灰：敌人路线；
+                // 紫：导弹目标，白：导弹当前速度方向；蓝：僚机的编队位置
+                let to_screen = |pos: Vec2| self.window_size.scale_vec2(pos);
+                let target_color = Color::new(1.0, 0.2, 0.2, 0.7);
+                let mut builder = graphics::MeshBuilder::new();
+                let mut empty = true;
+                for enemy in self.enemies.iter() {
+                    if let Some((path, distance)) = &enemy.path {
+                        let points: Vec<Vec2> = path.polyline().into_iter().map(to_screen).collect();
+                        if points.len() >= 2 {
+                            builder.line(&points, 1.0, Color::new(0.6, 0.6, 0.6, 0.5))?;
+                        }
+                        if let Some((anchor, _)) = path.sample(*distance) {
+                            builder.circle(graphics::DrawMode::stroke(1.0), to_screen(anchor), 6.0, 0.5, Color::new(0.6, 0.6, 0.6, 0.8))?;
+                        }
+                    }
+                    let ahead = enemy.pos + enemy.heading * 40.0;
+                    builder.line(&[to_screen(enemy.pos), to_screen(ahead)], 1.5, Color::new(1.0, 0.6, 0.1, 0.8))?;
+                    if let Some(target) = enemy.dive_target {
+                        builder.line(&[to_screen(enemy.pos), to_screen(target)], 1.0, target_color)?;
+                        builder.circle(graphics::DrawMode::stroke(1.0), to_screen(target), 8.0, 0.5, target_color)?;
+                    }
+                    empty = false;
+                }
+                for mine in self.enemy_bullets.iter().filter(|bullet| matches!(bullet.object_type, GameObjectType::HomingMine)) {
+                    builder.line(&[to_screen(mine.pos), to_screen(self.player.pos)], 1.0, target_color)?;
+                    empty = false;
+                }
+                for missile in self.bullets.iter().filter(|bullet| matches!(bullet.object_type, GameObjectType::GuidedMissile)) {
+                    if let Some(target) = missile.target.and_then(|target| self.enemies.get(target)) {
+                        builder.line(&[to_screen(missile.pos), to_screen(target.pos)], 1.0, Color::new(1.0, 0.3, 1.0, 0.7))?;
+                    }
+                    let steering = missile.speed.normalize_or_zero() * 30.0;
+                    builder.line(&[to_screen(missile.pos), to_screen(missile.pos + steering)], 2.0, Color::WHITE)?;
+                    empty = false;
+                }
+                for (idx, drone) in self.drones.iter().enumerate() {
+                    let anchor = Self::drone_anchor(self.player.pos, idx);
+                    builder.line(&[to_screen(drone.pos), to_screen(anchor)], 1.0, Color::new(0.4, 0.7, 1.0, 0.7))?;
+                    builder.circle(graphics::DrawMode::stroke(1.0), to_screen(anchor), 5.0, 0.5, Color::new(0.4, 0.7, 1.0, 0.9))?;
+                    empty = false;
+                }
+                if !empty {
+                    let mesh = Mesh::from_data(ctx, builder.build());
+                    canvas.draw(&mesh, DrawParam::default());
+                }
+            }
+            DrawPass::Hitbox => {
+                // 判定点画在子弹之上，大小与实际受击判定一致；设置中可以让它一直显示
+                if (self.focus_mode || self.config.show_hurtbox) && !self.game_over {
+                    if let Collider::Circle(circle) = self.player.hurtbox() {
+                        let center = self.window_size.scale_vec2(self.player.prev_pos.lerp(self.player.pos, alpha));
+                        let radius = circle.radius * self.window_size.scale_x.min(self.window_size.scale_y);
+                        let mut builder = graphics::MeshBuilder::new();
+                        builder.circle(graphics::DrawMode::fill(), center, radius, 0.1, Color::new(1.0, 1.0, 1.0, 0.8))?;
+                        builder.circle(graphics::DrawMode::stroke(2.0), center, radius, 0.1, palette.color(Swatch::Friendly))?;
+                        let mesh = Mesh::from_data(ctx, builder.build());
+                        canvas.draw(&mesh, DrawParam::default());
+                    }
+                }
+                // 躲避提示：飞机旁指向躲避方向的箭头
+                if self.config.dodge_hints && !self.game_over {
+                    if let Some(direction) = self.dodge_hint() {
+                        let ship = self.local_ship();
+                        let center = ship.prev_pos.lerp(ship.pos, alpha);
+                        let tip = center + direction * (ship.base_size.x * 0.5 + 26.0);
+                        let base = tip - direction * 14.0;
+                        let side = direction.perp() * 8.0;
+                        let arrow = Mesh::new_polygon(
+                            ctx,
+                            graphics::DrawMode::fill(),
+                            &[
+                                self.window_size.scale_vec2(tip),
+                                self.window_size.scale_vec2(base + side),
+                                self.window_size.scale_vec2(base - side),
+                            ],
+                            palette.color(Swatch::Friendly),
+                        )?;
+                        canvas.draw(&arrow, DrawParam::default());
+                    }
+                }
+                // 练习模式的轨迹预览：每颗敌弹接下来一秒的路线，终点画一个小圆
+                if self.preview {
+                    let color = palette.color(Swatch::Incoming);
+                    let mut builder = graphics::MeshBuilder::new();
+                    let mut empty = true;
+                    for path in self.predict_enemy_bullets(PREVIEW_DURATION) {
+                        let points: Vec<Vec2> = path.iter().map(|pos| self.window_size.scale_vec2(*pos)).collect();
+                        if let [.., last] = points.as_slice() {
+                            builder.circle(graphics::DrawMode::fill(), *last, 3.0, 0.5, color)?;
+                            empty = false;
+                        }
+                        if points.len() >= 2 {
+                            builder.line(&points, 1.5, Color::new(color.r, color.g, color.b, 0.6))?;
+                        }
+                    }
+                    if !empty {
+                        let mesh = Mesh::from_data(ctx, builder.build());
+                        canvas.draw(&mesh, DrawParam::default());
+                    }
+                }
+            }
+            DrawPass::Effects => {
+                // 绘制粒子效果
+                self.explosions.draw(canvas, &self.window_size);
+                self.particles.draw(canvas, &self.window_size);
+
+                for well in &self.gravity_wells {
+                    well.draw(ctx, canvas, &self.window_size)?;
+                }
+
+                // EMP 冲击波：逐渐扩大并淡出的圆环
+                for (center, age) in &self.shockwaves {
+                    let t = age / SHOCKWAVE_DURATION;
+                    let scale = self.window_size.scale_x.min(self.window_size.scale_y);
+                    let ring = Mesh::new_circle(
+                        ctx,
+                        graphics::DrawMode::stroke(6.0 * (1.0 - t) + 1.0),
+                        self.window_size.scale_vec2(*center),
+                        (SHOCKWAVE_RADIUS * t).max(1.0) * scale,
+                        0.5,
+                        Color::new(0.6, 0.9, 1.0, 1.0 - t),
+                    )?;
+                    canvas.set_blend_mode(graphics::BlendMode::ADD);
+                    canvas.draw(&ring, DrawParam::default());
+                    canvas.set_blend_mode(graphics::BlendMode::ALPHA);
+                }
+            }
+            DrawPass::Warnings => {
+                // 攻击预警
+                let world_scale = Vec2::new(self.window_size.scale_x, self.window_size.scale_y);
+                self.warnings.draw(ctx, canvas, world_scale, ctx.time.time_since_start().as_secs_f32())?;
+            }
+            DrawPass::Popups => {
+                // 浮动得分文字画在 HUD 层，不参与泛光
+                self.popups.draw(canvas, Vec2::new(self.window_size.scale_x, self.window_size.scale_y));
+            }
+            DrawPass::Indicators => {
+                // 屏幕外即将进入的敌人和敌方弹幕的边缘指示
+                let screen = Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT);
+                let hud_scale = Vec2::new(self.window_size.scale_x, self.window_size.scale_y);
+                let indicator_color = |swatch| Color { a: 0.9, ..palette.color(swatch) };
+                let threats = self.enemies.iter()
+                    .map(|enemy| (enemy, indicator_color(Swatch::Hostile)))
+                    .chain(self.enemy_bullets.iter().map(|bullet| (bullet, indicator_color(Swatch::Incoming))));
+                for (threat, color) in threats {
+                    if let Some(indicator) = hud::threat_indicator(threat.pos, screen) {
+                        indicator.draw(ctx, canvas, hud_scale, color)?;
+                    }
+                }
+
+                // 右下角雷达：敌人、道具、Boss 与玩家自己
+                let mut blips: Vec<hud::Blip> = self.enemies.iter()
+                    .map(|enemy| hud::Blip { pos: enemy.pos, color: palette.color(Swatch::Hostile), radius: 2.0 })
+                    .chain(self.ammo_items.iter().map(|item| hud::Blip {
+                        pos: item.pos,
+                        color: pickups::info(&item.object_type).map_or(Color::WHITE, |info| palette.color(info.swatch)),
+                        radius: 2.0,
+                    }))
+                    .collect();
+                if let Some((body, _)) = &self.boss {
+                    blips.push(hud::Blip { pos: body.pos, color: palette.color(Swatch::Boss), radius: 5.0 });
+                }
+                if !self.game_over {
+                    blips.push(hud::Blip { pos: self.player.pos, color: palette.color(Swatch::Friendly), radius: 2.5 });
+                }
+                hud::draw_radar(ctx, canvas, blips, screen, hud_scale)?;
+            }
+            DrawPass::Status => {
+                // HUD 文字每帧都要绘制，按位置缓存，数值变化时才重新生成
+                let window_size = &self.window_size;
+                // 分数由滚动计数控件绘制，弹药和武器等级画成图标，其余文字按位置直接绘制
+                self.score_counter.draw(canvas, &mut self.hud_text, window_size, Vec2::new(10.0, 10.0), Color::WHITE);
+
+                // 导弹数量画成一排导弹图标
+                let missiles = IconRow {
+                    image: self.assets.image("/img/bullet.png")?,
+                    size: Vec2::new(7.0, 20.0),
+                    color: palette.color(Swatch::PickupMissile),
+                };
+                missiles.draw(canvas, &mut self.hud_text, "missiles", window_size, Vec2::new(10.0, 38.0), self.missile_ammo.max(0) as u32);
+
+                // 炸弹（或 EMP、引力井）次数，图标颜色区分副武器
+                let charge_color = match self.loadout.secondary {
+                    Secondary::Emp => palette.color(Swatch::PickupEmp),
+                    Secondary::GravityWell => palette.color(Swatch::HudMutators),
+                    _ => Color::WHITE,
+                };
+                let charges = IconRow { image: self.assets.image("/img/shield.png")?, size: Vec2::splat(16.0), color: charge_color };
+                charges.draw(canvas, &mut self.hud_text, "charges", window_size, Vec2::new(150.0, 72.0), self.bombs);
+
+                // 武器升级等级刻度
+                let tier_color = palette.color(Swatch::HudUpgrade);
+                hud::draw_pips(ctx, canvas, window_size, Vec2::new(150.0, 46.0), self.weapon_tier, shop::MAX_WEAPON_TIER, tier_color)?;
+
+                // 当前主武器（拾取扇形弹药后覆盖出击装备的主武器）
+                let mut hud = |text: &mut TextCache, slot: &'static str, content: String, pos: Vec2, color: Color| {
+                    canvas.draw(
+                        text.get(slot, content, window_size),
+                        DrawParam::default().dest(window_size.scale_vec2(pos)).color(color),
+                    );
+                };
+                let (primary, primary_color) = if self.has_spread_shot {
+                    ("Spread Shot: Active".to_string(), palette.color(Swatch::SpreadShot))
+                } else {
+                    (format!("Primary: {}", self.loadout.primary.name()), Color::new(0.5, 0.5, 0.5, 1.0)) // 灰色
+                };
+                hud(&mut self.hud_text, "primary", primary, Vec2::new(10.0, 70.0), primary_color);
+
+                // 绘制波次
+                hud(&mut self.hud_text, "wave", format!("Wave: {}", self.wave), Vec2::new(10.0, 100.0), Color::WHITE);
+
+                // 绘制擦弹次数
+                let grazes = format!("Graze: {}", self.grazes);
+                hud(&mut self.hud_text, "grazes", grazes, Vec2::new(150.0, 100.0), palette.color(Swatch::HudGraze));
+
+                // 专注模式状态与按键提示
+                let (focus_text, focus_color) = if self.focus_mode {
+                    ("FOCUS".to_string(), palette.color(Swatch::HudInfo))
+                } else {
+                    (
+                        format!("Hold {}: focus", self.config.bindings.describe(Action::Focus)),
+                        Color::new(0.5, 0.5, 0.5, 1.0),
+                    )
+                };
+                hud(&mut self.hud_text, "focus", focus_text, Vec2::new(290.0, 100.0), focus_color);
+
+                // 绘制启用的挑战规则与分数倍率
+                if !self.mutators.is_empty() {
+                    let mutators = format!(
+                        "Mutators: {} (x{:.2})",
+                        self.mutators.describe(),
+                        self.mutators.score_multiplier()
+                    );
+                    hud(&mut self.hud_text, "mutators", mutators, Vec2::new(10.0, 130.0), palette.color(Swatch::HudMutators));
+                }
+
+                // 绘制生存时间与最佳纪录
+                let best = self.high_scores.get(self.mode);
+                let time_text = match self.practice {
+                    Some(_) => format!(
+                        "Time: {}\nPractice  {}: preview",
+                        highscores::format_time(self.run_time),
+                        self.config.bindings.describe(Action::Preview)
+                    ),
+                    None => format!(
+                        "Time: {}\nBest: {} / {}",
+                        highscores::format_time(self.run_time),
+                        best.best_score,
+                        highscores::format_time(best.best_time)
+                    ),
+                };
+                hud(&mut self.hud_text, "time", time_text, Vec2::new(BASE_WINDOW_WIDTH - 180.0, 10.0), Color::WHITE);
+
+                // 每日挑战：日期与规则，结束后显示最近的挑战历史
+                if let Some(challenge) = &self.daily {
+                    let mut daily_text = format!(
+                        "Daily {}{}\nEdges: {}",
+                        challenge.date(),
+                        if self.daily_practice { " (practice)" } else { "" },
+                        challenge.boundary.name()
+                    );
+                    if self.game_over {
+                        daily_text.push_str("\n\nHistory:");
+                        for (date, record) in self.daily_history.recent(7) {
+                            daily_text.push_str(&format!(
+                                "\n{}  {}  {}",
+                                date,
+                                record.best_score,
+                                highscores::format_time(record.best_time)
+                            ));
+                        }
+                    }
+                    hud(&mut self.hud_text, "daily", daily_text, Vec2::new(BASE_WINDOW_WIDTH - 260.0, 60.0), palette.color(Swatch::HudInfo));
+                }
+
+                // 生存模式：剩余路障和续关次数
+                if self.mode == GameMode::Horde {
+                    let horde_text = format!("Barricades: {}/{}\nLives: {}", self.barricades.len(), BARRICADE_COUNT, self.continues_left);
+                    let horde_color = if self.barricades.is_empty() { palette.color(Swatch::HudAlert) } else { palette.color(Swatch::HudInfo) };
+                    hud(&mut self.hud_text, "horde", horde_text, Vec2::new(BASE_WINDOW_WIDTH - 180.0, 60.0), horde_color);
+                }
+
+                // 联机合作：本机操作的飞机、往返延迟和同步状态
+                #[cfg(feature = "online")]
+                if let Some(session) = &self.online {
+                    let seat = match session.role() {
+                        net::Role::Host => "P1",
+                        net::Role::Guest => "P2",
+                    };
+                    let latency = session.latency_ms().map_or_else(|| "--".to_string(), |ms| ms.to_string());
+                    let mut online_text = format!("Co-op {}  Ping: {} ms", seat, latency);
+                    let mut online_color = palette.color(Swatch::HudInfo);
+                    if session.is_waiting() {
+                        online_text.push_str("\nWaiting for partner...");
+                    }
+                    if session.is_desynced() {
+                        online_text.push_str("\nDESYNC");
+                        online_color = palette.color(Swatch::HudAlert);
+                    }
+                    hud(&mut self.hud_text, "online", online_text, Vec2::new(BASE_WINDOW_WIDTH - 260.0, 200.0), online_color);
+                }
+            }
+            DrawPass::BossBar => {
+                // Boss 血条，刻度标出阶段切换的阈值
+                if let Some((_, boss)) = &self.boss {
+                    let bar = graphics::Rect::new(BASE_WINDOW_WIDTH / 2.0 - 200.0, 12.0, 400.0, 12.0);
+                    let scaled = |rect: graphics::Rect| {
+                        let pos = self.window_size.scale_vec2(Vec2::new(rect.x, rect.y));
+                        let size = self.window_size.scale_vec2(Vec2::new(rect.w, rect.h));
+                        graphics::Rect::new(pos.x, pos.y, size.x, size.y)
+                    };
+                    let mut filled = bar;
+                    filled.w *= boss.displayed_health();
+                    let background = Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), scaled(bar), Color::new(0.2, 0.2, 0.2, 0.8))?;
+                    // 护盾期间血条换成护盾颜色，表示打不动
+                    let fill = if boss.is_shielded() { BOSS_SHIELD_COLOR } else { boss.def().tint };
+                    let health = Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), scaled(filled), fill)?;
+                    canvas.draw(&background, DrawParam::default());
+                    canvas.draw(&health, DrawParam::default());
+                    for def in &boss::PHASES[1..] {
+                        let tick = graphics::Rect::new(bar.x + bar.w * def.threshold - 1.0, bar.y, 2.0, bar.h);
+                        let marker = Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), scaled(tick), Color::WHITE)?;
+                        canvas.draw(&marker, DrawParam::default());
+                    }
+
+                    // 登场演出中的名字横幅
+                    if let Some(banner) = boss.banner() {
+                        let mut text = graphics::Text::new(format!("WARNING\n{}", banner.name));
+                        text.set_layout(graphics::TextLayout::center());
+                        canvas.draw(
+                            &text,
+                            DrawParam::default()
+                                .dest(self.window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH / 2.0, BASE_WINDOW_HEIGHT / 3.0)))
+                                .color(Color { a: banner.alpha, ..palette.color(Swatch::HudAlert) })
+                                .scale(Vec2::new(
+                                    self.window_size.scale_x * 3.0,
+                                    self.window_size.scale_y * 3.0
+                                ))
+                        );
+                    }
+                }
+            }
+            DrawPass::Highlight => self.highlights.draw(canvas, &self.window_size),
+            DrawPass::Alerts => {
+                // 最后一搏：屏幕泛红并提示引爆炸弹
+                if let Some(remaining) = self.last_stand {
+                    let alert = palette.color(Swatch::HudAlert);
+                    canvas.draw(
+                        &graphics::Quad,
+                        DrawParam::default()
+                            .scale(self.window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT)))
+                            .color(Color { a: 0.25, ..alert }),
+                    );
+                    canvas.draw(
+                        &graphics::Text::new(format!(
+                            "LAST STAND! {:.1}\nPress {} to bomb",
+                            remaining,
+                            self.config.bindings.describe(Action::Bomb)
+                        )),
+                        DrawParam::default()
+                            .dest(self.window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH / 2.0 - 130.0, BASE_WINDOW_HEIGHT / 2.0 - 60.0)))
+                            .color(alert)
+                            .scale(Vec2::new(
+                                self.window_size.scale_x * 2.0,
+                                self.window_size.scale_y * 2.0
+                            ))
+                    );
+                }
+
+                // 流星雨来临前的警告横幅
+                if self.meteor_shower.as_ref().is_some_and(MeteorShower::is_announcing) {
+                    let blink = 0.5 + 0.5 * (ctx.time.time_since_start().as_secs_f32() * 8.0).sin();
+                    let alert = palette.color(Swatch::HudAlert);
+                    canvas.draw(
+                        &graphics::Quad,
+                        DrawParam::default()
+                            .dest(self.window_size.scale_vec2(Vec2::new(0.0, 200.0)))
+                            .scale(self.window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH, 50.0)))
+                            .color(Color { a: 0.15 + 0.2 * blink, ..alert }),
+                    );
+                    canvas.draw(
+                        &graphics::Text::new("WARNING: METEOR SHOWER"),
+                        DrawParam::default()
+                            .dest(self.window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH / 2.0 - 200.0, 210.0)))
+                            .color(Color { a: 0.5 + 0.5 * blink, ..alert })
+                            .scale(Vec2::new(
+                                self.window_size.scale_x * 2.0,
+                                self.window_size.scale_y * 2.0
+                            ))
+                    );
+                }
+
+                // 续关倒计时
+                if let Some(remaining) = self.continue_timer {
+                    let continue_text = graphics::Text::new(format!(
+                        "Continue? {}\nPress {} to continue ({} left)",
+                        remaining.ceil() as u32,
+                        self.config.bindings.describe(Action::Continue),
+                        self.continues_left
+                    ));
+                    let text_pos = self.window_size.scale_vec2(Vec2::new(
+                        BASE_WINDOW_WIDTH/2.0 - 100.0,
+                        BASE_WINDOW_HEIGHT/2.0
+                    ));
+                    canvas.draw(
+                        &continue_text,
+                        DrawParam::default()
+                            .dest(text_pos)
+                            .color(palette.color(Swatch::HudAlert))
+                            .scale(Vec2::new(
+                                self.window_size.scale_x * 2.0,
+                                self.window_size.scale_y * 2.0
+                            ))
+                    );
+                }
+            }
+            DrawPass::MessageBar => {
+                // 绘制剧情消息栏
+                self.message_bar.draw(canvas, &self.window_size, &self.config.bindings.describe(Action::Skip));
+            }
+            DrawPass::Debug => {
+                if self.options.debug {
+                    let camera = match &self.free_camera {
+                        Some(camera) => format!(
+                            "\nCamera: ({:.0}, {:.0}) {:.2}x  (IJKL pan, PgUp/PgDn zoom, Home reset, F10 exit)",
+                            camera.center().x,
+                            camera.center().y,
+                            camera.zoom()
+                        ),
+                        None => "\nF10: free camera".to_string(),
+                    };
+                    canvas.draw(
+                        &graphics::Text::new(format!(
+                            "Enemies: {}/{} (+{} queued)  Speed: {}x  Rewind: {} ticks  (F5-F8 speed; when paused F9 step, F11 back, F3 verify){}",
+                            self.enemies.len(),
+                            self.enemy_cap(),
+                            self.spawn_queue.len(),
+                            self.debug_speed,
+                            self.rollback.len(),
+                            camera
+                        )),
+                        DrawParam::default()
+                            .dest(self.window_size.scale_vec2(Vec2::new(10.0, BASE_WINDOW_HEIGHT - 80.0)))
+                            .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                            .scale(Vec2::new(
+                                self.window_size.scale_x,
+                                self.window_size.scale_y
+                            ))
+                    );
+                }
+            }
+            DrawPass::Pause => {
+                if self.paused {
+                    let pause_text = graphics::Text::new(format!(
+                        "PAUSED\nPress {} to continue\nF1: settings\nF2: profiles",
+                        self.config.bindings.describe(Action::Pause)
+                    ));
+                    let text_pos = self.window_size.scale_vec2(Vec2::new(
+                        BASE_WINDOW_WIDTH/2.0 - 100.0,
+                        BASE_WINDOW_HEIGHT/2.0
+                    ));
+                    canvas.draw(
+                        &pause_text,
+                        DrawParam::default()
+                            .dest(text_pos)
+                            .color(Color::YELLOW)
+                            .scale(Vec2::new(
+                                self.window_size.scale_x * 2.0,
+                                self.window_size.scale_y * 2.0
+                            ))
+                    );
+
+                    // 道具图例
+                    let scale = Vec2::new(self.window_size.scale_x, self.window_size.scale_y);
+                    canvas.draw(
+                        &graphics::Text::new(format!("Seed: {}   Code: {}", self.run_seed, self.run_code().encode())),
+                        DrawParam::default()
+                            .dest(self.window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH / 2.0 - 100.0, BASE_WINDOW_HEIGHT / 2.0 - 40.0)))
+                            .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                            .scale(scale),
+                    );
+                    for (idx, (info, icon)) in pickups::PICKUPS.iter().zip(&self.pickup_icons).enumerate() {
+                        let row = Vec2::new(BASE_WINDOW_WIDTH / 2.0 - 100.0, BASE_WINDOW_HEIGHT / 2.0 + 120.0 + idx as f32 * 32.0);
+                        let icon_size = self.window_size.scale_vec2(Vec2::splat(20.0));
+                        canvas.draw(
+                            icon,
+                            DrawParam::default()
+                                .dest(self.window_size.scale_vec2(row))
+                                .offset(Vec2::new(0.5, 0.5))
+                                .color(palette.color(info.swatch))
+                                .scale(Vec2::new(
+                                    icon_size.x / icon.width() as f32,
+                                    icon_size.y / icon.height() as f32,
+                                )),
+                        );
+                        let label = graphics::Text::new(format!("{} - {}", info.name, info.rarity.name()));
+                        let mut label_color = palette.color(info.rarity.glow_swatch());
+                        label_color.a = 1.0;
+                        canvas.draw(
+                            &label,
+                            DrawParam::default()
+                                .dest(self.window_size.scale_vec2(row + Vec2::new(24.0, -8.0)))
+                                .color(label_color)
+                                .scale(scale),
+                        );
+                    }
+                    let rarity_key: Vec<&str> = pickups::Rarity::ALL.iter().map(|rarity| rarity.name()).collect();
+                    let key_text = graphics::Text::new(format!("Rarity: {}", rarity_key.join(" < ")));
+                    canvas.draw(
+                        &key_text,
+                        DrawParam::default()
+                            .dest(self.window_size.scale_vec2(Vec2::new(
+                                BASE_WINDOW_WIDTH / 2.0 - 110.0,
+                                BASE_WINDOW_HEIGHT / 2.0 + 120.0 + pickups::PICKUPS.len() as f32 * 32.0,
+                            )))
+                            .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                            .scale(scale),
+                    );
+                } else if self.preview {
+                    let preview_text = graphics::Text::new(format!(
+                        "PREVIEW\nEnemy fire over the next {} s\nPress {} to resume",
+                        PREVIEW_DURATION,
+                        self.config.bindings.describe(Action::Preview)
+                    ));
+                    canvas.draw(
+                        &preview_text,
+                        DrawParam::default()
+                            .dest(self.window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH / 2.0 - 100.0, 80.0)))
+                            .color(palette.color(Swatch::Incoming))
+                            .scale(Vec2::new(self.window_size.scale_x, self.window_size.scale_y) * 1.2),
+                    );
+                }
+            }
+            DrawPass::Scene => {
+                // 主菜单与结算界面
+                match &self.scene {
+                    Scene::MainMenu(menu) => {
+                        let best = self.high_scores.get(self.mode).best_score;
+                        let best = match self.high_scores.holder(self.mode) {
+                            Some(name) => format!("Best: {} by {}", best, name),
+                            None => format!("Best: {}", best),
+                        };
+                        menu.draw(canvas, &self.window_size, &best);
+                    }
+                    Scene::GameOver(screen) => screen.draw(canvas, &self.window_size, palette.color(Swatch::HudAlert)),
+                    Scene::Loadout(screen) => screen.draw(canvas, &self.window_size),
+                    Scene::Practice(screen) => screen.draw(canvas, &self.window_size),
+                    Scene::LevelEditor(editor) => editor.draw(canvas, &self.window_size),
+                    Scene::Demo(_) => {
+                        if (ctx.time.time_since_start().as_secs_f32() * 2.0).fract() < 0.6 {
+                            canvas.draw(
+                                &graphics::Text::new("DEMO"),
+                                DrawParam::default()
+                                    .dest(self.window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH / 2.0 - 60.0, BASE_WINDOW_HEIGHT / 2.0 - 80.0)))
+                                    .color(Color::YELLOW)
+                                    .scale(Vec2::new(
+                                        self.window_size.scale_x * 3.0,
+                                        self.window_size.scale_y * 3.0
+                                    ))
+                            );
+                        }
+                        canvas.draw(
+                            &graphics::Text::new("Press any key"),
+                            DrawParam::default()
+                                .dest(self.window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH / 2.0 - 60.0, BASE_WINDOW_HEIGHT / 2.0 - 20.0)))
+                                .color(Color::WHITE)
+                                .scale(Vec2::new(
+                                    self.window_size.scale_x,
+                                    self.window_size.scale_y
+                                ))
+                        );
+                    }
+                    Scene::Playing => {}
+                }
+
+                // 在暂停、主菜单和结算界面列出当前档案和已加载的 mod
+                if self.paused || matches!(self.scene, Scene::MainMenu(_) | Scene::GameOver(_)) {
+                    canvas.draw(
+                        &graphics::Text::new(format!("Profile: {}", self.profile)),
+                        DrawParam::default()
+                            .dest(self.window_size.scale_vec2(Vec2::new(10.0, BASE_WINDOW_HEIGHT - 55.0)))
+                            .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                            .scale(Vec2::new(
+                                self.window_size.scale_x,
+                                self.window_size.scale_y
+                            ))
+                    );
+                    if let Some(mod_list) = mods::describe(&self.mods) {
+                        canvas.draw(
+                            &graphics::Text::new(mod_list),
+                            DrawParam::default()
+                                .dest(self.window_size.scale_vec2(Vec2::new(10.0, BASE_WINDOW_HEIGHT - 30.0)))
+                                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                                .scale(Vec2::new(
+                                    self.window_size.scale_x,
+                                    self.window_size.scale_y
+                                ))
+                        );
+                    }
+                }
+            }
+            DrawPass::Menus => {
+                if let Some(menu) = &self.settings {
+                    menu.draw(canvas, &self.window_size, &self.config.bindings);
+                }
+                if let Some(menu) = &self.profile_menu {
+                    menu.draw(canvas, &self.window_size);
+                }
+                if let Some(hangar) = &self.hangar {
+                    hangar.draw(canvas, &self.window_size, &self.progress);
+                }
+                if let Some(shop) = &self.shop {
+                    let prices: Vec<Option<i32>> = ShopItem::ALL.iter().map(|item| self.shop_price(*item)).collect();
+                    shop.draw(canvas, &self.window_size, self.score, &prices);
+                }
+            }
+            DrawPass::Transition => {
+                if let Some(transition) = &self.transition {
+                    transition.draw(ctx, canvas, &self.window_size)?;
+                }
+            }
+        }
+        Ok(())
     }
 }
 
 impl EventHandler for MainState {
     fn update(&mut self, ctx: &mut ggez::Context) -> GameResult {
         self.update_window_size(ctx);
+        self.poll_jobs();
+        #[cfg(feature = "online")]
+        self.poll_online(ctx)?;
+        self.update_window_title(ctx, ctx.time.delta().as_secs_f32());
+        let presence = PresenceState {
+            scene: self.scene.name(),
+            mode: self.mode.name(),
+            wave: self.wave,
+            score: self.score,
+            playing: matches!(self.scene, Scene::Playing),
+        };
+        self.presence.update(ctx.time.delta().as_secs_f32(), presence);
+        // 暂停、商店等界面不录入片段
+        let recording = self.config.record_highlights
+            && matches!(self.scene, Scene::Playing)
+            && !self.paused
+            && self.shop.is_none();
+        self.highlights.update(ctx, ctx.time.delta().as_secs_f32(), recording)?;
+
+        // 自由镜头按真实时间平移，暂停和单步时同样可以移动
+        if let Some(camera) = &mut self.free_camera {
+            let held = |key| if ctx.keyboard.is_key_pressed(key) { 1.0 } else { 0.0 };
+            let direction = Vec2::new(
+                held(KeyCode::L) - held(KeyCode::J),
+                held(KeyCode::K) - held(KeyCode::I),
+            );
+            camera.pan(direction, ctx.time.delta().as_secs_f32());
+        }
 
-        // 处理暂停键
-        if keyboard::is_key_pressed(ctx, KeyCode::P) {
-            if !self.p_key_pressed {  // 只在按键首次按下时触发
-                self.paused = !self.paused;
-                self.p_key_pressed = true;
+        // 场景过渡：画面完全遮住时切换场景，遮住之前模拟暂停
+        if let Some(transition) = &mut self.transition {
+            transition.update(ctx.time.delta().as_secs_f32());
+            if !transition.is_covering() {
+                if let Some(scene) = self.pending_scene.take() {
+                    self.scene = scene;
+                }
             }
-        } else {
-            self.p_key_pressed = false;  // 当按键释放时重置状态
+            if transition.is_finished() {
+                self.transition = None;
+            }
+            if self.pending_scene.is_some() {
+                return Ok(());
+            }
+        }
+
+        // 设置、档案、机库或商店界面打开时游戏暂停
+        if self.settings.is_some() || self.profile_menu.is_some() || self.hangar.is_some() || self.shop.is_some() {
+            return Ok(());
+        }
+        // 主菜单和装备界面不推进游戏，主菜单闲置太久则开始演示；演示在回放结束时回到主菜单
+        match &mut self.scene {
+            Scene::MainMenu(menu) => {
+                if menu.update(ctx.time.delta().as_secs_f32()) {
+                    self.start_demo(ctx)?;
+                }
+                return Ok(());
+            }
+            Scene::Loadout(_) | Scene::Practice(_) | Scene::LevelEditor(_) => return Ok(()),
+            Scene::Demo(replay) if replay.sample(self.run_time).is_none() => return self.end_demo(ctx),
+            _ => {}
         }
 
-        //重新开始
+        // 处理暂停键
+        // 游戏结束
         if self.game_over {
-            if keyboard::is_key_pressed(ctx, KeyCode::Space) {
-                self.reset(ctx)?;
+            // 游戏结束后爆炸效果继续播放完；在 tick 之外被击毁（最后一搏超时、联机断开）时这里才播放击毁的效果
+            let dt = ctx.time.delta().as_secs_f32();
+            self.play_cues(ctx)?;
+            self.particles.update(dt);
+            self.explosions.update(dt);
+            self.popups.update(dt);
+            self.score_counter.update(self.score, dt);
+            if let Scene::GameOver(screen) = &mut self.scene {
+                screen.update(dt);
+            }
+
+            // 续关倒计时，结束后进入结算界面
+            if let Some(remaining) = self.continue_timer {
+                self.continue_timer = Some(remaining - dt).filter(|left| *left > 0.0);
+                if self.continue_timer.is_none() {
+                    self.finish_run();
+                }
             }
             return Ok(());
         }
 
-        // 如果游戏暂停，只处理继续游戏的输入
+        // 如果游戏暂停，只处理继续游戏的输入；调试模式下可以单步推进
+        let tick = 1.0 / self.options.tick_rate as f32;
         if self.paused {
+            if std::mem::take(&mut self.step_requested) {
+                let inputs = Inputs { player: self.local_input(ctx), partner: None };
+                self.tick(ctx, tick, inputs)?;
+            }
             return Ok(());
         }
-
-
-        let mut dx = 0.0;
-        let mut dy = 0.0;
-
-        let player_speed = PLAYER_SPEED_RATIO * self.window_size.width;
-
-        if keyboard::is_key_pressed(ctx, KeyCode::Left) || keyboard::is_key_pressed(ctx, KeyCode::A) {
-            dx -= player_speed;
-        }
-        if keyboard::is_key_pressed(ctx, KeyCode::Right) || keyboard::is_key_pressed(ctx, KeyCode::D) {
-            dx += player_speed;
-        }
-        if keyboard::is_key_pressed(ctx, KeyCode::Up) || keyboard::is_key_pressed(ctx, KeyCode::W) {
-            dy -= player_speed;
-        }
-        if keyboard::is_key_pressed(ctx, KeyCode::Down) || keyboard::is_key_pressed(ctx, KeyCode::S) {
-            dy += player_speed;
+        // 练习模式的轨迹预览期间画面冻结
+        if self.preview {
+            return Ok(());
         }
 
-        self.player.pos.x = (self.player.pos.x + dx)
-            .clamp(0.0, BASE_WINDOW_WIDTH - self.player.base_size.x);
-        self.player.pos.y = (self.player.pos.y + dy)
-            .clamp(0.0, BASE_WINDOW_HEIGHT - self.player.base_size.y);
+        let real_dt = ctx.time.delta().as_secs_f32();
+        #[cfg(feature = "online")]
+        if self.online.is_some() && self.coop_rules.is_some() {
+            return self.update_online(ctx, tick, real_dt);
+        }
 
-        self.shoot_cooldown = self.shoot_cooldown.saturating_sub(ctx.time.delta());
+        // 最后一搏按真实时间倒计时，期间没有引爆炸弹则被击毁
+        if let Some(remaining) = &mut self.last_stand {
+            *remaining -= real_dt;
+            if *remaining <= 0.0 {
+                self.last_stand = None;
+                self.time_scale = 1.0;
+                return self.kill_player();
+            }
+        }
 
-        if keyboard::is_key_pressed(ctx, KeyCode::Space) && self.shoot_cooldown.is_zero() {
-            self.shoot(ctx)?;
-            self.shoot_cooldown = Duration::from_millis(250);
+        // 固定步长更新：累积（按全局时间流速缩放的）真实时间，按固定 tick 推进模拟
+        self.tick_accumulator += real_dt * self.time_scale * self.debug_speed;
+        let mut steps = 0;
+        while self.tick_accumulator >= tick && !self.game_over && self.shop.is_none() {
+            let inputs = Inputs { player: self.local_input(ctx), partner: None };
+            self.tick(ctx, tick, inputs)?;
+            self.tick_accumulator -= tick;
+            steps += 1;
+            // 防止卡顿后追帧过多导致“死亡螺旋”
+            if steps >= MAX_TICKS_PER_FRAME {
+                self.tick_accumulator = 0.0;
+                break;
+            }
         }
 
-        // 更新导弹冷却时间
-        self.missile_cooldown = self.missile_cooldown.saturating_sub(ctx.time.delta());
+        Ok(())
+    }
 
-        // 处理发射追踪导弹
-        if keyboard::is_key_pressed(ctx, KeyCode::X) && self.missile_cooldown.is_zero() {
-            self.launch_missile(ctx)?;
-            self.missile_cooldown = Duration::from_millis(1000);  // 1秒冷却时间
+    fn draw(&mut self, ctx: &mut ggez::Context) -> GameResult {
+        let mut queue = DrawQueue::default();
+        queue.push(Layer::Background, DrawPass::Stars);
+        queue.push(Layer::Pickups, DrawPass::Pickups);
+        queue.push(Layer::Enemies, DrawPass::Hazards);
+        queue.push(Layer::Enemies, DrawPass::Enemies);
+        queue.push(Layer::Enemies, DrawPass::Boss);
+        queue.push(Layer::Player, DrawPass::Ghost);
+        queue.push(Layer::Player, DrawPass::Player);
+        queue.push(Layer::Bullets, DrawPass::PlayerBullets);
+        queue.push(Layer::Bullets, DrawPass::EnemyBullets);
+        queue.push(Layer::Bullets, DrawPass::Hitbox);
+        queue.push(Layer::Particles, DrawPass::Effects);
+        queue.push(Layer::Particles, DrawPass::Warnings);
+        if self.free_camera.is_some() {
+            queue.push(Layer::Particles, DrawPass::Viewport);
+        }
+        if self.options.debug {
+            queue.push(Layer::Particles, DrawPass::Ai);
+        }
+        queue.push(Layer::Hud, DrawPass::Popups);
+        queue.push(Layer::Hud, DrawPass::Indicators);
+        queue.push(Layer::Hud, DrawPass::Status);
+        queue.push(Layer::Hud, DrawPass::BossBar);
+        queue.push(Layer::Hud, DrawPass::Alerts);
+        queue.push(Layer::Hud, DrawPass::Highlight);
+        queue.push(Layer::Hud, DrawPass::MessageBar);
+        queue.push(Layer::Hud, DrawPass::Debug);
+        queue.push(Layer::Overlay, DrawPass::Pause);
+        queue.push(Layer::Overlay, DrawPass::Scene);
+        queue.push(Layer::Overlay, DrawPass::Menus);
+        queue.push(Layer::Overlay, DrawPass::Transition);
+
+        // 开启泛光或屏幕滤镜时游戏画面先画到离屏图像，HUD 不参与泛光
+        let bloom = self.config.bloom;
+        let filter = self.config.screen_filter;
+        let mut offscreen = bloom || filter != ScreenFilter::Off;
+        let mut canvas = if offscreen {
+            self.post.scene_canvas(ctx, self.background)
+        } else {
+            Canvas::from_frame(ctx, self.background)
+        };
+        let alpha = (self.tick_accumulator * self.options.tick_rate as f32).clamp(0.0, 1.0);
+        // 自由镜头只改变游戏画面层的投影
+        if let Some(camera) = &self.free_camera {
+            canvas.set_screen_coordinates(camera.screen_coordinates(&self.window_size));
         }
 
-        // 在子弹更新逻辑中添加扇形子弹的处理
-        let bullet_speed = BULLET_SPEED_RATIO * self.window_size.height;
-        for bullet in &mut self.bullets {
-            match bullet.object_type {
-                GameObjectType::Bullet => {
-                    bullet.pos.y -= bullet_speed;
-                }
-                GameObjectType::SpreadShot => {
-                    bullet.pos += bullet.speed;  // 使用预设的速度和方向
-                }
-                GameObjectType::GuidedMissile => {
-                    bullet.update_guided_missile(&self.enemies, &self.window_size);
-                    bullet.pos += bullet.speed;
+        let mut world = true;
+        for (layer, pass) in queue.into_sorted() {
+            // 游戏画面画完后做后处理，再在其上绘制 HUD 与界面
+            if world && !layer.is_world() {
+                world = false;
+                if offscreen {
+                    canvas.finish(ctx)?;
+                    canvas = self.post.overlay_canvas(ctx, bloom, filter)?;
+                    offscreen = false;
+                } else if self.free_camera.is_some() {
+                    canvas.set_screen_coordinates(graphics::Rect::new(0.0, 0.0, self.window_size.width, self.window_size.height));
                 }
-                _ => {}
             }
+            self.draw_pass(ctx, &mut canvas, pass, alpha)?;
         }
-
-        // 在弹药生成逻辑中随机生成扇形弹药
-        self.ammo_spawn_timer += ctx.time.delta();
-        if self.ammo_spawn_timer.as_secs_f32() >= 1.0 {
-            if rand::random::<bool>() {  // 50%概率生成普通导弹弹药或扇形弹药
-                self.spawn_missile_ammo(ctx)?;
-            } else {
-                self.spawn_spread_ammo(ctx)?;
-            }
-            self.ammo_spawn_timer = Duration::from_secs(0);
+        if offscreen {
+            canvas.finish(ctx)?;
+            canvas = self.post.overlay_canvas(ctx, bloom, filter)?;
         }
 
+        canvas.finish(ctx)?;
+        self.post.present(ctx, filter)
+    }
 
-        self.bullets.retain(|bullet| bullet.pos.y > -bullet.base_size.y);
-
-        // 处理敌人生成
-        self.spawn_timer += ctx.time.delta();
-        if self.spawn_timer.as_secs_f32() >= 1.0 {
-            self.spawn_enemy(ctx)?;
-            self.spawn_timer = Duration::from_secs(0);
+    fn key_down_event(&mut self, ctx: &mut ggez::Context, input: KeyInput, repeated: bool) -> GameResult {
+        let Some(key) = input.keycode else {
+            return Ok(());
+        };
+        // 过渡遮住画面之前忽略按键，避免重复触发场景切换
+        if self.pending_scene.is_some() {
+            return Ok(());
         }
 
-        // 更新敌人位置
-        let enemy_speed = ENEMY_SPEED_RATIO * self.window_size.height;
-        for enemy in &mut self.enemies {
-            enemy.pos.y += enemy_speed;
-            if enemy.intersects(&self.player, &self.window_size) {
-                self.game_over = true;
+        // 设置界面打开时由其处理所有按键，关闭时保存配置
+        if let Some(menu) = &mut self.settings {
+            match menu.key_down(key, &mut self.config) {
+                SettingsEvent::None => {}
+                SettingsEvent::Changed => self.refresh_volume(),
+                SettingsEvent::Close => {
+                    self.settings = None;
+                    self.save_config();
+                }
             }
+            return Ok(());
         }
-        self.enemies.retain(|enemy| enemy.pos.y < BASE_WINDOW_HEIGHT);
 
-        // 更新星空
-        for (pos, _) in &mut self.star_field {
-            pos.y += 0.5 * self.window_size.scale_y;
-            if pos.y > BASE_WINDOW_HEIGHT {
-                pos.y = 0.0;
+        // 商店打开时由其处理所有按键
+        if let Some(shop) = &mut self.shop {
+            match shop.key_down(key) {
+                ShopEvent::None => {}
+                ShopEvent::Buy(item) => {
+                    let result = self.buy(item);
+                    if let Some(shop) = &mut self.shop {
+                        shop.message = Some(match result {
+                            Ok(()) => format!("Bought {}", item.name()),
+                            Err(message) => message,
+                        });
+                    }
+                }
+                ShopEvent::Close => {
+                    self.shop = None;
+                    // 丢弃商店打开期间累积的时间，避免关闭后一次追赶多个 tick
+                    self.tick_accumulator = 0.0;
+                    // 购买发生在模拟之外，之前的快照回退后无法重现
+                    self.rollback.clear();
+                }
             }
+            return Ok(());
         }
 
-        // 碰撞检测和爆炸效果
-        let mut destroyed_bullets = HashSet::new();
-        let mut destroyed_enemies = HashSet::new();
-        let mut explosion_positions = Vec::new();
-
-        for (bullet_idx, bullet) in self.bullets.iter().enumerate() {
-            for (enemy_idx, enemy) in self.enemies.iter().enumerate() {
-                if !destroyed_bullets.contains(&bullet_idx) &&
-                    !destroyed_enemies.contains(&enemy_idx) &&
-                    bullet.intersects(enemy, &self.window_size) {
-                    destroyed_bullets.insert(bullet_idx);
-                    destroyed_enemies.insert(enemy_idx);
-                    // 导弹击中给更多分数
-                    self.score += match bullet.object_type {
-                        GameObjectType::GuidedMissile => 20,
-                        _ => 10,
-                    };
-
-                    self.sounds.play_explosion(ctx)?;
-
-                    explosion_positions.push((
-                        enemy.pos + enemy.base_size * 0.5,
-                        Color::new(1.0, 0.5, 0.0, 1.0)
-                    ));
+        // 机库界面打开时由其处理所有按键，购买和装备后立即保存
+        if let Some(hangar) = &mut self.hangar {
+            match hangar.key_down(key, &mut self.progress) {
+                HangarEvent::None => {}
+                HangarEvent::Changed => {
+                    self.save_progress();
                 }
+                HangarEvent::Close => self.hangar = None,
             }
+            return Ok(());
         }
 
-        // 移除被销毁的对象
-        let mut bullets_to_remove: Vec<_> = destroyed_bullets.into_iter().collect();
-        let mut enemies_to_remove: Vec<_> = destroyed_enemies.into_iter().collect();
-        bullets_to_remove.sort_unstable_by(|a, b| b.cmp(a));
-        enemies_to_remove.sort_unstable_by(|a, b| b.cmp(a));
-
-        for idx in bullets_to_remove {
-            if idx < self.bullets.len() {
-                self.bullets.remove(idx);
+        // 档案界面打开时由其处理所有按键
+        if let Some(menu) = &mut self.profile_menu {
+            match menu.key_down(key, &self.profiles) {
+                ProfileEvent::None => {}
+                ProfileEvent::Close => self.profile_menu = None,
+                ProfileEvent::Selected(name) => {
+                    self.profile_menu = None;
+                    self.switch_profile(ctx, name)?;
+                }
             }
+            return Ok(());
         }
-        for idx in enemies_to_remove {
-            if idx < self.enemies.len() {
-                self.enemies.remove(idx);
+
+        let entering_code = matches!(&self.scene, Scene::MainMenu(menu) if menu.is_entering_code());
+        match key {
+            KeyCode::F1 => self.settings = Some(SettingsMenu::new(&self.config)),
+            KeyCode::F2 if self.paused => {
+                self.profile_menu = Some(ProfileMenu::new(&self.profiles, &self.profile));
+            }
+            // 调试：切换模拟倍速，暂停时单步推进（按住可连续单步）、单步后退或重新模拟校验
+            KeyCode::F9 if self.options.debug && self.paused && !self.game_over => self.step_requested = true,
+            KeyCode::F11 if self.options.debug && self.paused && !self.game_over => self.step_back(),
+            KeyCode::F3 if self.options.debug && self.paused && !self.game_over => self.verify_determinism(ctx)?,
+            KeyCode::F5 if self.options.debug => self.debug_speed = 0.25,
+            KeyCode::F6 if self.options.debug => self.debug_speed = 0.5,
+            KeyCode::F7 if self.options.debug => self.debug_speed = 1.0,
+            KeyCode::F8 if self.options.debug => self.debug_speed = 2.0,
+            KeyCode::F10 if self.options.debug => {
+                self.free_camera = match self.free_camera {
+                    Some(_) => None,
+                    None => Some(FreeCamera::default()),
+                };
+            }
+            KeyCode::PageUp => {
+                if let Some(camera) = &mut self.free_camera {
+                    camera.zoom_by(1);
+                }
             }
+            KeyCode::PageDown => {
+                if let Some(camera) = &mut self.free_camera {
+                    camera.zoom_by(-1);
+                }
+            }
+            KeyCode::Home if self.free_camera.is_some() => self.free_camera = Some(FreeCamera::default()),
+            // 结算界面的 Esc 用于跳过留名，装备界面和关卡编辑器的 Esc 用于返回主菜单，输入分享码时用于取消输入
+            KeyCode::Escape if !matches!(self.scene, Scene::GameOver(_) | Scene::Loadout(_) | Scene::Practice(_) | Scene::LevelEditor(_)) && !entering_code => ctx.request_quit(),
+            _ => {}
         }
 
-        // 创建爆炸效果
-        for (pos, color) in explosion_positions {
-            self.particles.add_explosion(pos, color, &self.window_size);
+        // 离散动作只响应首次按下，忽略按键重复
+        if repeated {
+            return Ok(());
         }
 
-        // 更新粒子系统
-        self.particles.update(ctx.time.delta().as_secs_f32(), &self.window_size);
-
-
-        // 更新弹药生成计时器
-        // self.ammo_spawn_timer += ctx.time.delta();
-        // if self.ammo_spawn_timer.as_secs_f32() >= 15.0 { // 每15秒生成一个弹药包
-        //     self.spawn_missile_ammo(ctx)?;
-        //     self.ammo_spawn_timer = Duration::from_secs(0);
-        // }
-
-        // 更新弹药位置
-        let ammo_speed = ENEMY_SPEED_RATIO * self.window_size.height;
-        for ammo in &mut self.ammo_items {
-            ammo.pos.y += ammo_speed;
+        // 演示中按任意键回到主菜单
+        if let Scene::Demo(_) = self.scene {
+            return self.end_demo(ctx);
         }
-        self.ammo_items.retain(|ammo| ammo.pos.y < BASE_WINDOW_HEIGHT);
 
-        // // 检测玩家与弹药的碰撞
-        // let mut collected_ammo = Vec::new();
-        // for (idx, ammo) in self.ammo_items.iter().enumerate() {
-        //     if ammo.intersects(&self.player, &self.window_size) {
-        //         collected_ammo.push(idx);
-        //         self.missile_ammo += 3; // 每个弹药包补充3发导弹
-        //
-        //         // 添加收集效果
-        //         self.particles.add_explosion(
-        //             ammo.pos,
-        //             Color::new(0.0, 1.0, 1.0, 1.0), // 青色粒子效果
-        //             &self.window_size,
-        //         );
-        //     }
-        // }
-        //
-        // // 移除被收集的弹药
-        // for idx in collected_ammo.iter().rev() {
-        //     self.ammo_items.remove(*idx);
-        // }
+        let typing = entering_code || matches!(&self.scene, Scene::GameOver(screen) if screen.is_entering_name());
+        if !typing && self.config.bindings.action_for(key) == Some(Action::Mute) {
+            self.toggle_mute();
+            return Ok(());
+        }
 
-        // 保留并修改这段代码
-        let mut collected_ammo = Vec::new();
-        for (idx, ammo) in self.ammo_items.iter().enumerate() {
-            if ammo.intersects(&self.player, &self.window_size) {
-                collected_ammo.push(idx);
-                match ammo.object_type {
-                    GameObjectType::SpreadAmmo => {
-                        self.has_spread_shot = true;
-                        self.particles.add_explosion(
-                            ammo.pos,
-                            Color::new(1.0, 0.5, 0.0, 1.0), // 橙色粒子效果
-                            &self.window_size,
-                        );
+        // 主菜单和结算界面由其处理按键，射击键不会误触重新开始
+        match &mut self.scene {
+            Scene::MainMenu(menu) => {
+                match menu.key_down(key) {
+                    MainMenuEvent::None => {}
+                    MainMenuEvent::Start => {
+                        // 从菜单正常开始时回到启动参数指定的种子和规则
+                        self.mode = self.default_mode();
+                        self.practice = None;
+                        self.seed_override = self.options.seed;
+                        self.mutators_override = self.options.mutators;
+                        self.set_scene(Scene::Loadout(LoadoutScreen::new(&self.progress)));
                     }
-                    GameObjectType::MissileAmmo => {
-                        self.missile_ammo += 3; // 每个弹药包补充3发导弹
-                        self.particles.add_explosion(
-                            ammo.pos,
-                            Color::new(0.0, 1.0, 1.0, 1.0), // 青色粒子效果
-                            &self.window_size,
-                        );
+                    MainMenuEvent::Horde => {
+                        self.mode = GameMode::Horde;
+                        self.practice = None;
+                        self.seed_override = self.options.seed;
+                        self.mutators_override = self.options.mutators;
+                        self.set_scene(Scene::Loadout(LoadoutScreen::new(&self.progress)));
                     }
-                    _ => {}
+                    MainMenuEvent::Practice => self.set_scene(Scene::Practice(PracticeScreen::new(&self.progress, self.practice))),
+                    MainMenuEvent::PlayCode(code) => {
+                        self.mode = self.default_mode();
+                        self.practice = None;
+                        self.seed_override = Some(code.seed);
+                        self.mutators_override = Some(code.mutators);
+                        self.set_scene(Scene::Loadout(LoadoutScreen::new(&self.progress)));
+                    }
+                    MainMenuEvent::Hangar => self.hangar = Some(Hangar::new()),
+                    MainMenuEvent::LevelEditor => {
+                        let editor = LevelEditor::new(self.level.clone().unwrap_or_default());
+                        self.set_scene(Scene::LevelEditor(editor));
+                    }
+                    MainMenuEvent::Profiles => self.profile_menu = Some(ProfileMenu::new(&self.profiles, &self.profile)),
+                    MainMenuEvent::Settings => self.settings = Some(SettingsMenu::new(&self.config)),
+                    #[cfg(feature = "online")]
+                    MainMenuEvent::HostCoop => {
+                        let rules = CoopRules {
+                            seed: rand::random(),
+                            mutators: self.config.mutators,
+                            difficulty: self.config.difficulty,
+                            boundary: self.config.boundary,
+                            classic_movement: self.config.classic_movement,
+                        };
+                        let waiting = format!("Waiting for player 2 on port {}...", net::DEFAULT_PORT);
+                        self.start_session(net::Session::host(net::DEFAULT_PORT, rules), waiting);
+                    }
+                    #[cfg(feature = "online")]
+                    MainMenuEvent::JoinCoop(address) => {
+                        let waiting = format!("Connecting to {}...", address);
+                        self.start_session(net::Session::join(&address), waiting);
+                    }
+                    #[cfg(feature = "online")]
+                    MainMenuEvent::CancelCoop => self.online = None,
+                    MainMenuEvent::Quit => ctx.request_quit(),
+                }
+                return Ok(());
+            }
+            Scene::GameOver(screen) => {
+                match screen.key_down(key) {
+                    GameOverEvent::None => {}
+                    GameOverEvent::NameEntered(name) => {
+                        self.high_scores.set_holder(self.mode, &name);
+                        self.save_high_scores();
+                    }
+                    GameOverEvent::Retry => {
+                        self.reset(ctx)?;
+                        self.set_scene(Scene::Playing);
+                    }
+                    GameOverEvent::MainMenu => {
+                        self.reset(ctx)?;
+                        self.set_scene(Scene::MainMenu(MainMenu::new()));
+                    }
+                }
+                return Ok(());
+            }
+            Scene::Loadout(screen) => {
+                match screen.key_down(key) {
+                    LoadoutEvent::None => {}
+                    LoadoutEvent::Launch(loadout) => {
+                        self.progress.loadout = loadout;
+                        self.save_progress();
+                        self.reset(ctx)?;
+                        self.set_scene(Scene::Playing);
+                    }
+                    LoadoutEvent::Back => self.set_scene(Scene::MainMenu(MainMenu::new())),
+                }
+                return Ok(());
+            }
+            Scene::Practice(screen) => {
+                match screen.key_down(key) {
+                    PracticeEvent::None => {}
+                    PracticeEvent::Start(practice) => {
+                        self.mode = self.default_mode();
+                        self.practice = Some(practice);
+                        self.seed_override = self.options.seed;
+                        self.mutators_override = self.options.mutators;
+                        self.reset(ctx)?;
+                        self.set_scene(Scene::Playing);
+                    }
+                    PracticeEvent::Back => self.set_scene(Scene::MainMenu(MainMenu::new())),
+                }
+                return Ok(());
+            }
+            Scene::LevelEditor(editor) => {
+                match editor.key_down(key) {
+                    LevelEditorEvent::None => {}
+                    // 导出到 --level 指定的文件，没有指定时导出到用户数据目录；编辑的正是当前关卡时下一局即按新的时间轴出怪
+                    LevelEditorEvent::Export(mut level) => {
+                        let path = match &self.options.level {
+                            Some(path) => {
+                                self.level = Some(level.clone());
+                                path.clone()
+                            }
+                            None => ctx.fs.user_data_dir().join(LEVELS_DIR).join("custom.ron"),
+                        };
+                        if level.name.is_empty() {
+                            level.name = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+                        }
+                        editor.set_status(format!("Exported {} events to {}", level.events.len(), path.display()));
+                        self.jobs.save("level", move || level.save(&path));
+                    }
+                    LevelEditorEvent::Back => self.set_scene(Scene::MainMenu(MainMenu::new())),
+                }
+                return Ok(());
+            }
+            Scene::Playing | Scene::Demo(_) => {}
+        }
+        match self.config.bindings.action_for(key) {
+            // 联机合作不能暂停，对方会一直等待
+            Some(Action::Pause) if !self.game_over && self.coop_rules.is_none() => self.paused = !self.paused,
+            Some(Action::Missile) if !self.game_over && !self.paused => self.missile_queued = true,
+            Some(Action::Bomb) if !self.game_over && !self.paused => self.bomb_queued = true,
+            Some(Action::Continue) if self.continue_timer.is_some() => self.continue_run()?,
+            Some(Action::Skip) if self.message_bar.is_active() && !self.paused => self.message_bar.skip(),
+            Some(Action::Preview) if self.practice.is_some() && !self.game_over && !self.paused => self.preview = !self.preview,
+            Some(Action::Highlight) if self.config.record_highlights => {
+                let dir = ctx.fs.user_data_dir().join(HIGHLIGHTS_DIR);
+                if !self.highlights.export(&dir) {
+                    debug!("No highlight frames recorded yet");
                 }
             }
+            _ => {}
         }
+        Ok(())
+    }
 
-        // 移除被收集的弹药
-        for idx in collected_ammo.iter().rev() {
-            self.ammo_items.remove(*idx);
+    fn text_input_event(&mut self, _ctx: &mut ggez::Context, character: char) -> GameResult {
+        if let Some(menu) = &mut self.profile_menu {
+            menu.text_input(character);
+        } else if let Scene::GameOver(screen) = &mut self.scene {
+            screen.text_input(character);
+        } else if let Scene::MainMenu(menu) = &mut self.scene {
+            menu.text_input(character);
         }
+        Ok(())
+    }
 
+    // 切到其他窗口时静音，游戏中则自动暂停；回来后恢复声音，由玩家自己取消暂停
+    fn focus_event(&mut self, _ctx: &mut ggez::Context, gained: bool) -> GameResult {
+        self.focused = gained;
+        self.refresh_volume();
+        if gained {
+            self.title_flash = None;
+        }
+        if !gained && matches!(self.scene, Scene::Playing) && !self.game_over && self.coop_rules.is_none() {
+            self.paused = true;
+        }
         Ok(())
     }
 
-    fn draw(&mut self, ctx: &mut ggez::Context) -> GameResult {
-        let mut canvas = Canvas::from_frame(ctx, Color::new(0.0, 0.05, 0.1, 1.0));
+    fn resize_event(&mut self, _ctx: &mut ggez::Context, width: f32, height: f32) -> GameResult {
+        self.window_size = WindowSize::new(width, height);
+        Ok(())
+    }
+}
 
-        // 绘制星空
-        for (pos, size) in &self.star_field {
-            let scaled_pos = self.window_size.scale_vec2(*pos);
-            let scaled_size = size * self.window_size.scale_x.min(self.window_size.scale_y);
+// 先在载入界面预加载资源，初始化成功时运行游戏，初始化或运行中出错时显示错误界面
+enum Game {
+    Loading(LoadingScreen),
+    Running(Box<MainState>),
+    Failed(ErrorScreen),
+    #[cfg(feature = "editor")]
+    Editor(PathEditor),
+}
 
-            let star = Mesh::new_circle(
-                ctx,
-                graphics::DrawMode::fill(),
-                scaled_pos,
-                scaled_size,
-                0.1,
-                Color::WHITE,
-            )?;
-            canvas.draw(&star, DrawParam::default());
-        }
+// 顶层事件处理：错误不再从 main 返回，而是切换到可以重试的错误界面
+struct App {
+    game: Game,
+    options: LaunchOptions, // 重试时用同样的启动参数重新初始化
+    mods: Vec<ModInfo>,
+}
+
+impl App {
+    fn new(options: LaunchOptions, mods: Vec<ModInfo>) -> Self {
+        let game = Self::init(&options);
+        App { game, options, mods }
+    }
 
-        // 绘制游戏对象
-        self.player.draw(&mut canvas, &self.window_size);
+    fn init(options: &LaunchOptions) -> Game {
+        Game::Loading(LoadingScreen::new(!options.no_audio))
+    }
 
-        // 绘制弹药包和扇形弹药，只给扇形弹药显示碰撞圈
-        for ammo in &self.ammo_items {
-            ammo.draw(&mut canvas, &self.window_size);
-            if let GameObjectType::SpreadAmmo = ammo.object_type {
-                ammo.draw_collision_circle(ctx, &mut canvas, &self.window_size)?;  // 只给扇形弹药添加碰撞圈
+    // 资源载入完成后创建游戏；缺少字体或贴图时列出所有缺少的文件
+    fn start(ctx: &mut ggez::Context, options: &LaunchOptions, mods: &[ModInfo], result: LoadResult) -> Game {
+        let assets = match result {
+            LoadResult::Loaded(assets) => assets,
+            LoadResult::Missing(paths) => {
+                let message = format!("Missing resource files:\n  {}", paths.join("\n  "));
+                return Self::failed(GameError::ResourceLoadError(message));
             }
+        };
+        // 路线编辑器保存到磁盘上的资源目录（--resources 指定的是资源包时仍保存到 resources/）
+        #[cfg(feature = "editor")]
+        if let Some(name) = &options.editor {
+            let dir = options.resources.clone().filter(|dir| dir.is_dir()).unwrap_or_else(|| path::PathBuf::from(RESOURCE_DIR));
+            return match assets.image("/img/player.png") {
+                Ok(image) => Game::Editor(PathEditor::new(name.clone(), &dir, image)),
+                Err(err) => Self::failed(err),
+            };
         }
-
-        for bullet in &self.bullets {
-            bullet.draw(&mut canvas, &self.window_size);
-            bullet.draw_collision_circle(ctx, &mut canvas, &self.window_size)?;
+        match MainState::new(ctx, options.clone(), mods.to_vec(), assets) {
+            Ok(state) => Game::Running(Box::new(state)),
+            Err(err) => Self::failed(err),
         }
+    }
+
+    fn failed(err: GameError) -> Game {
+        error!("{}", err);
+        Game::Failed(ErrorScreen::new(err.to_string()))
+    }
 
-        for enemy in &self.enemies {
-            enemy.draw(&mut canvas, &self.window_size);
+    // 运行中的错误同样切换到错误界面
+    fn check(&mut self, result: GameResult) -> GameResult {
+        if let Err(err) = result {
+            self.game = Self::failed(err);
         }
+        Ok(())
+    }
+}
 
-        // 绘制导弹数量和扇形状态
-        let ammo_text = graphics::Text::new(format!("Missiles: {}", self.missile_ammo));
-        let ammo_pos = self.window_size.scale_vec2(Vec2::new(10.0, 40.0));
-        canvas.draw(
-            &ammo_text,
-            DrawParam::default()
-                .dest(ammo_pos)
-                .color(Color::WHITE)
-                .scale(Vec2::new(
-                    self.window_size.scale_x,
-                    self.window_size.scale_y
-                ))
-        );
+impl EventHandler for App {
+    fn update(&mut self, ctx: &mut ggez::Context) -> GameResult {
+        let result = match &mut self.game {
+            Game::Loading(screen) => {
+                if let Some(result) = screen.update(ctx) {
+                    self.game = Self::start(ctx, &self.options, &self.mods, result);
+                }
+                Ok(())
+            }
+            Game::Running(state) => state.update(ctx),
+            Game::Failed(_) => Ok(()),
+            #[cfg(feature = "editor")]
+            Game::Editor(editor) => {
+                editor.update(ctx.time.delta().as_secs_f32());
+                Ok(())
+            }
+        };
+        self.check(result)
+    }
 
-        // 绘制扇形弹药状态
-        let spread_text = graphics::Text::new(
-            if self.has_spread_shot {
-                "Spread Shot: Active"
-            } else {
-                "Spread Shot: -"
+    fn draw(&mut self, ctx: &mut ggez::Context) -> GameResult {
+        match &mut self.game {
+            Game::Running(state) => {
+                let result = state.draw(ctx);
+                self.check(result)
             }
-        );
-        let spread_pos = self.window_size.scale_vec2(Vec2::new(10.0, 70.0));
-        canvas.draw(
-            &spread_text,
-            DrawParam::default()
-                .dest(spread_pos)
-                .color(if self.has_spread_shot {
-                    Color::new(1.0, 0.5, 0.0, 1.0) // 橙色
-                } else {
-                    Color::new(0.5, 0.5, 0.5, 1.0) // 灰色
-                })
-                .scale(Vec2::new(
-                    self.window_size.scale_x,
-                    self.window_size.scale_y
-                ))
-        );
+            Game::Loading(screen) => {
+                let (width, height) = ctx.gfx.drawable_size();
+                let mut canvas = Canvas::from_frame(ctx, Color::BLACK);
+                screen.draw(&mut canvas, &WindowSize::new(width, height));
+                canvas.finish(ctx)
+            }
+            Game::Failed(screen) => {
+                let (width, height) = ctx.gfx.drawable_size();
+                let mut canvas = Canvas::from_frame(ctx, Color::BLACK);
+                screen.draw(&mut canvas, &WindowSize::new(width, height));
+                canvas.finish(ctx)
+            }
+            #[cfg(feature = "editor")]
+            Game::Editor(editor) => {
+                let (width, height) = ctx.gfx.drawable_size();
+                let mut canvas = Canvas::from_frame(ctx, Color::new(0.05, 0.05, 0.1, 1.0));
+                editor.draw(ctx, &mut canvas, &WindowSize::new(width, height))?;
+                canvas.finish(ctx)
+            }
+        }
+    }
 
-        // 绘制分数
-        let score_text = graphics::Text::new(format!("Score: {}", self.score));
-        let score_pos = self.window_size.scale_vec2(Vec2::new(10.0, 10.0));
-        canvas.draw(
-            &score_text,
-            DrawParam::default()
-                .dest(score_pos)
-                .color(Color::WHITE)
-                .scale(Vec2::new(
-                    self.window_size.scale_x,
-                    self.window_size.scale_y
-                ))
-        );
+    fn key_down_event(&mut self, ctx: &mut ggez::Context, input: KeyInput, repeated: bool) -> GameResult {
+        match &mut self.game {
+            Game::Running(state) => {
+                let result = state.key_down_event(ctx, input, repeated);
+                self.check(result)
+            }
+            Game::Loading(_) => Ok(()),
+            Game::Failed(screen) => {
+                let Some(key) = input.keycode else {
+                    return Ok(());
+                };
+                match screen.key_down(key) {
+                    ErrorEvent::None => {}
+                    ErrorEvent::Retry => self.game = Self::init(&self.options),
+                    ErrorEvent::Quit => ctx.request_quit(),
+                }
+                Ok(())
+            }
+            #[cfg(feature = "editor")]
+            Game::Editor(editor) => {
+                if let Some(EditorEvent::Quit) = input.keycode.map(|key| editor.key_down(key)) {
+                    ctx.request_quit();
+                }
+                Ok(())
+            }
+        }
+    }
 
-        // 绘制粒子效果
-        self.particles.draw(ctx, &mut canvas, &self.window_size)?;
+    fn text_input_event(&mut self, ctx: &mut ggez::Context, character: char) -> GameResult {
+        match &mut self.game {
+            Game::Running(state) => {
+                let result = state.text_input_event(ctx, character);
+                self.check(result)
+            }
+            Game::Loading(_) | Game::Failed(_) => Ok(()),
+            #[cfg(feature = "editor")]
+            Game::Editor(_) => Ok(()),
+        }
+    }
 
-        // 绘制游戏结束和暂停提示
-        if self.game_over {
-            let game_over_text = graphics::Text::new("Game Over!\nPress SPACE to restart");
-            let text_pos = self.window_size.scale_vec2(Vec2::new(
-                BASE_WINDOW_WIDTH/2.0 - 100.0,
-                BASE_WINDOW_HEIGHT/2.0
-            ));
-            canvas.draw(
-                &game_over_text,
-                DrawParam::default()
-                    .dest(text_pos)
-                    .color(Color::RED)
-                    .scale(Vec2::new(
-                        self.window_size.scale_x * 2.0,
-                        self.window_size.scale_y * 2.0
-                    ))
-            );
+    fn focus_event(&mut self, ctx: &mut ggez::Context, gained: bool) -> GameResult {
+        match &mut self.game {
+            Game::Running(state) => state.focus_event(ctx, gained),
+            Game::Loading(_) | Game::Failed(_) => Ok(()),
+            #[cfg(feature = "editor")]
+            Game::Editor(_) => Ok(()),
         }
+    }
 
-        if self.paused {
-            let pause_text = graphics::Text::new("PAUSED\nPress P to continue");
-            let text_pos = self.window_size.scale_vec2(Vec2::new(
-                BASE_WINDOW_WIDTH/2.0 - 100.0,
-                BASE_WINDOW_HEIGHT/2.0
-            ));
-            canvas.draw(
-                &pause_text,
-                DrawParam::default()
-                    .dest(text_pos)
-                    .color(Color::YELLOW)
-                    .scale(Vec2::new(
-                        self.window_size.scale_x * 2.0,
-                        self.window_size.scale_y * 2.0
-                    ))
-            );
+    fn resize_event(&mut self, ctx: &mut ggez::Context, width: f32, height: f32) -> GameResult {
+        match &mut self.game {
+            Game::Running(state) => state.resize_event(ctx, width, height),
+            Game::Loading(_) | Game::Failed(_) => Ok(()),
+            #[cfg(feature = "editor")]
+            Game::Editor(_) => Ok(()),
         }
+    }
 
-        canvas.finish(ctx)?;
+    // 鼠标只用于路线编辑器
+    #[cfg(feature = "editor")]
+    fn mouse_button_down_event(&mut self, ctx: &mut ggez::Context, button: ggez::input::mouse::MouseButton, x: f32, y: f32) -> GameResult {
+        if let Game::Editor(editor) = &mut self.game {
+            let (width, height) = ctx.gfx.drawable_size();
+            editor.mouse_down(button, x, y, &WindowSize::new(width, height));
+        }
         Ok(())
     }
 
-    fn resize_event(&mut self, ctx: &mut ggez::Context, width: f32, height: f32) -> GameResult {
-        self.window_size = WindowSize::new(width, height);
+    #[cfg(feature = "editor")]
+    fn mouse_button_up_event(&mut self, _ctx: &mut ggez::Context, button: ggez::input::mouse::MouseButton, _x: f32, _y: f32) -> GameResult {
+        if let Game::Editor(editor) = &mut self.game {
+            editor.mouse_up(button);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "editor")]
+    fn mouse_motion_event(&mut self, ctx: &mut ggez::Context, x: f32, y: f32, _dx: f32, _dy: f32) -> GameResult {
+        if let Game::Editor(editor) = &mut self.game {
+            let (width, height) = ctx.gfx.drawable_size();
+            editor.mouse_motion(x, y, &WindowSize::new(width, height));
+        }
         Ok(())
     }
+
+    // 退出前等后台存档写完，避免丢失刚刚保存的纪录
+    fn quit_event(&mut self, _ctx: &mut ggez::Context) -> GameResult<bool> {
+        if let Game::Running(state) = &mut self.game {
+            state.finish_jobs();
+        }
+        Ok(false)
+    }
+}
+
+// 挂载基础资源：--resources 指定的目录或资源包，否则为 resources/ 目录，
+// 旁边有 resources.zip 时也一并挂载（排在目录之后，目录中的同名文件优先）
+fn mount_resources(mut cb: ggez::ContextBuilder, resources: Option<&path::Path>) -> GameResult<ggez::ContextBuilder> {
+    let paths = match resources {
+        Some(path) => vec![path.to_path_buf()],
+        None => vec![path::PathBuf::from(RESOURCE_DIR), path::PathBuf::from(RESOURCE_PACK)],
+    };
+    for path in paths {
+        if path.is_dir() {
+            info!("Adding resource path: {:?}", path);
+            cb = cb.add_resource_path(path);
+        } else if path.is_file() {
+            info!("Adding resource pack: {:?}", path);
+            let bytes = fs::read(&path).map_err(|err| {
+                GameError::ResourceLoadError(format!("{}: {}", path.display(), err))
+            })?;
+            cb = cb.add_zipfile_bytes(bytes);
+        }
+    }
+    Ok(cb)
+}
+
+// 创建窗口或打开音频设备失败时给玩家看的说明
+fn startup_error(err: &GameError) -> String {
+    match err {
+        GameError::AudioError(_) => format!(
+            "Could not open an audio device: {}\nOn machines without audio, build without sound: cargo run --no-default-features",
+            err
+        ),
+        _ => format!("Could not start the game: {}", err),
+    }
 }
 
 fn main() -> GameResult {
+    // 解析命令行参数
+    let options = LaunchOptions::from_env().map_err(|err| {
+        eprintln!("{}\n\n{}", err, cli::USAGE);
+        GameError::CustomError(err)
+    })?;
+    if options.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    // 本游戏的日志级别由 --log-level 控制，依赖库（ggez、wgpu 等）只输出警告以上
+    let mut logger = env_logger::Builder::new();
+    logger.filter_level(log::LevelFilter::Warn).parse_default_env();
+    if let Some(level) = options.log_level {
+        logger.filter_module(env!("CARGO_CRATE_NAME"), level);
+    }
+    logger.init();
+
+    if let Some(level) = &options.level {
+        info!("Using level file: {:?}", level);
+    }
+
     // 设置资源目录
     if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
         let mut path = path::PathBuf::from(manifest_dir);
         path.push(RESOURCE_DIR);
-        println!("Adding resource path: {:?}", path);
+        info!("Adding resource path: {:?}", path);
         env::set_var("CARGO_RESOURCE_ROOT", path);
     }
 
     // 创建游戏上下文
-    let cb = ggez::ContextBuilder::new("vertical_shooter", "author")
+    let mut cb = ggez::ContextBuilder::new("vertical_shooter", "author")
         .window_setup(ggez::conf::WindowSetup::default()
-            .title("Vertical Shooter")
+            .title(WINDOW_TITLE)
             .vsync(true))
         .window_mode(ggez::conf::WindowMode::default()
             .dimensions(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT)
             .resizable(true)
-            .min_dimensions(400.0, 300.0))  // 设置最小窗口尺寸
-        .add_resource_path(path::PathBuf::from(RESOURCE_DIR));
-
-    // 构建游戏并运行
-    let (mut ctx, event_loop) = cb.build()?;
-    let state = MainState::new(&mut ctx)?;
-    event::run(ctx, event_loop, state)
-}
\ No newline at end of file
+            .min_dimensions(400.0, 300.0)  // 设置最小窗口尺寸
+            .fullscreen_type(if options.fullscreen {
+                conf::FullscreenType::Desktop
+            } else {
+                conf::FullscreenType::Windowed
+            }));
+
+    // mod 目录先于基础资源挂载，先挂载的路径优先查找
+    let mods = mods::scan(path::Path::new(mods::MODS_DIR));
+    for info in mods.iter().rev() {
+        info!("Loading mod: {}", info.name);
+        cb = cb.add_resource_path(info.dir.clone());
+    }
+    cb = mount_resources(cb, options.resources.as_deref())?;
+
+    // 构建游戏并运行；这时窗口还没有建立，错误界面无法显示，只能在终端说明原因后退出
+    let (ctx, event_loop) = match cb.build() {
+        Ok(built) => built,
+        Err(err) => {
+            error!("Failed to create the game context: {}", err);
+            eprintln!("{}", startup_error(&err));
+            std::process::exit(1);
+        }
+    };
+    if let Err(err) = ctx.gfx.set_window_icon(&ctx.fs, WINDOW_ICON) {
+        warn!("Window icon {} could not be loaded: {}", WINDOW_ICON, err);
+    }
+    let app = App::new(options, mods);
+    event::run(ctx, event_loop, app)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试中不需要贴图
+    fn object(object_type: GameObjectType, x: f32, y: f32, shooter: Option<Shooter>) -> GameObject {
+        let mut object = GameObject::with_image(None, 0.0, x, y, 8.0, 16.0, object_type);
+        object.shooter = shooter;
+        object
+    }
+
+    fn gem_positions(ammo_items: &Arena<GameObject>) -> Vec<Vec2> {
+        ammo_items
+            .iter()
+            .filter(|item| matches!(item.object_type, GameObjectType::ScoreGem))
+            .map(|item| item.pos)
+            .collect()
+    }
+
+    #[test]
+    fn enemy_kill_cancels_nearby_bullets() {
+        let center = Vec2::new(200.0, 200.0);
+        let mut enemy_bullets = vec![
+            object(GameObjectType::EnemyBullet, 210.0, 200.0, Some(Shooter::Boss)),
+            object(GameObjectType::EnemyBullet, 200.0, 180.0, None),
+            object(GameObjectType::EnemyBullet, 200.0 + CANCEL_RADIUS + 10.0, 200.0, Some(Shooter::Boss)),
+            object(GameObjectType::HomingMine, 195.0, 200.0, None),
+        ];
+        let mut ammo_items = Arena::default();
+        let gem = object(GameObjectType::ScoreGem, center.x, center.y, None);
+        MainState::convert_bullets(&mut enemy_bullets, &mut ammo_items, &gem, None, center, CANCEL_RADIUS);
+
+        assert_eq!(gem_positions(&ammo_items), vec![Vec2::new(210.0, 200.0), Vec2::new(200.0, 180.0)]);
+        // 范围外的子弹和地雷保留
+        assert_eq!(enemy_bullets.len(), 2);
+        assert!(enemy_bullets.iter().any(|bullet| matches!(bullet.object_type, GameObjectType::HomingMine)));
+    }
+
+    #[test]
+    fn boss_kill_cancels_only_boss_bullets() {
+        let mut arena = Arena::default();
+        let burst = Shooter::Enemy(arena.insert(()));
+        let mut enemy_bullets = vec![
+            object(GameObjectType::EnemyBullet, 10.0, 10.0, Some(Shooter::Boss)),
+            object(GameObjectType::EnemyBullet, 500.0, 600.0, Some(Shooter::Boss)),
+            object(GameObjectType::EnemyBullet, 300.0, 300.0, Some(burst)),
+        ];
+        let mut ammo_items = Arena::default();
+        let gem = object(GameObjectType::ScoreGem, 0.0, 0.0, None);
+        MainState::convert_bullets(&mut enemy_bullets, &mut ammo_items, &gem, Some(Shooter::Boss), Vec2::ZERO, f32::INFINITY);
+
+        assert_eq!(gem_positions(&ammo_items).len(), 2);
+        assert_eq!(enemy_bullets.len(), 1);
+        assert_eq!(enemy_bullets[0].shooter, Some(burst));
+    }
+}
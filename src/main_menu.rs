@@ -0,0 +1,230 @@
+// main_menu.rs
+// 主菜单：启动和结算后回到这里，开始游戏、生存模式或练习，或打开机库、档案、设置；闲置一段时间后播放演示。
+// 也可以输入别人分享的分享码，重玩同样种子和规则的一局；以 online 特性编译时可以开设或加入联机合作
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::run_code::RunCode;
+use crate::ui::{UiEvent, Widget, WidgetList};
+use crate::{WindowSize, BASE_WINDOW_HEIGHT, BASE_WINDOW_WIDTH};
+
+const IDLE_DEMO_DELAY: f32 = 20.0; // 无操作多少秒后开始演示
+
+// 主菜单按键处理的结果
+pub enum MainMenuEvent {
+    None,
+    Start,
+    Horde, // 开始生存模式
+    Practice, // 打开练习模式设置
+    PlayCode(RunCode), // 用分享码开始
+    Hangar,
+    LevelEditor,
+    Profiles,
+    Settings,
+    #[cfg(feature = "online")]
+    HostCoop,
+    #[cfg(feature = "online")]
+    JoinCoop(String), // 主机地址
+    #[cfg(feature = "online")]
+    CancelCoop,
+    Quit,
+}
+
+pub struct MainMenu {
+    widgets: WidgetList,
+    idle: f32, // 距上次按键的时间（秒）
+    entering: Option<String>, // 正在输入的分享码
+    error: bool,              // 分享码无效
+    address: Option<String>,  // 正在输入的联机主机地址
+    lobby: Option<String>,    // 联机状态提示
+    waiting: bool,            // 正在等待联机对方，Esc 取消
+}
+
+impl MainMenu {
+    pub fn new() -> Self {
+        MainMenu {
+            widgets: WidgetList::new(vec![
+                Widget::button("start", "Start"),
+                Widget::button("horde", "Horde"),
+                Widget::button("practice", "Practice"),
+                Widget::button("code", "Enter Code"),
+                #[cfg(feature = "online")]
+                Widget::button("host", "Host Co-op"),
+                #[cfg(feature = "online")]
+                Widget::button("join", "Join Co-op"),
+                Widget::button("hangar", "Hangar"),
+                Widget::button("editor", "Level Editor"),
+                Widget::button("profiles", "Profiles"),
+                Widget::button("settings", "Settings"),
+                Widget::button("quit", "Quit"),
+            ]),
+            idle: 0.0,
+            entering: None,
+            error: false,
+            address: None,
+            lobby: None,
+            waiting: false,
+        }
+    }
+
+    // 联机会话已开始，等待对方
+    #[cfg_attr(not(feature = "online"), allow(dead_code))]
+    pub fn set_waiting(&mut self, message: String) {
+        self.lobby = Some(message);
+        self.waiting = true;
+    }
+
+    // 联机失败或断开的原因，下次按键时消失
+    #[cfg_attr(not(feature = "online"), allow(dead_code))]
+    pub fn set_notice(&mut self, message: String) {
+        self.lobby = Some(message);
+        self.waiting = false;
+    }
+
+    // 返回是否该开始演示
+    pub fn update(&mut self, dt: f32) -> bool {
+        self.idle += dt;
+        self.idle >= IDLE_DEMO_DELAY && self.entering.is_none() && self.address.is_none() && !self.waiting
+    }
+
+    // 正在输入分享码、主机地址或等待联机时，字母键和 Esc 不应触发其他操作
+    pub fn is_entering_code(&self) -> bool {
+        self.entering.is_some() || self.address.is_some() || self.waiting
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> MainMenuEvent {
+        self.idle = 0.0;
+        #[cfg(feature = "online")]
+        if self.waiting {
+            if key == KeyCode::Escape {
+                self.lobby = None;
+                self.waiting = false;
+                return MainMenuEvent::CancelCoop;
+            }
+            return MainMenuEvent::None;
+        }
+        self.lobby = None;
+        #[cfg(feature = "online")]
+        if let Some(address) = &mut self.address {
+            match key {
+                KeyCode::Back => {
+                    address.pop();
+                }
+                KeyCode::Escape => self.address = None,
+                KeyCode::Return if !address.is_empty() => {
+                    let address = self.address.take().unwrap_or_default();
+                    return MainMenuEvent::JoinCoop(address);
+                }
+                _ => {}
+            }
+            return MainMenuEvent::None;
+        }
+        if let Some(code) = &mut self.entering {
+            match key {
+                KeyCode::Back => {
+                    code.pop();
+                    self.error = false;
+                }
+                KeyCode::Escape => {
+                    self.entering = None;
+                    self.error = false;
+                }
+                KeyCode::Return => match RunCode::decode(code) {
+                    Some(run) => {
+                        self.entering = None;
+                        return MainMenuEvent::PlayCode(run);
+                    }
+                    None => self.error = true,
+                },
+                _ => {}
+            }
+            return MainMenuEvent::None;
+        }
+        match self.widgets.key_down(key) {
+            Some(UiEvent::Activated("start")) => MainMenuEvent::Start,
+            Some(UiEvent::Activated("horde")) => MainMenuEvent::Horde,
+            Some(UiEvent::Activated("practice")) => MainMenuEvent::Practice,
+            Some(UiEvent::Activated("code")) => {
+                self.entering = Some(String::new());
+                MainMenuEvent::None
+            }
+            #[cfg(feature = "online")]
+            Some(UiEvent::Activated("host")) => MainMenuEvent::HostCoop,
+            #[cfg(feature = "online")]
+            Some(UiEvent::Activated("join")) => {
+                self.address = Some(String::new());
+                MainMenuEvent::None
+            }
+            Some(UiEvent::Activated("hangar")) => MainMenuEvent::Hangar,
+            Some(UiEvent::Activated("editor")) => MainMenuEvent::LevelEditor,
+            Some(UiEvent::Activated("profiles")) => MainMenuEvent::Profiles,
+            Some(UiEvent::Activated("settings")) => MainMenuEvent::Settings,
+            Some(UiEvent::Activated("quit")) => MainMenuEvent::Quit,
+            _ => MainMenuEvent::None,
+        }
+    }
+
+    // 输入分享码或主机地址时接收字符
+    pub fn text_input(&mut self, character: char) {
+        if let Some(address) = &mut self.address {
+            if (character.is_ascii_alphanumeric() || matches!(character, '.' | ':' | '-')) && address.len() < 64 {
+                address.push(character);
+            }
+            return;
+        }
+        if let Some(code) = &mut self.entering {
+            if (character.is_ascii_alphanumeric() || character == '-') && code.len() < 24 {
+                code.push(character.to_ascii_uppercase());
+                self.error = false;
+            }
+        }
+    }
+
+    // best 为当前模式的最高分与留名
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize, best: &str) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let left = BASE_WINDOW_WIDTH / 2.0 - 120.0;
+
+        // 遮住背后的游戏画面
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .scale(window_size.scale_vec2(Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT)))
+                .color(Color::new(0.0, 0.0, 0.0, 0.85)),
+        );
+        canvas.draw(
+            &graphics::Text::new("VERTICAL SHOOTER"),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left - 60.0, 100.0)))
+                .color(Color::YELLOW)
+                .scale(scale * 2.5),
+        );
+        canvas.draw(
+            &graphics::Text::new(best),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 170.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+
+        self.widgets.draw(canvas, window_size, Vec2::new(left, 230.0));
+        let hint = match (&self.entering, &self.address, &self.lobby) {
+            (Some(code), _, _) => {
+                let error = if self.error { "Invalid code\n" } else { "" };
+                format!("Code: {}_\n{}Type a share code   Enter: play   Esc: cancel", code, error)
+            }
+            (_, Some(address), _) => format!("Host: {}_\nType host[:port]   Enter: join   Esc: cancel", address),
+            (_, _, Some(lobby)) if self.waiting => format!("{}\nEsc: cancel", lobby),
+            (_, _, Some(lobby)) => lobby.clone(),
+            _ => "Up/Down: select   Enter: choose".to_string(),
+        };
+        canvas.draw(
+            &graphics::Text::new(hint),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 230.0 + self.widgets.widgets.len() as f32 * 34.0 + 20.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+}
@@ -0,0 +1,60 @@
+// mods.rs
+// Mod 加载：mods/<name>/ 下的文件按与 resources 相同的目录结构覆盖基础资源
+// （图片、音效、scripts/ 下的波次脚本），enemies.txt 用于修改敌人定义。
+// 多个 mod 按目录名排序加载，排在后面的 mod 覆盖前面的。
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::warn;
+
+use crate::enemies::EnemyTable;
+
+pub const MODS_DIR: &str = "mods";
+const ENEMY_FILE: &str = "enemies.txt";
+
+#[derive(Clone, Debug)]
+pub struct ModInfo {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+// 扫描 mods 目录下的所有子目录；目录不存在时返回空列表
+pub fn scan(root: &Path) -> Vec<ModInfo> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut mods: Vec<ModInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| ModInfo {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            dir: entry.path(),
+        })
+        .collect();
+    mods.sort_by(|a, b| a.name.cmp(&b.name));
+    mods
+}
+
+// 依次叠加各 mod 的敌人定义覆盖
+pub fn load_enemy_table(mods: &[ModInfo]) -> EnemyTable {
+    let mut table = EnemyTable::default();
+    for info in mods {
+        let path = info.dir.join(ENEMY_FILE);
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for err in table.apply_overrides(&source) {
+            warn!("Mod '{}' {}: {}", info.name, ENEMY_FILE, err);
+        }
+    }
+    table
+}
+
+// 标题/暂停界面上显示的 mod 列表
+pub fn describe(mods: &[ModInfo]) -> Option<String> {
+    if mods.is_empty() {
+        return None;
+    }
+    let names: Vec<&str> = mods.iter().map(|info| info.name.as_str()).collect();
+    Some(format!("Mods: {}", names.join(", ")))
+}
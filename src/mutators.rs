@@ -0,0 +1,116 @@
+// mutators.rs
+// 挑战规则（mutator）：开局前选择，调整游戏参数并按难度给出分数倍率
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mutator {
+    DoubleSpeed, // 敌人速度翻倍
+    NoMissiles,  // 没有追踪导弹
+    OneHitKills, // 装甲失效、不能续关，一击定胜负
+    TinyShip,    // 玩家飞船缩小
+    Ricochet,    // 玩家子弹在屏幕左右边缘反弹一次
+}
+
+impl Mutator {
+    pub const ALL: [Mutator; 5] = [
+        Mutator::DoubleSpeed,
+        Mutator::NoMissiles,
+        Mutator::OneHitKills,
+        Mutator::TinyShip,
+        Mutator::Ricochet,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Mutator::DoubleSpeed => "double_speed",
+            Mutator::NoMissiles => "no_missiles",
+            Mutator::OneHitKills => "one_hit_kills",
+            Mutator::TinyShip => "tiny_ship",
+            Mutator::Ricochet => "ricochet",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Mutator> {
+        Mutator::ALL.iter().copied().find(|mutator| mutator.id() == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Mutator::DoubleSpeed => "2x Enemy Speed",
+            Mutator::NoMissiles => "No Missiles",
+            Mutator::OneHitKills => "One-Hit Kills",
+            Mutator::TinyShip => "Tiny Ship",
+            Mutator::Ricochet => "Ricochet Shots",
+        }
+    }
+
+    // 让游戏更难的规则倍率大于 1，更容易的小于 1
+    pub fn score_multiplier(self) -> f32 {
+        match self {
+            Mutator::DoubleSpeed => 1.5,
+            Mutator::NoMissiles => 1.25,
+            Mutator::OneHitKills => 1.1,
+            Mutator::TinyShip => 0.8,
+            Mutator::Ricochet => 0.9,
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << Mutator::ALL.iter().position(|mutator| *mutator == self).unwrap_or(0)
+    }
+}
+
+// 一组启用的规则
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Mutators(u8);
+
+impl Mutators {
+    // 低位依次对应 Mutator::ALL，多余的位被忽略
+    pub fn from_bits(bits: u8) -> Self {
+        Mutators(bits & ((1 << Mutator::ALL.len()) - 1))
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn contains(self, mutator: Mutator) -> bool {
+        self.0 & mutator.bit() != 0
+    }
+
+    pub fn set(&mut self, mutator: Mutator, enabled: bool) {
+        if enabled {
+            self.0 |= mutator.bit();
+        } else {
+            self.0 &= !mutator.bit();
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Mutator> {
+        Mutator::ALL.into_iter().filter(move |mutator| self.contains(*mutator))
+    }
+
+    pub fn score_multiplier(self) -> f32 {
+        self.iter().map(Mutator::score_multiplier).product()
+    }
+
+    // 空格分隔的 id 列表，用于保存到文件；无法识别的 id 被忽略
+    pub fn to_ids(self) -> String {
+        self.iter().map(Mutator::id).collect::<Vec<_>>().join(" ")
+    }
+
+    pub fn parse(ids: &str) -> Self {
+        let mut mutators = Mutators::default();
+        for mutator in ids.split_whitespace().filter_map(Mutator::from_id) {
+            mutators.set(mutator, true);
+        }
+        mutators
+    }
+
+    // HUD 上显示的名称列表
+    pub fn describe(self) -> String {
+        self.iter().map(Mutator::name).collect::<Vec<_>>().join(", ")
+    }
+}
@@ -0,0 +1,434 @@
+// net.rs
+// 联机合作（实验性，需以 online 特性编译）：两名玩家通过 UDP 锁步同步。双方每个 tick 只交换操作输入，
+// 两端用主机决定的种子和规则、同样的输入各自推进同样的模拟；本地输入延后几个 tick 生效以掩盖网络延迟，
+// 某个 tick 缺少对方输入时暂停推进等待。定期比较状态校验值，不一致时提示两端已经不同步。
+// 没有使用 laminar、renet 这类带可靠通道的网络库：锁步只需要每个 tick 几个字节的输入，
+// 每个输入包附带最近若干 tick 的输入，丢包由后续的包补上，按 tick 编号存放，乱序和重复都不影响；
+// 握手和心跳定期重发即可。可靠通道的重传反而会让一次丢包卡住后面所有的输入
+use crate::boundary::BoundaryPolicy;
+use crate::config::Difficulty;
+use crate::mutators::Mutators;
+
+// 一局联机合作的规则，由主机决定并在握手时发给加入方
+#[cfg_attr(not(feature = "online"), allow(dead_code))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CoopRules {
+    pub seed: u64,
+    pub mutators: Mutators,
+    pub difficulty: Difficulty,
+    pub boundary: BoundaryPolicy,
+    pub classic_movement: bool,
+}
+
+#[cfg(feature = "online")]
+pub use session::{Role, Session, SessionStatus, DEFAULT_PORT};
+
+#[cfg(feature = "online")]
+mod session {
+    use std::collections::BTreeMap;
+    use std::io;
+    use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+    use std::time::Instant;
+
+    use super::CoopRules;
+    use crate::boundary::BoundaryPolicy;
+    use crate::config::Difficulty;
+    use crate::input::TickInput;
+    use crate::mutators::Mutators;
+
+    pub const DEFAULT_PORT: u16 = 7777;
+    const PROTOCOL: u8 = 2;            // 协议版本，不同版本的游戏不能联机
+    const INPUT_DELAY: u32 = 3;        // 本地输入延后几个 tick 生效，留出网络传输的时间
+    const REDUNDANT_INPUTS: u32 = 16;  // 每个输入包附带最近多少个 tick 的输入，丢包时不必重传
+    const CHECKSUM_INTERVAL: u32 = 60; // 每隔多少 tick 比较一次状态校验值
+    const MAX_LEAD: u32 = 4 * INPUT_DELAY; // 对方的包最多领先本地多少 tick（正常不超过 2 * INPUT_DELAY），超出的包丢弃
+    const HELLO_INTERVAL: f32 = 0.5;   // 加入方重发握手的间隔（秒）
+    const PING_INTERVAL: f32 = 0.5;    // 测量往返延迟的间隔（秒）
+    const TIMEOUT: f32 = 5.0;          // 多久收不到对方的包算作断线（秒）
+    const MAX_PACKET: usize = 256;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Role {
+        Host,  // 操作一号机，决定本局规则
+        Guest, // 操作二号机
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum SessionStatus {
+        Waiting,        // 主机等待加入方，或加入方等待主机回应
+        Running,
+        Closed(String), // 连接结束的原因
+    }
+
+    enum Message {
+        Hello { protocol: u8 },
+        Welcome { protocol: u8, rules: CoopRules },
+        Input { tick: u32, inputs: Vec<TickInput> }, // 截至 tick（含）的连续输入
+        Checksum { tick: u32, value: u64 },
+        Ping { id: u32 },
+        Pong { id: u32 },
+        Bye,
+    }
+
+    impl Message {
+        fn encode(&self) -> Vec<u8> {
+            let mut bytes = Vec::new();
+            match self {
+                Message::Hello { protocol } => bytes.extend([0, *protocol]),
+                Message::Welcome { protocol, rules } => {
+                    bytes.extend([1, *protocol]);
+                    bytes.extend(rules.seed.to_le_bytes());
+                    bytes.push(rules.mutators.bits());
+                    bytes.push(Difficulty::ALL.iter().position(|difficulty| *difficulty == rules.difficulty).unwrap_or(0) as u8);
+                    bytes.push(BoundaryPolicy::ALL.iter().position(|policy| *policy == rules.boundary).unwrap_or(0) as u8);
+                    bytes.push(rules.classic_movement as u8);
+                }
+                Message::Input { tick, inputs } => {
+                    bytes.push(2);
+                    bytes.extend(tick.to_le_bytes());
+                    bytes.push(inputs.len() as u8);
+                    bytes.extend(inputs.iter().map(|input| input.bits()));
+                }
+                Message::Checksum { tick, value } => {
+                    bytes.push(3);
+                    bytes.extend(tick.to_le_bytes());
+                    bytes.extend(value.to_le_bytes());
+                }
+                Message::Ping { id } => {
+                    bytes.push(4);
+                    bytes.extend(id.to_le_bytes());
+                }
+                Message::Pong { id } => {
+                    bytes.push(5);
+                    bytes.extend(id.to_le_bytes());
+                }
+                Message::Bye => bytes.push(6),
+            }
+            bytes
+        }
+
+        // 格式不对的包返回 None，直接丢弃
+        fn decode(bytes: &[u8]) -> Option<Message> {
+            let (tag, body) = bytes.split_first()?;
+            let u32_at = |at: usize| Some(u32::from_le_bytes(body.get(at..at + 4)?.try_into().ok()?));
+            let u64_at = |at: usize| Some(u64::from_le_bytes(body.get(at..at + 8)?.try_into().ok()?));
+            match tag {
+                0 => Some(Message::Hello { protocol: *body.first()? }),
+                1 => Some(Message::Welcome {
+                    protocol: *body.first()?,
+                    rules: CoopRules {
+                        seed: u64_at(1)?,
+                        mutators: Mutators::from_bits(*body.get(9)?),
+                        difficulty: *Difficulty::ALL.get(*body.get(10)? as usize)?,
+                        boundary: *BoundaryPolicy::ALL.get(*body.get(11)? as usize)?,
+                        classic_movement: *body.get(12)? != 0,
+                    },
+                }),
+                2 => {
+                    let count = *body.get(4)? as usize;
+                    let inputs = body.get(5..5 + count)?.iter().map(|bits| TickInput::from_bits(*bits)).collect();
+                    Some(Message::Input { tick: u32_at(0)?, inputs })
+                }
+                3 => Some(Message::Checksum { tick: u32_at(0)?, value: u64_at(4)? }),
+                4 => Some(Message::Ping { id: u32_at(0)? }),
+                5 => Some(Message::Pong { id: u32_at(0)? }),
+                6 => Some(Message::Bye),
+                _ => None,
+            }
+        }
+    }
+
+    pub struct Session {
+        socket: UdpSocket,
+        role: Role,
+        peer: Option<SocketAddr>,
+        status: SessionStatus,
+        rules: Option<CoopRules>,
+        started: bool,                        // 开局已交给游戏
+        tick: u32,                            // 下一个要模拟的 tick
+        local: BTreeMap<u32, TickInput>,      // 本地输入（按生效的 tick）
+        remote: BTreeMap<u32, TickInput>,     // 对方的输入
+        checksums: BTreeMap<u32, u64>,        // 本地尚未与对方比较的校验值
+        remote_checksums: BTreeMap<u32, u64>, // 对方发来、本地还没算到的校验值
+        desync: bool,
+        rtt: Option<f32>,                     // 平滑后的往返延迟（秒）
+        ping: Option<(u32, Instant)>,         // 等待回应的 ping
+        next_ping: u32,
+        ping_timer: f32,
+        silence: f32,                         // 距上次收到对方的包（秒）
+        hello_timer: f32,
+    }
+
+    impl Session {
+        // 在 port 上等待加入方，rules 为本局规则
+        pub fn host(port: u16, rules: CoopRules) -> io::Result<Self> {
+            let socket = UdpSocket::bind(("0.0.0.0", port))?;
+            log::info!("Hosting co-op on port {}", port);
+            Self::new(socket, Role::Host, None, Some(rules))
+        }
+
+        // address 为 "主机:端口"，省略端口时使用默认端口
+        pub fn join(address: &str) -> io::Result<Self> {
+            let address = if address.contains(':') { address.to_string() } else { format!("{}:{}", address, DEFAULT_PORT) };
+            let peer = address
+                .to_socket_addrs()?
+                .find(SocketAddr::is_ipv4)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("No IPv4 address for {}", address)))?;
+            let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+            log::info!("Joining co-op at {}", peer);
+            Self::new(socket, Role::Guest, Some(peer), None)
+        }
+
+        fn new(socket: UdpSocket, role: Role, peer: Option<SocketAddr>, rules: Option<CoopRules>) -> io::Result<Self> {
+            socket.set_nonblocking(true)?;
+            // 最初几个 tick 双方都没有输入，按空输入推进
+            let idle: BTreeMap<u32, TickInput> = (0..INPUT_DELAY).map(|tick| (tick, TickInput::default())).collect();
+            Ok(Session {
+                socket,
+                role,
+                peer,
+                status: SessionStatus::Waiting,
+                rules,
+                started: false,
+                tick: 0,
+                local: idle.clone(),
+                remote: idle,
+                checksums: BTreeMap::new(),
+                remote_checksums: BTreeMap::new(),
+                desync: false,
+                rtt: None,
+                ping: None,
+                next_ping: 0,
+                ping_timer: 0.0,
+                silence: 0.0,
+                hello_timer: 0.0,
+            })
+        }
+
+        pub fn role(&self) -> Role {
+            self.role
+        }
+
+        pub fn status(&self) -> &SessionStatus {
+            &self.status
+        }
+
+        pub fn latency_ms(&self) -> Option<u32> {
+            self.rtt.map(|rtt| (rtt * 1000.0).round() as u32)
+        }
+
+        pub fn is_desynced(&self) -> bool {
+            self.desync
+        }
+
+        // 连接建立后返回一次本局规则，游戏据此开局
+        pub fn take_start(&mut self) -> Option<CoopRules> {
+            if self.started || self.status != SessionStatus::Running {
+                return None;
+            }
+            self.started = true;
+            self.rules
+        }
+
+        // 收取所有到达的包，处理握手、延迟测量和超时
+        pub fn poll(&mut self, dt: f32) {
+            let mut buffer = [0; MAX_PACKET];
+            loop {
+                match self.socket.recv_from(&mut buffer) {
+                    Ok((len, from)) => {
+                        if let Some(message) = Message::decode(&buffer[..len]) {
+                            self.receive(message, from);
+                        }
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                    // Windows 上对方端口不可达会以错误的形式返回，忽略即可，由超时判断断线
+                    Err(err) => {
+                        log::debug!("Co-op receive failed: {}", err);
+                        break;
+                    }
+                }
+            }
+            if matches!(self.status, SessionStatus::Closed(_)) {
+                return;
+            }
+
+            match (self.role, &self.status) {
+                (Role::Guest, SessionStatus::Waiting) => {
+                    self.hello_timer -= dt;
+                    if self.hello_timer <= 0.0 {
+                        self.hello_timer = HELLO_INTERVAL;
+                        self.send(&Message::Hello { protocol: PROTOCOL });
+                    }
+                }
+                (_, SessionStatus::Running) => {
+                    self.ping_timer -= dt;
+                    if self.ping_timer <= 0.0 {
+                        self.ping_timer = PING_INTERVAL;
+                        let id = self.next_ping;
+                        self.next_ping = id.wrapping_add(1);
+                        self.ping = Some((id, Instant::now()));
+                        self.send(&Message::Ping { id });
+                    }
+                }
+                _ => {}
+            }
+            // 主机等待加入方时不会超时
+            if self.peer.is_some() {
+                self.silence += dt;
+                if self.silence > TIMEOUT {
+                    let reason = match self.status {
+                        SessionStatus::Waiting => "No response from host",
+                        _ => "Connection lost",
+                    };
+                    log::warn!("Co-op session closed: {}", reason);
+                    self.status = SessionStatus::Closed(reason.to_string());
+                }
+            }
+        }
+
+        fn receive(&mut self, message: Message, from: SocketAddr) {
+            // 主机收到第一个握手后只和这个地址通信
+            match (self.role, self.peer, &message) {
+                (Role::Host, None, Message::Hello { protocol }) if *protocol == PROTOCOL => self.peer = Some(from),
+                (Role::Host, None, Message::Hello { protocol }) => {
+                    log::warn!("Ignoring co-op player at {} with protocol {} (expected {})", from, protocol, PROTOCOL);
+                    return;
+                }
+                (_, Some(peer), _) if peer == from => {}
+                _ => return,
+            }
+            self.silence = 0.0;
+            match message {
+                // 欢迎包可能丢失，加入方会重发握手，每次都回应
+                Message::Hello { .. } => {
+                    if let Some(rules) = self.rules {
+                        self.send(&Message::Welcome { protocol: PROTOCOL, rules });
+                    }
+                    if self.status == SessionStatus::Waiting {
+                        log::info!("Co-op player joined from {}", from);
+                        self.status = SessionStatus::Running;
+                    }
+                }
+                Message::Welcome { protocol, rules } if self.role == Role::Guest && self.status == SessionStatus::Waiting => {
+                    if protocol != PROTOCOL {
+                        self.status = SessionStatus::Closed("Host runs a different game version".to_string());
+                        return;
+                    }
+                    log::info!("Joined co-op with seed {}", rules.seed);
+                    self.rules = Some(rules);
+                    self.status = SessionStatus::Running;
+                }
+                Message::Welcome { .. } => {}
+                // tick 来自网络，不可信：远超本地进度的包不是正常的对方发来的，丢弃以免越界或无限积累
+                Message::Input { tick, .. } | Message::Checksum { tick, .. } if tick > self.tick.saturating_add(MAX_LEAD) => {
+                    log::debug!("Ignoring co-op packet for tick {} (local tick {})", tick, self.tick);
+                }
+                Message::Input { tick, inputs } => {
+                    let first = tick.saturating_add(1).saturating_sub(inputs.len() as u32);
+                    for (offset, input) in inputs.into_iter().enumerate() {
+                        let at = first + offset as u32;
+                        if at >= self.tick {
+                            self.remote.entry(at).or_insert(input);
+                        }
+                    }
+                }
+                // 本地已经算过却没有记录的 tick 永远比较不上，不必保存
+                Message::Checksum { tick, value } => {
+                    if tick >= self.tick || self.checksums.contains_key(&tick) {
+                        self.remote_checksums.insert(tick, value);
+                        self.compare_checksums();
+                    }
+                }
+                Message::Ping { id } => self.send(&Message::Pong { id }),
+                Message::Pong { id } => {
+                    if let Some((_, sent)) = self.ping.filter(|(sent_id, _)| *sent_id == id) {
+                        let sample = sent.elapsed().as_secs_f32();
+                        self.rtt = Some(self.rtt.map_or(sample, |rtt| rtt + (sample - rtt) * 0.2));
+                        self.ping = None;
+                    }
+                }
+                Message::Bye => {
+                    log::info!("Co-op partner left");
+                    self.status = SessionStatus::Closed("Partner left".to_string());
+                }
+            }
+        }
+
+        fn send(&self, message: &Message) {
+            if let Some(peer) = self.peer {
+                if let Err(err) = self.socket.send_to(&message.encode(), peer) {
+                    log::debug!("Co-op send failed: {}", err);
+                }
+            }
+        }
+
+        // 下一个 tick 推进前，本地输入还没有记录时需要读取一次
+        pub fn needs_local(&self) -> bool {
+            !self.local.contains_key(&(self.tick + INPUT_DELAY))
+        }
+
+        // 记录本地输入并连同最近的输入一起发给对方
+        pub fn push_local(&mut self, input: TickInput) {
+            let tick = self.tick + INPUT_DELAY;
+            self.local.insert(tick, input);
+            self.send_inputs(tick);
+        }
+
+        fn send_inputs(&self, tick: u32) {
+            let inputs = self.local.range(tick.saturating_sub(REDUNDANT_INPUTS - 1)..=tick).map(|(_, input)| *input).collect();
+            self.send(&Message::Input { tick, inputs });
+        }
+
+        // 当前 tick 双方的输入（一号机、二号机）；对方的输入还没到时返回 None
+        pub fn inputs(&self) -> Option<(TickInput, TickInput)> {
+            let local = *self.local.get(&self.tick)?;
+            let remote = *self.remote.get(&self.tick)?;
+            Some(match self.role {
+                Role::Host => (local, remote),
+                Role::Guest => (remote, local),
+            })
+        }
+
+        // 正在等待对方当前 tick 的输入
+        pub fn is_waiting(&self) -> bool {
+            self.status == SessionStatus::Running && !self.remote.contains_key(&self.tick)
+        }
+
+        // 当前 tick 模拟完毕，state 为模拟后的状态校验值
+        pub fn advance(&mut self, state: u64) {
+            if self.tick.is_multiple_of(CHECKSUM_INTERVAL) {
+                self.checksums.insert(self.tick, state);
+                self.send(&Message::Checksum { tick: self.tick, value: state });
+                self.compare_checksums();
+            }
+            self.tick += 1;
+            // 只保留还要重发的本地输入和尚未用到的对方输入
+            let keep = (self.tick + INPUT_DELAY).saturating_sub(REDUNDANT_INPUTS);
+            self.local = self.local.split_off(&keep);
+            self.remote = self.remote.split_off(&self.tick);
+        }
+
+        fn compare_checksums(&mut self) {
+            let matched: Vec<u32> = self.checksums.keys().copied().filter(|tick| self.remote_checksums.contains_key(tick)).collect();
+            for tick in matched {
+                let (local, remote) = (self.checksums.remove(&tick), self.remote_checksums.remove(&tick));
+                if local != remote && !self.desync {
+                    log::error!("Co-op desync detected at tick {}", tick);
+                    self.desync = true;
+                }
+            }
+        }
+    }
+
+    // 离开时通知对方，并把最后的输入再发一次，对方还差几个 tick 时不必等到超时
+    impl Drop for Session {
+        fn drop(&mut self) {
+            if self.status == SessionStatus::Running {
+                if let Some((&tick, _)) = self.local.last_key_value() {
+                    self.send_inputs(tick);
+                }
+            }
+            self.send(&Message::Bye);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+// palette.rs
+// 调色板：子弹、道具和 HUD 的颜色统一在这里查找，提供适合色觉障碍玩家的配色
+use ggez::graphics::Color;
+
+// 按用途区分的颜色
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Swatch {
+    PlayerShot,
+    SpreadShot,
+    DroneShot,
+    EnemyShot,
+    EnemyMine,
+    BossShot,
+    PickupMissile,
+    PickupSpread,
+    PickupDrone,
+    PickupEmp,
+    BonusStar, // 连锁奖励星和它的得分文字
+    ScoreGem,  // 击毁敌人时附近敌弹变成的得分宝石
+    RarityCommon,
+    RarityUncommon,
+    RarityRare,
+    Hostile,  // 敌人指示和雷达光点
+    Incoming, // 即将进入屏幕的敌方子弹
+    Friendly, // 雷达上的玩家
+    Boss,
+    HudUpgrade,
+    HudGraze,
+    HudMutators,
+    HudInfo,
+    HudAlert, // 游戏结束
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Palette {
+    Standard,
+    Deuteranopia, // 绿色弱
+    Protanopia,   // 红色弱
+    Tritanopia,   // 蓝黄色弱
+}
+
+const fn rgb(r: f32, g: f32, b: f32) -> Color {
+    Color::new(r, g, b, 1.0)
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [Palette::Standard, Palette::Deuteranopia, Palette::Protanopia, Palette::Tritanopia];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Standard => "Standard",
+            Palette::Deuteranopia => "Deuteranopia",
+            Palette::Protanopia => "Protanopia",
+            Palette::Tritanopia => "Tritanopia",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Palette> {
+        Palette::ALL.iter().copied().find(|palette| palette.name() == name)
+    }
+
+    pub fn color(self, swatch: Swatch) -> Color {
+        match self {
+            Palette::Standard => standard(swatch),
+            Palette::Deuteranopia => red_green(swatch, 0.0),
+            // 红色弱对红光不敏感，敌方颜色再提亮一些
+            Palette::Protanopia => red_green(swatch, 0.15),
+            Palette::Tritanopia => tritan(swatch),
+        }
+    }
+}
+
+fn standard(swatch: Swatch) -> Color {
+    match swatch {
+        Swatch::PlayerShot => rgb(1.0, 1.0, 0.0),
+        Swatch::SpreadShot => rgb(1.0, 0.5, 0.0),
+        Swatch::DroneShot => rgb(0.5, 0.8, 1.0),
+        Swatch::EnemyShot => rgb(1.0, 0.3, 0.3),
+        Swatch::EnemyMine => rgb(1.0, 0.4, 0.4),
+        Swatch::BossShot => rgb(1.0, 0.5, 0.9),
+        Swatch::PickupMissile => rgb(0.3, 1.0, 1.0),
+        Swatch::PickupSpread => rgb(1.0, 0.6, 0.2),
+        Swatch::PickupDrone => rgb(0.5, 0.8, 1.0),
+        Swatch::PickupEmp => rgb(1.0, 1.0, 0.5),
+        Swatch::BonusStar => rgb(1.0, 0.85, 0.2),
+        Swatch::ScoreGem => rgb(0.4, 1.0, 0.8),
+        Swatch::RarityCommon => Color::new(0.8, 0.8, 0.8, 0.35),
+        Swatch::RarityUncommon => Color::new(0.2, 0.6, 1.0, 0.45),
+        Swatch::RarityRare => Color::new(1.0, 0.8, 0.1, 0.55),
+        Swatch::Hostile => rgb(1.0, 0.3, 0.3),
+        Swatch::Incoming => rgb(1.0, 0.7, 0.2),
+        Swatch::Friendly => rgb(0.3, 1.0, 0.3),
+        Swatch::Boss => rgb(1.0, 0.2, 0.8),
+        Swatch::HudUpgrade => rgb(0.6, 1.0, 0.6),
+        Swatch::HudGraze => rgb(1.0, 0.95, 0.6),
+        Swatch::HudMutators => rgb(1.0, 0.6, 0.9),
+        Swatch::HudInfo => rgb(0.6, 0.9, 1.0),
+        Swatch::HudAlert => Color::RED,
+    }
+}
+
+// 红绿色弱：敌我用橙色和蓝色区分，避免红绿对比
+fn red_green(swatch: Swatch, hostile_boost: f32) -> Color {
+    let hostile = |r: f32, g: f32, b: f32| rgb(r, (g + hostile_boost).min(1.0), (b + hostile_boost * 0.5).min(1.0));
+    match swatch {
+        Swatch::PlayerShot => rgb(0.95, 0.9, 0.25),
+        Swatch::SpreadShot => rgb(0.9, 0.62, 0.0),
+        Swatch::DroneShot => rgb(0.35, 0.7, 0.95),
+        Swatch::EnemyShot => hostile(1.0, 0.4, 0.0),
+        Swatch::EnemyMine => hostile(1.0, 0.5, 0.1),
+        Swatch::BossShot => hostile(0.9, 0.45, 0.75),
+        Swatch::PickupMissile => rgb(0.2, 0.85, 0.7),
+        Swatch::PickupSpread => rgb(0.95, 0.7, 0.1),
+        Swatch::PickupDrone => rgb(0.35, 0.6, 1.0),
+        Swatch::PickupEmp => rgb(0.95, 0.95, 0.6),
+        Swatch::BonusStar => rgb(1.0, 0.85, 0.1),
+        Swatch::ScoreGem => rgb(0.4, 0.8, 1.0),
+        Swatch::RarityCommon => Color::new(0.8, 0.8, 0.8, 0.35),
+        Swatch::RarityUncommon => Color::new(0.0, 0.45, 0.9, 0.45),
+        Swatch::RarityRare => Color::new(0.95, 0.8, 0.1, 0.55),
+        Swatch::Hostile => hostile(1.0, 0.45, 0.0),
+        Swatch::Incoming => rgb(0.95, 0.85, 0.3),
+        Swatch::Friendly => rgb(0.3, 0.6, 1.0),
+        Swatch::Boss => hostile(0.85, 0.45, 0.9),
+        Swatch::HudUpgrade => rgb(0.5, 0.75, 1.0),
+        Swatch::HudGraze => rgb(1.0, 0.95, 0.6),
+        Swatch::HudMutators => rgb(0.85, 0.6, 0.95),
+        Swatch::HudInfo => rgb(0.6, 0.9, 1.0),
+        Swatch::HudAlert => hostile(1.0, 0.45, 0.0),
+    }
+}
+
+// 蓝黄色弱：避免蓝黄对比，改用红粉与青色区分
+fn tritan(swatch: Swatch) -> Color {
+    match swatch {
+        Swatch::PlayerShot => rgb(1.0, 0.75, 0.8),
+        Swatch::SpreadShot => rgb(1.0, 0.45, 0.45),
+        Swatch::DroneShot => rgb(0.3, 0.9, 0.9),
+        Swatch::EnemyShot => rgb(1.0, 0.2, 0.3),
+        Swatch::EnemyMine => rgb(1.0, 0.3, 0.4),
+        Swatch::BossShot => rgb(1.0, 0.4, 0.6),
+        Swatch::PickupMissile => rgb(0.3, 0.95, 0.9),
+        Swatch::PickupSpread => rgb(1.0, 0.45, 0.45),
+        Swatch::PickupDrone => rgb(0.55, 0.8, 0.8),
+        Swatch::PickupEmp => rgb(1.0, 0.75, 0.85),
+        Swatch::BonusStar => rgb(1.0, 0.55, 0.75),
+        Swatch::ScoreGem => rgb(0.3, 0.95, 0.95),
+        Swatch::RarityCommon => Color::new(0.8, 0.8, 0.8, 0.35),
+        Swatch::RarityUncommon => Color::new(0.1, 0.8, 0.8, 0.45),
+        Swatch::RarityRare => Color::new(1.0, 0.3, 0.5, 0.55),
+        Swatch::Hostile => rgb(1.0, 0.25, 0.3),
+        Swatch::Incoming => rgb(1.0, 0.6, 0.7),
+        Swatch::Friendly => rgb(0.3, 0.9, 0.9),
+        Swatch::Boss => rgb(1.0, 0.2, 0.6),
+        Swatch::HudUpgrade => rgb(0.6, 0.95, 0.95),
+        Swatch::HudGraze => rgb(1.0, 0.8, 0.85),
+        Swatch::HudMutators => rgb(1.0, 0.6, 0.8),
+        Swatch::HudInfo => rgb(0.6, 0.95, 0.95),
+        Swatch::HudAlert => Color::RED,
+    }
+}
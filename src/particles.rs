@@ -0,0 +1,290 @@
+// particles.rs
+// 粒子系统：所有粒子按混合模式写入 InstanceArray，每帧只需两次绘制调用
+use ggez::graphics::{BlendMode, Canvas, Color, DrawParam, InstanceArray};
+use glam::Vec2;
+use rand::Rng;
+
+use crate::{WindowSize, BASE_WINDOW_HEIGHT, BASE_WINDOW_WIDTH};
+
+pub const MAX_PARTICLES: usize = 2000;
+
+// 发射器类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmitterKind {
+    Explosion, // 爆炸：全方向迸散
+    Trail,     // 尾迹：沿反方向少量喷出
+    Ambient,   // 环境：缓慢漂浮的光点
+}
+
+// 随生命周期变化的颜色渐变
+#[derive(Clone, Copy, Debug)]
+pub struct ColorGradient {
+    pub start: Color,
+    pub end: Color,
+}
+
+impl ColorGradient {
+    pub fn new(start: Color, end: Color) -> Self {
+        ColorGradient { start, end }
+    }
+
+    // 从指定颜色渐隐到透明
+    pub fn fade(color: Color) -> Self {
+        ColorGradient::new(color, Color::new(color.r, color.g, color.b, 0.0))
+    }
+
+    // t 为生命周期进度，0 为刚出生，1 为消亡
+    pub fn sample(&self, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        Color::new(
+            self.start.r + (self.end.r - self.start.r) * t,
+            self.start.g + (self.end.g - self.start.g) * t,
+            self.start.b + (self.end.b - self.start.b) * t,
+            self.start.a + (self.end.a - self.start.a) * t,
+        )
+    }
+}
+
+// 发射器参数
+#[derive(Clone, Copy, Debug)]
+pub struct EmitterConfig {
+    pub count: usize,
+    pub lifetime: f32, // 秒
+    pub speed: f32,    // 基准坐标下每秒像素
+    pub size: f32,     // 基准坐标下像素
+    pub spread: f32,   // 发射角度范围（弧度）
+    pub additive: bool,
+}
+
+impl EmitterKind {
+    pub fn config(self) -> EmitterConfig {
+        match self {
+            EmitterKind::Explosion => EmitterConfig {
+                count: 10,
+                lifetime: 0.5,
+                speed: 50.0,
+                size: 2.0,
+                spread: std::f32::consts::TAU,
+                additive: true,
+            },
+            EmitterKind::Trail => EmitterConfig {
+                count: 1,
+                lifetime: 0.3,
+                speed: 20.0,
+                size: 1.5,
+                spread: 0.6,
+                additive: true,
+            },
+            EmitterKind::Ambient => EmitterConfig {
+                count: 1,
+                lifetime: 1.2,
+                speed: 8.0,
+                size: 1.5,
+                spread: std::f32::consts::TAU,
+                additive: false,
+            },
+        }
+    }
+}
+
+// 命名的环境效果：不跟随任何对象，开启后在整个屏幕范围内持续生成粒子
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmbientEffect {
+    Dust,        // 缓慢飘落的尘埃
+    WarpStreaks, // 高速掠过的跃迁光条（Boss 登场）
+    DebrisRain,  // 残骸碎片雨（Boss 被击毁后）
+}
+
+// 名称只供脚本使用，未启用 rhai 特性时不会用到
+#[cfg_attr(not(feature = "rhai"), allow(dead_code))]
+impl AmbientEffect {
+    pub const ALL: [AmbientEffect; 3] = [AmbientEffect::Dust, AmbientEffect::WarpStreaks, AmbientEffect::DebrisRain];
+
+    // 脚本中使用的名称
+    pub fn id(self) -> &'static str {
+        match self {
+            AmbientEffect::Dust => "dust",
+            AmbientEffect::WarpStreaks => "warp",
+            AmbientEffect::DebrisRain => "debris",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<AmbientEffect> {
+        AmbientEffect::ALL.iter().copied().find(|effect| effect.id() == id)
+    }
+}
+
+impl AmbientEffect {
+    // 每秒生成的粒子数
+    fn rate(self) -> f32 {
+        match self {
+            AmbientEffect::Dust => 6.0,
+            AmbientEffect::WarpStreaks => 40.0,
+            AmbientEffect::DebrisRain => 25.0,
+        }
+    }
+
+    fn spawn(self, rng: &mut impl Rng) -> Particle {
+        let x = rng.gen_range(0.0..BASE_WINDOW_WIDTH);
+        match self {
+            AmbientEffect::Dust => Particle {
+                pos: Vec2::new(x, rng.gen_range(0.0..BASE_WINDOW_HEIGHT)),
+                vel: Vec2::new(rng.gen_range(-8.0..8.0), rng.gen_range(15.0..30.0)),
+                age: 0.0,
+                lifetime: rng.gen_range(3.0..5.0),
+                size: rng.gen_range(1.0..2.5),
+                stretch: 1.0,
+                gradient: ColorGradient::fade(Color::new(0.7, 0.7, 0.75, 0.35)),
+                additive: false,
+            },
+            AmbientEffect::WarpStreaks => Particle {
+                pos: Vec2::new(x, -20.0),
+                vel: Vec2::new(0.0, rng.gen_range(900.0..1300.0)),
+                age: 0.0,
+                lifetime: 1.0,
+                size: 1.5,
+                stretch: 12.0,
+                gradient: ColorGradient::fade(Color::new(0.6, 0.8, 1.0, 0.8)),
+                additive: true,
+            },
+            AmbientEffect::DebrisRain => Particle {
+                pos: Vec2::new(x, -10.0),
+                vel: Vec2::new(rng.gen_range(-30.0..30.0), rng.gen_range(150.0..250.0)),
+                age: 0.0,
+                lifetime: 4.0,
+                size: rng.gen_range(2.0..4.0),
+                stretch: 1.0,
+                gradient: ColorGradient::new(Color::new(1.0, 0.6, 0.2, 1.0), Color::new(0.3, 0.3, 0.3, 0.0)),
+                additive: false,
+            },
+        }
+    }
+}
+
+// 开启中的环境效果
+struct AmbientEmitter {
+    effect: AmbientEffect,
+    remaining: Option<f32>, // 剩余秒数，None 表示一直持续到手动停止
+    accumulator: f32,       // 尚未生成的粒子数（小数部分累积到下一帧）
+}
+
+struct Particle {
+    pos: Vec2,
+    vel: Vec2,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    stretch: f32, // 纵向拉伸倍数，用于光条类粒子
+    gradient: ColorGradient,
+    additive: bool,
+}
+
+impl Particle {
+    fn progress(&self) -> f32 {
+        self.age / self.lifetime
+    }
+}
+
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    ambient: Vec<AmbientEmitter>,
+    alpha_batch: InstanceArray,
+    additive_batch: InstanceArray,
+}
+
+impl ParticleSystem {
+    pub fn new(ctx: &ggez::Context) -> Self {
+        ParticleSystem {
+            particles: Vec::with_capacity(MAX_PARTICLES),
+            ambient: Vec::new(),
+            alpha_batch: InstanceArray::new(ctx, None),
+            additive_batch: InstanceArray::new(ctx, None),
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.particles.retain_mut(|particle| {
+            particle.pos += particle.vel * dt;
+            particle.age += dt;
+            particle.age < particle.lifetime
+        });
+
+        let mut rng = rand::thread_rng();
+        for emitter in &mut self.ambient {
+            emitter.accumulator += emitter.effect.rate() * dt;
+            while emitter.accumulator >= 1.0 {
+                emitter.accumulator -= 1.0;
+                if self.particles.len() < MAX_PARTICLES {
+                    self.particles.push(emitter.effect.spawn(&mut rng));
+                }
+            }
+            if let Some(remaining) = &mut emitter.remaining {
+                *remaining -= dt;
+            }
+        }
+        self.ambient.retain(|emitter| emitter.remaining.is_none_or(|remaining| remaining > 0.0));
+    }
+
+    // 开启环境效果，已开启的同名效果改为新的持续时间
+    pub fn start_ambient(&mut self, effect: AmbientEffect, duration: Option<f32>) {
+        self.stop_ambient(effect);
+        self.ambient.push(AmbientEmitter { effect, remaining: duration, accumulator: 0.0 });
+    }
+
+    // 停止生成新粒子，已生成的粒子照常消散
+    pub fn stop_ambient(&mut self, effect: AmbientEffect) {
+        self.ambient.retain(|emitter| emitter.effect != effect);
+    }
+
+    pub fn clear_ambient(&mut self) {
+        self.ambient.clear();
+    }
+
+    // direction 为发射主方向（弧度），全方向发射器会忽略它
+    pub fn emit(&mut self, kind: EmitterKind, pos: Vec2, direction: f32, gradient: ColorGradient) {
+        let config = kind.config();
+        let mut rng = rand::thread_rng();
+        let available_slots = MAX_PARTICLES.saturating_sub(self.particles.len());
+
+        for _ in 0..config.count.min(available_slots) {
+            let angle = direction + rng.gen_range(-0.5..0.5) * config.spread;
+            let speed = rng.gen_range(config.speed * 0.5..config.speed);
+            self.particles.push(Particle {
+                pos,
+                vel: Vec2::new(angle.cos(), angle.sin()) * speed,
+                age: 0.0,
+                lifetime: rng.gen_range(config.lifetime * 0.7..config.lifetime),
+                size: rng.gen_range(config.size * 0.5..config.size * 1.5),
+                stretch: 1.0,
+                gradient,
+                additive: config.additive,
+            });
+        }
+    }
+
+    pub fn add_explosion(&mut self, pos: Vec2, color: Color) {
+        self.emit(EmitterKind::Explosion, pos, 0.0, ColorGradient::fade(color));
+    }
+
+    pub fn draw(&mut self, canvas: &mut Canvas, window_size: &WindowSize) {
+        let scale = window_size.scale_x.min(window_size.scale_y);
+        let instance = |particle: &Particle| {
+            let t = particle.progress();
+            // 粒子随生命周期缩小
+            let size = particle.size * (1.0 - t * 0.9) * scale;
+            let extent = Vec2::new(size, size * particle.stretch);
+            DrawParam::default()
+                .dest(window_size.scale_vec2(particle.pos) - extent / 2.0)
+                .scale(extent)
+                .color(particle.gradient.sample(t))
+        };
+
+        self.alpha_batch.set(self.particles.iter().filter(|p| !p.additive).map(instance));
+        self.additive_batch.set(self.particles.iter().filter(|p| p.additive).map(instance));
+
+        canvas.draw(&self.alpha_batch, DrawParam::default());
+        canvas.set_blend_mode(BlendMode::ADD);
+        canvas.draw(&self.additive_batch, DrawParam::default());
+        canvas.set_blend_mode(BlendMode::ALPHA);
+    }
+}
@@ -0,0 +1,251 @@
+// path_editor.rs
+// 敌人路线编辑器（--editor NAME，需以 editor 特性编译）：左键添加或拖动航点，右键删除航点，
+// 空格预览敌机沿路线飞行，S 保存到资源目录的 paths/NAME.txt，波次脚本用 spawn_path 引用
+use std::path::{Path, PathBuf};
+
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Image, Mesh, Rect};
+use ggez::input::keyboard::KeyCode;
+use ggez::input::mouse::MouseButton;
+use ggez::GameResult;
+use glam::Vec2;
+
+use crate::paths::{self, EnemyPath};
+use crate::{text, WindowSize, BASE_WINDOW_HEIGHT, BASE_WINDOW_WIDTH};
+
+const VIEW_MARGIN: f32 = 120.0;   // 屏幕外可编辑的范围，路线可以从屏幕外进入
+const POINT_RADIUS: f32 = 6.0;    // 航点的绘制半径（像素）
+const PICK_RADIUS: f32 = 14.0;    // 点击离航点多近算选中（基准坐标）
+const PREVIEW_SPEED: f32 = 240.0; // 预览敌机的飞行速度（基准坐标/秒）
+const PREVIEW_SIZE: f32 = 40.0;
+
+const HELP: &str = "LMB add/drag  RMB delete  Backspace undo  Delete clear  Space preview  S save  Esc quit";
+
+pub enum EditorEvent {
+    None,
+    Quit,
+}
+
+pub struct PathEditor {
+    name: String,
+    file: PathBuf,
+    points: Vec<Vec2>,
+    path: EnemyPath,          // 由 points 生成
+    dragging: Option<usize>,  // 正在拖动的航点
+    preview: Option<f32>,     // 预览敌机已飞行的距离
+    image: Image,
+    status: String,
+    dirty: bool,              // 有未保存的修改
+    confirm_quit: bool,       // 有未保存修改时再按一次 Esc 才退出
+}
+
+impl PathEditor {
+    // dir 为资源目录；已有同名路线时载入继续编辑
+    pub fn new(name: String, dir: &Path, image: Image) -> Self {
+        let file = paths::file(dir, &name);
+        let (points, status) = match EnemyPath::load(&file) {
+            Some(path) => (path.points().to_vec(), format!("Loaded {}", file.display())),
+            None => (Vec::new(), format!("New path {}", file.display())),
+        };
+        PathEditor {
+            name,
+            file,
+            path: EnemyPath::new(points.clone()),
+            points,
+            dragging: None,
+            preview: None,
+            image,
+            status,
+            dirty: false,
+            confirm_quit: false,
+        }
+    }
+
+    fn edited(&mut self) {
+        self.path = EnemyPath::new(self.points.clone());
+        self.dirty = true;
+        self.confirm_quit = false;
+    }
+
+    // 离 pos 最近且在选取范围内的航点
+    fn pick(&self, pos: Vec2) -> Option<usize> {
+        self.points
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| point.distance(pos) <= PICK_RADIUS)
+            .min_by(|(_, a), (_, b)| a.distance(pos).total_cmp(&b.distance(pos)))
+            .map(|(index, _)| index)
+    }
+
+    // x、y 为窗口像素坐标
+    pub fn mouse_down(&mut self, button: MouseButton, x: f32, y: f32, window_size: &WindowSize) {
+        let pos = to_world(Vec2::new(x, y), window_size);
+        match button {
+            MouseButton::Left => {
+                self.dragging = self.pick(pos).or_else(|| {
+                    self.points.push(pos);
+                    self.edited();
+                    Some(self.points.len() - 1)
+                });
+            }
+            MouseButton::Right => {
+                if let Some(index) = self.pick(pos) {
+                    self.points.remove(index);
+                    self.dragging = None;
+                    self.edited();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn mouse_up(&mut self, button: MouseButton) {
+        if button == MouseButton::Left {
+            self.dragging = None;
+        }
+    }
+
+    pub fn mouse_motion(&mut self, x: f32, y: f32, window_size: &WindowSize) {
+        // 拖动中的航点已被删除时放弃拖动
+        let Some(index) = self.dragging else {
+            return;
+        };
+        match self.points.get_mut(index) {
+            Some(point) => {
+                *point = to_world(Vec2::new(x, y), window_size);
+                self.edited();
+            }
+            None => self.dragging = None,
+        }
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> EditorEvent {
+        match key {
+            KeyCode::Space => {
+                self.preview = match self.preview {
+                    Some(_) => None,
+                    None => Some(0.0),
+                };
+            }
+            KeyCode::Back if !self.points.is_empty() => {
+                self.points.pop();
+                self.dragging = None;
+                self.edited();
+            }
+            KeyCode::Delete => {
+                self.points.clear();
+                self.dragging = None;
+                self.edited();
+            }
+            KeyCode::S => self.save(),
+            KeyCode::Escape => {
+                if !self.dirty || self.confirm_quit {
+                    return EditorEvent::Quit;
+                }
+                self.confirm_quit = true;
+                self.status = "Unsaved changes - press Esc again to quit".to_string();
+            }
+            _ => {}
+        }
+        EditorEvent::None
+    }
+
+    fn save(&mut self) {
+        if self.points.len() < 2 {
+            self.status = "A path needs at least two waypoints".to_string();
+            return;
+        }
+        self.status = match self.path.save(&self.file) {
+            Ok(()) => {
+                self.dirty = false;
+                log::info!("Saved path {} to {}", self.name, self.file.display());
+                format!("Saved {} - use spawn_path(kind, \"{}\") in a wave script", self.file.display(), self.name)
+            }
+            Err(err) => {
+                log::error!("Failed to save {}: {}", self.file.display(), err);
+                format!("Save failed: {}", err)
+            }
+        };
+    }
+
+    // 预览敌机飞完路线后从头再飞
+    pub fn update(&mut self, dt: f32) {
+        if let Some(distance) = &mut self.preview {
+            *distance += PREVIEW_SPEED * dt;
+            if *distance > self.path.length() {
+                *distance = 0.0;
+            }
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut ggez::Context, canvas: &mut Canvas, window_size: &WindowSize) -> GameResult {
+        // 游戏画面范围
+        let top_left = to_screen(Vec2::ZERO, window_size);
+        let bottom_right = to_screen(Vec2::new(BASE_WINDOW_WIDTH, BASE_WINDOW_HEIGHT), window_size);
+        let screen = Rect::new(top_left.x, top_left.y, bottom_right.x - top_left.x, bottom_right.y - top_left.y);
+        let frame = Mesh::new_rectangle(ctx, DrawMode::stroke(2.0), screen, Color::new(0.4, 0.4, 0.5, 1.0))?;
+        canvas.draw(&frame, DrawParam::default());
+
+        let line: Vec<Vec2> = self.path.polyline().into_iter().map(|pos| to_screen(pos, window_size)).collect();
+        if line.len() >= 2 {
+            let spline = Mesh::new_line(ctx, &line, 2.0, Color::new(0.3, 0.8, 1.0, 1.0))?;
+            canvas.draw(&spline, DrawParam::default());
+        }
+
+        for (index, point) in self.points.iter().enumerate() {
+            let pos = to_screen(*point, window_size);
+            let color = if index == 0 { Color::GREEN } else { Color::new(1.0, 0.8, 0.2, 1.0) };
+            let marker = Mesh::new_circle(ctx, DrawMode::fill(), pos, POINT_RADIUS, 0.5, color)?;
+            canvas.draw(&marker, DrawParam::default());
+            canvas.draw(
+                &text::label(index.to_string(), window_size),
+                DrawParam::default().dest(pos + Vec2::new(POINT_RADIUS + 2.0, -POINT_RADIUS * 2.0)).color(Color::WHITE),
+            );
+        }
+
+        // 预览敌机：机头朝向前进方向（贴图默认朝上）
+        if let Some((pos, heading)) = self.preview.and_then(|distance| self.path.sample(distance)) {
+            let scale = view_scale(window_size);
+            canvas.draw(
+                &self.image,
+                DrawParam::default()
+                    .dest(to_screen(pos, window_size))
+                    .offset(Vec2::splat(0.5))
+                    .rotation(heading.y.atan2(heading.x) + std::f32::consts::FRAC_PI_2)
+                    .scale(scale * PREVIEW_SIZE / Vec2::new(self.image.width() as f32, self.image.height() as f32)),
+            );
+        }
+
+        let title = format!(
+            "Path: {}{}  ({} waypoints, length {:.0})",
+            self.name,
+            if self.dirty { " *" } else { "" },
+            self.points.len(),
+            self.path.length(),
+        );
+        for (row, content) in [title.as_str(), HELP, self.status.as_str()].into_iter().enumerate() {
+            canvas.draw(
+                &text::label(content, window_size),
+                DrawParam::default()
+                    .dest(Vec2::new(10.0, 10.0 + row as f32 * text::point_size(22.0, window_size)))
+                    .color(Color::new(0.85, 0.85, 0.85, 1.0)),
+            );
+        }
+        Ok(())
+    }
+}
+
+// 编辑视图在游戏画面四周各多显示 VIEW_MARGIN，基准坐标与窗口像素互相换算
+fn view_scale(window_size: &WindowSize) -> Vec2 {
+    Vec2::new(
+        window_size.width / (BASE_WINDOW_WIDTH + VIEW_MARGIN * 2.0),
+        window_size.height / (BASE_WINDOW_HEIGHT + VIEW_MARGIN * 2.0),
+    )
+}
+
+fn to_screen(pos: Vec2, window_size: &WindowSize) -> Vec2 {
+    (pos + VIEW_MARGIN) * view_scale(window_size)
+}
+
+fn to_world(pos: Vec2, window_size: &WindowSize) -> Vec2 {
+    pos / view_scale(window_size) - VIEW_MARGIN
+}
@@ -0,0 +1,136 @@
+// paths.rs
+// 敌人飞行路线：穿过各航点的 Catmull-Rom 样条，敌人按路线长度匀速飞行，飞完后沿末端方向直线离开。
+// 路线文件位于 resources/paths/<name>.txt，由路线编辑器（--editor）生成，波次脚本用 spawn_path 引用
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use glam::Vec2;
+
+pub const PATHS_DIR: &str = "paths";
+const SEGMENT_SAMPLES: usize = 16; // 相邻航点之间的采样数
+
+#[derive(Clone, Debug)]
+pub struct EnemyPath {
+    points: Vec<Vec2>,         // 航点（基准坐标）
+    samples: Vec<(f32, Vec2)>, // 沿样条的采样点及其距起点的长度
+}
+
+impl EnemyPath {
+    pub fn new(points: Vec<Vec2>) -> Self {
+        let mut samples = Vec::new();
+        let mut length = 0.0;
+        let mut push = |pos: Vec2, samples: &mut Vec<(f32, Vec2)>| {
+            if let Some((_, last)) = samples.last() {
+                length += pos.distance(*last);
+            }
+            samples.push((length, pos));
+        };
+        for i in 0..points.len().saturating_sub(1) {
+            // 首尾两段用端点本身作为外侧控制点
+            let p0 = points[i.saturating_sub(1)];
+            let (p1, p2) = (points[i], points[i + 1]);
+            let p3 = points[(i + 2).min(points.len() - 1)];
+            for step in 0..SEGMENT_SAMPLES {
+                push(catmull_rom(p0, p1, p2, p3, step as f32 / SEGMENT_SAMPLES as f32), &mut samples);
+            }
+        }
+        if let Some(last) = points.last() {
+            push(*last, &mut samples);
+        }
+        EnemyPath { points, samples }
+    }
+
+    #[cfg_attr(not(feature = "editor"), allow(dead_code))]
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    pub fn length(&self) -> f32 {
+        self.samples.last().map_or(0.0, |(length, _)| *length)
+    }
+
+    // 绘制用的折线
+    #[cfg_attr(not(feature = "editor"), allow(dead_code))]
+    pub fn polyline(&self) -> Vec<Vec2> {
+        self.samples.iter().map(|(_, pos)| *pos).collect()
+    }
+
+    // 距起点 distance 处的位置和前进方向（单位向量）；超过终点或航点不足两个时返回 None
+    pub fn sample(&self, distance: f32) -> Option<(Vec2, Vec2)> {
+        if distance > self.length() || self.samples.len() < 2 {
+            return None;
+        }
+        let distance = distance.max(0.0);
+        let next = self.samples.partition_point(|(length, _)| *length < distance).clamp(1, self.samples.len() - 1);
+        let (start, from) = self.samples[next - 1];
+        let (end, to) = self.samples[next];
+        let t = if end > start { (distance - start) / (end - start) } else { 0.0 };
+        Some((from.lerp(to, t), (to - from).try_normalize().unwrap_or(Vec2::Y)))
+    }
+
+    // 随游戏发布（或 mod 提供）的路线，位于资源目录中
+    pub fn load_resource(ctx: &ggez::Context, name: &str) -> Option<Self> {
+        use std::io::Read;
+
+        let path = format!("/{}/{}.txt", PATHS_DIR, name);
+        let mut text = String::new();
+        let read = ctx
+            .fs
+            .open(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|mut file| file.read_to_string(&mut text).map_err(|err| err.to_string()));
+        if let Err(err) = read {
+            log::error!("Failed to load path {}: {}", path, err);
+            return None;
+        }
+        let enemy_path = Self::parse(&text);
+        if enemy_path.is_none() {
+            log::error!("Path {} needs at least two waypoints", path);
+        }
+        enemy_path
+    }
+
+    #[cfg_attr(not(feature = "editor"), allow(dead_code))]
+    pub fn load(file: &Path) -> Option<Self> {
+        Self::parse(&fs::read_to_string(file).ok()?)
+    }
+
+    // 每行一个航点：x,y；# 开头的行为注释
+    fn parse(text: &str) -> Option<Self> {
+        let points: Vec<Vec2> = text
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .filter_map(|line| {
+                let (x, y) = line.split_once(',')?;
+                Some(Vec2::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+            })
+            .collect();
+        (points.len() >= 2).then(|| EnemyPath::new(points))
+    }
+
+    #[cfg_attr(not(feature = "editor"), allow(dead_code))]
+    pub fn save(&self, file: &Path) -> io::Result<()> {
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text: String = self.points.iter().map(|point| format!("{:.1},{:.1}\n", point.x, point.y)).collect();
+        fs::write(file, text)
+    }
+}
+
+// 资源目录 dir 中名为 name 的路线文件
+#[cfg_attr(not(feature = "editor"), allow(dead_code))]
+pub fn file(dir: &Path, name: &str) -> PathBuf {
+    dir.join(PATHS_DIR).join(format!("{}.txt", name))
+}
+
+// 经过 p1、p2 的一段 Catmull-Rom 样条，t 从 0 到 1
+fn catmull_rom(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, t: f32) -> Vec2 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
@@ -0,0 +1,113 @@
+// pickups.rs
+// 道具稀有度、外观与掉落权重
+use rand::Rng;
+
+use crate::palette::Swatch;
+use crate::GameObjectType;
+
+// 稀有度等级
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+}
+
+impl Rarity {
+    pub const ALL: [Rarity; 3] = [Rarity::Common, Rarity::Uncommon, Rarity::Rare];
+
+    // 掉落权重，越稀有越低
+    pub fn weight(self) -> u32 {
+        match self {
+            Rarity::Common => 6,
+            Rarity::Uncommon => 3,
+            Rarity::Rare => 1,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Rarity::Common => "Common",
+            Rarity::Uncommon => "Uncommon",
+            Rarity::Rare => "Rare",
+        }
+    }
+
+    // 光晕颜色
+    pub fn glow_swatch(self) -> Swatch {
+        match self {
+            Rarity::Common => Swatch::RarityCommon,
+            Rarity::Uncommon => Swatch::RarityUncommon,
+            Rarity::Rare => Swatch::RarityRare,
+        }
+    }
+
+    // 光晕半径相对道具尺寸的倍数
+    pub fn glow_scale(self) -> f32 {
+        match self {
+            Rarity::Common => 0.8,
+            Rarity::Uncommon => 1.0,
+            Rarity::Rare => 1.25,
+        }
+    }
+}
+
+// 单种道具的外观与稀有度
+pub struct PickupInfo {
+    pub object_type: GameObjectType,
+    pub name: &'static str,
+    pub image: &'static str,
+    pub swatch: Swatch,
+    pub rarity: Rarity,
+}
+
+pub const PICKUPS: [PickupInfo; 4] = [
+    PickupInfo {
+        object_type: GameObjectType::MissileAmmo,
+        name: "Missile Ammo (+3)",
+        image: "/img/bullet.png",
+        swatch: Swatch::PickupMissile,
+        rarity: Rarity::Common,
+    },
+    PickupInfo {
+        object_type: GameObjectType::SpreadAmmo,
+        name: "Spread Shot",
+        image: "/img/gun.png",
+        swatch: Swatch::PickupSpread,
+        rarity: Rarity::Rare,
+    },
+    PickupInfo {
+        object_type: GameObjectType::DronePickup,
+        name: "Drone",
+        image: "/img/player.png",
+        swatch: Swatch::PickupDrone,
+        rarity: Rarity::Uncommon,
+    },
+    PickupInfo {
+        object_type: GameObjectType::EmpPickup,
+        name: "EMP Blast",
+        image: "/img/shield.png",
+        swatch: Swatch::PickupEmp,
+        rarity: Rarity::Rare,
+    },
+];
+
+pub fn info(object_type: &GameObjectType) -> Option<&'static PickupInfo> {
+    PICKUPS
+        .iter()
+        .find(|pickup| std::mem::discriminant(&pickup.object_type) == std::mem::discriminant(object_type))
+}
+
+// 按稀有度权重随机选择一种道具
+pub fn roll(rng: &mut impl Rng) -> &'static PickupInfo {
+    let total: u32 = PICKUPS.iter().map(|pickup| pickup.rarity.weight()).sum();
+    let mut roll = rng.gen_range(0..total);
+    for pickup in &PICKUPS {
+        let weight = pickup.rarity.weight();
+        if roll < weight {
+            return pickup;
+        }
+        roll -= weight;
+    }
+    &PICKUPS[0]
+}
@@ -0,0 +1,116 @@
+// popups.rs
+// 浮动得分文字：同一位置短时间内的多次得分合并为一个数字；
+// 连锁击破：短时间内连续击毁足够多的敌人时触发奖励
+use std::collections::VecDeque;
+
+use ggez::graphics::{self, Canvas, Color, DrawParam, TextLayout};
+use glam::Vec2;
+
+const POPUP_LIFETIME: f32 = 0.9; // 秒
+const POPUP_RISE: f32 = 40.0;    // 整个显示期间上升的距离（基准坐标）
+const MERGE_RADIUS: f32 = 80.0;  // 该距离内的得分合并为同一个数字
+const MERGE_WINDOW: f32 = 0.3;   // 只合并刚出现不久的数字（秒）
+
+pub const CHAIN_KILLS: usize = 5;  // 触发连锁奖励所需的击毁数
+pub const CHAIN_WINDOW: f32 = 2.0; // 这些击毁需要发生在多少秒之内
+
+struct Popup {
+    pos: Vec2,
+    points: i32,
+    label: Option<String>, // 有说明文字的得分（如连锁奖励）不参与合并
+    color: Color,
+    age: f32,
+}
+
+#[derive(Default)]
+pub struct ScorePopups {
+    active: Vec<Popup>,
+}
+
+impl ScorePopups {
+    // 附近刚出现的同色数字会累加，而不是再叠一个新数字
+    pub fn add(&mut self, pos: Vec2, points: i32, color: Color) {
+        let nearby = self.active.iter_mut().find(|popup| {
+            popup.label.is_none()
+                && popup.color == color
+                && popup.age < MERGE_WINDOW
+                && popup.pos.distance(pos) <= MERGE_RADIUS
+        });
+        if let Some(popup) = nearby {
+            popup.points += points;
+            popup.pos = popup.pos.lerp(pos, 0.5);
+            popup.age = 0.0;
+            return;
+        }
+        self.active.push(Popup { pos, points, label: None, color, age: 0.0 });
+    }
+
+    // points 为 0 时只显示说明文字
+    pub fn announce(&mut self, pos: Vec2, label: &str, points: i32, color: Color) {
+        self.active.push(Popup { pos, points, label: Some(label.to_string()), color, age: 0.0 });
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for popup in &mut self.active {
+            popup.age += dt;
+        }
+        self.active.retain(|popup| popup.age < POPUP_LIFETIME);
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    // scale 为基准坐标到屏幕坐标的缩放；后半段逐渐淡出
+    pub fn draw(&self, canvas: &mut Canvas, scale: Vec2) {
+        for popup in &self.active {
+            let t = popup.age / POPUP_LIFETIME;
+            let alpha = (2.0 - 2.0 * t).min(1.0);
+            let text = match &popup.label {
+                Some(label) if popup.points == 0 => label.clone(),
+                Some(label) => format!("{}\n+{}", label, popup.points),
+                None => format!("+{}", popup.points),
+            };
+            let mut text = graphics::Text::new(text);
+            text.set_layout(TextLayout::center());
+            let size = if popup.label.is_some() { 1.6 } else { 1.0 };
+            canvas.draw(
+                &text,
+                DrawParam::default()
+                    .dest((popup.pos - Vec2::new(0.0, POPUP_RISE * t)) * scale)
+                    .color(Color { a: popup.color.a * alpha, ..popup.color })
+                    .scale(scale * size),
+            );
+        }
+    }
+}
+
+// 记录最近的击毁时间，判断是否达成连锁
+#[derive(Clone, Default)]
+pub struct ChainTracker {
+    kills: VecDeque<f32>,
+    time: f32,
+}
+
+impl ChainTracker {
+    pub fn update(&mut self, dt: f32) {
+        self.time += dt;
+        while self.kills.front().is_some_and(|kill| self.time - kill > CHAIN_WINDOW) {
+            self.kills.pop_front();
+        }
+    }
+
+    // 记录一次击毁；凑满一组连锁时返回 true 并重新计数
+    pub fn record_kill(&mut self) -> bool {
+        self.kills.push_back(self.time);
+        if self.kills.len() >= CHAIN_KILLS {
+            self.kills.clear();
+            return true;
+        }
+        false
+    }
+
+    pub fn clear(&mut self) {
+        self.kills.clear();
+    }
+}
@@ -0,0 +1,119 @@
+// postfx.rs
+// 后期处理：游戏画面先绘制到离屏图像，再提取亮部、模糊并叠加回屏幕，让子弹和爆炸发光；
+// 可选的屏幕滤镜（CRT）在最后作用于包括 HUD 在内的整个画面
+use ggez::graphics::{BlendMode, Canvas, Color, DrawParam, Image, ScreenImage, Shader, ShaderBuilder};
+use ggez::GameResult;
+use glam::Vec2;
+
+// 亮部在一半分辨率下模糊，开销更小、光晕也更宽
+const BLOOM_DOWNSCALE: f32 = 0.5;
+
+// 全屏滤镜
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScreenFilter {
+    Off,
+    Crt, // 扫描线、屏幕弯曲和色差
+}
+
+impl ScreenFilter {
+    pub const ALL: [ScreenFilter; 2] = [ScreenFilter::Off, ScreenFilter::Crt];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ScreenFilter::Off => "Off",
+            ScreenFilter::Crt => "CRT",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ScreenFilter> {
+        ScreenFilter::ALL.iter().copied().find(|filter| filter.name() == name)
+    }
+}
+
+pub struct PostProcess {
+    scene: ScreenImage,     // 游戏画面
+    bright: ScreenImage,    // 亮部（降采样）
+    blur: ScreenImage,      // 模糊的中间结果
+    composite: ScreenImage, // 开启滤镜时的完整画面（游戏 + 泛光 + HUD）
+    threshold: Shader,
+    blur_h: Shader,
+    blur_v: Shader,
+    crt: Shader,
+}
+
+impl PostProcess {
+    pub fn new(ctx: &mut ggez::Context) -> GameResult<Self> {
+        Ok(PostProcess {
+            scene: ScreenImage::new(ctx, None, 1.0, 1.0, 1),
+            bright: ScreenImage::new(ctx, None, BLOOM_DOWNSCALE, BLOOM_DOWNSCALE, 1),
+            blur: ScreenImage::new(ctx, None, BLOOM_DOWNSCALE, BLOOM_DOWNSCALE, 1),
+            composite: ScreenImage::new(ctx, None, 1.0, 1.0, 1),
+            threshold: ShaderBuilder::new().fragment_path("/shaders/bloom_threshold.wgsl").build(&ctx.gfx)?,
+            blur_h: ShaderBuilder::new().fragment_path("/shaders/blur_h.wgsl").build(&ctx.gfx)?,
+            blur_v: ShaderBuilder::new().fragment_path("/shaders/blur_v.wgsl").build(&ctx.gfx)?,
+            crt: ShaderBuilder::new().fragment_path("/shaders/crt.wgsl").build(&ctx.gfx)?,
+        })
+    }
+
+    // 开始绘制游戏画面（窗口尺寸变化时离屏图像会自动重建）
+    pub fn scene_canvas(&mut self, ctx: &mut ggez::Context, clear: Color) -> Canvas {
+        Canvas::from_screen_image(ctx, &mut self.scene, clear)
+    }
+
+    // 游戏画面绘制完毕：按需叠加泛光，返回继续绘制 HUD 的画布
+    // 没有滤镜时直接输出到屏幕，否则输出到 composite 等待 present
+    pub fn overlay_canvas(&mut self, ctx: &mut ggez::Context, bloom: bool, filter: ScreenFilter) -> GameResult<Canvas> {
+        let scene = self.scene.image(ctx);
+        let target = (filter != ScreenFilter::Off).then(|| self.composite.image(ctx));
+
+        let mut canvas = match &target {
+            Some(image) => Canvas::from_image(ctx, image.clone(), Color::BLACK),
+            None => Canvas::from_frame(ctx, Color::BLACK),
+        };
+        canvas.draw(&scene, DrawParam::default());
+        if bloom {
+            let bright = self.bloom(ctx, &scene)?;
+            canvas.set_blend_mode(BlendMode::ADD);
+            canvas.draw(&bright, DrawParam::default().scale(Vec2::splat(1.0 / BLOOM_DOWNSCALE)));
+            canvas.set_blend_mode(BlendMode::ALPHA);
+        }
+        Ok(canvas)
+    }
+
+    // 提取亮部并模糊，返回降采样的光晕图像
+    fn bloom(&mut self, ctx: &mut ggez::Context, scene: &Image) -> GameResult<Image> {
+        let bright = self.bright.image(ctx);
+        let blur = self.blur.image(ctx);
+
+        // 提取亮部并降采样
+        let mut canvas = Canvas::from_image(ctx, bright.clone(), Color::BLACK);
+        canvas.set_shader(&self.threshold);
+        canvas.draw(scene, DrawParam::default().scale(Vec2::splat(BLOOM_DOWNSCALE)));
+        canvas.finish(ctx)?;
+
+        // 两次一维高斯模糊
+        let mut canvas = Canvas::from_image(ctx, blur.clone(), Color::BLACK);
+        canvas.set_shader(&self.blur_h);
+        canvas.draw(&bright, DrawParam::default());
+        canvas.finish(ctx)?;
+
+        let mut canvas = Canvas::from_image(ctx, bright.clone(), Color::BLACK);
+        canvas.set_shader(&self.blur_v);
+        canvas.draw(&blur, DrawParam::default());
+        canvas.finish(ctx)?;
+        Ok(bright)
+    }
+
+    // 整个画面绘制完毕后调用：开启滤镜时把 composite 经过滤镜输出到屏幕
+    pub fn present(&mut self, ctx: &mut ggez::Context, filter: ScreenFilter) -> GameResult {
+        let shader = match filter {
+            ScreenFilter::Off => return Ok(()),
+            ScreenFilter::Crt => &self.crt,
+        };
+        let composite = self.composite.image(ctx);
+        let mut canvas = Canvas::from_frame(ctx, Color::BLACK);
+        canvas.set_shader(shader);
+        canvas.draw(&composite, DrawParam::default());
+        canvas.finish(ctx)
+    }
+}
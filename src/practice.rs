@@ -0,0 +1,149 @@
+// practice.rs
+// 练习模式：从到达过的任意波次或 Boss 阶段直接开始，自选续关次数和武器，用来熟悉敌人的弹幕。
+// 练习的成绩不计入最高分、每日挑战和局外点数
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::boss::{self, BossPhase};
+use crate::loadout::Primary;
+use crate::progression::Progress;
+use crate::shop::MAX_WEAPON_TIER;
+use crate::ui::{UiEvent, Widget, WidgetList};
+use crate::{WindowSize, BASE_WINDOW_WIDTH, BOSS_WAVE_INTERVAL, MAX_CONTINUES};
+
+const MAX_MISSILES: f32 = 30.0;
+const MAX_BOMBS: f32 = 5.0;
+
+// 一局练习的起点与装备
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Practice {
+    pub wave: u32,
+    pub phase: BossPhase, // 起始波次是 Boss 波时从这个阶段开始
+    pub lives: u32,       // 续关次数
+    pub primary: Primary,
+    pub weapon_tier: u32,
+    pub missiles: i32,
+    pub bombs: u32,
+}
+
+impl Practice {
+    pub fn is_boss_wave(&self) -> bool {
+        self.wave.is_multiple_of(BOSS_WAVE_INTERVAL)
+    }
+}
+
+fn phase_name(phase: BossPhase) -> &'static str {
+    match phase {
+        BossPhase::Opening => "Opening",
+        BossPhase::Enraged => "Enraged",
+        BossPhase::Desperate => "Desperate",
+    }
+}
+
+// 练习设置界面按键处理的结果
+pub enum PracticeEvent {
+    None,
+    Start(Practice),
+    Back,
+}
+
+pub struct PracticeScreen {
+    primaries: Vec<Primary>,
+    widgets: WidgetList,
+}
+
+impl PracticeScreen {
+    // last 为上一次练习的设置，没有时按档案的装备填入
+    pub fn new(progress: &Progress, last: Option<Practice>) -> Self {
+        let primaries = Primary::available(progress);
+        let practice = last.unwrap_or(Practice {
+            wave: 1,
+            phase: BossPhase::Opening,
+            lives: MAX_CONTINUES,
+            primary: progress.loadout.primary,
+            weapon_tier: 0,
+            missiles: 5,
+            bombs: 1,
+        });
+        let primary = primaries.iter().position(|p| *p == practice.primary).unwrap_or(0);
+        let phases: Vec<BossPhase> = boss::PHASES.iter().map(|def| def.phase).collect();
+        let phase = phases.iter().position(|p| *p == practice.phase).unwrap_or(0);
+        let furthest = progress.furthest_wave.max(1) as f32;
+        let widgets = WidgetList::new(vec![
+            Widget::number("wave", "Wave", (practice.wave as f32).min(furthest), 1.0, furthest, 1.0, ""),
+            Widget::selector("phase", "Boss Phase", phases.into_iter().map(phase_name).collect(), phase),
+            Widget::number("lives", "Lives", practice.lives as f32, 0.0, MAX_CONTINUES as f32, 1.0, ""),
+            Widget::selector("primary", "Primary", primaries.iter().map(|p| p.name()).collect(), primary),
+            Widget::number("tier", "Weapon Tier", practice.weapon_tier as f32, 0.0, MAX_WEAPON_TIER as f32, 1.0, ""),
+            Widget::number("missiles", "Missiles", practice.missiles as f32, 0.0, MAX_MISSILES, 1.0, ""),
+            Widget::number("bombs", "Bombs", practice.bombs as f32, 0.0, MAX_BOMBS, 1.0, ""),
+            Widget::button("start", "Start Practice"),
+            Widget::button("back", "Back"),
+        ]);
+        PracticeScreen { primaries, widgets }
+    }
+
+    fn practice(&self) -> Practice {
+        Practice {
+            wave: self.widgets.number_value("wave").unwrap_or(1.0) as u32,
+            phase: boss::PHASES[self.widgets.selector_index("phase").unwrap_or(0)].phase,
+            lives: self.widgets.number_value("lives").unwrap_or(0.0) as u32,
+            primary: self.primaries[self.widgets.selector_index("primary").unwrap_or(0)],
+            weapon_tier: self.widgets.number_value("tier").unwrap_or(0.0) as u32,
+            missiles: self.widgets.number_value("missiles").unwrap_or(0.0) as i32,
+            bombs: self.widgets.number_value("bombs").unwrap_or(0.0) as u32,
+        }
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> PracticeEvent {
+        if key == KeyCode::Escape {
+            return PracticeEvent::Back;
+        }
+        match self.widgets.key_down(key) {
+            Some(UiEvent::Activated("start")) => PracticeEvent::Start(self.practice()),
+            Some(UiEvent::Activated("back")) => PracticeEvent::Back,
+            _ => PracticeEvent::None,
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let left = BASE_WINDOW_WIDTH / 2.0 - 200.0;
+
+        // 半透明背景
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left - 20.0, 120.0)))
+                .scale(window_size.scale_vec2(Vec2::new(460.0, 480.0)))
+                .color(Color::new(0.0, 0.0, 0.0, 0.8)),
+        );
+        canvas.draw(
+            &graphics::Text::new("PRACTICE"),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 140.0)))
+                .color(Color::YELLOW)
+                .scale(scale * 2.0),
+        );
+
+        self.widgets.draw(canvas, window_size, Vec2::new(left, 200.0));
+
+        let practice = self.practice();
+        let checkpoint = if practice.is_boss_wave() {
+            format!("Wave {} boss, {} phase", practice.wave, phase_name(practice.phase))
+        } else {
+            format!("Wave {} (bosses every {} waves)", practice.wave, BOSS_WAVE_INTERVAL)
+        };
+        canvas.draw(
+            &graphics::Text::new(format!(
+                "{}\nScores are not recorded\nLeft/Right: change   Enter: choose   Esc: back",
+                checkpoint
+            )),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 520.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+}
@@ -0,0 +1,164 @@
+// presence.rs
+// 在线状态：向外部平台报告当前场景、模式、波次和分数（例如 Discord 好友列表中的“正在玩”）。
+// 后端通过 Presence trait 抽象；未启用 discord 特性、没有配置应用 ID 或连接失败时使用空实现
+#[derive(Clone, Debug, PartialEq)]
+pub struct PresenceState {
+    pub scene: &'static str,
+    pub mode: &'static str,
+    pub wave: u32,
+    pub score: i32,
+    pub playing: bool, // 游戏中才显示波次和分数
+}
+
+impl PresenceState {
+    // 状态的两行文字：第一行为模式或场景，第二行为波次和分数
+    #[cfg_attr(not(feature = "discord"), allow(dead_code))]
+    pub fn lines(&self) -> (String, Option<String>) {
+        if !self.playing {
+            return (format!("In {}", self.scene), None);
+        }
+        (format!("Playing {}", self.mode), Some(format!("Wave {} - Score {}", self.wave, self.score)))
+    }
+}
+
+pub trait Presence {
+    fn update(&mut self, state: &PresenceState);
+    fn clear(&mut self);
+}
+
+// 空实现：不报告任何状态
+pub struct NullPresence;
+
+impl Presence for NullPresence {
+    fn update(&mut self, _state: &PresenceState) {}
+
+    fn clear(&mut self) {}
+}
+
+const UPDATE_INTERVAL: f32 = 15.0; // Discord 限制状态更新频率，分数变化至少间隔这么久才报告
+
+// 只在状态变化时报告，场景切换立即报告，分数和波次的变化按间隔合并
+pub struct PresenceReporter {
+    backend: Box<dyn Presence>,
+    last: Option<PresenceState>,
+    cooldown: f32,
+}
+
+impl PresenceReporter {
+    pub fn new() -> Self {
+        PresenceReporter { backend: connect(), last: None, cooldown: 0.0 }
+    }
+
+    pub fn update(&mut self, dt: f32, state: PresenceState) {
+        self.cooldown -= dt;
+        let Some(last) = &self.last else {
+            return self.send(state);
+        };
+        let scene_changed = last.scene != state.scene || last.playing != state.playing;
+        if *last != state && (scene_changed || self.cooldown <= 0.0) {
+            self.send(state);
+        }
+    }
+
+    fn send(&mut self, state: PresenceState) {
+        self.backend.update(&state);
+        self.last = Some(state);
+        self.cooldown = UPDATE_INTERVAL;
+    }
+}
+
+impl Drop for PresenceReporter {
+    fn drop(&mut self) {
+        self.backend.clear();
+    }
+}
+
+#[cfg(feature = "discord")]
+fn connect() -> Box<dyn Presence> {
+    match std::env::var(discord::CLIENT_ID_VAR) {
+        Ok(client_id) if !client_id.is_empty() => Box::new(discord::DiscordPresence::new(client_id)),
+        _ => {
+            log::info!("{} is not set, Discord presence disabled", discord::CLIENT_ID_VAR);
+            Box::new(NullPresence)
+        }
+    }
+}
+
+#[cfg(not(feature = "discord"))]
+fn connect() -> Box<dyn Presence> {
+    Box::new(NullPresence)
+}
+
+// Discord 后端：IPC 连接和写入都在单独的线程上进行，Discord 没有运行时不会卡住游戏；
+// 连接断开后在下一次更新时重连
+#[cfg(feature = "discord")]
+mod discord {
+    use std::sync::mpsc::{self, Sender};
+    use std::thread;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use discord_rich_presence::{activity, DiscordIpc, DiscordIpcClient};
+
+    use super::{Presence, PresenceState};
+
+    pub const CLIENT_ID_VAR: &str = "DISCORD_CLIENT_ID"; // Discord 开发者后台中应用的 ID
+
+    pub struct DiscordPresence {
+        sender: Sender<Option<PresenceState>>, // None 表示清除状态
+    }
+
+    impl DiscordPresence {
+        pub fn new(client_id: String) -> Self {
+            let (sender, updates) = mpsc::channel::<Option<PresenceState>>();
+            let started = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs() as i64);
+            let spawned = thread::Builder::new().name("discord-presence".to_string()).spawn(move || {
+                let mut client = DiscordIpcClient::new(&client_id);
+                let mut connected = false;
+                for update in updates {
+                    if !connected {
+                        connected = client.connect().is_ok();
+                        if !connected {
+                            log::debug!("Discord is not running, presence not reported");
+                            continue;
+                        }
+                    }
+                    let result = match &update {
+                        Some(state) => {
+                            let (details, state_line) = state.lines();
+                            let mut payload = activity::Activity::new()
+                                .details(details.as_str())
+                                .timestamps(activity::Timestamps::new().start(started));
+                            if let Some(line) = &state_line {
+                                payload = payload.state(line.as_str());
+                            }
+                            client.set_activity(payload)
+                        }
+                        None => client.clear_activity(),
+                    };
+                    if let Err(err) = result {
+                        log::warn!("Discord presence update failed: {}", err);
+                        let _ = client.close();
+                        connected = false;
+                    }
+                }
+                if connected {
+                    let _ = client.close();
+                }
+            });
+            if let Err(err) = spawned {
+                log::error!("Failed to start Discord presence thread: {}", err);
+            }
+            DiscordPresence { sender }
+        }
+    }
+
+    impl Presence for DiscordPresence {
+        fn update(&mut self, state: &PresenceState) {
+            let _ = self.sender.send(Some(state.clone()));
+        }
+
+        fn clear(&mut self) {
+            let _ = self.sender.send(None);
+        }
+    }
+}
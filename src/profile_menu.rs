@@ -0,0 +1,136 @@
+// profile_menu.rs
+// 档案选择界面：启动时选择或新建玩家档案，暂停时也可以切换
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::profiles::{self, ProfileStore, MAX_NAME_LEN};
+use crate::{WindowSize, BASE_WINDOW_WIDTH};
+
+// 档案界面按键处理的结果
+pub enum ProfileEvent {
+    None,
+    Close,            // 保持当前档案
+    Selected(String), // 切换到该档案
+}
+
+pub struct ProfileMenu {
+    names: Vec<String>,
+    selected: usize,          // 等于 names.len() 时选中“新建档案”
+    entering: Option<String>, // 正在输入的新档案名
+    error: Option<String>,
+}
+
+impl ProfileMenu {
+    pub fn new(store: &ProfileStore, current: &str) -> Self {
+        let names = store.list();
+        let selected = names.iter().position(|name| name == current).unwrap_or(0);
+        ProfileMenu { names, selected, entering: None, error: None }
+    }
+
+    pub fn key_down(&mut self, key: KeyCode, store: &ProfileStore) -> ProfileEvent {
+        if let Some(name) = &mut self.entering {
+            match key {
+                KeyCode::Back => {
+                    name.pop();
+                }
+                KeyCode::Escape => {
+                    self.entering = None;
+                    self.error = None;
+                }
+                KeyCode::Return => {
+                    let name = name.trim().to_string();
+                    if self.names.contains(&name) {
+                        self.error = Some(format!("'{}' already exists", name));
+                    } else if let Err(err) = store.create(&name) {
+                        self.error = Some(err.to_string());
+                    } else {
+                        return ProfileEvent::Selected(name);
+                    }
+                }
+                _ => {}
+            }
+            return ProfileEvent::None;
+        }
+
+        let rows = self.names.len() + 1;
+        match key {
+            KeyCode::Up | KeyCode::W => self.selected = (self.selected + rows - 1) % rows,
+            KeyCode::Down | KeyCode::S => self.selected = (self.selected + 1) % rows,
+            KeyCode::Return | KeyCode::Space => match self.names.get(self.selected) {
+                Some(name) => return ProfileEvent::Selected(name.clone()),
+                None => self.entering = Some(String::new()),
+            },
+            KeyCode::Escape => return ProfileEvent::Close,
+            _ => {}
+        }
+        ProfileEvent::None
+    }
+
+    // 输入新档案名时接收字符
+    pub fn text_input(&mut self, character: char) {
+        if let Some(name) = &mut self.entering {
+            let mut candidate = name.clone();
+            candidate.push(character);
+            if candidate.chars().count() <= MAX_NAME_LEN && profiles::is_valid_name(&candidate) {
+                *name = candidate;
+                self.error = None;
+            }
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let left = BASE_WINDOW_WIDTH / 2.0 - 200.0;
+
+        // 半透明背景
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left - 20.0, 120.0)))
+                .scale(window_size.scale_vec2(Vec2::new(460.0, 460.0)))
+                .color(Color::new(0.0, 0.0, 0.0, 0.8)),
+        );
+        canvas.draw(
+            &graphics::Text::new("SELECT PROFILE"),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 140.0)))
+                .color(Color::YELLOW)
+                .scale(scale * 1.5),
+        );
+
+        let new_label = match &self.entering {
+            Some(name) => format!("Name: {}_", name),
+            None => "New Profile...".to_string(),
+        };
+        let labels = self.names.iter().map(String::as_str).chain(std::iter::once(new_label.as_str()));
+        let mut rows = 0;
+        for (idx, label) in labels.enumerate() {
+            let is_selected = idx == self.selected;
+            canvas.draw(
+                &graphics::Text::new(format!("{} {}", if is_selected { ">" } else { " " }, label)),
+                DrawParam::default()
+                    .dest(window_size.scale_vec2(Vec2::new(left, 190.0 + idx as f32 * 30.0)))
+                    .color(if is_selected { Color::YELLOW } else { Color::WHITE })
+                    .scale(scale),
+            );
+            rows += 1;
+        }
+
+        let mut hint = if self.entering.is_some() {
+            "Type a name   Enter: create   Esc: cancel".to_string()
+        } else {
+            "Up/Down: select   Enter: choose   Esc: keep current".to_string()
+        };
+        if let Some(error) = &self.error {
+            hint = format!("{}\n{}", error, hint);
+        }
+        canvas.draw(
+            &graphics::Text::new(hint),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 190.0 + rows as f32 * 30.0 + 20.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+}
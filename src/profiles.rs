@@ -0,0 +1,98 @@
+// profiles.rs
+// 玩家档案：每个档案一个目录，分别保存设置、最高纪录、每日挑战历史和回放等存档文件
+// 目录结构：<配置目录>/profiles/<名称>/...，<配置目录>/profile.txt 记录上次使用的档案
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const PROFILES_DIR: &str = "profiles";
+const LAST_PROFILE_FILE: &str = "profile.txt";
+pub const DEFAULT_PROFILE: &str = "Player";
+pub const MAX_NAME_LEN: usize = 16;
+
+// 档案名同时用作目录名，只允许字母、数字、空格、- 和 _
+pub fn is_valid_name(name: &str) -> bool {
+    let name = name.trim();
+    !name.is_empty()
+        && name.chars().count() <= MAX_NAME_LEN
+        && name.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+}
+
+pub struct ProfileStore {
+    root: PathBuf,
+}
+
+impl ProfileStore {
+    pub fn new(root: &Path) -> Self {
+        ProfileStore { root: root.to_path_buf() }
+    }
+
+    pub fn dir(&self, name: &str) -> PathBuf {
+        self.root.join(PROFILES_DIR).join(name)
+    }
+
+    // 已有档案的名称，按名称排序
+    pub fn list(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(self.root.join(PROFILES_DIR)) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| is_valid_name(name))
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn create(&self, name: &str) -> io::Result<()> {
+        if !is_valid_name(name) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("invalid profile name '{}'", name)));
+        }
+        fs::create_dir_all(self.dir(name.trim()))
+    }
+
+    // 格式：last = 名称
+    pub fn last_used(&self) -> Option<String> {
+        let text = fs::read_to_string(self.root.join(LAST_PROFILE_FILE)).ok()?;
+        text.lines()
+            .filter_map(|line| line.split_once('='))
+            .find(|(key, _)| key.trim() == "last")
+            .map(|(_, value)| value.trim().to_string())
+            .filter(|name| is_valid_name(name) && self.dir(name).is_dir())
+    }
+
+    pub fn set_last_used(&self, name: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.root)?;
+        fs::write(self.root.join(LAST_PROFILE_FILE), format!("last = {}\n", name))
+    }
+
+    // 旧版本把存档直接放在配置目录下；还没有任何档案时把它们移入默认档案
+    pub fn migrate_legacy(&self) -> io::Result<()> {
+        if !self.list().is_empty() {
+            return Ok(());
+        }
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return Ok(());
+        };
+        let legacy: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+            .filter(|path| path.file_name().is_some_and(|name| name != LAST_PROFILE_FILE))
+            .collect();
+        if legacy.is_empty() {
+            return Ok(());
+        }
+
+        let target = self.dir(DEFAULT_PROFILE);
+        fs::create_dir_all(&target)?;
+        for path in legacy {
+            if let Some(name) = path.file_name() {
+                fs::rename(&path, target.join(name))?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,368 @@
+// progression.rs
+// 局外成长：每局结束获得点数，在机库中解锁飞船、初始武器和装饰尾迹，保存在玩家档案中
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ggez::graphics::Color;
+
+use crate::loadout::{Loadout, Primary, Secondary};
+
+const PROGRESS_FILE: &str = "progress.txt";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ship {
+    Standard,
+    Interceptor, // 更快更小
+    Gunship,     // 更慢更大，起始导弹更多
+}
+
+impl Ship {
+    pub const ALL: [Ship; 3] = [Ship::Standard, Ship::Interceptor, Ship::Gunship];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Ship::Standard => "standard",
+            Ship::Interceptor => "interceptor",
+            Ship::Gunship => "gunship",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Ship> {
+        Ship::ALL.iter().copied().find(|ship| ship.id() == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Ship::Standard => "Standard",
+            Ship::Interceptor => "Interceptor",
+            Ship::Gunship => "Gunship",
+        }
+    }
+
+    pub fn cost(self) -> u32 {
+        match self {
+            Ship::Standard => 0,
+            Ship::Interceptor => 200,
+            Ship::Gunship => 350,
+        }
+    }
+
+    pub fn speed_scale(self) -> f32 {
+        match self {
+            Ship::Standard => 1.0,
+            Ship::Interceptor => 1.3,
+            Ship::Gunship => 0.8,
+        }
+    }
+
+    // 加速度：按住方向时每秒获得的速度，以最高速度为单位
+    pub fn acceleration(self) -> f32 {
+        match self {
+            Ship::Standard => 8.0,
+            Ship::Interceptor => 10.0,
+            Ship::Gunship => 5.0,
+        }
+    }
+
+    // 摩擦：松开方向键后每秒损失的速度，以最高速度为单位，越小滑行越远
+    pub fn friction(self) -> f32 {
+        match self {
+            Ship::Standard => 6.0,
+            Ship::Interceptor => 4.0,
+            Ship::Gunship => 5.0,
+        }
+    }
+
+    pub fn size_scale(self) -> f32 {
+        match self {
+            Ship::Standard => 1.0,
+            Ship::Interceptor => 0.8,
+            Ship::Gunship => 1.25,
+        }
+    }
+
+    pub fn bonus_missiles(self) -> i32 {
+        match self {
+            Ship::Gunship => 3,
+            _ => 0,
+        }
+    }
+
+    pub fn tint(self) -> Color {
+        match self {
+            Ship::Standard => Color::WHITE,
+            Ship::Interceptor => Color::new(0.7, 0.9, 1.0, 1.0),
+            Ship::Gunship => Color::new(1.0, 0.85, 0.6, 1.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StartingWeapon {
+    Blaster,
+    MissilePack, // 额外 5 发导弹
+    Spread,      // 开局即拥有扇形射击
+}
+
+impl StartingWeapon {
+    pub const ALL: [StartingWeapon; 3] = [StartingWeapon::Blaster, StartingWeapon::MissilePack, StartingWeapon::Spread];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            StartingWeapon::Blaster => "blaster",
+            StartingWeapon::MissilePack => "missile_pack",
+            StartingWeapon::Spread => "spread",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<StartingWeapon> {
+        StartingWeapon::ALL.iter().copied().find(|weapon| weapon.id() == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StartingWeapon::Blaster => "Blaster",
+            StartingWeapon::MissilePack => "Missile Pack",
+            StartingWeapon::Spread => "Spread Shot",
+        }
+    }
+
+    pub fn cost(self) -> u32 {
+        match self {
+            StartingWeapon::Blaster => 0,
+            StartingWeapon::MissilePack => 150,
+            StartingWeapon::Spread => 300,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trail {
+    None,
+    Flame,
+    Ion,
+    Rainbow,
+}
+
+impl Trail {
+    pub const ALL: [Trail; 4] = [Trail::None, Trail::Flame, Trail::Ion, Trail::Rainbow];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Trail::None => "none",
+            Trail::Flame => "flame",
+            Trail::Ion => "ion",
+            Trail::Rainbow => "rainbow",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Trail> {
+        Trail::ALL.iter().copied().find(|trail| trail.id() == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Trail::None => "No Trail",
+            Trail::Flame => "Flame Trail",
+            Trail::Ion => "Ion Trail",
+            Trail::Rainbow => "Rainbow Trail",
+        }
+    }
+
+    pub fn cost(self) -> u32 {
+        match self {
+            Trail::None => 0,
+            Trail::Flame | Trail::Ion => 100,
+            Trail::Rainbow => 250,
+        }
+    }
+
+    // 尾迹粒子颜色，time 为游戏时间（秒），彩虹尾迹随时间变色
+    pub fn color(self, time: f32) -> Option<Color> {
+        match self {
+            Trail::None => None,
+            Trail::Flame => Some(Color::new(1.0, 0.6, 0.2, 0.9)),
+            Trail::Ion => Some(Color::new(0.4, 0.8, 1.0, 0.9)),
+            Trail::Rainbow => {
+                let phase = time * 3.0;
+                let channel = |offset: f32| 0.5 + 0.5 * (phase + offset).sin();
+                Some(Color::new(channel(0.0), channel(2.1), channel(4.2), 0.9))
+            }
+        }
+    }
+}
+
+// 机库中可购买的一项
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unlock {
+    Ship(Ship),
+    Weapon(StartingWeapon),
+    Trail(Trail),
+}
+
+impl Unlock {
+    pub fn all() -> Vec<Unlock> {
+        Ship::ALL.into_iter().map(Unlock::Ship)
+            .chain(StartingWeapon::ALL.into_iter().map(Unlock::Weapon))
+            .chain(Trail::ALL.into_iter().map(Unlock::Trail))
+            .collect()
+    }
+
+    // 保存用的 id，带类别前缀
+    pub fn id(self) -> String {
+        match self {
+            Unlock::Ship(ship) => format!("ship.{}", ship.id()),
+            Unlock::Weapon(weapon) => format!("weapon.{}", weapon.id()),
+            Unlock::Trail(trail) => format!("trail.{}", trail.id()),
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Unlock::Ship(ship) => format!("Ship: {}", ship.name()),
+            Unlock::Weapon(weapon) => format!("Weapon: {}", weapon.name()),
+            Unlock::Trail(trail) => format!("Trail: {}", trail.name()),
+        }
+    }
+
+    pub fn cost(self) -> u32 {
+        match self {
+            Unlock::Ship(ship) => ship.cost(),
+            Unlock::Weapon(weapon) => weapon.cost(),
+            Unlock::Trail(trail) => trail.cost(),
+        }
+    }
+}
+
+// 一局结束获得的点数
+pub fn credits_for_run(score: i32, wave: u32) -> u32 {
+    score.max(0) as u32 / 50 + wave.saturating_sub(1) * 5
+}
+
+#[derive(Clone, Debug)]
+pub struct Progress {
+    pub credits: u32,
+    unlocked: BTreeSet<String>,
+    pub ship: Ship,
+    pub weapon: StartingWeapon,
+    pub trail: Trail,
+    pub loadout: Loadout, // 上一次出击选择的装备
+    pub furthest_wave: u32, // 到达过的最远波次，练习模式可以从这之前的任意波次开始
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Progress {
+            credits: 0,
+            unlocked: BTreeSet::new(),
+            ship: Ship::Standard,
+            weapon: StartingWeapon::Blaster,
+            trail: Trail::None,
+            loadout: Loadout::default(),
+            furthest_wave: 1,
+        }
+    }
+}
+
+impl Progress {
+    fn path(dir: &Path) -> PathBuf {
+        dir.join(PROGRESS_FILE)
+    }
+
+    // 格式：key = value，unlocked 为空格分隔的 id 列表
+    pub fn load(dir: &Path) -> Self {
+        let mut progress = Progress::default();
+        let Ok(text) = fs::read_to_string(Self::path(dir)) else {
+            return progress;
+        };
+
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "credits" => progress.credits = value.parse().unwrap_or(0),
+                "unlocked" => progress.unlocked = value.split_whitespace().map(str::to_string).collect(),
+                "ship" => progress.ship = Ship::from_id(value).unwrap_or(progress.ship),
+                "weapon" => progress.weapon = StartingWeapon::from_id(value).unwrap_or(progress.weapon),
+                "trail" => progress.trail = Trail::from_id(value).unwrap_or(progress.trail),
+                "primary" => progress.loadout.primary = Primary::from_id(value).unwrap_or(progress.loadout.primary),
+                "secondary" => {
+                    progress.loadout.secondary = Secondary::from_id(value).unwrap_or(progress.loadout.secondary)
+                }
+                "furthest_wave" => progress.furthest_wave = value.parse().unwrap_or(1).max(1),
+                _ => {}
+            }
+        }
+
+        // 手动修改文件装备了未解锁的项目时退回默认
+        if !progress.is_unlocked(Unlock::Ship(progress.ship)) {
+            progress.ship = Ship::Standard;
+        }
+        if !progress.is_unlocked(Unlock::Weapon(progress.weapon)) {
+            progress.weapon = StartingWeapon::Blaster;
+        }
+        if !progress.is_unlocked(Unlock::Trail(progress.trail)) {
+            progress.trail = Trail::None;
+        }
+        if !Primary::available(&progress).contains(&progress.loadout.primary) {
+            progress.loadout.primary = Primary::Normal;
+        }
+        progress
+    }
+
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        let unlocked: Vec<&str> = self.unlocked.iter().map(String::as_str).collect();
+        let text = format!(
+            "credits = {}\nunlocked = {}\nship = {}\nweapon = {}\ntrail = {}\nprimary = {}\nsecondary = {}\nfurthest_wave = {}\n",
+            self.credits,
+            unlocked.join(" "),
+            self.ship.id(),
+            self.weapon.id(),
+            self.trail.id(),
+            self.loadout.primary.id(),
+            self.loadout.secondary.id(),
+            self.furthest_wave
+        );
+        fs::create_dir_all(dir)?;
+        fs::write(Self::path(dir), text)
+    }
+
+    // 免费项目默认解锁
+    pub fn is_unlocked(&self, unlock: Unlock) -> bool {
+        unlock.cost() == 0 || self.unlocked.contains(&unlock.id())
+    }
+
+    pub fn is_equipped(&self, unlock: Unlock) -> bool {
+        match unlock {
+            Unlock::Ship(ship) => self.ship == ship,
+            Unlock::Weapon(weapon) => self.weapon == weapon,
+            Unlock::Trail(trail) => self.trail == trail,
+        }
+    }
+
+    // 点数足够时购买，返回是否成功
+    pub fn buy(&mut self, unlock: Unlock) -> bool {
+        if self.is_unlocked(unlock) || self.credits < unlock.cost() {
+            return false;
+        }
+        self.credits -= unlock.cost();
+        self.unlocked.insert(unlock.id());
+        true
+    }
+
+    pub fn equip(&mut self, unlock: Unlock) -> bool {
+        if !self.is_unlocked(unlock) {
+            return false;
+        }
+        match unlock {
+            Unlock::Ship(ship) => self.ship = ship,
+            Unlock::Weapon(weapon) => self.weapon = weapon,
+            Unlock::Trail(trail) => self.trail = trail,
+        }
+        true
+    }
+}
@@ -0,0 +1,43 @@
+// render.rs
+// 渲染分层：每帧先把要画的内容连同所在层压入绘制队列，再按层从下到上依次绘制，
+// 同一层内保持压入顺序。新加的绘制内容只需选对层，不必关心它在 draw() 中的位置
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Background, // 星空等背景
+    Pickups,    // 掉落的道具
+    Enemies,    // 敌人、水雷、流星与 Boss
+    Player,     // 玩家、僚机和幽灵
+    Bullets,    // 双方的子弹
+    Particles,  // 爆炸、粒子、冲击波与攻击预警
+    Hud,        // 得分、雷达、血条、提示文字等
+    Overlay,    // 暂停、菜单、各类界面与场景过渡
+}
+
+impl Layer {
+    // 这一层是否属于游戏画面：泛光和屏幕滤镜只作用于游戏画面，HUD 与界面画在其上
+    pub fn is_world(self) -> bool {
+        self < Layer::Hud
+    }
+}
+
+pub struct DrawQueue<T> {
+    items: Vec<(Layer, T)>,
+}
+
+impl<T> Default for DrawQueue<T> {
+    fn default() -> Self {
+        DrawQueue { items: Vec::new() }
+    }
+}
+
+impl<T> DrawQueue<T> {
+    pub fn push(&mut self, layer: Layer, item: T) {
+        self.items.push((layer, item));
+    }
+
+    // 按层排序后的绘制顺序；排序是稳定的，同层内按压入顺序
+    pub fn into_sorted(mut self) -> impl Iterator<Item = (Layer, T)> {
+        self.items.sort_by_key(|(layer, _)| *layer);
+        self.items.into_iter()
+    }
+}
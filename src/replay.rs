@@ -0,0 +1,87 @@
+// replay.rs
+// 回放：按固定间隔记录玩家位置，用于“幽灵”重放最佳一局和标题画面的演示
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use glam::Vec2;
+
+use crate::highscores::GameMode;
+
+// 采样间隔（秒）
+pub const SAMPLE_INTERVAL: f32 = 1.0 / 30.0;
+
+#[derive(Clone, Debug, Default)]
+pub struct Replay {
+    positions: Vec<Vec2>,
+}
+
+impl Replay {
+    fn path(dir: &Path, mode: GameMode) -> PathBuf {
+        dir.join(format!("ghost_{}.txt", mode.id()))
+    }
+
+    pub fn load(dir: &Path, mode: GameMode) -> Option<Self> {
+        Self::parse(&fs::read_to_string(Self::path(dir, mode)).ok()?)
+    }
+
+    // 随游戏发布的回放，位于资源目录中
+    pub fn load_resource(ctx: &ggez::Context, path: &str) -> Option<Self> {
+        use std::io::Read;
+
+        let mut text = String::new();
+        ctx.fs.open(path).ok()?.read_to_string(&mut text).ok()?;
+        let replay = Self::parse(&text);
+        match &replay {
+            Some(replay) => log::debug!("Loaded {} ({} samples)", path, replay.positions.len()),
+            None => log::warn!("Replay {} has no samples", path),
+        }
+        replay
+    }
+
+    // 每行一个采样点：x,y
+    fn parse(text: &str) -> Option<Self> {
+        let positions: Vec<Vec2> = text
+            .lines()
+            .filter_map(|line| {
+                let (x, y) = line.split_once(',')?;
+                Some(Vec2::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+            })
+            .collect();
+        if positions.is_empty() {
+            None
+        } else {
+            Some(Replay { positions })
+        }
+    }
+
+    pub fn save(&self, dir: &Path, mode: GameMode) -> io::Result<()> {
+        let text: String = self
+            .positions
+            .iter()
+            .map(|pos| format!("{:.1},{:.1}\n", pos.x, pos.y))
+            .collect();
+        fs::create_dir_all(dir)?;
+        fs::write(Self::path(dir, mode), text)
+    }
+
+    pub fn clear(&mut self) {
+        self.positions.clear();
+    }
+
+    // 按时间补齐采样点
+    pub fn record(&mut self, time: f32, pos: Vec2) {
+        while (self.positions.len() as f32) * SAMPLE_INTERVAL <= time {
+            self.positions.push(pos);
+        }
+    }
+
+    // 取指定时间的位置，在相邻采样点间插值；超出记录范围返回 None
+    pub fn sample(&self, time: f32) -> Option<Vec2> {
+        let t = time / SAMPLE_INTERVAL;
+        let idx = t.floor() as usize;
+        let current = *self.positions.get(idx)?;
+        let next = self.positions.get(idx + 1).copied().unwrap_or(current);
+        Some(current.lerp(next, t.fract()))
+    }
+}
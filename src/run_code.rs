@@ -0,0 +1,61 @@
+// run_code.rs
+// 分享码：把一局的随机种子和挑战规则编码成一串短字符，别人输入后可以重玩同样的一局。
+// 64 位种子、8 位规则和 8 位校验共 80 位，用 Crockford Base32 写成 16 个字符，每 4 个一组
+use crate::mutators::Mutators;
+
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const CODE_CHARS: usize = 16;
+const GROUP: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunCode {
+    pub seed: u64,
+    pub mutators: Mutators,
+}
+
+impl RunCode {
+    pub fn encode(self) -> String {
+        let payload = ((self.seed as u128) << 16) | ((self.mutators.bits() as u128) << 8) | self.checksum() as u128;
+        let mut code = String::new();
+        for idx in 0..CODE_CHARS {
+            if idx > 0 && idx % GROUP == 0 {
+                code.push('-');
+            }
+            let shift = (CODE_CHARS - 1 - idx) * 5;
+            code.push(ALPHABET[((payload >> shift) & 0x1f) as usize] as char);
+        }
+        code
+    }
+
+    // 忽略大小写、分隔符和空白，容易看错的 I/L 按 1、O 按 0 处理；校验不通过时返回 None
+    pub fn decode(code: &str) -> Option<RunCode> {
+        let mut payload = 0u128;
+        let mut count = 0;
+        for character in code.chars().filter(|c| *c != '-' && !c.is_whitespace()) {
+            let character = match character.to_ascii_uppercase() {
+                'I' | 'L' => '1',
+                'O' => '0',
+                other => other,
+            };
+            let value = ALPHABET.iter().position(|symbol| *symbol as char == character)?;
+            payload = (payload << 5) | value as u128;
+            count += 1;
+        }
+        if count != CODE_CHARS {
+            return None;
+        }
+        let code = RunCode {
+            seed: (payload >> 16) as u64,
+            mutators: Mutators::from_bits((payload >> 8) as u8),
+        };
+        (code.checksum() == payload as u8).then_some(code)
+    }
+
+    fn checksum(self) -> u8 {
+        self.seed
+            .to_le_bytes()
+            .into_iter()
+            .chain([self.mutators.bits()])
+            .fold(0x5a, |sum: u8, byte| sum.rotate_left(3) ^ byte)
+    }
+}
@@ -0,0 +1,249 @@
+// scripting.rs
+// 波次脚本：resources/scripts/wave_<n>.rhai 在波次开始时执行一次，
+// 生成带等待时间的指令序列，游戏按时间轴逐条执行。
+// 脚本只能通过下面注册的函数影响游戏，无法访问文件系统等外部资源。
+// 未启用 rhai 特性时不会产生任何指令
+#![cfg_attr(not(feature = "rhai"), allow(dead_code))]
+use std::collections::VecDeque;
+
+use ggez::graphics::Color;
+
+use crate::dialogue::DialogueLine;
+use crate::enemies::{EnemyKind, EntryEdge};
+use crate::particles::AmbientEffect;
+
+// 脚本可播放的音效名称
+pub const SOUNDS: [&str; 3] = ["shoot", "explosion", "emp"];
+
+#[derive(Clone)]
+pub enum ScriptCommand {
+    Spawn { kind: EnemyKind, edge: EntryEdge, offset: f32 }, // offset 为沿进入边的基准坐标
+    SpawnPath { kind: EnemyKind, path: String },            // 沿 paths/<path>.txt 的路线飞行
+    Wait(f32),                         // 秒
+    SetBackground(Color),
+    PlaySound(&'static str),
+    Dialog(DialogueLine),
+    Ambient { effect: AmbientEffect, duration: Option<f32> }, // None 表示持续到 StopAmbient
+    StopAmbient(AmbientEffect),
+}
+
+// 正在执行的波次脚本
+#[derive(Clone)]
+pub struct WaveScript {
+    commands: VecDeque<ScriptCommand>,
+    wait: f32,
+}
+
+impl WaveScript {
+    pub fn new(commands: Vec<ScriptCommand>) -> Self {
+        WaveScript {
+            commands: commands.into(),
+            wait: 0.0,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.commands.is_empty() && self.wait <= 0.0
+    }
+
+    // 推进 dt 秒，返回到期需要执行的指令（等待指令在内部消化）
+    pub fn update(&mut self, dt: f32) -> Vec<ScriptCommand> {
+        let mut due = Vec::new();
+        self.wait -= dt;
+        while self.wait <= 0.0 {
+            match self.commands.pop_front() {
+                Some(ScriptCommand::Wait(secs)) => self.wait += secs,
+                Some(command) => due.push(command),
+                None => {
+                    self.wait = 0.0;
+                    break;
+                }
+            }
+        }
+        due
+    }
+}
+
+// 读取并执行指定波次的脚本；不存在或出错时返回 None
+#[cfg(feature = "rhai")]
+pub fn load(ctx: &ggez::Context, wave: u32) -> Option<WaveScript> {
+    use std::io::Read;
+
+    let path = format!("/scripts/wave_{}.rhai", wave);
+    if !ctx.fs.exists(&path) {
+        return None;
+    }
+
+    let mut source = String::new();
+    let read = ctx
+        .fs
+        .open(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|mut file| file.read_to_string(&mut source).map_err(|err| err.to_string()));
+    match read.and_then(|_| compile(&source)) {
+        Ok(commands) => {
+            log::debug!("Loaded {} ({} commands)", path, commands.len());
+            Some(WaveScript::new(commands))
+        }
+        Err(err) => {
+            log::error!("Failed to run {}: {}", path, err);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "rhai"))]
+pub fn load(_ctx: &ggez::Context, _wave: u32) -> Option<WaveScript> {
+    None
+}
+
+#[cfg(feature = "rhai")]
+fn compile(source: &str) -> Result<Vec<ScriptCommand>, String> {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use rhai::{Engine, EvalAltResult};
+
+    use crate::dialogue::{Speaker, DEFAULT_DURATION};
+
+    type ScriptResult = Result<(), Box<EvalAltResult>>;
+
+    let commands = Rc::new(RefCell::new(Vec::new()));
+    let mut engine = Engine::new();
+
+    // 限制脚本开销，防止死循环卡住游戏
+    engine.set_max_operations(100_000);
+    engine.set_max_call_levels(32);
+    engine.set_max_string_size(1024);
+    engine.set_max_array_size(1024);
+
+    let sink = commands.clone();
+    let spawn = move |kind: &str, x: f64| -> ScriptResult {
+        let kind = EnemyKind::from_id(kind).ok_or(format!("unknown enemy kind '{}'", kind))?;
+        sink.borrow_mut().push(ScriptCommand::Spawn { kind, edge: EntryEdge::Top, offset: x as f32 });
+        Ok(())
+    };
+    let spawn_int = spawn.clone();
+    engine.register_fn("spawn", spawn);
+    engine.register_fn("spawn", move |kind: &str, x: i64| spawn_int(kind, x as f64));
+
+    let sink = commands.clone();
+    let spawn_from = move |kind: &str, edge: &str, offset: f64| -> ScriptResult {
+        let kind = EnemyKind::from_id(kind).ok_or(format!("unknown enemy kind '{}'", kind))?;
+        let edge = parse_edge(edge).ok_or(format!("unknown edge '{}'", edge))?;
+        sink.borrow_mut().push(ScriptCommand::Spawn { kind, edge, offset: offset as f32 });
+        Ok(())
+    };
+    let spawn_from_int = spawn_from.clone();
+    engine.register_fn("spawn_from", spawn_from);
+    engine.register_fn("spawn_from", move |kind: &str, edge: &str, offset: i64| {
+        spawn_from_int(kind, edge, offset as f64)
+    });
+
+    // 路线名称只能引用 paths 目录下的文件
+    let sink = commands.clone();
+    engine.register_fn("spawn_path", move |kind: &str, path: &str| -> ScriptResult {
+        let kind = EnemyKind::from_id(kind).ok_or(format!("unknown enemy kind '{}'", kind))?;
+        if path.is_empty() || !path.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("invalid path name '{}'", path).into());
+        }
+        sink.borrow_mut().push(ScriptCommand::SpawnPath { kind, path: path.to_string() });
+        Ok(())
+    });
+
+    let sink = commands.clone();
+    let wait = move |secs: f64| -> ScriptResult {
+        if secs < 0.0 {
+            return Err("wait time must not be negative".into());
+        }
+        sink.borrow_mut().push(ScriptCommand::Wait(secs as f32));
+        Ok(())
+    };
+    let wait_int = wait.clone();
+    engine.register_fn("wait", wait);
+    engine.register_fn("wait", move |secs: i64| wait_int(secs as f64));
+
+    let sink = commands.clone();
+    engine.register_fn("set_background", move |r: f64, g: f64, b: f64| {
+        sink.borrow_mut().push(ScriptCommand::SetBackground(Color::new(
+            r.clamp(0.0, 1.0) as f32,
+            g.clamp(0.0, 1.0) as f32,
+            b.clamp(0.0, 1.0) as f32,
+            1.0,
+        )));
+    });
+
+    let sink = commands.clone();
+    engine.register_fn("play_sound", move |name: &str| -> ScriptResult {
+        let name = SOUNDS
+            .iter()
+            .find(|sound| **sound == name)
+            .ok_or(format!("unknown sound '{}'", name))?;
+        sink.borrow_mut().push(ScriptCommand::PlaySound(name));
+        Ok(())
+    });
+
+    let sink = commands.clone();
+    engine.register_fn("dialog", move |text: &str| {
+        sink.borrow_mut().push(ScriptCommand::Dialog(DialogueLine {
+            speaker: None,
+            text: text.to_string(),
+            duration: DEFAULT_DURATION,
+        }));
+    });
+
+    let sink = commands.clone();
+    let say = move |speaker: &str, text: &str, secs: f64| -> ScriptResult {
+        let speaker = Speaker::from_id(speaker).ok_or(format!("unknown speaker '{}'", speaker))?;
+        if secs < 0.0 {
+            return Err("dialog time must not be negative".into());
+        }
+        sink.borrow_mut().push(ScriptCommand::Dialog(DialogueLine {
+            speaker: Some(speaker),
+            text: text.to_string(),
+            duration: secs as f32,
+        }));
+        Ok(())
+    };
+    let say_default = say.clone();
+    let say_int = say.clone();
+    engine.register_fn("say", say);
+    engine.register_fn("say", move |speaker: &str, text: &str, secs: i64| say_int(speaker, text, secs as f64));
+    engine.register_fn("say", move |speaker: &str, text: &str| {
+        say_default(speaker, text, DEFAULT_DURATION as f64)
+    });
+
+    let sink = commands.clone();
+    let ambient = move |name: &str, duration: Option<f32>| -> ScriptResult {
+        let effect = AmbientEffect::from_id(name).ok_or(format!("unknown ambient effect '{}'", name))?;
+        sink.borrow_mut().push(ScriptCommand::Ambient { effect, duration });
+        Ok(())
+    };
+    let ambient_secs = ambient.clone();
+    let ambient_int = ambient.clone();
+    engine.register_fn("ambient", move |name: &str| ambient(name, None));
+    engine.register_fn("ambient", move |name: &str, secs: f64| ambient_secs(name, Some(secs.max(0.0) as f32)));
+    engine.register_fn("ambient", move |name: &str, secs: i64| ambient_int(name, Some(secs.max(0) as f32)));
+
+    let sink = commands.clone();
+    engine.register_fn("stop_ambient", move |name: &str| -> ScriptResult {
+        let effect = AmbientEffect::from_id(name).ok_or(format!("unknown ambient effect '{}'", name))?;
+        sink.borrow_mut().push(ScriptCommand::StopAmbient(effect));
+        Ok(())
+    });
+
+    engine.run(source).map_err(|err| err.to_string())?;
+
+    Ok(commands.take())
+}
+
+#[cfg(feature = "rhai")]
+fn parse_edge(id: &str) -> Option<EntryEdge> {
+    match id {
+        "top" => Some(EntryEdge::Top),
+        "left" => Some(EntryEdge::Left),
+        "right" => Some(EntryEdge::Right),
+        "bottom" => Some(EntryEdge::Bottom),
+        _ => None,
+    }
+}
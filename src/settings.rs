@@ -0,0 +1,345 @@
+// settings.rs
+// 设置界面：音量、难度、屏幕边界、泛光、屏幕滤镜、配色、低配模式、录像、移动手感、操作方案，
+// 以及辅助功能（自动射击、自动居中、躲避提示、判定显示、道具离屏提示）、挑战规则和按键重新绑定
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::boundary::BoundaryPolicy;
+use crate::config::{Config, Difficulty};
+use crate::input::{self, Action, ControlScheme, InputMap};
+use crate::mutators::Mutator;
+use crate::palette::Palette;
+use crate::postfx::ScreenFilter;
+use crate::ui::{UiEvent, Widget, WidgetList};
+use crate::{WindowSize, BASE_WINDOW_WIDTH};
+
+// 设置界面按键处理的结果
+pub enum SettingsEvent {
+    None,
+    Changed, // 配置已修改，需要立即生效
+    Close,   // 关闭并保存
+}
+
+enum Page {
+    Main,
+    Controls { selected: usize, capturing: bool },
+    Mutators(WidgetList),
+    Assists(WidgetList),
+}
+
+pub struct SettingsMenu {
+    page: Page,
+    widgets: WidgetList,
+}
+
+impl SettingsMenu {
+    pub fn new(config: &Config) -> Self {
+        let difficulty = Difficulty::ALL
+            .iter()
+            .position(|difficulty| *difficulty == config.difficulty)
+            .unwrap_or(1);
+        let boundary = BoundaryPolicy::ALL
+            .iter()
+            .position(|policy| *policy == config.boundary)
+            .unwrap_or(0);
+        let filter = ScreenFilter::ALL
+            .iter()
+            .position(|filter| *filter == config.screen_filter)
+            .unwrap_or(0);
+        let palette = Palette::ALL
+            .iter()
+            .position(|palette| *palette == config.palette)
+            .unwrap_or(0);
+        let scheme = ControlScheme::detect(&config.bindings)
+            .and_then(|scheme| ControlScheme::ALL.iter().position(|s| *s == scheme))
+            .unwrap_or(0);
+
+        SettingsMenu {
+            page: Page::Main,
+            widgets: WidgetList::new(vec![
+                Widget::slider("master_volume", "Master Volume", config.master_volume, 0.0, 1.0, 0.1),
+                Widget::slider("sfx_volume", "Effects Volume", config.sfx_volume, 0.0, 1.0, 0.1),
+                Widget::toggle("muted", "Mute", config.muted),
+                Widget::selector(
+                    "difficulty",
+                    "Difficulty",
+                    Difficulty::ALL.iter().map(|difficulty| difficulty.name()).collect(),
+                    difficulty,
+                ),
+                Widget::selector(
+                    "boundary",
+                    "Screen Edges",
+                    BoundaryPolicy::ALL.iter().map(|policy| policy.name()).collect(),
+                    boundary,
+                ),
+                Widget::toggle("bloom", "Bloom", config.bloom),
+                Widget::selector(
+                    "screen_filter",
+                    "Screen Filter",
+                    ScreenFilter::ALL.iter().map(|filter| filter.name()).collect(),
+                    filter,
+                ),
+                Widget::selector(
+                    "palette",
+                    "Colors",
+                    Palette::ALL.iter().map(|palette| palette.name()).collect(),
+                    palette,
+                ),
+                Widget::toggle("low_spec", "Low-Spec Mode", config.low_spec),
+                Widget::toggle("record_highlights", "Highlight Recorder", config.record_highlights),
+                Widget::toggle("classic_movement", "Classic Movement", config.classic_movement),
+                Widget::selector(
+                    "control_scheme",
+                    "Key Layout",
+                    ControlScheme::ALL.iter().map(|scheme| scheme.name()).collect(),
+                    scheme,
+                ),
+                Widget::button("assists", "Assists..."),
+                Widget::button("mutators", "Mutators..."),
+                Widget::button("rebind", "Rebind Keys..."),
+                Widget::button("back", "Back"),
+            ]),
+        }
+    }
+
+    pub fn key_down(&mut self, key: KeyCode, config: &mut Config) -> SettingsEvent {
+        match &mut self.page {
+            Page::Main => {
+                if key == KeyCode::Escape || key == KeyCode::F1 {
+                    return SettingsEvent::Close;
+                }
+                match self.widgets.key_down(key) {
+                    Some(UiEvent::Activated("rebind")) => {
+                        self.page = Page::Controls { selected: 0, capturing: false };
+                        SettingsEvent::None
+                    }
+                    Some(UiEvent::Activated("assists")) => {
+                        self.page = Page::Assists(WidgetList::new(vec![
+                            Widget::toggle("auto_fire", "Auto-Fire", config.auto_fire),
+                            Widget::toggle("auto_center", "Auto-Center Ship", config.auto_center),
+                            Widget::toggle("dodge_hints", "Dodge Hints", config.dodge_hints),
+                            Widget::toggle("show_hurtbox", "Always Show Hitbox", config.show_hurtbox),
+                            Widget::toggle("pickup_warnings", "Pickup Exit Warnings", config.pickup_warnings),
+                            Widget::button("back", "Back"),
+                        ]));
+                        SettingsEvent::None
+                    }
+                    Some(UiEvent::Activated("mutators")) => {
+                        let mut widgets: Vec<Widget> = Mutator::ALL
+                            .iter()
+                            .map(|mutator| Widget::toggle(mutator.id(), mutator.name(), config.mutators.contains(*mutator)))
+                            .collect();
+                        widgets.push(Widget::button("back", "Back"));
+                        self.page = Page::Mutators(WidgetList::new(widgets));
+                        SettingsEvent::None
+                    }
+                    Some(UiEvent::Activated("back")) => SettingsEvent::Close,
+                    Some(UiEvent::Changed(id)) => {
+                        self.apply(id, config);
+                        SettingsEvent::Changed
+                    }
+                    _ => SettingsEvent::None,
+                }
+            }
+            Page::Controls { selected, capturing } => {
+                let action = Action::ALL[*selected];
+                if *capturing {
+                    if key == KeyCode::Escape {
+                        *capturing = false;
+                    } else if input::is_bindable(key) {
+                        config.bindings.bind(action, key);
+                        *capturing = false;
+                    }
+                    return SettingsEvent::None;
+                }
+
+                match key {
+                    KeyCode::Up => *selected = (*selected + Action::ALL.len() - 1) % Action::ALL.len(),
+                    KeyCode::Down => *selected = (*selected + 1) % Action::ALL.len(),
+                    KeyCode::Return => *capturing = true,
+                    KeyCode::Back => config.bindings = InputMap::default(),
+                    KeyCode::Escape | KeyCode::F1 => self.page = Page::Main,
+                    _ => {}
+                }
+                SettingsEvent::None
+            }
+            // 挑战规则从下一局开始生效
+            Page::Mutators(widgets) => {
+                if key == KeyCode::Escape || key == KeyCode::F1 {
+                    self.page = Page::Main;
+                    return SettingsEvent::None;
+                }
+                match widgets.key_down(key) {
+                    Some(UiEvent::Activated("back")) => self.page = Page::Main,
+                    Some(UiEvent::Changed(id)) => {
+                        if let Some(mutator) = Mutator::from_id(id) {
+                            config.mutators.set(mutator, widgets.toggle_value(id).unwrap_or(false));
+                        }
+                    }
+                    _ => {}
+                }
+                SettingsEvent::None
+            }
+            // 辅助功能立即生效
+            Page::Assists(widgets) => {
+                if key == KeyCode::Escape || key == KeyCode::F1 {
+                    self.page = Page::Main;
+                    return SettingsEvent::None;
+                }
+                match widgets.key_down(key) {
+                    Some(UiEvent::Activated("back")) => {
+                        self.page = Page::Main;
+                        SettingsEvent::None
+                    }
+                    Some(UiEvent::Changed(id)) => {
+                        let value = widgets.toggle_value(id).unwrap_or(false);
+                        match id {
+                            "auto_fire" => config.auto_fire = value,
+                            "auto_center" => config.auto_center = value,
+                            "dodge_hints" => config.dodge_hints = value,
+                            "show_hurtbox" => config.show_hurtbox = value,
+                            "pickup_warnings" => config.pickup_warnings = value,
+                            _ => {}
+                        }
+                        SettingsEvent::Changed
+                    }
+                    _ => SettingsEvent::None,
+                }
+            }
+        }
+    }
+
+    // 把控件取值写回配置
+    fn apply(&self, id: &str, config: &mut Config) {
+        match id {
+            "master_volume" => {
+                config.master_volume = self.widgets.slider_value(id).unwrap_or(config.master_volume);
+            }
+            "sfx_volume" => {
+                config.sfx_volume = self.widgets.slider_value(id).unwrap_or(config.sfx_volume);
+            }
+            "muted" => {
+                config.muted = self.widgets.toggle_value(id).unwrap_or(config.muted);
+            }
+            "difficulty" => {
+                if let Some(index) = self.widgets.selector_index(id) {
+                    config.difficulty = Difficulty::ALL[index];
+                }
+            }
+            "boundary" => {
+                if let Some(index) = self.widgets.selector_index(id) {
+                    config.boundary = BoundaryPolicy::ALL[index];
+                }
+            }
+            "bloom" => {
+                config.bloom = self.widgets.toggle_value(id).unwrap_or(config.bloom);
+            }
+            "screen_filter" => {
+                if let Some(index) = self.widgets.selector_index(id) {
+                    config.screen_filter = ScreenFilter::ALL[index];
+                }
+            }
+            "palette" => {
+                if let Some(index) = self.widgets.selector_index(id) {
+                    config.palette = Palette::ALL[index];
+                }
+            }
+            "low_spec" => {
+                config.low_spec = self.widgets.toggle_value(id).unwrap_or(config.low_spec);
+            }
+            "record_highlights" => {
+                config.record_highlights = self.widgets.toggle_value(id).unwrap_or(config.record_highlights);
+            }
+            "classic_movement" => {
+                config.classic_movement = self.widgets.toggle_value(id).unwrap_or(config.classic_movement);
+            }
+            "control_scheme" => {
+                if let Some(index) = self.widgets.selector_index(id) {
+                    ControlScheme::ALL[index].apply(&mut config.bindings);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize, bindings: &InputMap) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let left = BASE_WINDOW_WIDTH / 2.0 - 200.0;
+
+        // 半透明背景
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left - 20.0, 65.0)))
+                .scale(window_size.scale_vec2(Vec2::new(460.0, 700.0)))
+                .color(Color::new(0.0, 0.0, 0.0, 0.8)),
+        );
+
+        let (title, hint) = match self.page {
+            Page::Main => (
+                "SETTINGS",
+                "Up/Down: select   Left/Right: change\nEnter: activate   Esc: save & close",
+            ),
+            Page::Controls { .. } => (
+                "SETTINGS - Controls",
+                "Up/Down: select   Enter: rebind\nBackspace: reset defaults   Esc: back",
+            ),
+            Page::Mutators(_) => (
+                "SETTINGS - Mutators",
+                "Up/Down: select   Enter: toggle\nApplies from the next run   Esc: back",
+            ),
+            Page::Assists(_) => ("SETTINGS - Assists", "Up/Down: select   Enter: toggle\nEsc: back"),
+        };
+        canvas.draw(
+            &graphics::Text::new(title),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 85.0)))
+                .color(Color::YELLOW)
+                .scale(scale * 1.5),
+        );
+
+        let rows = match &self.page {
+            Page::Main => {
+                self.widgets.draw(canvas, window_size, Vec2::new(left, 125.0));
+                self.widgets.widgets.len()
+            }
+            Page::Mutators(widgets) | Page::Assists(widgets) => {
+                widgets.draw(canvas, window_size, Vec2::new(left, 125.0));
+                widgets.widgets.len()
+            }
+            Page::Controls { selected, capturing } => {
+                for (idx, action) in Action::ALL.iter().enumerate() {
+                    let is_selected = idx == *selected;
+                    let keys = if is_selected && *capturing {
+                        "Press a key...".to_string()
+                    } else {
+                        bindings.describe(*action)
+                    };
+                    let line = graphics::Text::new(format!(
+                        "{} {:<12} {}",
+                        if is_selected { ">" } else { " " },
+                        action.label(),
+                        keys
+                    ));
+                    canvas.draw(
+                        &line,
+                        DrawParam::default()
+                            .dest(window_size.scale_vec2(Vec2::new(left, 125.0 + idx as f32 * 30.0)))
+                            .color(if is_selected { Color::YELLOW } else { Color::WHITE })
+                            .scale(scale),
+                    );
+                }
+                Action::ALL.len()
+            }
+        };
+
+        canvas.draw(
+            &graphics::Text::new(hint),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 125.0 + rows as f32 * 34.0 + 20.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+}
@@ -0,0 +1,140 @@
+// shop.rs
+// 波次间商店：每隔几波暂停游戏，用分数购买续关次数、导弹或武器升级
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::{WindowSize, BASE_WINDOW_WIDTH};
+
+pub const MAX_WEAPON_TIER: u32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShopItem {
+    ExtraLife,   // 多一次续关
+    Missiles,    // 5 发导弹
+    UpgradeTier, // 提升射速
+}
+
+impl ShopItem {
+    pub const ALL: [ShopItem; 3] = [ShopItem::ExtraLife, ShopItem::Missiles, ShopItem::UpgradeTier];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ShopItem::ExtraLife => "Extra Life",
+            ShopItem::Missiles => "Missiles x5",
+            ShopItem::UpgradeTier => "Weapon Upgrade",
+        }
+    }
+
+    // 价格（分数），升级越往后越贵；已满级时返回 None
+    pub fn price(self, weapon_tier: u32) -> Option<i32> {
+        match self {
+            ShopItem::ExtraLife => Some(1500),
+            ShopItem::Missiles => Some(400),
+            ShopItem::UpgradeTier if weapon_tier < MAX_WEAPON_TIER => Some(800 * (weapon_tier as i32 + 1)),
+            ShopItem::UpgradeTier => None,
+        }
+    }
+}
+
+// 商店界面按键处理的结果
+pub enum ShopEvent {
+    None,
+    Buy(ShopItem),
+    Close, // 离开商店，继续下一波
+}
+
+pub struct Shop {
+    selected: usize, // 等于 ShopItem::ALL.len() 时选中“继续”
+    pub message: Option<String>,
+}
+
+impl Shop {
+    pub fn new() -> Self {
+        Shop { selected: 0, message: None }
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> ShopEvent {
+        let rows = ShopItem::ALL.len() + 1;
+        match key {
+            KeyCode::Up | KeyCode::W => self.selected = (self.selected + rows - 1) % rows,
+            KeyCode::Down | KeyCode::S => self.selected = (self.selected + 1) % rows,
+            KeyCode::Return | KeyCode::Space => {
+                return match ShopItem::ALL.get(self.selected) {
+                    Some(item) => ShopEvent::Buy(*item),
+                    None => ShopEvent::Close,
+                };
+            }
+            KeyCode::Escape => return ShopEvent::Close,
+            _ => {}
+        }
+        self.message = None;
+        ShopEvent::None
+    }
+
+    // prices 与 ShopItem::ALL 一一对应，None 表示当前不可购买
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize, score: i32, prices: &[Option<i32>]) {
+        let scale = Vec2::new(window_size.scale_x, window_size.scale_y);
+        let left = BASE_WINDOW_WIDTH / 2.0 - 200.0;
+
+        // 半透明背景
+        canvas.draw(
+            &graphics::Quad,
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left - 20.0, 160.0)))
+                .scale(window_size.scale_vec2(Vec2::new(460.0, 320.0)))
+                .color(Color::new(0.0, 0.0, 0.0, 0.85)),
+        );
+        canvas.draw(
+            &graphics::Text::new(format!("SHOP   Score: {}", score)),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 180.0)))
+                .color(Color::YELLOW)
+                .scale(scale * 1.5),
+        );
+
+        let rows = ShopItem::ALL
+            .iter()
+            .zip(prices)
+            .map(|(item, price)| {
+                let price = price.map_or("-".to_string(), |price| price.to_string());
+                (item.name(), price)
+            })
+            .chain(std::iter::once(("Continue", String::new())));
+        for (idx, (name, price)) in rows.enumerate() {
+            let is_selected = idx == self.selected;
+            let affordable = prices.get(idx).is_none_or(|price| price.is_some_and(|price| price <= score));
+            let color = if is_selected {
+                Color::YELLOW
+            } else if affordable {
+                Color::WHITE
+            } else {
+                Color::new(0.5, 0.5, 0.5, 1.0)
+            };
+            let row = Vec2::new(left, 230.0 + idx as f32 * 32.0);
+            canvas.draw(
+                &graphics::Text::new(format!("{} {}", if is_selected { ">" } else { " " }, name)),
+                DrawParam::default().dest(window_size.scale_vec2(row)).color(color).scale(scale),
+            );
+            canvas.draw(
+                &graphics::Text::new(price),
+                DrawParam::default()
+                    .dest(window_size.scale_vec2(row + Vec2::new(300.0, 0.0)))
+                    .color(color)
+                    .scale(scale),
+            );
+        }
+
+        let mut hint = "Up/Down: select   Enter: buy\nPrices are paid with score".to_string();
+        if let Some(message) = &self.message {
+            hint = format!("{}\n{}", message, hint);
+        }
+        canvas.draw(
+            &graphics::Text::new(hint),
+            DrawParam::default()
+                .dest(window_size.scale_vec2(Vec2::new(left, 230.0 + (ShopItem::ALL.len() + 1) as f32 * 32.0 + 16.0)))
+                .color(Color::new(0.7, 0.7, 0.7, 1.0))
+                .scale(scale),
+        );
+    }
+}
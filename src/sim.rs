@@ -0,0 +1,145 @@
+// sim.rs
+// 模拟核心与表现层的分界：一个 tick 的模拟只由当前状态和双方输入决定下一个状态，
+// 不接触 Context、不播放声音、不使用模拟随机数以外的随机源。声音、粒子、爆炸动画等效果
+// 记为 Cue，tick 结束后由表现层统一播放；重新模拟时静音丢弃。
+// Rollback 保存最近若干 tick 的状态快照和输入，可以回到过去的 tick 按同样的输入重新模拟
+use std::collections::VecDeque;
+
+use ggez::graphics::Color;
+use glam::Vec2;
+
+use crate::animation::ExplosionKind;
+use crate::dialogue::DialogueLine;
+use crate::input::TickInput;
+use crate::particles::{AmbientEffect, ColorGradient, EmitterKind};
+use crate::paths::EnemyPath;
+use crate::scripting::{self, WaveScript};
+use crate::sounds::SoundEvent;
+
+// 一个 tick 的输入：一号机，以及联机合作时的二号机
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Inputs {
+    pub player: TickInput,
+    pub partner: Option<TickInput>,
+}
+
+// 模拟发给表现层的效果，不影响之后的模拟
+pub enum Cue {
+    Sound(SoundEvent),
+    Music { path: &'static str, pitch: f32 },
+    StopMusic,
+    Emit { emitter: EmitterKind, pos: Vec2, direction: f32, gradient: ColorGradient },
+    Burst { pos: Vec2, color: Color },                       // 一团向四周散开的粒子
+    Explosion { kind: ExplosionKind, pos: Vec2, size: f32 }, // 爆炸帧动画
+    Ambient { effect: AmbientEffect, duration: Option<f32> },
+    StopAmbient(AmbientEffect),
+    Popup { pos: Vec2, points: i32, color: Color },
+    Announce { pos: Vec2, label: String, points: i32, color: Color },
+    Dialog(DialogueLine),
+}
+
+// 本 tick 积累的效果
+#[derive(Default)]
+pub struct Cues {
+    pending: Vec<Cue>,
+    muted: bool,
+}
+
+impl Cues {
+    pub fn push(&mut self, cue: Cue) {
+        if !self.muted {
+            self.pending.push(cue);
+        }
+    }
+
+    pub fn sound(&mut self, event: SoundEvent) {
+        self.push(Cue::Sound(event));
+    }
+
+    pub fn burst(&mut self, pos: Vec2, color: Color) {
+        self.push(Cue::Burst { pos, color });
+    }
+
+    pub fn explosion(&mut self, kind: ExplosionKind, pos: Vec2, size: f32) {
+        self.push(Cue::Explosion { kind, pos, size });
+    }
+
+    pub fn emit(&mut self, emitter: EmitterKind, pos: Vec2, direction: f32, gradient: ColorGradient) {
+        self.push(Cue::Emit { emitter, pos, direction, gradient });
+    }
+
+    // 重新模拟已经播放过的 tick 时静音，期间的效果直接丢弃
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    pub fn take(&mut self) -> Vec<Cue> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+// 模拟中按需读取的资源（波次脚本、敌人路线）
+pub trait Resources {
+    fn wave_script(&self, wave: u32) -> Option<WaveScript>;
+    fn enemy_path(&self, name: &str) -> Option<EnemyPath>;
+}
+
+impl Resources for ggez::Context {
+    fn wave_script(&self, wave: u32) -> Option<WaveScript> {
+        scripting::load(self, wave)
+    }
+
+    fn enemy_path(&self, name: &str) -> Option<EnemyPath> {
+        EnemyPath::load_resource(self, name)
+    }
+}
+
+// 最近若干 tick 的记录：模拟该 tick 之前的状态，以及该 tick 使用的输入
+pub struct Rollback<S> {
+    frames: VecDeque<(S, Inputs)>,
+    capacity: usize,
+}
+
+impl<S> Rollback<S> {
+    pub fn new(capacity: usize) -> Self {
+        Rollback { frames: VecDeque::with_capacity(capacity), capacity: capacity.max(1) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    // 记录即将模拟的 tick，超出容量时丢弃最早的记录
+    pub fn push(&mut self, state: S, inputs: Inputs) {
+        if self.frames.len() >= self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back((state, inputs));
+    }
+
+    // 回到 ticks 个 tick 之前（记录不足时回到最早的记录），返回当时的状态和此后各 tick 的输入。
+    // 这些记录随之移除，按返回的输入重新模拟时会再次记录
+    pub fn rewind(&mut self, ticks: usize) -> Option<(S, Vec<Inputs>)> {
+        let at = self.frames.len().saturating_sub(ticks.max(1));
+        let mut frames = self.frames.split_off(at).into_iter();
+        let (state, first) = frames.next()?;
+        let inputs = std::iter::once(first).chain(frames.map(|(_, inputs)| inputs)).collect();
+        Some((state, inputs))
+    }
+}
+
+// 状态校验值（FNV-1a），用于比较两次模拟（或联机两端的模拟）是否一致
+pub fn checksum(values: &[u64]) -> u64 {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
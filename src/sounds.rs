@@ -0,0 +1,323 @@
+// sounds.rs
+// 音效库：每个事件对应若干备选音频，播放时随机挑选一个并随机微调音高和音量，
+// 连续射击和成群击毁时不会听起来千篇一律。
+// 音频后端通过 Audio trait 抽象，--no-audio、没有启用 audio 特性或音效初始化失败时换成静默实现
+#[cfg(feature = "audio")]
+use std::collections::HashMap;
+#[cfg(feature = "audio")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "audio")]
+use ggez::audio::{SoundData, SoundSource, Source};
+#[cfg(not(feature = "audio"))]
+use ggez::GameError;
+use ggez::GameResult;
+#[cfg(feature = "audio")]
+use rand::Rng;
+
+use crate::assets::Assets;
+#[cfg(feature = "audio")]
+use crate::boss;
+use crate::enemies::EnemyKind;
+
+// 发出射击声的武器
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Weapon {
+    Blaster,
+    Spread,
+    Laser,
+    Missile,
+    Drone,
+}
+
+impl Weapon {
+    pub const ALL: [Weapon; 5] = [Weapon::Blaster, Weapon::Spread, Weapon::Laser, Weapon::Missile, Weapon::Drone];
+}
+
+// 连锁、清波、击毁 Boss 时的提示音；同一时间只播放一个
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stinger {
+    Combo,
+    WaveClear,
+    BossKill,
+}
+
+impl Stinger {
+    pub const ALL: [Stinger; 3] = [Stinger::Combo, Stinger::WaveClear, Stinger::BossKill];
+
+    // 冷却期间只有更重要的提示音可以打断正在播放的提示音
+    #[cfg_attr(not(feature = "audio"), allow(dead_code))]
+    fn priority(self) -> u8 {
+        match self {
+            Stinger::Combo => 0,
+            Stinger::WaveClear => 1,
+            Stinger::BossKill => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SoundEvent {
+    Shot(Weapon),
+    EnemyDeath(EnemyKind),
+    BossPhase,   // Boss 进入新阶段
+    BossDeath,
+    PlayerDeath,
+    Explosion,   // 水雷、炮击等场景爆炸
+    Emp,
+    PickupWarning, // 稀有道具即将掉出屏幕
+    Stinger(Stinger),
+}
+
+impl SoundEvent {
+    pub fn all() -> Vec<SoundEvent> {
+        Weapon::ALL
+            .iter()
+            .map(|weapon| SoundEvent::Shot(*weapon))
+            .chain(EnemyKind::ALL.iter().map(|kind| SoundEvent::EnemyDeath(*kind)))
+            .chain([
+                SoundEvent::BossPhase,
+                SoundEvent::BossDeath,
+                SoundEvent::PlayerDeath,
+                SoundEvent::Explosion,
+                SoundEvent::Emp,
+                SoundEvent::PickupWarning,
+            ])
+            .chain(Stinger::ALL.iter().map(|stinger| SoundEvent::Stinger(*stinger)))
+            .collect()
+    }
+
+    // 按名称查找音效（供波次脚本使用，名称见 scripting::SOUNDS）
+    pub fn named(name: &str) -> Option<SoundEvent> {
+        match name {
+            "shoot" => Some(SoundEvent::Shot(Weapon::Blaster)),
+            "explosion" => Some(SoundEvent::Explosion),
+            "emp" => Some(SoundEvent::Emp),
+            _ => None,
+        }
+    }
+}
+
+// 音效参数：音高和音量在 (1 ± jitter) 倍之间随机
+#[cfg_attr(not(feature = "audio"), allow(dead_code))]
+struct SoundDef {
+    clips: &'static [&'static str],
+    volume: f32,
+    pitch: f32,
+    pitch_jitter: f32,
+    volume_jitter: f32,
+}
+
+#[cfg(feature = "audio")]
+const MUSIC_VOLUME: f32 = 0.4; // 背景音乐相对音效的音量
+#[cfg(feature = "audio")]
+const STINGER_COOLDOWN: Duration = Duration::from_millis(1500); // 提示音之间的最短间隔
+
+const SHOOT: &[&str] = &["/sound/shoot.wav"];
+const EXPLOSIONS: &[&str] = &["/sound/expl0.wav", "/sound/expl1.wav"];
+const POWER: &[&str] = &["/sound/pow0.wav", "/sound/pow1.wav"];
+
+const fn sound(clips: &'static [&'static str], volume: f32, pitch: f32, pitch_jitter: f32) -> SoundDef {
+    SoundDef { clips, volume, pitch, pitch_jitter, volume_jitter: 0.1 }
+}
+
+fn def(event: SoundEvent) -> SoundDef {
+    match event {
+        SoundEvent::Shot(Weapon::Blaster) => sound(SHOOT, 0.3, 1.0, 0.06),
+        SoundEvent::Shot(Weapon::Spread) => sound(SHOOT, 0.3, 0.8, 0.06),
+        SoundEvent::Shot(Weapon::Laser) => sound(POWER, 0.25, 1.8, 0.04),
+        SoundEvent::Shot(Weapon::Missile) => sound(POWER, 0.35, 1.4, 0.05),
+        SoundEvent::Shot(Weapon::Drone) => sound(SHOOT, 0.15, 1.5, 0.08),
+        // 体型越大的敌人爆炸声越低沉
+        SoundEvent::EnemyDeath(kind) => match kind {
+            EnemyKind::Fighter => sound(EXPLOSIONS, 0.5, 1.0, 0.1),
+            EnemyKind::Bomber => sound(EXPLOSIONS, 0.55, 0.8, 0.08),
+            EnemyKind::Splitter => sound(EXPLOSIONS, 0.5, 1.1, 0.1),
+            EnemyKind::Shard => sound(EXPLOSIONS, 0.35, 1.5, 0.12),
+            EnemyKind::MineLayer => sound(EXPLOSIONS, 0.5, 0.9, 0.1),
+            EnemyKind::Tank => sound(EXPLOSIONS, 0.65, 0.6, 0.05),
+            EnemyKind::Kamikaze => sound(EXPLOSIONS, 0.5, 1.25, 0.1),
+            EnemyKind::Carrier => sound(EXPLOSIONS, 0.7, 0.5, 0.05),
+            EnemyKind::Interceptor => sound(EXPLOSIONS, 0.3, 1.6, 0.12),
+            EnemyKind::Sentinel => sound(EXPLOSIONS, 0.55, 0.95, 0.1),
+        },
+        SoundEvent::BossPhase => sound(EXPLOSIONS, 0.6, 0.7, 0.0),
+        SoundEvent::BossDeath => sound(EXPLOSIONS, 0.8, 0.5, 0.0),
+        SoundEvent::PlayerDeath => sound(EXPLOSIONS, 0.6, 0.75, 0.03),
+        SoundEvent::Explosion => sound(EXPLOSIONS, 0.5, 0.85, 0.1),
+        SoundEvent::Emp => sound(POWER, 0.6, 1.0, 0.0),
+        SoundEvent::PickupWarning => sound(POWER, 0.15, 2.2, 0.02),
+        // 提示音不做随机微调，每次听起来都一样，容易辨认
+        SoundEvent::Stinger(Stinger::Combo) => sound(POWER, 0.5, 1.5, 0.0),
+        SoundEvent::Stinger(Stinger::WaveClear) => sound(POWER, 0.55, 1.1, 0.0),
+        SoundEvent::Stinger(Stinger::BossKill) => sound(POWER, 0.7, 0.7, 0.0),
+    }
+}
+
+// 所有音效用到的音频文件（可能重复），供载入界面预加载
+pub fn clip_paths() -> Vec<&'static str> {
+    SoundEvent::all().into_iter().flat_map(|event| def(event).clips.iter().copied()).collect()
+}
+
+#[cfg(feature = "audio")]
+pub struct SoundBank {
+    clips: HashMap<SoundEvent, Vec<Source>>,
+    volume: f32,
+    stinger: Option<(Stinger, Instant)>, // 最近一次播放的提示音及其开始时间
+}
+
+#[cfg(feature = "audio")]
+impl SoundBank {
+    pub fn new(ctx: &mut ggez::Context, assets: &Assets) -> GameResult<Self> {
+        let mut clips = HashMap::new();
+        for event in SoundEvent::all() {
+            let sources = def(event)
+                .clips
+                .iter()
+                .map(|path| Source::from_data(ctx, assets.sound(path)?))
+                .collect::<GameResult<Vec<_>>>()?;
+            clips.insert(event, sources);
+        }
+        log::info!("Loaded sound bank ({} events)", clips.len());
+        Ok(SoundBank { clips, volume: 1.0, stinger: None })
+    }
+
+    // 随机音高只影响听感，使用线程随机数，不占用游戏的种子随机序列
+    pub fn play(&mut self, ctx: &mut ggez::Context, event: SoundEvent) -> GameResult {
+        if let SoundEvent::Stinger(stinger) = event {
+            if !self.start_stinger(ctx, stinger)? {
+                return Ok(());
+            }
+        }
+        let def = def(event);
+        let Some(sources) = self.clips.get_mut(&event) else {
+            return Ok(());
+        };
+        let mut rng = rand::thread_rng();
+        let index = rng.gen_range(0..sources.len());
+        let source = &mut sources[index];
+        if source.playing() {
+            source.stop(ctx)?;
+        }
+        let jitter = |rng: &mut rand::rngs::ThreadRng, amount: f32| 1.0 + rng.gen_range(-1.0..=1.0) * amount;
+        source.set_pitch(def.pitch * jitter(&mut rng, def.pitch_jitter));
+        source.set_volume(def.volume * self.volume * jitter(&mut rng, def.volume_jitter));
+        source.play(ctx)
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+    }
+
+    // 提示音互不重叠：冷却期间同级或较次要的提示音直接丢弃，更重要的则打断正在播放的提示音
+    fn start_stinger(&mut self, ctx: &mut ggez::Context, stinger: Stinger) -> GameResult<bool> {
+        if let Some((current, started)) = self.stinger {
+            if started.elapsed() < STINGER_COOLDOWN {
+                if stinger.priority() <= current.priority() {
+                    return Ok(false);
+                }
+                for source in self.clips.get_mut(&SoundEvent::Stinger(current)).into_iter().flatten() {
+                    if source.playing() {
+                        source.stop(ctx)?;
+                    }
+                }
+            }
+        }
+        self.stinger = Some((stinger, Instant::now()));
+        Ok(true)
+    }
+}
+
+pub trait Audio {
+    fn play(&mut self, ctx: &mut ggez::Context, event: SoundEvent) -> GameResult;
+    // 循环播放背景音乐，替换正在播放的音乐
+    fn play_music(&mut self, ctx: &mut ggez::Context, path: &str, pitch: f32) -> GameResult;
+    fn stop_music(&mut self, ctx: &mut ggez::Context) -> GameResult;
+    fn set_volume(&mut self, volume: f32);
+}
+
+// 打开 ggez 音频后端
+#[cfg(feature = "audio")]
+pub fn open(ctx: &mut ggez::Context, assets: &Assets) -> GameResult<Box<dyn Audio>> {
+    Ok(Box::new(GgezAudio::new(ctx, assets)?))
+}
+
+#[cfg(not(feature = "audio"))]
+pub fn open(_ctx: &mut ggez::Context, _assets: &Assets) -> GameResult<Box<dyn Audio>> {
+    Err(GameError::AudioError("built without the audio feature".to_string()))
+}
+
+// 使用 ggez 音频播放音效和背景音乐
+#[cfg(feature = "audio")]
+pub struct GgezAudio {
+    bank: SoundBank,
+    tracks: HashMap<&'static str, SoundData>, // 预加载的背景音乐
+    music: Option<Source>, // 正在循环播放的背景音乐
+    volume: f32,           // 当前的总音量，用于新播放的音乐
+}
+
+#[cfg(feature = "audio")]
+impl GgezAudio {
+    pub fn new(ctx: &mut ggez::Context, assets: &Assets) -> GameResult<Self> {
+        let tracks = boss::music_tracks()
+            .map(|path| Ok((path, assets.sound(path)?)))
+            .collect::<GameResult<_>>()?;
+        Ok(GgezAudio { bank: SoundBank::new(ctx, assets)?, tracks, music: None, volume: 1.0 })
+    }
+}
+
+#[cfg(feature = "audio")]
+impl Audio for GgezAudio {
+    fn play(&mut self, ctx: &mut ggez::Context, event: SoundEvent) -> GameResult {
+        self.bank.play(ctx, event)
+    }
+
+    fn play_music(&mut self, ctx: &mut ggez::Context, path: &str, pitch: f32) -> GameResult {
+        self.stop_music(ctx)?;
+        let data = match self.tracks.get(path) {
+            Some(data) => data.clone(),
+            None => SoundData::new(ctx, path)?,
+        };
+        let mut music = Source::from_data(ctx, data)?;
+        music.set_repeat(true);
+        music.set_pitch(pitch);
+        music.set_volume(MUSIC_VOLUME * self.volume);
+        music.play(ctx)?;
+        self.music = Some(music);
+        Ok(())
+    }
+
+    fn stop_music(&mut self, ctx: &mut ggez::Context) -> GameResult {
+        if let Some(mut music) = self.music.take() {
+            music.stop(ctx)?;
+        }
+        Ok(())
+    }
+
+    fn set_volume(&mut self, volume: f32) {
+        self.bank.set_volume(volume);
+        if let Some(music) = &mut self.music {
+            music.set_volume(MUSIC_VOLUME * volume);
+        }
+        self.volume = volume;
+    }
+}
+
+// 静默后端：不加载也不播放任何音频
+pub struct NullAudio;
+
+impl Audio for NullAudio {
+    fn play(&mut self, _ctx: &mut ggez::Context, _event: SoundEvent) -> GameResult {
+        Ok(())
+    }
+
+    fn play_music(&mut self, _ctx: &mut ggez::Context, _path: &str, _pitch: f32) -> GameResult {
+        Ok(())
+    }
+
+    fn stop_music(&mut self, _ctx: &mut ggez::Context) -> GameResult {
+        Ok(())
+    }
+
+    fn set_volume(&mut self, _volume: f32) {}
+}
@@ -0,0 +1,64 @@
+// spatial.rs
+// 均匀网格空间索引，用于范围查询（爆炸伤害等）
+use std::collections::HashMap;
+
+use glam::Vec2;
+
+use crate::arena::Handle;
+
+// K 为对象的引用方式：对象池中的对象用 Handle，普通列表中的对象用下标
+pub struct SpatialGrid<K = Handle> {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<(K, Vec2)>>,
+}
+
+impl<K: Copy> SpatialGrid<K> {
+    pub fn new(cell_size: f32) -> Self {
+        SpatialGrid {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn build(cell_size: f32, entries: impl IntoIterator<Item = (K, Vec2)>) -> Self {
+        let mut grid = SpatialGrid::new(cell_size);
+        for (key, pos) in entries {
+            grid.insert(key, pos);
+        }
+        grid
+    }
+
+    fn cell(&self, pos: Vec2) -> (i32, i32) {
+        (
+            (pos.x / self.cell_size).floor() as i32,
+            (pos.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    pub fn insert(&mut self, key: K, pos: Vec2) {
+        let cell = self.cell(pos);
+        self.cells.entry(cell).or_default().push((key, pos));
+    }
+
+    // 返回中心点 radius 范围内所有对象的 handle 或下标
+    pub fn query_radius(&self, center: Vec2, radius: f32) -> Vec<K> {
+        let (min_x, min_y) = self.cell(center - Vec2::splat(radius));
+        let (max_x, max_y) = self.cell(center + Vec2::splat(radius));
+        let mut found = Vec::new();
+
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                if let Some(entries) = self.cells.get(&(x, y)) {
+                    found.extend(
+                        entries
+                            .iter()
+                            .filter(|(_, pos)| pos.distance(center) <= radius)
+                            .map(|(key, _)| *key),
+                    );
+                }
+            }
+        }
+
+        found
+    }
+}
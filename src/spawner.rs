@@ -0,0 +1,52 @@
+// spawner.rs
+// 生成预算：同屏敌人数量有上限，超出上限的生成请求排队，等有敌人被击毁或离开屏幕后再放出
+use std::collections::VecDeque;
+
+use crate::config::Difficulty;
+use crate::enemies::{EnemyKind, EntryEdge};
+
+const MAX_QUEUED: usize = 32; // 排队请求的上限，超出的请求直接丢弃
+
+#[derive(Clone, Copy, Debug)]
+pub struct SpawnRequest {
+    pub kind: EnemyKind,
+    pub edge: EntryEdge,
+    pub offset: f32,
+}
+
+// 同屏敌人上限，低配模式再减少三分之一
+pub fn enemy_cap(difficulty: Difficulty, low_spec: bool) -> usize {
+    let cap = difficulty.max_enemies();
+    if low_spec {
+        cap * 2 / 3
+    } else {
+        cap
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SpawnQueue {
+    pending: VecDeque<SpawnRequest>,
+}
+
+impl SpawnQueue {
+    pub fn push(&mut self, request: SpawnRequest) {
+        if self.pending.len() < MAX_QUEUED {
+            self.pending.push_back(request);
+        }
+    }
+
+    // 按先后顺序取出最多 free 个请求
+    pub fn release(&mut self, free: usize) -> Vec<SpawnRequest> {
+        let count = free.min(self.pending.len());
+        self.pending.drain(..count).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
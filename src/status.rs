@@ -0,0 +1,94 @@
+// status.rs
+// 敌人身上的状态效果：燃烧（持续伤害）、减速、EMP 瘫痪，各自带有剩余时间
+use ggez::graphics::Color;
+
+const BURN_TICK_INTERVAL: f32 = 0.5; // 燃烧每隔多久造成一次伤害（秒）
+const BURN_TICK_DAMAGE: u32 = 1;
+const SLOW_FACTOR: f32 = 0.4;        // 减速时的速度比例
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusKind {
+    Burning,
+    Slowed,
+    Stunned,
+}
+
+impl StatusKind {
+    // 受影响时叠加在贴图上的颜色
+    pub fn tint(self) -> Color {
+        match self {
+            StatusKind::Burning => Color::new(1.0, 0.55, 0.2, 1.0),
+            StatusKind::Slowed => Color::new(0.45, 0.7, 1.0, 1.0),
+            StatusKind::Stunned => Color::new(1.0, 1.0, 0.45, 1.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct StatusEffect {
+    kind: StatusKind,
+    remaining: f32, // 剩余时间（秒）
+    tick_timer: f32, // 距下一次持续伤害的时间（秒）
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    // 施加效果；已有同类效果时刷新为较长的剩余时间
+    pub fn apply(&mut self, kind: StatusKind, duration: f32) {
+        match self.effects.iter_mut().find(|effect| effect.kind == kind) {
+            Some(effect) => effect.remaining = effect.remaining.max(duration),
+            None => self.effects.push(StatusEffect { kind, remaining: duration, tick_timer: BURN_TICK_INTERVAL }),
+        }
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    pub fn kinds(&self) -> impl Iterator<Item = StatusKind> + '_ {
+        self.effects.iter().map(|effect| effect.kind)
+    }
+
+    // 移动速度比例：瘫痪时完全停止
+    pub fn speed_scale(&self) -> f32 {
+        if self.has(StatusKind::Stunned) {
+            0.0
+        } else if self.has(StatusKind::Slowed) {
+            SLOW_FACTOR
+        } else {
+            1.0
+        }
+    }
+
+    // 推进 dt 秒并移除到期的效果，返回这一步的持续伤害
+    pub fn update(&mut self, dt: f32) -> u32 {
+        let mut damage = 0;
+        for effect in &mut self.effects {
+            effect.remaining -= dt;
+            if effect.kind == StatusKind::Burning {
+                effect.tick_timer -= dt;
+                while effect.tick_timer <= 0.0 {
+                    effect.tick_timer += BURN_TICK_INTERVAL;
+                    damage += BURN_TICK_DAMAGE;
+                }
+            }
+        }
+        self.effects.retain(|effect| effect.remaining > 0.0);
+        damage
+    }
+
+    // 把最近施加的效果颜色乘到基础着色上
+    pub fn tint(&self, base: Color) -> Color {
+        match self.effects.last() {
+            Some(effect) => {
+                let tint = effect.kind.tint();
+                Color::new(base.r * tint.r, base.g * tint.g, base.b * tint.b, base.a)
+            }
+            None => base,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+// text.rs
+// 界面文字：使用自带的 TTF 字体，按窗口缩放选择字号，而不是把默认字号的文字放大绘制，
+// 大窗口下文字依然清晰；HUD 每帧绘制的文字缓存为 Text 对象，内容或字号变化时才重新生成
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use ggez::graphics::{FontData, Text};
+
+use crate::WindowSize;
+
+pub const FONT_PATH: &str = "/font.ttf";
+pub const FONT_NAME: &str = "ui";
+pub const BASE_SIZE: f32 = 16.0; // 基准窗口下的字号，与 ggez 默认字号一致
+
+// 字体载入之前（载入界面、缺少字体的错误界面）使用 ggez 默认字体
+static FONT_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+pub fn install_font(ctx: &mut ggez::Context, font: FontData) {
+    ctx.gfx.add_font(FONT_NAME, font);
+    FONT_INSTALLED.store(true, Ordering::Relaxed);
+}
+
+// 按窗口缩放选择字号；取整，窗口连续缩放时不会为每个小数字号都生成一套字形
+pub fn point_size(base: f32, window_size: &WindowSize) -> f32 {
+    (base * window_size.scale_x.min(window_size.scale_y)).round().max(1.0)
+}
+
+// 使用界面字体和当前窗口字号的文字，绘制时不需要再缩放
+pub fn label(content: impl Into<String>, window_size: &WindowSize) -> Text {
+    let mut text = Text::new(content.into());
+    if FONT_INSTALLED.load(Ordering::Relaxed) {
+        text.set_font(FONT_NAME);
+    }
+    text.set_scale(point_size(BASE_SIZE, window_size));
+    text
+}
+
+struct CachedText {
+    content: String,
+    size: f32,
+    text: Text,
+}
+
+// 按位置标识缓存的文字
+#[derive(Default)]
+pub struct TextCache {
+    entries: HashMap<&'static str, CachedText>,
+}
+
+impl TextCache {
+    // slot 处的文字；内容和字号都没有变化时复用上次生成的 Text
+    pub fn get(&mut self, slot: &'static str, content: String, window_size: &WindowSize) -> &Text {
+        let size = point_size(BASE_SIZE, window_size);
+        let fresh = matches!(self.entries.get(slot), Some(entry) if entry.content == content && entry.size == size);
+        if !fresh {
+            let text = label(content.as_str(), window_size);
+            self.entries.insert(slot, CachedText { content, size, text });
+        }
+        &self.entries[slot].text
+    }
+}
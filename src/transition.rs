@@ -0,0 +1,89 @@
+// transition.rs
+// 场景过渡：前半段逐渐遮住画面，完全遮住时切换场景，后半段再逐渐露出新场景
+use ggez::graphics::{self, Canvas, Color, DrawMode, DrawParam, Mesh};
+use ggez::GameResult;
+use glam::Vec2;
+
+use crate::WindowSize;
+
+const TRANSITION_DURATION: f32 = 0.6; // 整个过渡的时间（秒），遮住和露出各占一半
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionKind {
+    Fade,  // 淡入淡出黑屏
+    Slide, // 黑幕从左向右扫过
+    Iris,  // 圆形光圈收缩到屏幕中心再张开
+}
+
+pub struct Transition {
+    kind: TransitionKind,
+    elapsed: f32,
+}
+
+impl Transition {
+    pub fn new(kind: TransitionKind) -> Self {
+        Transition { kind, elapsed: 0.0 }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.elapsed += dt;
+    }
+
+    // 前半段：画面正在被遮住，旧场景还在显示
+    pub fn is_covering(&self) -> bool {
+        self.elapsed < TRANSITION_DURATION / 2.0
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= TRANSITION_DURATION
+    }
+
+    // 遮挡程度：0 为完全露出，1 为完全遮住
+    fn coverage(&self) -> f32 {
+        let t = (self.elapsed / TRANSITION_DURATION).clamp(0.0, 1.0);
+        1.0 - (2.0 * t - 1.0).abs()
+    }
+
+    pub fn draw(&self, ctx: &mut ggez::Context, canvas: &mut Canvas, window_size: &WindowSize) -> GameResult {
+        let coverage = self.coverage();
+        let screen = Vec2::new(window_size.width, window_size.height);
+        match self.kind {
+            TransitionKind::Fade => {
+                canvas.draw(
+                    &graphics::Quad,
+                    DrawParam::default().scale(screen).color(Color::new(0.0, 0.0, 0.0, coverage)),
+                );
+            }
+            TransitionKind::Slide => {
+                // 遮住时从左边缘伸出，露出时向右边缘收回
+                let width = screen.x * coverage;
+                let x = if self.is_covering() { 0.0 } else { screen.x - width };
+                canvas.draw(
+                    &graphics::Quad,
+                    DrawParam::default()
+                        .dest(Vec2::new(x, 0.0))
+                        .scale(Vec2::new(width, screen.y))
+                        .color(Color::BLACK),
+                );
+            }
+            TransitionKind::Iris => {
+                if coverage <= 0.0 {
+                    return Ok(());
+                }
+                // 用一个很粗的圆环盖住光圈以外的部分
+                let outer = screen.length() / 2.0 + 2.0;
+                let inner = outer * (1.0 - coverage);
+                let ring = Mesh::new_circle(
+                    ctx,
+                    DrawMode::stroke(outer - inner),
+                    screen / 2.0,
+                    (outer + inner) / 2.0,
+                    1.0,
+                    Color::BLACK,
+                )?;
+                canvas.draw(&ring, DrawParam::default());
+            }
+        }
+        Ok(())
+    }
+}
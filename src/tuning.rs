@@ -0,0 +1,63 @@
+// tuning.rs
+// 无尽模式的难度曲线：敌人速度、生成频率和敌方子弹速度随波次平滑增长。
+// 曲线定义在资源目录的 tuning.ron 中（mod 可以覆盖），缺少或无法解析时使用内置的默认曲线
+use std::io::Read;
+
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+
+const TUNING_FILE: &str = "/tuning.ron";
+
+// 从 start 出发、逐渐逼近 max 的倍数曲线，每过 half_life 波剩余的增长量减半
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Curve {
+    pub start: f32,
+    pub max: f32,
+    pub half_life: f32, // 波
+}
+
+impl Curve {
+    // progress 为开局后经过的波数，可以带小数，波内也平滑增长
+    pub fn at(&self, progress: f32) -> f32 {
+        if self.half_life <= 0.0 {
+            return self.max;
+        }
+        self.max - (self.max - self.start) * 0.5f32.powf(progress.max(0.0) / self.half_life)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Tuning {
+    pub enemy_speed: Curve,  // 敌人移动速度倍数
+    pub spawn_rate: Curve,   // 随机生成频率倍数，生成间隔按它缩短
+    pub bullet_speed: Curve, // 敌方子弹（含 Boss 弹幕）速度倍数
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Tuning {
+            enemy_speed: Curve { start: 1.0, max: 1.6, half_life: 12.0 },
+            spawn_rate: Curve { start: 1.0, max: 2.0, half_life: 10.0 },
+            bullet_speed: Curve { start: 1.0, max: 1.5, half_life: 15.0 },
+        }
+    }
+}
+
+impl Tuning {
+    pub fn load_resource(ctx: &ggez::Context) -> Self {
+        let mut text = String::new();
+        let read = ctx
+            .fs
+            .open(TUNING_FILE)
+            .map_err(|err| err.to_string())
+            .and_then(|mut file| file.read_to_string(&mut text).map_err(|err| err.to_string()));
+        if let Err(err) = read {
+            warn!("No tuning file {} ({}), using default difficulty curves", TUNING_FILE, err);
+            return Tuning::default();
+        }
+        ron::from_str(&text).unwrap_or_else(|err| {
+            error!("Failed to parse {}: {}", TUNING_FILE, err);
+            Tuning::default()
+        })
+    }
+}
@@ -0,0 +1,222 @@
+// ui.rs
+// 简单的 UI 控件：按钮、滑块、开关、选择器、数值，支持键盘焦点导航
+use ggez::graphics::{self, Canvas, Color, DrawParam};
+use ggez::input::keyboard::KeyCode;
+use glam::Vec2;
+
+use crate::text;
+use crate::WindowSize;
+
+const ROW_HEIGHT: f32 = 34.0;
+const LABEL_WIDTH: f32 = 200.0;
+const SLIDER_WIDTH: f32 = 160.0;
+
+pub enum WidgetKind {
+    Button,
+    Slider { value: f32, min: f32, max: f32, step: f32 },
+    Toggle { value: bool },
+    Selector { options: Vec<&'static str>, index: usize },
+    Number { value: f32, min: f32, max: f32, step: f32, unit: &'static str }, // 直接显示数值，不画滑块
+}
+
+pub struct Widget {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub kind: WidgetKind,
+}
+
+impl Widget {
+    pub fn button(id: &'static str, label: &'static str) -> Self {
+        Widget { id, label, kind: WidgetKind::Button }
+    }
+
+    pub fn slider(id: &'static str, label: &'static str, value: f32, min: f32, max: f32, step: f32) -> Self {
+        Widget { id, label, kind: WidgetKind::Slider { value, min, max, step } }
+    }
+
+    pub fn toggle(id: &'static str, label: &'static str, value: bool) -> Self {
+        Widget { id, label, kind: WidgetKind::Toggle { value } }
+    }
+
+    pub fn selector(id: &'static str, label: &'static str, options: Vec<&'static str>, index: usize) -> Self {
+        Widget { id, label, kind: WidgetKind::Selector { options, index } }
+    }
+
+    pub fn number(id: &'static str, label: &'static str, value: f32, min: f32, max: f32, step: f32, unit: &'static str) -> Self {
+        Widget { id, label, kind: WidgetKind::Number { value, min, max, step, unit } }
+    }
+
+    // 左右键调整取值，返回是否发生变化
+    fn adjust(&mut self, direction: i32) -> bool {
+        match &mut self.kind {
+            WidgetKind::Button => false,
+            WidgetKind::Slider { value, min, max, step } | WidgetKind::Number { value, min, max, step, .. } => {
+                let new_value = (*value + *step * direction as f32).clamp(*min, *max);
+                let changed = (new_value - *value).abs() > f32::EPSILON;
+                *value = new_value;
+                changed
+            }
+            WidgetKind::Toggle { value } => {
+                *value = !*value;
+                true
+            }
+            WidgetKind::Selector { options, index } => {
+                let len = options.len() as i32;
+                *index = ((*index as i32 + direction).rem_euclid(len)) as usize;
+                true
+            }
+        }
+    }
+
+    fn value_text(&self) -> String {
+        match &self.kind {
+            WidgetKind::Button => String::new(),
+            WidgetKind::Slider { value, .. } => format!("{:.0}%", value * 100.0),
+            WidgetKind::Toggle { value } => if *value { "On" } else { "Off" }.to_string(),
+            WidgetKind::Selector { options, index } => format!("< {} >", options[*index]),
+            // 步长不是整数时保留一位小数
+            WidgetKind::Number { value, step, unit, .. } if step.fract() != 0.0 => format!("< {:.1}{} >", value, unit),
+            WidgetKind::Number { value, unit, .. } => format!("< {:.0}{} >", value, unit),
+        }
+    }
+}
+
+// 控件交互产生的事件，携带控件 id
+pub enum UiEvent {
+    Activated(&'static str),
+    Changed(&'static str),
+}
+
+// 一组纵向排列、共享焦点的控件
+pub struct WidgetList {
+    pub widgets: Vec<Widget>,
+    focus: usize,
+}
+
+impl WidgetList {
+    pub fn new(widgets: Vec<Widget>) -> Self {
+        WidgetList { widgets, focus: 0 }
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> Option<UiEvent> {
+        let len = self.widgets.len();
+        if len == 0 {
+            return None;
+        }
+        let focused = &mut self.widgets[self.focus];
+        match key {
+            KeyCode::Up | KeyCode::W => {
+                self.focus = (self.focus + len - 1) % len;
+                None
+            }
+            KeyCode::Down | KeyCode::S => {
+                self.focus = (self.focus + 1) % len;
+                None
+            }
+            KeyCode::Left | KeyCode::A => focused.adjust(-1).then_some(UiEvent::Changed(focused.id)),
+            KeyCode::Right | KeyCode::D => focused.adjust(1).then_some(UiEvent::Changed(focused.id)),
+            KeyCode::Return | KeyCode::Space => match focused.kind {
+                WidgetKind::Button => Some(UiEvent::Activated(focused.id)),
+                _ => focused.adjust(1).then_some(UiEvent::Changed(focused.id)),
+            },
+            _ => None,
+        }
+    }
+
+    fn get(&self, id: &str) -> Option<&Widget> {
+        self.widgets.iter().find(|widget| widget.id == id)
+    }
+
+    pub fn slider_value(&self, id: &str) -> Option<f32> {
+        match self.get(id)?.kind {
+            WidgetKind::Slider { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn toggle_value(&self, id: &str) -> Option<bool> {
+        match self.get(id)?.kind {
+            WidgetKind::Toggle { value } => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn selector_index(&self, id: &str) -> Option<usize> {
+        match self.get(id)?.kind {
+            WidgetKind::Selector { index, .. } => Some(index),
+            _ => None,
+        }
+    }
+
+    pub fn number_value(&self, id: &str) -> Option<f32> {
+        match self.get(id)?.kind {
+            WidgetKind::Number { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    // 外部修改了控件对应的数据时（例如切换到另一个条目）同步显示的取值
+    pub fn set_number(&mut self, id: &str, new_value: f32) {
+        if let Some(WidgetKind::Number { value, min, max, .. }) = self.get_mut(id).map(|widget| &mut widget.kind) {
+            *value = new_value.clamp(*min, *max);
+        }
+    }
+
+    pub fn set_selector(&mut self, id: &str, new_index: usize) {
+        if let Some(WidgetKind::Selector { options, index }) = self.get_mut(id).map(|widget| &mut widget.kind) {
+            *index = new_index.min(options.len() - 1);
+        }
+    }
+
+    fn get_mut(&mut self, id: &str) -> Option<&mut Widget> {
+        self.widgets.iter_mut().find(|widget| widget.id == id)
+    }
+
+    // origin 为基准坐标下列表左上角
+    pub fn draw(&self, canvas: &mut Canvas, window_size: &WindowSize, origin: Vec2) {
+        for (idx, widget) in self.widgets.iter().enumerate() {
+            let focused = idx == self.focus;
+            let row = origin + Vec2::new(0.0, idx as f32 * ROW_HEIGHT);
+            let color = if focused { Color::YELLOW } else { Color::WHITE };
+
+            let label = text::label(
+                format!("{} {}", if focused { ">" } else { " " }, widget.label),
+                window_size,
+            );
+            canvas.draw(&label, DrawParam::default().dest(window_size.scale_vec2(row)).color(color));
+
+            let value_pos = row + Vec2::new(LABEL_WIDTH, 0.0);
+            if let WidgetKind::Slider { value, min, max, .. } = widget.kind {
+                // 滑块轨道与填充
+                let fill = ((value - min) / (max - min)).clamp(0.0, 1.0);
+                let track = window_size.scale_vec2(value_pos + Vec2::new(0.0, 6.0));
+                canvas.draw(
+                    &graphics::Quad,
+                    DrawParam::default()
+                        .dest(track)
+                        .scale(window_size.scale_vec2(Vec2::new(SLIDER_WIDTH, 6.0)))
+                        .color(Color::new(0.3, 0.3, 0.3, 1.0)),
+                );
+                canvas.draw(
+                    &graphics::Quad,
+                    DrawParam::default()
+                        .dest(track)
+                        .scale(window_size.scale_vec2(Vec2::new(SLIDER_WIDTH * fill, 6.0)))
+                        .color(color),
+                );
+            }
+
+            let value_text = text::label(widget.value_text(), window_size);
+            let text_offset = match widget.kind {
+                WidgetKind::Slider { .. } => SLIDER_WIDTH + 12.0,
+                _ => 0.0,
+            };
+            canvas.draw(
+                &value_text,
+                DrawParam::default()
+                    .dest(window_size.scale_vec2(value_pos + Vec2::new(text_offset, 0.0)))
+                    .color(color),
+            );
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// warnings.rs
+// 攻击预警：在攻击落下前的若干秒内闪烁显示危险区域或弹道线，时间越近闪得越快
+use ggez::graphics::{self, Canvas, Color, DrawParam, Mesh, Rect};
+use ggez::GameResult;
+use glam::Vec2;
+
+const WARNING_COLOR: Color = Color::new(1.0, 0.2, 0.1, 1.0);
+const MIN_BLINK_RATE: f32 = 4.0; // 刚出现时每秒闪烁次数
+const MAX_BLINK_RATE: f32 = 14.0; // 即将落下时每秒闪烁次数
+
+// 预警对应的攻击，攻击落下时由游戏逻辑处理
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Attack {
+    Dive,      // 俯冲敌人的冲刺路线
+    Artillery, // 屏幕外炮击
+    Laser,     // Boss 激光扫射的起始位置
+}
+
+// 预警形状（基准坐标）
+#[derive(Clone, Copy, Debug)]
+pub enum WarningShape {
+    Line { from: Vec2, to: Vec2, width: f32 },
+    Zone(Rect),
+}
+
+impl WarningShape {
+    pub fn contains(&self, point: Vec2) -> bool {
+        match self {
+            WarningShape::Line { from, to, width } => {
+                let segment = *to - *from;
+                let t = ((point - *from).dot(segment) / segment.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+                point.distance(*from + segment * t) <= width / 2.0
+            }
+            WarningShape::Zone(rect) => rect.contains(point),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Warning {
+    pub attack: Attack,
+    pub shape: WarningShape,
+    duration: f32,
+    remaining: f32,
+}
+
+#[derive(Clone, Default)]
+pub struct Warnings {
+    active: Vec<Warning>,
+}
+
+impl Warnings {
+    pub fn add(&mut self, attack: Attack, shape: WarningShape, duration: f32) {
+        self.active.push(Warning { attack, shape, duration, remaining: duration });
+    }
+
+    // 推进计时，返回本次到时（攻击落下）的预警
+    pub fn update(&mut self, dt: f32) -> Vec<Warning> {
+        for warning in &mut self.active {
+            warning.remaining -= dt;
+        }
+        let (landed, active) = self.active.drain(..).partition(|warning| warning.remaining <= 0.0);
+        self.active = active;
+        landed
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    // time 为游戏时间（秒），scale 为基准坐标到屏幕坐标的缩放
+    pub fn draw(&self, ctx: &mut ggez::Context, canvas: &mut Canvas, scale: Vec2, time: f32) -> GameResult {
+        for warning in &self.active {
+            let urgency = 1.0 - warning.remaining / warning.duration;
+            let rate = MIN_BLINK_RATE + (MAX_BLINK_RATE - MIN_BLINK_RATE) * urgency;
+            let blink = 0.5 + 0.5 * (time * rate * std::f32::consts::TAU).sin();
+            let color = Color { a: 0.25 + 0.5 * blink, ..WARNING_COLOR };
+
+            let mesh = match warning.shape {
+                WarningShape::Line { from, to, width } => {
+                    Mesh::new_line(ctx, &[from * scale, to * scale], width * scale.x.min(scale.y), color)?
+                }
+                WarningShape::Zone(rect) => {
+                    let screen = Rect::new(rect.x * scale.x, rect.y * scale.y, rect.w * scale.x, rect.h * scale.y);
+                    let mut builder = graphics::MeshBuilder::new();
+                    builder.rectangle(graphics::DrawMode::fill(), screen, Color { a: color.a * 0.4, ..color })?;
+                    builder.rectangle(graphics::DrawMode::stroke(2.0), screen, color)?;
+                    Mesh::from_data(ctx, builder.build())
+                }
+            };
+            canvas.draw(&mesh, DrawParam::default());
+        }
+        Ok(())
+    }
+}